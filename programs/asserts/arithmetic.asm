@@ -0,0 +1,12 @@
+; ADD and SUB work on AC, LOAD and STORE move it to the registers
+MOV AX, 10
+MOV BX, 4
+LOAD AX
+ADD BX
+STORE CX
+ASSERT CX, 14
+SUB AX
+SUB BX
+STORE DX
+ASSERT DX, 0
+INT 20H
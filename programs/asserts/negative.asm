@@ -0,0 +1,9 @@
+; Negative values are stored wrapped, -5 is 251 and subtracting it adds 5
+MOV AX, 10
+MOV BX, -5
+ASSERT BX, 251
+LOAD AX
+SUB BX
+STORE CX
+ASSERT CX, 15
+INT 20H
@@ -0,0 +1,12 @@
+; MOV, SWAP and STORE leave the values on the right registers
+MOV AX, 7
+MOV BX, AX
+ASSERT BX, 7
+MOV CX, 3
+SWAP AX, CX
+ASSERT AX, 3
+ASSERT CX, 7
+INC
+STORE DX
+ASSERT DX, 1
+INT 20H
@@ -0,0 +1,8 @@
+; INC and DEC with a register change it and wrap around at 0 and 255
+MOV CX, 0
+DEC CX
+ASSERT CX, 255
+INC CX
+INC CX
+ASSERT CX, 1
+INT 20H
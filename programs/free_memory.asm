@@ -0,0 +1,4 @@
+; INT 12H leaves the free user memory on DX, BX keeps it for the host to check
+INT 12H
+MOV BX, DX
+INT 20H
@@ -0,0 +1,141 @@
+use crate::config::Config;
+use crate::emulator::Scheduler;
+use crate::error::Error;
+use std::path::PathBuf;
+use std::str::FromStr;
+
+pub const USAGE: &str = "Usage: proyecto-1 [OPTIONS] [FILES.asm]...
+
+Options:
+    --config <path>      Config file, defaults to ./config.json
+    --cpus <N>           Number of CPUs
+    --memory <N>         Memory size in bytes
+    --storage <N>        Storage size in bytes
+    --scheduler <name>   fcfs, sjf, srt, rr, hrrn or priority
+    --quantum <N>        Round Robin quantum in ticks
+    --seed <N>           Seed of the random generator
+    --batch <dir>        Run every .asm file of the folder without the window and print the results
+    -h, --help           Show this message";
+
+// Values given on the command line, they override the config file
+#[derive(Debug, Default, Clone, PartialEq)]
+pub struct Args {
+    pub config: Option<PathBuf>,
+    pub cpus: Option<usize>,
+    pub memory: Option<usize>,
+    pub storage: Option<usize>,
+    pub scheduler: Option<Scheduler>,
+    pub quantum: Option<u8>,
+    pub seed: Option<u64>,
+    // Programs stored on startup
+    pub files: Vec<PathBuf>,
+    // Folder run without the window
+    pub batch: Option<PathBuf>,
+    pub help: bool,
+}
+
+impl Args {
+    pub fn parse(args: impl IntoIterator<Item = String>) -> Result<Self, Error> {
+        let mut parsed = Args::default();
+        let mut args = args.into_iter();
+
+        while let Some(arg) = args.next() {
+            match arg.as_str() {
+                "-h" | "--help" => parsed.help = true,
+                "--config" => parsed.config = Some(PathBuf::from(value(&arg, args.next())?)),
+                "--cpus" => parsed.cpus = Some(number(&arg, args.next())?),
+                "--memory" => parsed.memory = Some(number(&arg, args.next())?),
+                "--storage" => parsed.storage = Some(number(&arg, args.next())?),
+                "--scheduler" => {
+                    parsed.scheduler = Some(Scheduler::from_str(&value(&arg, args.next())?)?)
+                }
+                "--quantum" => parsed.quantum = Some(number(&arg, args.next())?),
+                "--seed" => parsed.seed = Some(number(&arg, args.next())?),
+                "--batch" => parsed.batch = Some(PathBuf::from(value(&arg, args.next())?)),
+                _ if arg.starts_with('-') => return Err(Error::InvalidArgument(arg)),
+                _ => parsed.files.push(PathBuf::from(arg)),
+            }
+        }
+
+        Ok(parsed)
+    }
+
+    // Replace the values of the config with the ones given on the command line
+    pub fn apply(&self, config: &mut Config) {
+        if let Some(cpus) = self.cpus {
+            config.cpu_quantity = cpus;
+        }
+        if let Some(memory) = self.memory {
+            config.memory = memory;
+        }
+        if let Some(storage) = self.storage {
+            config.storage = storage;
+        }
+        if let Some(scheduler) = self.scheduler {
+            config.scheduler = Some(scheduler);
+        }
+        if let Some(quantum) = self.quantum {
+            config.quantum = quantum;
+        }
+        if let Some(seed) = self.seed {
+            config.seed = Some(seed);
+        }
+    }
+}
+
+fn value(arg: &str, value: Option<String>) -> Result<String, Error> {
+    value.ok_or(Error::InvalidArgument(format!("{} needs a value", arg)))
+}
+
+fn number<T: FromStr>(arg: &str, value: Option<String>) -> Result<T, Error> {
+    let value = self::value(arg, value)?;
+    value
+        .parse::<T>()
+        .map_err(|_| Error::InvalidArgument(format!("{} {}", arg, value)))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn args(args: &str) -> Result<Args, Error> {
+        Args::parse(args.split_whitespace().map(|x| x.to_string()))
+    }
+
+    #[test]
+    fn override_config() {
+        let args = args("--cpus 3 --scheduler rr --quantum 4 --seed 7 a.asm b.asm").unwrap();
+        let mut config = Config::default();
+        config.memory = 1000;
+        args.apply(&mut config);
+
+        assert_eq!(config.cpu_quantity, 3);
+        assert_eq!(config.scheduler, Some(Scheduler::RR));
+        assert_eq!(config.quantum, 4);
+        assert_eq!(config.seed, Some(7));
+        // Values not given keep the ones of the file
+        assert_eq!(config.memory, 1000);
+        assert_eq!(config.storage, Config::default().storage);
+        assert_eq!(
+            args.files,
+            vec![PathBuf::from("a.asm"), PathBuf::from("b.asm")]
+        );
+    }
+
+    #[test]
+    fn invalid_arguments() {
+        assert!(matches!(args("--cpus two"), Err(Error::InvalidArgument(_))));
+        assert!(matches!(args("--memory"), Err(Error::InvalidArgument(_))));
+        assert!(matches!(
+            args("--scheduler lifo"),
+            Err(Error::ParseSchedulerError(_))
+        ));
+        assert!(matches!(args("--verbose"), Err(Error::InvalidArgument(_))));
+        assert!(args("--help").unwrap().help);
+        assert!(matches!(args("--batch"), Err(Error::InvalidArgument(_))));
+        assert_eq!(
+            args("--batch programs").unwrap().batch,
+            Some(PathBuf::from("programs"))
+        );
+    }
+}
@@ -0,0 +1,87 @@
+use serde::{Deserialize, Serialize};
+use std::fmt;
+use std::hash::{DefaultHasher, Hash, Hasher};
+
+// Colors of the processes, every view takes the color of a process from `process_color` so it
+// looks the same on all of them
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Deserialize, Serialize)]
+pub enum Palette {
+    // Okabe–Ito, distinguishable with the common kinds of color blindness
+    #[default]
+    OkabeIto,
+    // The colors of the Dracula theme
+    Dracula,
+}
+
+// Without the black of the original palette, it doesn't show on the dark themes
+const OKABE_ITO: [u32; 7] = [
+    0xe69f00, 0x56b4e9, 0x009e73, 0xf0e442, 0x0072b2, 0xd55e00, 0xcc79a7,
+];
+const DRACULA: [u32; 6] = [0x8be9fd, 0x50fa7b, 0xffb86c, 0xff79c6, 0xbd93f9, 0xf1fa8c];
+
+impl Palette {
+    pub const ALL: [Palette; 2] = [Palette::OkabeIto, Palette::Dracula];
+
+    pub fn colors(self) -> &'static [u32] {
+        match self {
+            Palette::OkabeIto => &OKABE_ITO,
+            Palette::Dracula => &DRACULA,
+        }
+    }
+}
+
+impl fmt::Display for Palette {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Palette::OkabeIto => write!(f, "Okabe–Ito"),
+            Palette::Dracula => write!(f, "Dracula"),
+        }
+    }
+}
+
+// 0xRRGGBB of a process, pids start at 1 so P1 gets the first color
+pub fn process_color(palette: Palette, p_id: usize) -> u32 {
+    let colors = palette.colors();
+    colors[p_id.saturating_sub(1) % colors.len()]
+}
+
+// 0xRRGGBB of a stored file that isn't loaded, it doesn't change while the file is stored
+pub fn file_color(palette: Palette, file_name: &str, address: usize) -> u32 {
+    let mut hasher = DefaultHasher::new();
+    (file_name, address).hash(&mut hasher);
+    let colors = palette.colors();
+    colors[hasher.finish() as usize % colors.len()]
+}
+
+// (r, g, b) of a 0xRRGGBB color
+pub fn rgb(color: u32) -> (u8, u8, u8) {
+    ((color >> 16) as u8, (color >> 8) as u8, color as u8)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn process_colors() {
+        for palette in Palette::ALL {
+            // The same process always gets the same color
+            assert_eq!(process_color(palette, 3), process_color(palette, 3));
+            assert_eq!(
+                file_color(palette, "a.asm", 4),
+                file_color(palette, "a.asm", 4)
+            );
+            // The first processes don't share a color
+            let n = palette.colors().len();
+            let colors = (1..=n)
+                .map(|x| process_color(palette, x))
+                .collect::<Vec<_>>();
+            for (i, a) in colors.iter().enumerate() {
+                assert!(colors[i + 1..].iter().all(|b| a != b), "{:?}", palette);
+            }
+            assert_eq!(process_color(palette, n + 1), process_color(palette, 1));
+        }
+        assert_eq!(process_color(Palette::OkabeIto, 1), 0xe69f00);
+        assert_eq!(rgb(0x56b4e9), (0x56, 0xb4, 0xe9));
+    }
+}
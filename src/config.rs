@@ -1,6 +1,10 @@
-use crate::emulator::Scheduler;
+use crate::colors::Palette;
+use crate::emulator::{BurstEstimation, CacheConfig, InstructionCosts, Operation, Scheduler};
+use crate::error::{ConfigError, Error};
+use crate::i18n::Lang;
 use serde::{Deserialize, Serialize};
 use std::default::Default;
+use std::path::{Path, PathBuf};
 
 #[derive(Debug, Clone, Copy, Deserialize, Serialize)]
 pub struct Config {
@@ -11,6 +15,152 @@ pub struct Config {
     virtual_memory: usize,
     pub scheduler: Option<Scheduler>,
     pub cpu_quantity: usize,
+    // Duration of a tick in automatic mode at normal speed
+    #[serde(default = "default_tick_interval_ms")]
+    pub tick_interval_ms: u64,
+    // Ticks of the Round Robin quantum
+    #[serde(default = "default_quantum")]
+    pub quantum: u8,
+    // Seed of the random generator, a random one is used when it's not set
+    #[serde(default)]
+    pub seed: Option<u64>,
+    // Language of the UI
+    #[serde(default)]
+    pub lang: Lang,
+    // Most ticks executed on a single frame in turbo mode
+    #[serde(default = "default_turbo_ticks")]
+    pub turbo_ticks: usize,
+    // Bytes of the stack of each process, it grows when it's full
+    #[serde(default = "default_stack_size")]
+    pub stack_size: usize,
+    // Ticks of each instruction
+    #[serde(default)]
+    pub costs: InstructionCosts,
+    // Only CMP sets the zero flag and INC/DEC with a register add it to AC or subtract it, like
+    // the first versions of the emulator
+    #[serde(default)]
+    pub legacy_flags: bool,
+    // Processes that get their input are dispatched before the rest of the ready queue
+    #[serde(default)]
+    pub io_boost: bool,
+    // Ticks that can be undone in manual mode, 0 turns the snapshots off
+    #[serde(default = "default_snapshot_ticks")]
+    pub snapshot_ticks: usize,
+    // Bytes of code the DMA copies to memory on each tick while loading a program, 0 loads them
+    // all at once on admission
+    #[serde(default)]
+    pub dma_bytes_per_tick: usize,
+    // Log every value taken from the random generator
+    #[serde(default)]
+    pub audit_rng: bool,
+    // Ticks between the writes of the recovery file, 0 turns it off
+    #[serde(default = "default_autosnapshot_ticks")]
+    pub autosnapshot_ticks: u64,
+    // Bytes of the device region of the OS segment, 0 turns it off. The first byte is the display
+    // register and the second one the keyboard register
+    #[serde(default = "default_device_size")]
+    pub device_size: usize,
+    // Start of the device region, the end of the OS segment when it's not set. The registers are
+    // addressed with a single byte, so the region has to be on the first 256 bytes
+    #[serde(default)]
+    pub device_address: Option<usize>,
+    // A process that executes this many times the instructions it has may never terminate and
+    // gets a warning, 0 turns it off
+    #[serde(default = "default_runaway_factor")]
+    pub runaway_factor: usize,
+    // The warning pauses the automatic mode and asks to kill the process
+    #[serde(default = "default_runaway_pause")]
+    pub runaway_pause: bool,
+    // A process that writes on a code segment only gets a warning instead of a fault
+    #[serde(default)]
+    pub self_modifying_code: bool,
+    // Burst SJF and SRT schedule with
+    #[serde(default)]
+    pub burst_estimation: BurstEstimation,
+    // Weight of the last CPU burst on the exponential average, from 0 to 1
+    #[serde(default = "default_burst_alpha")]
+    pub burst_alpha: f32,
+    // Bytes of the biggest file that is read as a program
+    #[serde(default = "default_max_file_size")]
+    pub max_file_size: usize,
+    // Colors of the processes on every view
+    #[serde(default)]
+    pub palette: Palette,
+    // Instruction cache in front of the fetch, off by default
+    #[serde(default)]
+    pub cache: CacheConfig,
+    // The processes of the same priority take turns with the quantum instead of running to the
+    // end
+    #[serde(default)]
+    pub priority_rr: bool,
+    // A process with a strictly higher priority takes the CPU of a running one
+    #[serde(default)]
+    pub priority_preemptive: bool,
+    // [warning, critical] percents used that get a warning on each region (the user memory, the
+    // OS segment and the storage), 0 turns a threshold off
+    #[serde(default = "default_usage_thresholds")]
+    pub usage_thresholds: [u8; 2],
+    // Ticks the automatic mode and the batch run before they stop, 0 turns the limit off
+    #[serde(default = "default_max_ticks")]
+    pub max_ticks: u64,
+}
+
+// Smallest storage that fits a few programs
+pub const MIN_STORAGE: usize = 64;
+// Smallest user segment that fits a program with its stack
+pub const MIN_USER_SEGMENT: usize = 32;
+pub const MAX_QUANTUM: u8 = 99;
+
+fn default_tick_interval_ms() -> u64 {
+    1000
+}
+
+fn default_quantum() -> u8 {
+    1
+}
+
+fn default_turbo_ticks() -> usize {
+    100
+}
+
+fn default_stack_size() -> usize {
+    16
+}
+
+fn default_snapshot_ticks() -> usize {
+    200
+}
+
+fn default_autosnapshot_ticks() -> u64 {
+    50
+}
+
+fn default_device_size() -> usize {
+    2
+}
+
+fn default_runaway_factor() -> usize {
+    50
+}
+
+fn default_runaway_pause() -> bool {
+    true
+}
+
+fn default_burst_alpha() -> f32 {
+    0.5
+}
+
+fn default_max_file_size() -> usize {
+    64 * 1024
+}
+
+fn default_usage_thresholds() -> [u8; 2] {
+    [80, 95]
+}
+
+fn default_max_ticks() -> u64 {
+    100_000
 }
 
 /*impl Config {
@@ -41,6 +191,395 @@ impl Default for Config {
             virtual_memory: 64,
             scheduler: Some(Scheduler::FCFS),
             cpu_quantity: 1,
+            tick_interval_ms: default_tick_interval_ms(),
+            quantum: default_quantum(),
+            seed: None,
+            lang: Lang::default(),
+            turbo_ticks: default_turbo_ticks(),
+            stack_size: default_stack_size(),
+            costs: InstructionCosts::default(),
+            legacy_flags: false,
+            io_boost: false,
+            snapshot_ticks: default_snapshot_ticks(),
+            dma_bytes_per_tick: 0,
+            audit_rng: false,
+            autosnapshot_ticks: default_autosnapshot_ticks(),
+            device_size: default_device_size(),
+            device_address: None,
+            runaway_factor: default_runaway_factor(),
+            runaway_pause: default_runaway_pause(),
+            self_modifying_code: false,
+            burst_estimation: BurstEstimation::default(),
+            burst_alpha: default_burst_alpha(),
+            max_file_size: default_max_file_size(),
+            palette: Palette::default(),
+            cache: CacheConfig::default(),
+            priority_rr: false,
+            priority_preemptive: false,
+            usage_thresholds: default_usage_thresholds(),
+            max_ticks: default_max_ticks(),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum ConfigFormat {
+    Json,
+    Toml,
+}
+
+impl ConfigFormat {
+    // Files with the toml extension are TOML, anything else is JSON
+    pub fn from_path(path: &Path) -> Self {
+        match path.extension().and_then(|x| x.to_str()) {
+            Some("toml") => ConfigFormat::Toml,
+            _ => ConfigFormat::Json,
+        }
+    }
+}
+
+impl Config {
+    // Check the values that would break the emulator, returns every problem found
+    pub fn validate(&self) -> Result<(), Vec<ConfigError>> {
+        let mut errors = vec![];
+
+        if self.os_segment >= self.memory {
+            errors.push(ConfigError::OsSegmentTooLarge(self.os_segment, self.memory));
+        } else if self.memory - self.os_segment < MIN_USER_SEGMENT {
+            errors.push(ConfigError::UserSegmentTooSmall(
+                self.memory - self.os_segment,
+                MIN_USER_SEGMENT,
+            ));
+        }
+        if self.cpu_quantity == 0 {
+            errors.push(ConfigError::NoCpus);
+        }
+        if self.storage < MIN_STORAGE {
+            errors.push(ConfigError::StorageTooSmall(self.storage, MIN_STORAGE));
+        }
+        if self.quantum == 0 || self.quantum > MAX_QUANTUM {
+            errors.push(ConfigError::InvalidQuantum(self.quantum, MAX_QUANTUM));
+        }
+        if self.tick_interval_ms == 0 {
+            errors.push(ConfigError::InvalidTickInterval);
+        }
+        if self.turbo_ticks == 0 {
+            errors.push(ConfigError::InvalidTurboTicks);
+        }
+        if self.stack_size == 0 {
+            errors.push(ConfigError::InvalidStackSize);
+        }
+        if let Some((address, size)) = self.device_region() {
+            if size < 2 || address + size > self.os_segment.min(256) {
+                errors.push(ConfigError::InvalidDeviceRegion(address, size));
+            }
+        }
+        if self.cache.enabled && (self.cache.lines == 0 || self.cache.line_size == 0) {
+            errors.push(ConfigError::InvalidCache(
+                self.cache.lines,
+                self.cache.line_size,
+            ));
+        }
+        for threshold in self.usage_thresholds {
+            if threshold > 100 {
+                errors.push(ConfigError::InvalidUsageThreshold(threshold));
+            }
+        }
+        let [warning, critical] = self.usage_thresholds;
+        if warning > 0 && critical > 0 && warning > critical {
+            errors.push(ConfigError::UnorderedUsageThresholds(warning, critical));
         }
+        if !(0.0..=1.0).contains(&self.burst_alpha) {
+            errors.push(ConfigError::InvalidBurstAlpha(self.burst_alpha));
+        }
+        for operation in Operation::ALL {
+            if self.costs.cost(operation) == 0 {
+                errors.push(ConfigError::InvalidCost(operation.to_string()));
+            }
+        }
+
+        if errors.is_empty() {
+            Ok(())
+        } else {
+            Err(errors)
+        }
+    }
+
+    // (address, size) of the device region
+    pub fn device_region(&self) -> Option<(usize, usize)> {
+        if self.device_size == 0 {
+            return None;
+        }
+        let address = self
+            .device_address
+            .unwrap_or(self.os_segment.min(256).saturating_sub(self.device_size));
+        Some((address, self.device_size))
+    }
+
+    // Config file of the directory, config.toml is used before config.json
+    pub fn find(dir: &Path) -> PathBuf {
+        let toml = dir.join("config.toml");
+        if toml.exists() {
+            toml
+        } else {
+            dir.join("config.json")
+        }
+    }
+
+    pub fn parse(text: &str, format: ConfigFormat) -> Result<Self, Error> {
+        match format {
+            ConfigFormat::Json => serde_json::from_str(text)
+                .map_err(|error| Error::InvalidConfigFile(error.to_string())),
+            ConfigFormat::Toml => {
+                toml::from_str(text).map_err(|error| Error::InvalidConfigFile(error.to_string()))
+            }
+        }
+    }
+
+    pub fn load(path: &Path) -> Result<Self, Error> {
+        let text = std::fs::read_to_string(path).map_err(|error| Error::IO(error.kind()))?;
+        Self::parse(&text, ConfigFormat::from_path(path))
+    }
+
+    // Write the config pretty printed on the format of the file
+    pub fn save(&self, path: &Path) -> Result<(), Error> {
+        let text = match ConfigFormat::from_path(path) {
+            ConfigFormat::Json => serde_json::to_string_pretty(self)
+                .map_err(|_| Error::IO(std::io::ErrorKind::InvalidData))?,
+            ConfigFormat::Toml => toml::to_string_pretty(self)
+                .map_err(|_| Error::IO(std::io::ErrorKind::InvalidData))?,
+        };
+        std::fs::write(path, text).map_err(|error| Error::IO(error.kind()))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn default_is_valid() {
+        assert_eq!(Config::default().validate(), Ok(()));
+    }
+
+    #[test]
+    fn invalid_fields() {
+        let config = Config {
+            os_segment: 256,
+            ..Default::default()
+        };
+        assert_eq!(
+            config.validate(),
+            Err(vec![ConfigError::OsSegmentTooLarge(256, 256)])
+        );
+
+        let config = Config {
+            os_segment: 240,
+            ..Default::default()
+        };
+        assert_eq!(
+            config.validate(),
+            Err(vec![ConfigError::UserSegmentTooSmall(16, MIN_USER_SEGMENT)])
+        );
+
+        let config = Config {
+            cpu_quantity: 0,
+            ..Default::default()
+        };
+        assert_eq!(config.validate(), Err(vec![ConfigError::NoCpus]));
+
+        let config = Config {
+            storage: 10,
+            ..Default::default()
+        };
+        assert_eq!(
+            config.validate(),
+            Err(vec![ConfigError::StorageTooSmall(10, MIN_STORAGE)])
+        );
+
+        let config = Config {
+            quantum: 0,
+            ..Default::default()
+        };
+        assert_eq!(
+            config.validate(),
+            Err(vec![ConfigError::InvalidQuantum(0, MAX_QUANTUM)])
+        );
+
+        let config = Config {
+            tick_interval_ms: 0,
+            ..Default::default()
+        };
+        assert_eq!(
+            config.validate(),
+            Err(vec![ConfigError::InvalidTickInterval])
+        );
+
+        let config = Config {
+            turbo_ticks: 0,
+            ..Default::default()
+        };
+        assert_eq!(config.validate(), Err(vec![ConfigError::InvalidTurboTicks]));
+
+        let config = Config {
+            stack_size: 0,
+            ..Default::default()
+        };
+        assert_eq!(config.validate(), Err(vec![ConfigError::InvalidStackSize]));
+
+        let mut config = Config::default();
+        config.costs.int = 0;
+        assert_eq!(
+            config.validate(),
+            Err(vec![ConfigError::InvalidCost("INT".to_string())])
+        );
+
+        let config = Config {
+            device_address: Some(119),
+            ..Default::default()
+        };
+        assert_eq!(
+            config.validate(),
+            Err(vec![ConfigError::InvalidDeviceRegion(119, 2)])
+        );
+        assert_eq!(Config::default().device_region(), Some((118, 2)));
+
+        let config = Config {
+            usage_thresholds: [80, 120],
+            ..Default::default()
+        };
+        assert_eq!(
+            config.validate(),
+            Err(vec![ConfigError::InvalidUsageThreshold(120)])
+        );
+        let thresholds = |usage_thresholds| Config {
+            usage_thresholds,
+            ..Default::default()
+        };
+        assert_eq!(
+            thresholds([95, 80]).validate(),
+            Err(vec![ConfigError::UnorderedUsageThresholds(95, 80)])
+        );
+        // A threshold turned off doesn't need to be ordered
+        assert!(thresholds([95, 0]).validate().is_ok());
+    }
+
+    #[test]
+    fn every_problem_reported() {
+        let config = Config {
+            memory: 100,
+            os_segment: 500,
+            cpu_quantity: 0,
+            storage: 0,
+            quantum: 200,
+            ..Default::default()
+        };
+        assert_eq!(
+            config.validate(),
+            Err(vec![
+                ConfigError::OsSegmentTooLarge(500, 100),
+                ConfigError::NoCpus,
+                ConfigError::StorageTooSmall(0, MIN_STORAGE),
+                ConfigError::InvalidQuantum(200, MAX_QUANTUM),
+            ])
+        );
+    }
+
+    #[test]
+    fn save_config() {
+        let path = std::env::temp_dir().join(format!("config_{}.json", std::process::id()));
+        let config = Config {
+            cpu_quantity: 3,
+            scheduler: Some(Scheduler::RR),
+            seed: Some(42),
+            ..Default::default()
+        };
+        config.save(&path).unwrap();
+
+        let json = std::fs::read_to_string(&path).unwrap();
+        assert!(json.contains("\n  \"cpu_quantity\": 3,"));
+        let loaded: Config = serde_json::from_str(&json).unwrap();
+        assert_eq!(loaded.cpu_quantity, 3);
+        assert_eq!(loaded.scheduler, Some(Scheduler::RR));
+        assert_eq!(loaded.seed, Some(42));
+        std::fs::remove_file(&path).unwrap();
+
+        // Missing directory
+        assert!(config.save(&path.join("config.json")).is_err());
+    }
+
+    #[test]
+    fn parse_formats() {
+        let json = r#"{
+            "memory": 512,
+            "storage": 256,
+            "os_segment": 128,
+            "user_segment": 100,
+            "virtual_memory": 64,
+            "scheduler": "RR",
+            "cpu_quantity": 2,
+            "tick_interval_ms": 250,
+            "seed": 9
+        }"#;
+        let toml = r#"
+            memory = 512
+            storage = 256
+            os_segment = 128
+            user_segment = 100
+            virtual_memory = 64
+            scheduler = "RR"
+            cpu_quantity = 2
+            tick_interval_ms = 250
+            seed = 9
+        "#;
+
+        for config in [
+            Config::parse(json, ConfigFormat::Json).unwrap(),
+            Config::parse(toml, ConfigFormat::Toml).unwrap(),
+        ] {
+            assert_eq!(config.memory, 512);
+            assert_eq!(config.scheduler, Some(Scheduler::RR));
+            assert_eq!(config.tick_interval_ms, 250);
+            assert_eq!(config.seed, Some(9));
+            // Optional fields use their defaults
+            assert_eq!(config.quantum, 1);
+            assert_eq!(config.lang, Lang::En);
+        }
+
+        // Optional fields can be left out
+        let config = Config::parse(
+            "memory = 512\nstorage = 256\nos_segment = 128\nuser_segment = 100\nvirtual_memory = 64\ncpu_quantity = 2",
+            ConfigFormat::Toml,
+        )
+        .unwrap();
+        assert_eq!(config.seed, None);
+        assert_eq!(config.scheduler, None);
+        assert_eq!(config.tick_interval_ms, 1000);
+
+        assert!(matches!(
+            Config::parse("memory = ", ConfigFormat::Toml),
+            Err(Error::InvalidConfigFile(_))
+        ));
+        assert!(matches!(
+            Config::parse("{ \"memory\": ", ConfigFormat::Json),
+            Err(Error::InvalidConfigFile(_))
+        ));
+    }
+
+    #[test]
+    fn save_toml() {
+        let path = std::env::temp_dir().join(format!("config_{}.toml", std::process::id()));
+        let config = Config {
+            scheduler: Some(Scheduler::SJF),
+            ..Default::default()
+        };
+        config.save(&path).unwrap();
+
+        let text = std::fs::read_to_string(&path).unwrap();
+        assert!(text.contains("scheduler = \"SJF\""));
+        let loaded = Config::load(&path).unwrap();
+        assert_eq!(loaded.scheduler, Some(Scheduler::SJF));
+        assert_eq!(loaded.seed, None);
+        std::fs::remove_file(&path).unwrap();
     }
 }
@@ -1,13 +1,29 @@
+pub mod admission;
+pub mod cache;
 pub mod cpu;
+pub mod diagram;
+pub mod dma;
+pub mod history;
 pub mod instruction;
+pub mod log;
 pub mod memory;
+pub mod observer;
 pub mod pcb;
+pub mod rng;
 pub mod scheduler;
 pub mod storage;
 
-pub use cpu::CPU;
+pub use admission::{create_pcbs, load_program, Admission, ArrivalPolicy};
+pub use cache::{hit_rate, Cache, CacheConfig};
+pub use cpu::{load_context, save_context, Core, CoreState, CpuUsage, CPU};
+pub use diagram::{exponential_average, short_name, Diagram, Timing, INITIAL_ESTIMATE};
+pub use dma::{Dma, Transfer};
+pub use history::{History, HistoryEntry};
 pub use instruction::*;
-pub use memory::Memory;
+pub use log::{Level, Log, LogEntry, LogWriter};
+pub use memory::{Memory, SegmentInfo, SegmentKind};
+pub use observer::{Observer, Trace, TraceEvent};
 pub use pcb::*;
+pub use rng::{AuditedRng, Draw};
 pub use scheduler::*;
-pub use storage::Storage;
+pub use storage::{bare_name, content_hash, Storage};
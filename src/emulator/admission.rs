@@ -0,0 +1,349 @@
+use crate::config::Config;
+use crate::emulator::{
+    bare_name, content_hash, to_bytes, verify_code, AuditedRng, Diagram, Instruction, Memory,
+    Storage, Timing, INITIAL_ESTIMATE, PCB,
+};
+use crate::error::Error;
+use crate::i18n::trf;
+use crate::parser::{read_file, ProgramMeta};
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::ops::RangeInclusive;
+
+// Why a stored file is not loaded yet
+#[derive(Debug, Clone, Copy, PartialEq, Deserialize, Serialize)]
+pub enum Admission {
+    // Loaded once there is enough free memory
+    Waiting,
+    // Bigger than the user memory, it will never be loaded
+    Failed,
+}
+
+// How the arrival tick of a new process is chosen
+#[derive(Debug, Clone)]
+pub enum ArrivalPolicy {
+    // The #ARRIVAL directive, processes without it arrive on tick 0
+    FromDirective,
+    // Every process arrives on tick 0, the directive is ignored
+    FixedZero,
+    // The #ARRIVAL directive, or a tick of the range when the file doesn't have it
+    SeededRandom {
+        range: RangeInclusive<u8>,
+        rng: Box<AuditedRng>,
+    },
+}
+
+impl ArrivalPolicy {
+    // Random arrivals on ticks 1 to 5, the same seed gives the same arrivals
+    pub fn seeded(seed: Option<u64>) -> Self {
+        ArrivalPolicy::SeededRandom {
+            range: 1..=5,
+            rng: Box::new(AuditedRng::seeded(seed)),
+        }
+    }
+
+    // Generator of the random arrivals
+    pub fn rng(&self) -> Option<&AuditedRng> {
+        match self {
+            ArrivalPolicy::SeededRandom { rng, .. } => Some(rng),
+            _ => None,
+        }
+    }
+
+    pub fn rng_mut(&mut self) -> Option<&mut AuditedRng> {
+        match self {
+            ArrivalPolicy::SeededRandom { rng, .. } => Some(rng),
+            _ => None,
+        }
+    }
+
+    pub fn arrival(&mut self, directive: Option<u8>) -> u8 {
+        match self {
+            ArrivalPolicy::FromDirective => directive.unwrap_or(0),
+            ArrivalPolicy::FixedZero => 0,
+            ArrivalPolicy::SeededRandom { range, rng } => {
+                directive.unwrap_or_else(|| rng.gen_range("arrival", range.clone()))
+            }
+        }
+    }
+}
+
+impl Default for ArrivalPolicy {
+    fn default() -> Self {
+        Self::seeded(None)
+    }
+}
+
+// Load a parsed program: stores its code and its stack on memory and creates the PCB and the
+// timing, returns the pid. Nothing is left on memory when it fails
+pub fn load_program(
+    memory: &mut Memory,
+    diagram: &mut Diagram,
+    arrival: &mut ArrivalPolicy,
+    config: &Config,
+    name: &str,
+    instructions: Vec<Instruction>,
+    meta: ProgramMeta,
+) -> Result<usize, Error> {
+    let serialized = to_bytes(instructions.clone());
+    let size = serialized.len();
+    let stack_size = meta.stack.unwrap_or(config.stack_size);
+    if size + stack_size > memory.user_size() {
+        return Err(Error::ProgramTooBig(memory.user_size()));
+    }
+    // Create the PCB only if there is enough space in memory
+    if size + stack_size > memory.free_size() {
+        return Err(Error::NotEnoughUserMemory);
+    }
+    let cost = config.costs.total(&instructions);
+    let length = instructions.len();
+    // Create new PCB
+    let next_id = memory.last_pcb_id() + 1;
+    let mut new_pcb = PCB::new(next_id);
+    new_pcb.priority = meta.priority.unwrap_or_default();
+    // Store the instructions on memory
+    let (address, size) = memory.store(serialized)?;
+    // Read the code back before running it
+    if let Err(index) = verify_code(&memory.data[address..address + size], &instructions) {
+        let _ = memory.free_memory(address);
+        return Err(Error::CodeMismatch(index));
+    }
+    new_pcb.code_segment(address, size);
+    memory.cache_program(new_pcb.id, address, instructions);
+
+    // Allocate the stack memory
+    let Ok((address, size)) = memory.store(vec![0; stack_size]) else {
+        let _ = memory.free_memory(new_pcb.code_segment);
+        return Err(Error::NotEnoughStackMemory);
+    };
+    new_pcb.stack_segment(address, size);
+
+    // No more memory to store PCBs
+    if let Err(error) = memory.store_pcb(new_pcb) {
+        let _ = memory.free_memory(new_pcb.code_segment);
+        let _ = memory.free_memory(new_pcb.stack_segment);
+        return Err(error);
+    }
+    memory.set_source(new_pcb.id, meta.source);
+
+    // Without directives the burst is the ticks of the instructions
+    let burst = meta.burst.unwrap_or(cost);
+    diagram.push(Timing {
+        p_id: new_pcb.id,
+        name: name.to_string(),
+        burst,
+        remaining_burst: burst,
+        arrival: arrival.arrival(meta.arrival),
+        input: (!meta.input.is_empty()).then(|| meta.input.into()),
+        input_range: meta.input_range,
+        after: meta.after,
+        declared: meta.burst,
+        estimate: meta.burst.map_or(INITIAL_ESTIMATE, |x| x as f32),
+        length,
+        start: None,
+        ..Default::default()
+    });
+    Ok(new_pcb.id)
+}
+
+// Files of the #AFTER chain that goes back to `name`, like [a.asm, b.asm, a.asm]. `after` has
+// the dependency of every stored file
+fn dependency_cycle(name: &str, after: &HashMap<&str, String>) -> Option<Vec<String>> {
+    let mut chain = vec![name.to_string()];
+    let mut current = name;
+    while let Some(next) = after.get(current) {
+        let found = chain.contains(next);
+        chain.push(next.clone());
+        if found {
+            // A cycle further down the chain is reported by its own files
+            return (next == name).then_some(chain);
+        }
+        current = next;
+    }
+    None
+}
+
+// Create the PCBs of the stored files that aren't loaded yet. Returns the problems found, files
+// that can't be parsed are removed from storage
+pub fn create_pcbs(
+    storage: &mut Storage,
+    memory: &mut Memory,
+    loaded_files: &mut Vec<(usize, Option<usize>)>,
+    pending: &mut Vec<(usize, Admission)>,
+    diagram: &mut Diagram,
+    arrival: &mut ArrivalPolicy,
+    config: &Config,
+) -> Vec<String> {
+    let lang = config.lang;
+    // Files that couldn't be loaded on this call, reported once
+    let mut failures = vec![];
+    // Files with errors, removed from storage after checking all of them
+    let mut invalid = vec![];
+    // #AFTER of every stored file by its bare name, the cycles are rejected before loading
+    // anything
+    let after = storage
+        .used
+        .iter()
+        .filter_map(|(name, address, size)| {
+            let (meta, _) = read_file(&storage.data[*address..*address + *size]).ok()?;
+            Some((bare_name(name), bare_name(&meta.after?).to_string()))
+        })
+        .collect::<HashMap<_, _>>();
+    // Before selecting the process to execute we have to make sure that PCBs have been created
+    // Check the list of stored files
+    for (file_name, file_address, data_size) in &storage.used {
+        let file_address = *file_address;
+        // We only load 5 files at a time
+        /*if memory.pcb_table.len() == 5 {
+            break;
+        }*/
+        if loaded_files.iter().any(|x| x.0 == file_address)
+            || pending.contains(&(file_address, Admission::Failed))
+        {
+            // File already loaded , so we can ignore it
+        }
+        // Load only files that have not already being loaded
+        else {
+            // Parse the file into to list of instructions
+            let file = &storage.data[file_address..(file_address + *data_size)];
+            let (meta, instructions) = match read_file(file) {
+                Ok(program) => program,
+                // Parsing Error
+                Err(error) => {
+                    invalid.push(file_address);
+                    failures.push(trf(lang, "invalid_program", &[file_name, &error]));
+                    continue;
+                }
+            };
+            // Its process would wait for a file that never runs
+            if let Some(missing) = meta
+                .after
+                .as_deref()
+                .filter(|x| !storage.used.iter().any(|(name, ..)| bare_name(name) == *x))
+            {
+                pending.retain(|x| x.0 != file_address);
+                pending.push((file_address, Admission::Failed));
+                failures.push(trf(
+                    lang,
+                    "dependency_missing",
+                    &[&bare_name(file_name), &missing],
+                ));
+                continue;
+            }
+            // Its processes would wait for each other forever
+            if let Some(cycle) = dependency_cycle(bare_name(file_name), &after) {
+                pending.retain(|x| x.0 != file_address);
+                pending.push((file_address, Admission::Failed));
+                failures.push(trf(
+                    lang,
+                    "dependency_cycle",
+                    &[file_name, &cycle.join(" -> ")],
+                ));
+                continue;
+            }
+            let waiting = pending.contains(&(file_address, Admission::Waiting));
+            match load_program(
+                memory,
+                diagram,
+                arrival,
+                config,
+                file_name,
+                instructions,
+                meta,
+            ) {
+                Ok(pid) => {
+                    pending.retain(|x| x.0 != file_address);
+                    loaded_files.push((file_address, Some(pid)));
+                    if let Some(timing) = diagram.get_mut(pid) {
+                        timing.source_hash = content_hash(file);
+                        timing.source = Some(file_address);
+                    }
+                }
+                // The code and the stack don't fit even on an empty memory
+                Err(Error::ProgramTooBig(user_size)) => {
+                    pending.retain(|x| x.0 != file_address);
+                    pending.push((file_address, Admission::Failed));
+                    failures.push(trf(lang, "file_too_big", &[file_name, &user_size]));
+                }
+                // A file that doesn't decode to what was parsed is never run
+                Err(Error::CodeMismatch(index)) => {
+                    pending.retain(|x| x.0 != file_address);
+                    pending.push((file_address, Admission::Failed));
+                    failures.push(trf(lang, "code_mismatch", &[file_name, &index]));
+                }
+                // Loaded once a process terminates and frees its memory
+                Err(error) => {
+                    if !waiting {
+                        pending.push((file_address, Admission::Waiting));
+                        match error {
+                            Error::NotEnoughStackMemory => {
+                                failures.push(trf(lang, "cant_allocate_stack", &[file_name]))
+                            }
+                            Error::NotEnoughOsMemory => {
+                                failures.push(trf(lang, "cant_store_pcb", &[file_name]))
+                            }
+                            _ => {}
+                        }
+                    }
+                }
+            }
+        }
+    }
+    // Remove the files with errors from storage
+    for address in invalid {
+        storage.free_file(address);
+    }
+    failures
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rand::rngs::StdRng;
+    use rand::SeedableRng;
+
+    // Arrivals given to a file with the directive and one without it
+    fn arrivals(mut policy: ArrivalPolicy) -> Vec<u8> {
+        let config = Config::default();
+        let mut storage = Storage::new(config.storage);
+        let mut memory = Memory::new(config.memory, config.os_segment);
+        for (name, program) in [("a.asm", "#ARRIVAL 3\nINT 20H"), ("b.asm", "INT 20H")] {
+            let data = program.as_bytes().to_vec();
+            storage.store_files(name, data).unwrap();
+        }
+        let mut diagram = Diagram::default();
+        let failures = create_pcbs(
+            &mut storage,
+            &mut memory,
+            &mut vec![],
+            &mut vec![],
+            &mut diagram,
+            &mut policy,
+            &config,
+        );
+        assert!(failures.is_empty());
+        assert_eq!(
+            diagram.iter().map(|x| x.p_id).collect::<Vec<_>>(),
+            vec![1, 2]
+        );
+        diagram.iter().map(|x| x.arrival).collect()
+    }
+
+    #[test]
+    fn arrival_policies() {
+        assert_eq!(arrivals(ArrivalPolicy::FromDirective), vec![3, 0]);
+        assert_eq!(arrivals(ArrivalPolicy::FixedZero), vec![0, 0]);
+
+        let random = arrivals(ArrivalPolicy::seeded(Some(7)));
+        assert_eq!(random[0], 3);
+        assert!((1..=5).contains(&random[1]));
+        // The same seed repeats the arrivals
+        assert_eq!(arrivals(ArrivalPolicy::seeded(Some(7))), random);
+
+        let range = ArrivalPolicy::SeededRandom {
+            range: 9..=9,
+            rng: Box::new(AuditedRng::new(StdRng::seed_from_u64(1))),
+        };
+        assert_eq!(arrivals(range), vec![3, 9]);
+    }
+}
@@ -0,0 +1,120 @@
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+
+#[derive(Debug, Clone, Copy, PartialEq, Deserialize, Serialize)]
+#[serde(default)]
+pub struct CacheConfig {
+    // Without it every fetch goes to memory as before
+    pub enabled: bool,
+    pub lines: usize,
+    // Bytes of memory on each line
+    pub line_size: usize,
+    // Extra ticks an instruction takes when it wasn't on the cache
+    pub miss_penalty: u8,
+}
+
+impl Default for CacheConfig {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            lines: 8,
+            line_size: 12,
+            miss_penalty: 1,
+        }
+    }
+}
+
+// Direct mapped instruction cache, it only keeps the tags. The instructions are still read from
+// memory, the cache decides how long the fetch takes
+#[derive(Debug, Clone, Default, Deserialize, Serialize)]
+pub struct Cache {
+    pub config: CacheConfig,
+    // Block of memory on each line
+    tags: Vec<Option<usize>>,
+    // pcb_id -> (hits, misses)
+    stats: HashMap<usize, (u64, u64)>,
+}
+
+impl Cache {
+    pub fn new(config: CacheConfig) -> Self {
+        Self {
+            config,
+            tags: vec![None; config.lines],
+            stats: HashMap::new(),
+        }
+    }
+
+    // Fetch of the instruction at the address, returns the extra ticks of a miss. The block of
+    // the address replaces the one on its line
+    pub fn fetch(&mut self, pcb_id: usize, address: usize) -> u8 {
+        if !self.config.enabled || self.tags.is_empty() {
+            return 0;
+        }
+        let block = address / self.config.line_size.max(1);
+        let line = block % self.tags.len();
+        let stats = self.stats.entry(pcb_id).or_default();
+        if self.tags[line] == Some(block) {
+            stats.0 += 1;
+            0
+        } else {
+            self.tags[line] = Some(block);
+            stats.1 += 1;
+            self.config.miss_penalty
+        }
+    }
+
+    // (hits, misses) of the process
+    pub fn stats(&self, pcb_id: usize) -> (u64, u64) {
+        self.stats.get(&pcb_id).copied().unwrap_or_default()
+    }
+
+    // (hits, misses) of every process
+    pub fn totals(&self) -> (u64, u64) {
+        self.stats
+            .values()
+            .fold((0, 0), |total, x| (total.0 + x.0, total.1 + x.1))
+    }
+
+    pub fn clear(&mut self) {
+        *self = Cache::new(self.config);
+    }
+}
+
+// Percent of the fetches that were hits, None before the first fetch
+pub fn hit_rate((hits, misses): (u64, u64)) -> Option<f32> {
+    match hits + misses {
+        0 => None,
+        total => Some(hits as f32 * 100.0 / total as f32),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn direct_mapped() {
+        let mut cache = Cache::new(CacheConfig {
+            enabled: true,
+            lines: 2,
+            line_size: 4,
+            miss_penalty: 2,
+        });
+        // 0 and 8 go to the same line and replace each other, 4 has its own line
+        let penalties = [0, 1, 4, 0, 8, 0, 5]
+            .map(|address| cache.fetch(1, address))
+            .to_vec();
+        assert_eq!(penalties, vec![2, 0, 2, 0, 2, 2, 0]);
+        assert_eq!(cache.stats(1), (3, 4));
+        assert_eq!(hit_rate(cache.totals()), Some(300.0 / 7.0));
+
+        cache.clear();
+        assert_eq!(cache.totals(), (0, 0));
+        assert_eq!(hit_rate(cache.totals()), None);
+
+        // Disabled it never charges anything
+        let mut cache = Cache::new(CacheConfig::default());
+        assert_eq!(cache.fetch(1, 0), 0);
+        assert_eq!(cache.totals(), (0, 0));
+    }
+}
@@ -1,7 +1,8 @@
-use crate::emulator::Operation;
-use std::time::{Instant, Duration};
+use crate::emulator::{Operation, Register, PCB};
+use serde::{Deserialize, Serialize};
+use std::time::Duration;
 
-#[derive(Debug, Default, Copy, Clone)]
+#[derive(Debug, Default, Copy, Clone, Deserialize, Serialize)]
 pub struct CPU {
     pub ax: u8,
     pub bx: u8,
@@ -12,6 +13,9 @@ pub struct CPU {
     pub sp: usize,
     pub ir: Option<Operation>,
     pub z: bool,
+    // Ticks left before the fetched instruction is executed
+    pub stall: u8,
+    #[serde(skip)]
     pub start_time: Option<std::time::Instant>,
     pub total_time: Option<Duration>,
 }
@@ -23,40 +27,156 @@ impl CPU {
         }
     }
 
-    pub fn is_empty(&self) -> bool {
-        self.ax == 0
-            && self.bx == 0
-            && self.cx == 0
-            && self.dx == 0
-            && self.ac == 0
-            && self.pc == 0
-            && self.sp == 0
-            && self.ir.is_none()
-            && !self.z
-    }
-   
-    // Método para iniciar la ejecución de un proceso
-    pub fn start_process(&mut self) {
-        if self.start_time.is_none() {
-            self.start_time = Some(Instant::now());
-            println!("Iniciando proceso en CPU. Tiempo de inicio: {:?}", self.start_time);
+    // The general purpose register, to read or change it
+    pub fn register_mut(&mut self, register: Register) -> &mut u8 {
+        match register {
+            Register::AX => &mut self.ax,
+            Register::BX => &mut self.bx,
+            Register::CX => &mut self.cx,
+            Register::DX => &mut self.dx,
+        }
+    }
+
+    // Método para limpiar el CPU
+    pub fn clear(&mut self) {
+        *self = CPU::new();
+    }
+}
+
+// What a core is doing on the current tick
+#[derive(Debug, Default, Copy, Clone, PartialEq, Eq, Deserialize, Serialize)]
+pub enum CoreState {
+    // Without a process
+    #[default]
+    Idle,
+    Running,
+    // Loading the context of the assigned process, the context switches don't take any ticks
+    // yet so `assign` goes straight to Running
+    Switching,
+    // Its process terminated or faulted on this tick, the OS releases it next
+    Halted,
+}
+
+// A CPU of the emulator and the process assigned to it. Idle is told by the assignment, a
+// process may have all its registers at 0
+#[derive(Debug, Default, Copy, Clone, Deserialize, Serialize)]
+pub struct Core {
+    pub cpu: CPU,
+    pub assigned: Option<usize>,
+    pub state: CoreState,
+}
+
+impl Core {
+    // Context switch to the process, its registers are loaded from the PCB
+    pub fn assign(&mut self, pcb: &PCB) {
+        load_context(&mut self.cpu, pcb);
+        // An instruction interrupted by a context switch starts again
+        self.cpu.stall = 0;
+        self.assigned = Some(pcb.id);
+        self.state = CoreState::Running;
+    }
+
+    // Clear the registers and leave the core idle, returns the process it had
+    pub fn release(&mut self) -> Option<usize> {
+        self.cpu = CPU::new();
+        self.state = CoreState::Idle;
+        self.assigned.take()
+    }
+
+    pub fn is_free(&self) -> bool {
+        self.assigned.is_none()
+    }
+}
+
+// Store the registers of the CPU on the PCB of the process leaving it
+pub fn save_context(cpu: &CPU, pcb: &mut PCB) {
+    pcb.ax = cpu.ax;
+    pcb.bx = cpu.bx;
+    pcb.cx = cpu.cx;
+    pcb.dx = cpu.dx;
+    pcb.ac = cpu.ac;
+    pcb.pc = cpu.pc;
+    pcb.sp = cpu.sp;
+    pcb.ir = cpu.ir;
+    pcb.z = cpu.z;
+}
+
+// Load the registers of the PCB to the CPU, the inverse of `save_context`
+pub fn load_context(cpu: &mut CPU, pcb: &PCB) {
+    cpu.ax = pcb.ax;
+    cpu.bx = pcb.bx;
+    cpu.cx = pcb.cx;
+    cpu.dx = pcb.dx;
+    cpu.ac = pcb.ac;
+    cpu.pc = pcb.pc;
+    cpu.sp = pcb.sp;
+    cpu.ir = pcb.ir;
+    cpu.z = pcb.z;
+}
+
+// Tick accounting of a CPU
+#[derive(Debug, Default, Copy, Clone, PartialEq, Deserialize, Serialize)]
+pub struct CpuUsage {
+    pub busy_ticks: u64,
+    pub idle_ticks: u64,
+    pub context_switches: u64,
+}
+
+impl CpuUsage {
+    pub fn new() -> Self {
+        Self {
+            ..Default::default()
+        }
+    }
+
+    pub fn tick(&mut self, busy: bool) {
+        if busy {
+            self.busy_ticks += 1;
         } else {
-            println!("El proceso ya ha sido iniciado en CPU. Tiempo de inicio existente: {:?}", self.start_time);
+            self.idle_ticks += 1;
         }
     }
 
-    // Método para finalizar el proceso y calcular el tiempo de ejecución
-    pub fn finalize_process(&mut self) {
-        if let Some(start) = self.start_time {
-            self.total_time = Some(start.elapsed());
-            println!("Proceso finalizado en CPU. Tiempo total de ejecución: {:?}", self.total_time);
-        
+    // Percentage of ticks the CPU had a process assigned
+    pub fn utilization(&self) -> f64 {
+        let total = self.busy_ticks + self.idle_ticks;
+        if total == 0 {
+            0.0
+        } else {
+            self.busy_ticks as f64 * 100.0 / total as f64
         }
-        self.clear(); // Limpia el CPU después de finalizar el proceso
     }
+}
 
-    // Método para limpiar el CPU
-    pub fn clear(&mut self) {
-        *self = CPU::new();
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn cores() {
+        let mut core = Core::default();
+        assert!(core.is_free());
+        assert_eq!(core.state, CoreState::Idle);
+
+        // A process with every register at 0 still uses the core
+        let mut pcb = PCB::new(3);
+        pcb.pc = 0;
+        core.cpu.stall = 2;
+        core.assign(&pcb);
+        assert!(!core.is_free());
+        assert_eq!(core.assigned, Some(3));
+        assert_eq!(core.state, CoreState::Running);
+        assert_eq!(core.cpu.stall, 0);
+
+        pcb.ax = 9;
+        pcb.pc = 40;
+        core.assign(&pcb);
+        assert_eq!((core.cpu.ax, core.cpu.pc), (9, 40));
+
+        assert_eq!(core.release(), Some(3));
+        assert!(core.is_free());
+        assert_eq!(core.state, CoreState::Idle);
+        assert_eq!((core.cpu.ax, core.cpu.pc), (0, 0));
+        assert_eq!(core.release(), None);
     }
 }
@@ -0,0 +1,166 @@
+use crate::emulator::{bare_name, BurstEstimation};
+use serde::{Deserialize, Serialize};
+use std::collections::{HashMap, VecDeque};
+use std::time::{Duration, Instant};
+
+// Estimate of the first CPU burst of a process without #BURST
+pub const INITIAL_ESTIMATE: f32 = 5.0;
+
+// Scheduling data of a process, kept after it terminates for the stats
+#[derive(Default, Debug, Clone, Deserialize, Serialize)]
+pub struct Timing {
+    pub p_id: usize,                   // Process ID
+    pub name: String,                  // File the process was loaded from
+    pub c_id: Option<usize>,           // CPU ID (if assigned to a CPU)
+    pub burst: usize,                  // Total burst time required
+    pub arrival: u8,                   // Arrival time of the process
+    pub execution: Option<Duration>,   // Time when process was last executed
+    pub remaining_burst: usize,        // Remaining burst time (updated during execution)
+    pub input: Option<VecDeque<u8>>,   // Values left for INT 09H from #INPUT or the .in file
+    pub input_range: Option<(u8, u8)>, // Values INT 09H accepts from the user, from #INPUT_RANGE
+    pub length: usize,                 // Instructions of the program
+    pub executed: usize,               // Instructions executed, loops count every time
+    pub runaway: bool,                 // Already warned that it may never terminate
+    pub declared: Option<usize>,       // Burst of the #BURST directive
+    pub estimate: f32,                 // Exponential average of the next CPU burst
+    pub ticks: usize,                  // Ticks executed on a CPU
+    pub burst_ticks: usize,            // Ticks executed since it got the CPU
+    pub estimated: Option<usize>,      // Burst expected when it was first dispatched
+    pub source_hash: u64,              // Hash of the file when the process was loaded
+    pub source: Option<usize>,         // Storage address of the file it was loaded from
+    pub queued: Option<u64>,           // Tick it last went to the tail of the ready queue
+    pub ready_at: Option<u64>,         // Tick it first became ready
+    pub dispatched_at: Option<u64>,    // Tick it first got a CPU
+    pub after: Option<String>,         // File it waits for, from #AFTER
+    // Wall clock times, the recovery file doesn't keep them
+    #[serde(skip)]
+    pub start: Option<Instant>, // Actual start time of the process
+    #[serde(skip)]
+    pub end_time: Option<Instant>, // Time when process was terminated
+}
+
+impl Timing {
+    // Place on a FIFO ready queue, the tick it got there and then the arrival and the pid
+    pub fn queue_key(&self) -> (u64, u8, usize) {
+        let queued = self.queued.unwrap_or(self.arrival as u64);
+        (queued, self.arrival, self.p_id)
+    }
+
+    // Ticks it waited ready before it first got a CPU
+    pub fn first_response(&self) -> Option<u64> {
+        Some(self.dispatched_at?.saturating_sub(self.ready_at?))
+    }
+
+    // Percent of the burst already executed
+    pub fn progress(&self) -> f32 {
+        if self.burst == 0 {
+            return 100.0;
+        }
+        (self.burst - self.remaining_burst.min(self.burst)) as f32 * 100.0 / self.burst as f32
+    }
+
+    // Burst the scheduler expects from the process
+    pub fn expected_burst(&self, estimation: BurstEstimation) -> usize {
+        match estimation {
+            BurstEstimation::Exact => self.burst,
+            BurstEstimation::Declared => self.declared.unwrap_or(usize::MAX),
+            BurstEstimation::Exponential => self.estimate.round() as usize,
+        }
+    }
+
+    // Ticks the scheduler expects the process to still need
+    pub fn expected_remaining(&self, estimation: BurstEstimation) -> usize {
+        match estimation {
+            BurstEstimation::Exact => self.remaining_burst,
+            BurstEstimation::Declared => self
+                .declared
+                .map_or(usize::MAX, |x| x.saturating_sub(self.ticks)),
+            BurstEstimation::Exponential => {
+                (self.estimate.round() as usize).saturating_sub(self.burst_ticks)
+            }
+        }
+    }
+
+    // (waiting + burst) / burst, the waiting time is the time since the arrival out of a CPU
+    pub fn response_ratio(&self, counter: u64, estimation: BurstEstimation) -> f32 {
+        let burst = self.expected_burst(estimation).max(1) as f32;
+        let waiting = counter.saturating_sub(self.arrival as u64 + self.ticks as u64) as f32;
+        (waiting + burst) / burst
+    }
+
+    // A tick executed on a CPU
+    pub fn run_tick(&mut self) {
+        self.remaining_burst = self.remaining_burst.saturating_sub(1);
+        self.ticks += 1;
+        self.burst_ticks += 1;
+    }
+
+    // The process left the CPU, the burst it ran updates the estimate of the next one
+    pub fn end_burst(&mut self, alpha: f32) {
+        if self.burst_ticks == 0 {
+            return;
+        }
+        self.estimate = exponential_average(self.estimate, self.burst_ticks, alpha);
+        self.burst_ticks = 0;
+    }
+
+    // "P3 fibonacci.asm", used wherever a process is shown
+    pub fn label(&self) -> String {
+        format!("P{} {}", self.p_id, short_name(&self.name))
+    }
+}
+
+// Timings of the processes indexed by pid, in the order the processes were created
+#[derive(Default, Debug, Clone, Deserialize, Serialize)]
+pub struct Diagram {
+    timings: Vec<Timing>,
+    // pid -> position on timings
+    index: HashMap<usize, usize>,
+}
+
+impl Diagram {
+    pub fn push(&mut self, timing: Timing) {
+        self.index.insert(timing.p_id, self.timings.len());
+        self.timings.push(timing);
+    }
+
+    pub fn get(&self, p_id: usize) -> Option<&Timing> {
+        self.index.get(&p_id).map(|x| &self.timings[*x])
+    }
+
+    pub fn get_mut(&mut self, p_id: usize) -> Option<&mut Timing> {
+        self.index.get(&p_id).map(|x| &mut self.timings[*x])
+    }
+
+    pub fn iter(&self) -> impl Iterator<Item = &Timing> {
+        self.timings.iter()
+    }
+
+    pub fn iter_mut(&mut self) -> impl Iterator<Item = &mut Timing> {
+        self.timings.iter_mut()
+    }
+
+    // Pids sorted by the key, ties keep the creation order
+    pub fn order_by_key<K: Ord>(&self, key: impl Fn(&Timing) -> K) -> Vec<usize> {
+        let mut order = self.timings.iter().collect::<Vec<&Timing>>();
+        order.sort_by_key(|x| key(x));
+        order.iter().map(|x| x.p_id).collect()
+    }
+}
+
+// τ(n+1) = α t(n) + (1 − α) τ(n)
+pub fn exponential_average(estimate: f32, burst: usize, alpha: f32) -> f32 {
+    alpha * burst as f32 + (1.0 - alpha) * estimate
+}
+
+// File name without the quotes, long names are truncated to fit on the PCB cards
+pub fn short_name(file_name: &str) -> String {
+    const MAX_LENGTH: usize = 14;
+    let name = bare_name(file_name);
+    if name.chars().count() <= MAX_LENGTH {
+        return name.to_string();
+    }
+    let mut short = name.chars().take(MAX_LENGTH - 1).collect::<String>();
+    short.push('…');
+    short
+}
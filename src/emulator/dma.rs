@@ -0,0 +1,111 @@
+use crate::emulator::{Memory, ProcessState};
+use serde::{Deserialize, Serialize};
+
+// Copy of a code segment from storage to memory
+#[derive(Debug, Clone, PartialEq, Deserialize, Serialize)]
+pub struct Transfer {
+    pub p_id: usize,
+    pub address: usize,
+    // Tick it started on
+    pub started: u64,
+    data: Vec<u8>,
+    // Bytes already on memory
+    pub copied: usize,
+}
+
+// DMA controller, it loads the code of the new processes a few bytes on each tick. The
+// transfers go one after another in the order they started
+#[derive(Debug, Clone, Default, Deserialize, Serialize)]
+pub struct Dma {
+    transfers: Vec<Transfer>,
+}
+
+impl Dma {
+    // The code segment of the process is cleared, `tick` copies it back
+    pub fn start(&mut self, memory: &mut Memory, p_id: usize, tick: u64) {
+        let Some(pcb) = memory.pcb(p_id) else {
+            return;
+        };
+        let segment = pcb.code_segment..pcb.code_segment + pcb.code_segment_size;
+        let data = memory.data[segment.clone()].to_vec();
+        memory.data[segment].fill(0);
+        self.transfers.push(Transfer {
+            p_id,
+            address: pcb.code_segment,
+            started: tick,
+            data,
+            copied: 0,
+        });
+    }
+
+    // Copy up to `bytes` bytes, returns the transfers that completed. The ones of processes that
+    // were removed meanwhile are dropped, their memory may belong to another process
+    pub fn tick(&mut self, memory: &mut Memory, bytes: usize) -> Vec<Transfer> {
+        self.transfers.retain(|x| {
+            memory
+                .pcb(x.p_id)
+                .is_some_and(|pcb| pcb.process_state != ProcessState::Terminated)
+        });
+        let mut left = bytes;
+        for transfer in &mut self.transfers {
+            if left == 0 {
+                break;
+            }
+            let count = left.min(transfer.data.len() - transfer.copied);
+            let start = transfer.address + transfer.copied;
+            memory.data[start..start + count]
+                .copy_from_slice(&transfer.data[transfer.copied..transfer.copied + count]);
+            transfer.copied += count;
+            left -= count;
+        }
+        let (completed, transfers) = self
+            .transfers
+            .drain(..)
+            .partition(|x| x.copied == x.data.len());
+        self.transfers = transfers;
+        completed
+    }
+
+    // (copied, total) bytes of the transfer of the process
+    pub fn progress(&self, p_id: usize) -> Option<(usize, usize)> {
+        self.transfers
+            .iter()
+            .find(|x| x.p_id == p_id)
+            .map(|x| (x.copied, x.data.len()))
+    }
+
+    pub fn clear(&mut self) {
+        self.transfers.clear();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::emulator::PCB;
+
+    #[test]
+    fn transfers() {
+        let mut memory = Memory::new(512, 256);
+        let mut dma = Dma::default();
+        for (id, code) in [(1, vec![1; 8]), (2, vec![2; 4])] {
+            let (address, size) = memory.store(code).unwrap();
+            let mut pcb = PCB::new(id);
+            pcb.code_segment(address, size);
+            memory.store_pcb(pcb).unwrap();
+            dma.start(&mut memory, id, 0);
+        }
+        assert_eq!(memory.data[256..268], [0; 12]);
+
+        // The second transfer only starts once the first one is done
+        assert!(dma.tick(&mut memory, 5).is_empty());
+        assert_eq!(dma.progress(1), Some((5, 8)));
+        assert_eq!(dma.progress(2), Some((0, 4)));
+        let completed = dma.tick(&mut memory, 5);
+        assert_eq!(completed.iter().map(|x| x.p_id).collect::<Vec<_>>(), [1]);
+        assert_eq!(dma.progress(2), Some((2, 4)));
+        assert_eq!(dma.tick(&mut memory, 5).len(), 1);
+        assert_eq!(memory.data[256..268], [1, 1, 1, 1, 1, 1, 1, 1, 2, 2, 2, 2]);
+        assert_eq!(dma.progress(2), None);
+    }
+}
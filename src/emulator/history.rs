@@ -0,0 +1,97 @@
+use crate::emulator::Instruction;
+use serde::{Deserialize, Serialize};
+use std::collections::VecDeque;
+
+// Number of executed instructions kept per CPU
+pub const HISTORY_SIZE: usize = 32;
+
+#[derive(Debug, Clone, PartialEq, Deserialize, Serialize)]
+pub struct HistoryEntry {
+    pub tick: u64,
+    pub pid: usize,
+    // Address of the instruction
+    pub pc: usize,
+    // Executed instruction, it's disassembled when displayed
+    pub instruction: Instruction,
+    // Value of AC after the instruction was executed
+    pub ac: u8,
+    // Ticks of quantum left after the instruction, only with Round Robin
+    pub quantum: Option<u64>,
+}
+
+// Ring buffer with the last instructions executed by a CPU
+#[derive(Debug, Clone, Default, Deserialize, Serialize)]
+pub struct History {
+    entries: VecDeque<HistoryEntry>,
+}
+
+impl History {
+    pub fn new() -> Self {
+        Self {
+            entries: VecDeque::with_capacity(HISTORY_SIZE),
+        }
+    }
+
+    pub fn push(
+        &mut self,
+        tick: u64,
+        pid: usize,
+        pc: usize,
+        instruction: Instruction,
+        ac: u8,
+        quantum: Option<u64>,
+    ) {
+        // Drop the oldest entry when the buffer is full
+        if self.entries.len() == HISTORY_SIZE {
+            self.entries.pop_front();
+        }
+        self.entries.push_back(HistoryEntry {
+            tick,
+            pid,
+            pc,
+            instruction,
+            ac,
+            quantum,
+        });
+    }
+
+    pub fn clear(&mut self) {
+        self.entries.clear();
+    }
+
+    pub fn len(&self) -> usize {
+        self.entries.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.entries.is_empty()
+    }
+
+    // Iterate the entries from the newest to the oldest
+    pub fn newest_first(&self) -> impl Iterator<Item = &HistoryEntry> {
+        self.entries.iter().rev()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::emulator::{Operands, Operation};
+
+    #[test]
+    fn keeps_last_entries() {
+        let mut history = History::new();
+        for tick in 0..(HISTORY_SIZE as u64 + 8) {
+            let instruction = Instruction {
+                operation: Operation::INC,
+                operands: Operands::V0,
+            };
+            history.push(tick, 1, 0, instruction, tick as u8, None);
+        }
+
+        assert_eq!(history.len(), HISTORY_SIZE);
+        let ticks = history.newest_first().map(|x| x.tick).collect::<Vec<u64>>();
+        assert_eq!(ticks.first(), Some(&(HISTORY_SIZE as u64 + 7)));
+        assert_eq!(ticks.last(), Some(&8));
+    }
+}
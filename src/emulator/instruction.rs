@@ -21,52 +21,108 @@ pub enum Operation {
     JNE,
     PUSH,
     POP,
+    // Read and write the device region of the memory
+    LOADM,
+    STOREM,
+    // Write a value on the stack of the process
+    MOVN,
+    // Terminate the process when the register doesn't have the value
+    ASSERT,
 }
 
-impl From<u8> for Operation {
-    fn from(i: u8) -> Self {
-        match i {
-            1 => Operation::PARAM,
-            2 => Operation::MOV,
-            3 => Operation::SWAP,
-            4 => Operation::CMP,
-            5 => Operation::ADD,
-            6 => Operation::SUB,
-            7 => Operation::LOAD,
-            8 => Operation::STORE,
-            9 => Operation::INC,
-            10 => Operation::DEC,
-            11 => Operation::INT,
-            12 => Operation::JMP,
-            13 => Operation::JE,
-            14 => Operation::JNE,
-            15 => Operation::PUSH,
-            16 => Operation::POP,
-            _ => todo!(),
-        }
+// Operands an operation takes on the source, the parser validates the line with it
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum OperandShape {
+    // PARAM 1, 2, 3
+    Params,
+    // MOV AX, 5 or MOV AX, BX
+    RegisterOrValue,
+    // LOADM AX, 118
+    RegisterAddress,
+    // SWAP AX, BX
+    TwoRegisters,
+    // JMP -2
+    Offset,
+    // ADD BX
+    Register,
+    // INT 20H
+    Interupt,
+    // INC changes AC, INC BX changes BX
+    OptionalRegister,
+    // MOVN 2, 5
+    OffsetValue,
+    // ASSERT AX, 5
+    RegisterValue,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct InstructionSpec {
+    pub op: Operation,
+    // First byte of the serialized instruction
+    pub code: u8,
+    pub mnemonic: &'static str,
+    pub operand_shape: OperandShape,
+}
+
+const fn spec(
+    op: Operation,
+    code: u8,
+    mnemonic: &'static str,
+    operand_shape: OperandShape,
+) -> InstructionSpec {
+    InstructionSpec {
+        op,
+        code,
+        mnemonic,
+        operand_shape,
+    }
+}
+
+// Codes, mnemonics and operands of every operation, the conversions and the parser use it so a
+// new instruction is only added here
+pub const INSTRUCTION_SET: &[InstructionSpec] = &[
+    spec(Operation::PARAM, 1, "PARAM", OperandShape::Params),
+    spec(Operation::MOV, 2, "MOV", OperandShape::RegisterOrValue),
+    spec(Operation::SWAP, 3, "SWAP", OperandShape::TwoRegisters),
+    spec(Operation::CMP, 4, "CMP", OperandShape::TwoRegisters),
+    spec(Operation::ADD, 5, "ADD", OperandShape::Register),
+    spec(Operation::SUB, 6, "SUB", OperandShape::Register),
+    spec(Operation::LOAD, 7, "LOAD", OperandShape::Register),
+    spec(Operation::STORE, 8, "STORE", OperandShape::Register),
+    spec(Operation::INC, 9, "INC", OperandShape::OptionalRegister),
+    spec(Operation::DEC, 10, "DEC", OperandShape::OptionalRegister),
+    spec(Operation::INT, 11, "INT", OperandShape::Interupt),
+    spec(Operation::JMP, 12, "JMP", OperandShape::Offset),
+    spec(Operation::JE, 13, "JE", OperandShape::Offset),
+    spec(Operation::JNE, 14, "JNE", OperandShape::Offset),
+    spec(Operation::PUSH, 15, "PUSH", OperandShape::Register),
+    spec(Operation::POP, 16, "POP", OperandShape::Register),
+    spec(Operation::LOADM, 17, "LOADM", OperandShape::RegisterAddress),
+    spec(
+        Operation::STOREM,
+        18,
+        "STOREM",
+        OperandShape::RegisterAddress,
+    ),
+    spec(Operation::MOVN, 19, "MOVN", OperandShape::OffsetValue),
+    spec(Operation::ASSERT, 20, "ASSERT", OperandShape::RegisterValue),
+];
+
+impl TryFrom<u8> for Operation {
+    type Error = Error;
+
+    fn try_from(byte: u8) -> Result<Self, Error> {
+        INSTRUCTION_SET
+            .iter()
+            .find(|x| x.code == byte)
+            .map(|x| x.op)
+            .ok_or(Error::UnknownOperationCode(byte))
     }
 }
 
 impl From<Operation> for u8 {
     fn from(o: Operation) -> u8 {
-        match o {
-            Operation::PARAM => 1,
-            Operation::MOV => 2,
-            Operation::SWAP => 3,
-            Operation::CMP => 4,
-            Operation::ADD => 5,
-            Operation::SUB => 6,
-            Operation::LOAD => 7,
-            Operation::STORE => 8,
-            Operation::INC => 9,
-            Operation::DEC => 10,
-            Operation::INT => 11,
-            Operation::JMP => 12,
-            Operation::JE => 13,
-            Operation::JNE => 14,
-            Operation::PUSH => 15,
-            Operation::POP => 16,
-        }
+        o.spec().code
     }
 }
 
@@ -74,65 +130,153 @@ impl FromStr for Operation {
     type Err = Error;
 
     fn from_str(s: &str) -> Result<Self, Self::Err> {
-        match s {
-            "PARAM" => Ok(Operation::PARAM),
-            "MOV" => Ok(Operation::MOV),
-            "SWAP" => Ok(Operation::SWAP),
-            "CMP" => Ok(Operation::CMP),
-            "ADD" => Ok(Operation::ADD),
-            "SUB" => Ok(Operation::SUB),
-            "LOAD" => Ok(Operation::LOAD),
-            "STORE" => Ok(Operation::STORE),
-            "INC" => Ok(Operation::INC),
-            "DEC" => Ok(Operation::DEC),
-            "INT" => Ok(Operation::INT),
-            "JMP" => Ok(Operation::JMP),
-            "JE" => Ok(Operation::JE),
-            "JNE" => Ok(Operation::JNE),
-            "PUSH" => Ok(Operation::PUSH),
-            "POP" => Ok(Operation::POP),
-            &_ => Err(Self::Err::ParseOperationError(s.to_string())),
-        }
+        INSTRUCTION_SET
+            .iter()
+            .find(|x| x.mnemonic == s)
+            .map(|x| x.op)
+            .ok_or_else(|| Self::Err::ParseOperationError(s.to_string()))
     }
 }
 
 impl fmt::Display for Operation {
     fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
-        match self {
-            Operation::PARAM => write!(f, "PARAM"),
-            Operation::MOV => write!(f, "MOV"),
-            Operation::SWAP => write!(f, "SWAP"),
-            Operation::CMP => write!(f, "CMP"),
-            Operation::ADD => write!(f, "ADD"),
-            Operation::SUB => write!(f, "SUB"),
-            Operation::LOAD => write!(f, "LOAD"),
-            Operation::STORE => write!(f, "STORE"),
-            Operation::INC => write!(f, "INC"),
-            Operation::DEC => write!(f, "DEC"),
-            Operation::INT => write!(f, "INT"),
-            Operation::JMP => write!(f, "JMP"),
-            Operation::JE => write!(f, "JE"),
-            Operation::JNE => write!(f, "JNE"),
-            Operation::PUSH => write!(f, "PUSH"),
-            Operation::POP => write!(f, "POP"),
-        }
+        write!(f, "{}", self.spec().mnemonic)
     }
 }
 
 impl Operation {
-    pub fn maybe_from(byte: u8) -> Option<Self> {
-        match byte {
-            1..16 => Some(Operation::from(byte)),
-            _ => None,
-        }
+    pub const ALL: [Operation; 20] = [
+        Operation::PARAM,
+        Operation::MOV,
+        Operation::SWAP,
+        Operation::CMP,
+        Operation::ADD,
+        Operation::SUB,
+        Operation::LOAD,
+        Operation::STORE,
+        Operation::INC,
+        Operation::DEC,
+        Operation::INT,
+        Operation::JMP,
+        Operation::JE,
+        Operation::JNE,
+        Operation::PUSH,
+        Operation::POP,
+        Operation::LOADM,
+        Operation::STOREM,
+        Operation::MOVN,
+        Operation::ASSERT,
+    ];
+
+    pub fn spec(self) -> &'static InstructionSpec {
+        INSTRUCTION_SET
+            .iter()
+            .find(|x| x.op == self)
+            .expect("every operation is on the instruction set")
     }
 
-    pub fn maybe_into(option: Option<Operation>) -> u8 {
-        match option {
+    // The IR is stored as a single byte, 0 means that there's no instruction
+    pub fn encode_ir(ir: Option<Operation>) -> u8 {
+        match ir {
             Some(operation) => operation.into(),
             None => 0,
         }
     }
+
+    pub fn decode_ir(byte: u8) -> Result<Option<Operation>, Error> {
+        match byte {
+            0 => Ok(None),
+            code => Operation::try_from(code).map(Some),
+        }
+    }
+}
+
+// Ticks each instruction keeps the CPU busy, its effect is applied on the last one
+#[derive(Debug, Clone, Copy, PartialEq, Deserialize, Serialize)]
+#[serde(default)]
+pub struct InstructionCosts {
+    pub param: u8,
+    pub mov: u8,
+    pub swap: u8,
+    pub cmp: u8,
+    pub add: u8,
+    pub sub: u8,
+    pub load: u8,
+    pub store: u8,
+    pub inc: u8,
+    pub dec: u8,
+    pub int: u8,
+    pub jmp: u8,
+    pub je: u8,
+    pub jne: u8,
+    pub push: u8,
+    pub pop: u8,
+    pub loadm: u8,
+    pub storem: u8,
+    pub movn: u8,
+    pub assert: u8,
+}
+
+impl Default for InstructionCosts {
+    fn default() -> Self {
+        Self {
+            param: 1,
+            mov: 1,
+            swap: 1,
+            cmp: 1,
+            add: 1,
+            sub: 1,
+            load: 1,
+            store: 1,
+            inc: 1,
+            dec: 1,
+            int: 1,
+            jmp: 1,
+            je: 1,
+            jne: 1,
+            push: 1,
+            pop: 1,
+            loadm: 1,
+            storem: 1,
+            movn: 1,
+            assert: 1,
+        }
+    }
+}
+
+impl InstructionCosts {
+    pub fn cost(&self, operation: Operation) -> u8 {
+        match operation {
+            Operation::PARAM => self.param,
+            Operation::MOV => self.mov,
+            Operation::SWAP => self.swap,
+            Operation::CMP => self.cmp,
+            Operation::ADD => self.add,
+            Operation::SUB => self.sub,
+            Operation::LOAD => self.load,
+            Operation::STORE => self.store,
+            Operation::INC => self.inc,
+            Operation::DEC => self.dec,
+            Operation::INT => self.int,
+            Operation::JMP => self.jmp,
+            Operation::JE => self.je,
+            Operation::JNE => self.jne,
+            Operation::PUSH => self.push,
+            Operation::POP => self.pop,
+            Operation::LOADM => self.loadm,
+            Operation::STOREM => self.storem,
+            Operation::MOVN => self.movn,
+            Operation::ASSERT => self.assert,
+        }
+    }
+
+    // Ticks the instructions take without jumps, used as the burst of the program
+    pub fn total(&self, instructions: &[Instruction]) -> usize {
+        instructions
+            .iter()
+            .map(|x| self.cost(x.operation) as usize)
+            .sum()
+    }
 }
 
 #[derive(Debug, Clone, Copy, PartialEq, Deserialize, Serialize)]
@@ -144,14 +288,16 @@ pub enum Register {
     DX,
 }
 
-impl From<u8> for Register {
-    fn from(i: u8) -> Self {
+impl TryFrom<u8> for Register {
+    type Error = Error;
+
+    fn try_from(i: u8) -> Result<Self, Error> {
         match i {
-            1 => Register::AX,
-            2 => Register::BX,
-            3 => Register::CX,
-            4 => Register::DX,
-            _ => todo!(),
+            1 => Ok(Register::AX),
+            2 => Ok(Register::BX),
+            3 => Ok(Register::CX),
+            4 => Ok(Register::DX),
+            _ => Err(Error::UnknownRegisterCode(i)),
         }
     }
 }
@@ -181,21 +327,40 @@ impl FromStr for Register {
     }
 }
 
+impl fmt::Display for Register {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Register::AX => write!(f, "AX"),
+            Register::BX => write!(f, "BX"),
+            Register::CX => write!(f, "CX"),
+            Register::DX => write!(f, "DX"),
+        }
+    }
+}
+
 #[derive(Debug, Clone, Copy, PartialEq, Deserialize, Serialize)]
-// ["09H", "10H", "20H"]
+// ["09H", "10H", "11H", "12H", "20H"]
 pub enum Interupt {
     H09,
     H10,
+    // Low byte of the tick counter to DX, it wraps to 0 after 255
+    H11,
+    // Free user memory to DX, up to 255
+    H12,
     H20,
 }
 
-impl From<u8> for Interupt {
-    fn from(i: u8) -> Self {
+impl TryFrom<u8> for Interupt {
+    type Error = Error;
+
+    fn try_from(i: u8) -> Result<Self, Error> {
         match i {
-            1 => Interupt::H09,
-            2 => Interupt::H10,
-            3 => Interupt::H20,
-            _ => todo!(),
+            1 => Ok(Interupt::H09),
+            2 => Ok(Interupt::H10),
+            3 => Ok(Interupt::H20),
+            4 => Ok(Interupt::H11),
+            5 => Ok(Interupt::H12),
+            _ => Err(Error::UnknownInteruptCode(i)),
         }
     }
 }
@@ -206,6 +371,8 @@ impl From<Interupt> for u8 {
             Interupt::H09 => 1,
             Interupt::H10 => 2,
             Interupt::H20 => 3,
+            Interupt::H11 => 4,
+            Interupt::H12 => 5,
         }
     }
 }
@@ -217,12 +384,26 @@ impl FromStr for Interupt {
         match s {
             "09H" => Ok(Interupt::H09),
             "10H" => Ok(Interupt::H10),
+            "11H" => Ok(Interupt::H11),
+            "12H" => Ok(Interupt::H12),
             "20H" => Ok(Interupt::H20),
             &_ => Err(Self::Err::ParseInteruptError(s.to_string())),
         }
     }
 }
 
+impl fmt::Display for Interupt {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Interupt::H09 => write!(f, "09H"),
+            Interupt::H10 => write!(f, "10H"),
+            Interupt::H11 => write!(f, "11H"),
+            Interupt::H12 => write!(f, "12H"),
+            Interupt::H20 => write!(f, "20H"),
+        }
+    }
+}
+
 #[derive(Debug, Clone, Copy, PartialEq, Deserialize, Serialize)]
 pub enum Operands {
     V0,
@@ -238,6 +419,8 @@ pub enum Operands {
     V5(Register, u8),
     // AX, BX
     V6(Register, Register),
+    // 2, 5
+    V7(u8, u8),
 }
 
 impl From<Operands> for Vec<u8> {
@@ -250,41 +433,96 @@ impl From<Operands> for Vec<u8> {
             Operands::V4(p1, p2, p3) => vec![4, p1, p2, p3],
             Operands::V5(register, num) => vec![5, register.into(), num, 0],
             Operands::V6(register1, register2) => vec![6, register1.into(), register2.into(), 0],
+            Operands::V7(offset, value) => vec![7, offset, value, 0],
         }
     }
 }
 
-impl From<&[u8]> for Operands {
-    fn from(bytes: &[u8]) -> Operands {
-        match bytes[0] {
+// The kind byte and the 3 values
+impl TryFrom<&[u8]> for Operands {
+    type Error = Error;
+
+    fn try_from(bytes: &[u8]) -> Result<Self, Error> {
+        let &[kind, a, b, c] = bytes else {
+            return Err(Error::InvalidInstruction(bytes.to_vec()));
+        };
+        Ok(match kind {
             0 => Operands::V0,
-            1 => Operands::V1(bytes[1], bytes[2]),
-            2 => Operands::V2(Register::from(bytes[1])),
-            3 => Operands::V3(Interupt::from(bytes[1])),
-            4 => Operands::V4(bytes[1], bytes[2], bytes[3]),
-            5 => Operands::V5(Register::from(bytes[1]), bytes[2]),
-            6 => Operands::V6(Register::from(bytes[1]), Register::from(bytes[2])),
-            _ => todo!(),
-        }
+            1 => Operands::V1(a, b),
+            2 => Operands::V2(Register::try_from(a)?),
+            3 => Operands::V3(Interupt::try_from(a)?),
+            4 => Operands::V4(a, b, c),
+            5 => Operands::V5(Register::try_from(a)?, b),
+            6 => Operands::V6(Register::try_from(a)?, Register::try_from(b)?),
+            7 => Operands::V7(a, b),
+            _ => return Err(Error::UnknownOperandsCode(kind)),
+        })
     }
 }
 
-#[derive(Debug, Clone, PartialEq, Deserialize, Serialize)]
+// Bytes used by a serialized instruction, including its length byte
+pub const INSTRUCTION_SIZE: usize = 6;
+
+#[derive(Debug, Clone, Copy, PartialEq, Deserialize, Serialize)]
 pub struct Instruction {
     pub operation: Operation,
     pub operands: Operands,
 }
 
-impl From<&[u8]> for Instruction {
-    fn from(bytes: &[u8]) -> Self {
-        Self {
-            // The operand is stored on the first byte
-            operation: Operation::from(bytes[0]),
-            operands: Operands::from(&bytes[1..]),
+// Disassemble the instruction back to its assembly form
+impl fmt::Display for Instruction {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self.operands {
+            Operands::V0 => write!(f, "{}", self.operation),
+            Operands::V1(sign, num) => match sign {
+                1 => write!(f, "{} -{}", self.operation, num),
+                _ => write!(f, "{} {}", self.operation, num),
+            },
+            Operands::V2(register) => write!(f, "{} {}", self.operation, register),
+            Operands::V3(interupt) => write!(f, "{} {}", self.operation, interupt),
+            Operands::V4(p1, p2, p3) => {
+                // Zeroed params are not pushed to the stack, so they are not shown
+                let params = [p1, p2, p3]
+                    .iter()
+                    .filter(|p| **p != 0)
+                    .map(|p| p.to_string())
+                    .collect::<Vec<String>>();
+                if params.is_empty() {
+                    write!(f, "{} {}", self.operation, p1)
+                } else {
+                    write!(f, "{} {}", self.operation, params.join(", "))
+                }
+            }
+            Operands::V5(register, num) => write!(f, "{} {}, {}", self.operation, register, num),
+            Operands::V6(r1, r2) => write!(f, "{} {}, {}", self.operation, r1, r2),
+            Operands::V7(offset, value) => write!(f, "{} {}, {}", self.operation, offset, value),
         }
     }
 }
 
+// The bytes don't include the length byte
+impl TryFrom<&[u8]> for Instruction {
+    type Error = Error;
+
+    fn try_from(bytes: &[u8]) -> Result<Self, Error> {
+        let Some((operation, operands)) = bytes.split_first() else {
+            return Err(Error::InvalidInstruction(bytes.to_vec()));
+        };
+        Ok(Self {
+            // The operand is stored on the first byte
+            operation: Operation::try_from(*operation)?,
+            operands: Operands::try_from(operands)?,
+        })
+    }
+}
+
+impl Instruction {
+    // Decode the bytes of a single instruction, every error is reported as the invalid bytes
+    pub fn decode(bytes: &[u8]) -> Result<Self, Error> {
+        Self::try_from(bytes).map_err(|_| Error::InvalidInstruction(bytes.to_vec()))
+    }
+}
+
 impl From<Instruction> for Vec<u8> {
     fn from(i: Instruction) -> Vec<u8> {
         let mut bytes: Vec<u8> = vec![];
@@ -299,7 +537,7 @@ pub fn to_bytes(instructions: Vec<Instruction>) -> Vec<u8> {
     let mut bytes: Vec<u8> = vec![];
 
     for instruction in instructions {
-        let mut instruction_u8: Vec<u8> = instruction.clone().into();
+        let mut instruction_u8: Vec<u8> = instruction.into();
         instruction_u8.insert(0, (instruction_u8.len() + 1) as u8);
         bytes.append(&mut instruction_u8);
     }
@@ -307,31 +545,129 @@ pub fn to_bytes(instructions: Vec<Instruction>) -> Vec<u8> {
     bytes
 }
 
-pub fn from_bytes(bytes: &[u8]) -> Vec<Instruction> {
+// Decode the instructions written by `to_bytes`, a wrong length byte or undecodable bytes stop
+// it with an error
+pub fn from_bytes(bytes: &[u8]) -> Result<Vec<Instruction>, Error> {
     let mut instructions: Vec<Instruction> = vec![];
 
     let mut i = 0;
 
     while i < bytes.len() {
         let len = bytes[i] as usize;
-        let test = &bytes[(i + 1)..(i + len)];
+        let Some(test) = bytes.get(i + 1..i + len).filter(|_| len > 1) else {
+            return Err(Error::InvalidInstruction(bytes[i..].to_vec()));
+        };
 
-        instructions.push(Instruction::from(test));
+        instructions.push(Instruction::decode(test)?);
         i += len;
     }
 
-    instructions
+    Ok(instructions)
+}
+
+// One entry for each instruction slot of a code segment, the slots that don't decode are kept as
+// their bytes. Unlike `from_bytes` it never stops, the code may be half loaded or overwritten
+pub fn disassemble(bytes: &[u8]) -> Vec<Result<Instruction, Vec<u8>>> {
+    bytes
+        .chunks(INSTRUCTION_SIZE)
+        .map(|slot| match slot.split_first() {
+            Some((len, rest)) if *len as usize == INSTRUCTION_SIZE => {
+                Instruction::decode(rest).map_err(|_| slot.to_vec())
+            }
+            _ => Err(slot.to_vec()),
+        })
+        .collect()
+}
+
+// Decode the stored code back and compare it with the instructions that were written, the
+// error is the index of the first instruction that doesn't match
+pub fn verify_code(bytes: &[u8], instructions: &[Instruction]) -> Result<(), usize> {
+    let mut i = 0;
+    for (index, instruction) in instructions.iter().enumerate() {
+        let len = *bytes.get(i).ok_or(index)? as usize;
+        if len != INSTRUCTION_SIZE || i + len > bytes.len() {
+            return Err(index);
+        }
+        if Instruction::decode(&bytes[i + 1..i + len]).ok() != Some(*instruction) {
+            return Err(index);
+        }
+        i += len;
+    }
+    // Leftover bytes are instructions that weren't written
+    if i != bytes.len() {
+        return Err(instructions.len());
+    }
+    Ok(())
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
 
+    #[test]
+    fn verify_written_code() {
+        let instructions = vec![
+            Instruction {
+                operation: Operation::MOV,
+                operands: Operands::V5(Register::AX, 5),
+            },
+            Instruction {
+                operation: Operation::CMP,
+                operands: Operands::V6(Register::AX, Register::BX),
+            },
+            Instruction {
+                operation: Operation::INT,
+                operands: Operands::V3(Interupt::H20),
+            },
+        ];
+        let bytes = to_bytes(instructions.clone());
+        assert_eq!(verify_code(&bytes, &instructions), Ok(()));
+
+        // Operand that changed value
+        let mut corrupted = bytes.clone();
+        corrupted[4] = 7;
+        assert_eq!(verify_code(&corrupted, &instructions), Err(0));
+        // Register that doesn't exist
+        let mut corrupted = bytes.clone();
+        corrupted[9] = 9;
+        assert_eq!(verify_code(&corrupted, &instructions), Err(1));
+        // Wrong length byte
+        let mut corrupted = bytes.clone();
+        corrupted[12] = 7;
+        assert_eq!(verify_code(&corrupted, &instructions), Err(2));
+        // Missing bytes
+        assert_eq!(verify_code(&bytes[..15], &instructions), Err(2));
+    }
+
+    #[test]
+    fn instruction_set_table() {
+        // Every operation has a single entry, codes and mnemonics don't repeat
+        for operation in Operation::ALL {
+            let specs = INSTRUCTION_SET.iter().filter(|x| x.op == operation).count();
+            assert_eq!(specs, 1, "{:?}", operation);
+        }
+        assert_eq!(INSTRUCTION_SET.len(), Operation::ALL.len());
+        for spec in INSTRUCTION_SET {
+            assert_eq!(Operation::try_from(spec.code).unwrap(), spec.op);
+            assert_eq!(Operation::from_str(spec.mnemonic).unwrap(), spec.op);
+            assert_eq!(u8::from(spec.op), spec.code);
+            assert_eq!(spec.op.to_string(), spec.mnemonic);
+        }
+        assert!(matches!(
+            Operation::try_from(0),
+            Err(Error::UnknownOperationCode(0))
+        ));
+        assert!(matches!(
+            Operation::try_from(21),
+            Err(Error::UnknownOperationCode(21))
+        ));
+    }
+
     #[test]
     fn from_into_operation() {
         let operation = Operation::MOV;
         let operation_u8: u8 = operation.into();
-        let deserialize: Operation = Operation::from(operation_u8);
+        let deserialize = Operation::try_from(operation_u8).unwrap();
 
         assert_eq!(operation, deserialize);
     }
@@ -343,8 +679,8 @@ mod tests {
             operands: Operands::V5(Register::AX, 5),
         };
 
-        let instruction_u8: Vec<u8> = instruction.clone().into();
-        let deserialize: Instruction = Instruction::from(&instruction_u8[..]);
+        let instruction_u8: Vec<u8> = instruction.into();
+        let deserialize = Instruction::try_from(&instruction_u8[..]).unwrap();
 
         assert_eq!(instruction, deserialize);
     }
@@ -364,8 +700,76 @@ mod tests {
 
         let serialize = to_bytes(instructions.clone());
 
-        let deserialize: Vec<Instruction> = from_bytes(&serialize);
+        let deserialize: Vec<Instruction> = from_bytes(&serialize).unwrap();
 
         assert_eq!(instructions, deserialize);
+
+        // Codes that don't exist and length bytes that don't fit are errors, not panics
+        assert!(matches!(
+            Register::try_from(9),
+            Err(Error::UnknownRegisterCode(9))
+        ));
+        assert!(matches!(
+            Interupt::try_from(0),
+            Err(Error::UnknownInteruptCode(0))
+        ));
+        assert!(matches!(
+            Instruction::try_from(&[2, 8, 1, 0, 0][..]),
+            Err(Error::UnknownOperandsCode(8))
+        ));
+        assert!(from_bytes(&[6, 1, 5, 1, 3, 0, 0, 0]).is_err());
+        let mut half_loaded = to_bytes(instructions.clone());
+        half_loaded[6..].fill(0);
+        let slots = disassemble(&half_loaded);
+        assert_eq!(slots[0], Ok(instructions[0]));
+        assert_eq!(slots[1], Err(vec![0; 6]));
+        assert!(from_bytes(&serialize[..8]).is_err());
+    }
+
+    #[test]
+    fn display_instruction() {
+        let instructions = [
+            Instruction {
+                operation: Operation::MOV,
+                operands: Operands::V5(Register::AX, 5),
+            },
+            Instruction {
+                operation: Operation::SWAP,
+                operands: Operands::V6(Register::AX, Register::DX),
+            },
+            Instruction {
+                operation: Operation::JNE,
+                operands: Operands::V1(1, 3),
+            },
+            Instruction {
+                operation: Operation::INT,
+                operands: Operands::V3(Interupt::H09),
+            },
+            Instruction {
+                operation: Operation::PARAM,
+                operands: Operands::V4(1, 2, 0),
+            },
+            Instruction {
+                operation: Operation::INC,
+                operands: Operands::V0,
+            },
+        ];
+
+        let disassembled = instructions
+            .iter()
+            .map(|i| i.to_string())
+            .collect::<Vec<String>>();
+
+        assert_eq!(
+            disassembled,
+            vec![
+                "MOV AX, 5",
+                "SWAP AX, DX",
+                "JNE -3",
+                "INT 09H",
+                "PARAM 1, 2",
+                "INC"
+            ]
+        );
     }
 }
@@ -0,0 +1,126 @@
+use crate::emulator::Observer;
+use crate::i18n::{trf, Lang};
+use std::fmt;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Default)]
+pub enum Level {
+    Debug,
+    #[default]
+    Info,
+    Warning,
+}
+
+impl Level {
+    pub const ALL: [Level; 3] = [Level::Debug, Level::Info, Level::Warning];
+}
+
+impl fmt::Display for Level {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Level::Debug => write!(f, "Debug"),
+            Level::Info => write!(f, "Info"),
+            Level::Warning => write!(f, "Warning"),
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct LogEntry {
+    pub tick: u64,
+    pub level: Level,
+    pub message: String,
+}
+
+impl fmt::Display for LogEntry {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "[{:03}] {}: {}", self.tick, self.level, self.message)
+    }
+}
+
+// Messages of the emulator shown on the log console
+#[derive(Debug, Clone, Default)]
+pub struct Log {
+    entries: Vec<LogEntry>,
+}
+
+impl Log {
+    pub fn new() -> Self {
+        Self { entries: vec![] }
+    }
+
+    pub fn push(&mut self, tick: u64, level: Level, message: String) {
+        self.entries.push(LogEntry {
+            tick,
+            level,
+            message,
+        });
+    }
+
+    // Drop the entries after the first `len`
+    pub fn truncate(&mut self, len: usize) {
+        self.entries.truncate(len);
+    }
+
+    pub fn clear(&mut self) {
+        self.entries.clear();
+    }
+
+    pub fn len(&self) -> usize {
+        self.entries.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.entries.is_empty()
+    }
+
+    // Entries with at least the given level
+    pub fn filter(&self, level: Level) -> impl Iterator<Item = &LogEntry> {
+        self.entries.iter().filter(move |x| x.level >= level)
+    }
+
+    // Plain text of the entries with at least the given level
+    pub fn to_text(&self, level: Level) -> String {
+        self.filter(level)
+            .map(|x| x.to_string())
+            .collect::<Vec<String>>()
+            .join("\n")
+    }
+}
+
+// The log console as an observer, it writes the dispatches and the terminations
+pub struct LogWriter<'a> {
+    pub log: &'a mut Log,
+    pub lang: Lang,
+}
+
+impl Observer for LogWriter<'_> {
+    fn on_dispatch(&mut self, tick: u64, p_id: usize, cpu: usize) {
+        let message = trf(self.lang, "process_assigned", &[&p_id, &cpu]);
+        self.log.push(tick, Level::Info, message);
+    }
+
+    fn on_terminate(&mut self, tick: u64, p_id: usize, cpu: usize) {
+        let message = trf(self.lang, "process_terminated", &[&p_id, &cpu]);
+        self.log.push(tick, Level::Info, message);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn filter_by_level() {
+        let mut log = Log::new();
+        log.push(0, Level::Debug, "prev".to_string());
+        log.push(1, Level::Info, "Asignando proceso".to_string());
+        log.push(2, Level::Warning, "No se puede cambiar".to_string());
+
+        assert_eq!(log.filter(Level::Debug).count(), 3);
+        assert_eq!(log.filter(Level::Info).count(), 2);
+        assert_eq!(
+            log.to_text(Level::Warning),
+            "[002] Warning: No se puede cambiar"
+        );
+    }
+}
@@ -1,16 +1,102 @@
-use crate::emulator::{ProcessState, PCB};
+use crate::config::Config;
+use crate::emulator::{Instruction, ProcessState, INSTRUCTION_SIZE, PCB};
 use crate::error::Error;
+use crate::parser::SourceMap;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
 
-#[derive(Debug, Default)]
+// What a part of the memory or the storage is used for
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum SegmentKind {
+    Os,
+    Pcb,
+    Code,
+    Stack,
+    // Registers of the memory mapped devices
+    Device,
+    // Used block without a process, like a block being allocated
+    Data,
+    File,
+    Free,
+}
+
+// Contiguous part of the memory or the storage
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct SegmentInfo {
+    // Process the segment belongs to
+    pub owner: Option<usize>,
+    pub kind: SegmentKind,
+    pub address: usize,
+    pub size: usize,
+}
+
+impl SegmentInfo {
+    pub fn end(&self) -> usize {
+        self.address + self.size
+    }
+}
+
+// Segments sorted by address with the gaps between them filled with `gap`, adjacent gaps are
+// merged
+pub(crate) fn fill_gaps(
+    mut segments: Vec<SegmentInfo>,
+    range: std::ops::Range<usize>,
+    gap: SegmentKind,
+) -> Vec<SegmentInfo> {
+    segments.sort_by_key(|x| x.address);
+    let mut layout: Vec<SegmentInfo> = vec![];
+    let mut address = range.start;
+    for segment in segments.into_iter().chain([SegmentInfo {
+        owner: None,
+        kind: gap,
+        address: range.end,
+        size: 0,
+    }]) {
+        if segment.address > address {
+            layout.push(SegmentInfo {
+                owner: None,
+                kind: gap,
+                address,
+                size: segment.address - address,
+            });
+        }
+        if segment.size > 0 {
+            address = segment.end();
+            layout.push(segment);
+        }
+    }
+    layout
+}
+
+#[derive(Debug, Default, Clone, Deserialize, Serialize)]
 pub struct Memory {
     pub data: Vec<u8>,
     os_segment_size: usize,
     // (address, size)
     pub used: Vec<(usize, usize)>,
+    // Bytes of the blocks on `used`, updated with it so the free space is known without reading
+    // the memory
+    used_size: usize,
     // (address, size)
     pub freed: Vec<(usize, usize)>,
+    // Addresses of the spaces that the last store or free moved between used and freed
+    pub changed: Vec<usize>,
+    // Bytes of the code segments written by a process since they were stored
+    pub written_code: Vec<usize>,
     // (pcb_id, address, size)
     pub pcb_table: Vec<(usize, usize, usize)>,
+    // pcb_id -> position on pcb_table and pcbs
+    pcb_index: HashMap<usize, usize>,
+    // Deserialized copy of the PCBs, updated every time a PCB is written
+    pcbs: Vec<PCB>,
+    // Decoded instructions of each process, pcb_id -> (code segment, instructions)
+    programs: HashMap<usize, (usize, Vec<Instruction>)>,
+    // Source lines of each process, pcb_id -> lines. Unlike the decoded code they are kept when
+    // the code is written
+    sources: HashMap<usize, SourceMap>,
+    // (address, size) of the device region of the OS segment, the only bytes of the OS segment
+    // that programs can read and write
+    device: Option<(usize, usize)>,
 }
 
 impl Memory {
@@ -19,45 +105,102 @@ impl Memory {
             data: vec![0; size],
             os_segment_size: os_segment,
             used: vec![],
+            used_size: 0,
             freed: vec![],
+            changed: vec![],
+            written_code: vec![],
             pcb_table: vec![],
+            pcb_index: HashMap::new(),
+            pcbs: vec![],
+            programs: HashMap::new(),
+            sources: HashMap::new(),
+            device: None,
         }
     }
 
-    pub fn store(&mut self, data: Vec<u8>, size: usize) -> Result<(usize, usize), Error> {
+    // Memory with the sizes and the device region of the config
+    pub fn from_config(config: &Config) -> Self {
+        let mut memory = Self::new(config.memory, config.os_segment);
+        memory.device = config.device_region();
+        memory
+    }
+
+    pub fn device(&self) -> Option<(usize, usize)> {
+        self.device
+    }
+
+    pub fn is_device(&self, address: usize) -> bool {
+        self.device
+            .is_some_and(|(start, size)| start <= address && address < start + size)
+    }
+
+    // Put the last input on the keyboard register
+    pub fn write_keyboard(&mut self, value: u8) {
+        if let Some((address, _)) = self.device {
+            self.data[address + 1] = value;
+        }
+    }
+
+    // PCB records go before the device region
+    fn pcb_limit(&self) -> usize {
+        match self.device {
+            Some((address, _)) => address.min(self.os_segment_size),
+            None => self.os_segment_size,
+        }
+    }
+
+    // Store the data on a freed block of its size or after the used memory, returns its
+    // (address, size)
+    pub fn store(&mut self, data: Vec<u8>) -> Result<(usize, usize), Error> {
+        self.changed.clear();
+        let size = data.len();
+        // An empty block would be a used block that ends where it starts
+        if size == 0 {
+            return Err(Error::InvalidAllocation);
+        }
         // Some memory space has been freed
         if !self.freed.is_empty() && !self.used.is_empty() {
             for (i, (address, m_size)) in self.freed.clone().iter_mut().enumerate() {
                 if size == *m_size {
-                    println!("{:?} {:?}", &size, &m_size);
                     self.data[*address..*address + size].copy_from_slice(&data[..]);
                     self.used.push(self.freed.remove(i));
+                    self.used_size += size;
+                    self.changed.push(*address);
                     return Ok((*address, *m_size));
                 }
             }
         }
         // No memory has been used
         if self.used.is_empty() {
-            if (self.data.len() - self.os_segment_size) > size {
+            if (self.data.len() - self.os_segment_size) >= size {
                 // Copy data to "memory"
                 self.data[self.os_segment_size..self.os_segment_size + size]
                     .copy_from_slice(&data[..]);
                 self.used.push((self.os_segment_size, size));
+                self.used_size += size;
+                self.changed.push(self.os_segment_size);
                 Ok((self.os_segment_size, size))
             } else {
                 Err(Error::NotEnoughUserMemory)
             }
         } else {
-            // Last used memory information
-            let (address, data_size) = &self.used.last().unwrap();
+            // End of the used memory, a reused freed block is the last one of the list but not
+            // the one at the end
+            let next_address = self
+                .used
+                .iter()
+                .map(|(address, data_size)| address + data_size)
+                .max()
+                .unwrap_or_default();
 
             // We need to know if there is enough space in memory
-            let next_address = address + data_size;
             let available_space = self.data.len() - next_address;
             // Store the data in memory when we have the space
-            if available_space > size {
+            if available_space >= size {
                 self.data[next_address..next_address + size].copy_from_slice(&data[..]);
                 self.used.push((next_address, size));
+                self.used_size += size;
+                self.changed.push(next_address);
                 Ok((next_address, size))
             } else {
                 Err(Error::NotEnoughUserMemory)
@@ -67,24 +210,54 @@ impl Memory {
 
     // Move the memory space data to the freed queue
     pub fn free_memory(&mut self, address: usize) -> Result<(), Error> {
+        self.changed.clear();
         if let Some(position) = self.used.iter().position(|x| x.0 == address) {
             let space = self.used.remove(position);
+            self.used_size -= space.1;
             // Set memory to 0
             self.data[space.0..space.0 + space.1].copy_from_slice(&vec![0; space.1]);
             self.freed.push(space);
+            self.changed.push(address);
+            self.written_code
+                .retain(|x| !(space.0..space.0 + space.1).contains(x));
             if self.used.is_empty() {
                 self.freed.clear();
             }
+            // The decoded code is no longer valid
+            self.programs
+                .retain(|_, (code_segment, _)| *code_segment != address);
+            for pcb in self.pcbs.iter().filter(|x| x.code_segment == address) {
+                self.sources.remove(&pcb.id);
+            }
         }
 
         Ok(())
     }
 
+    // Write a byte for a process, returns the process that owns the code segment of the address
+    pub fn write(&mut self, address: usize, value: u8) -> Option<usize> {
+        self.data[address] = value;
+        let owner = self
+            .pcbs
+            .iter()
+            .find(|x| {
+                x.process_state != ProcessState::Terminated
+                    && (x.code_segment..x.code_segment + x.code_segment_size).contains(&address)
+            })?
+            .id;
+        if !self.written_code.contains(&address) {
+            self.written_code.push(address);
+        }
+        // The decoded program no longer matches the memory
+        self.programs.remove(&owner);
+        Some(owner)
+    }
+
     pub fn store_pcb(&mut self, pcb: PCB) -> Result<(), Error> {
-        let bytes: Vec<u8> = pcb.into();
+        let bytes = pcb.to_record();
         // No PCB has been stored
         if self.pcb_table.is_empty() {
-            if self.os_segment_size > bytes.len() {
+            if self.pcb_limit() >= bytes.len() {
                 self.data[0..bytes.len()].copy_from_slice(&bytes[..]);
                 self.pcb_table.push((pcb.id, 0, bytes.len()));
             } else {
@@ -95,39 +268,383 @@ impl Memory {
             let (_, address, data_size) = &self.pcb_table.last().unwrap();
 
             let next_address = address + data_size;
-            let available_space = self.os_segment_size - next_address;
+            let available_space = self.pcb_limit().saturating_sub(next_address);
 
-            if available_space > bytes.len() {
+            if available_space >= bytes.len() {
                 self.data[next_address..next_address + bytes.len()].copy_from_slice(&bytes[..]);
                 self.pcb_table.push((pcb.id, next_address, bytes.len()));
             } else {
-                return Err(Error::NotEnoughOsMemory);
+                // Reuse the record of a terminated process, all the records have the same size
+                let position = self
+                    .pcbs
+                    .iter()
+                    .position(|x| x.process_state == ProcessState::Terminated)
+                    .ok_or(Error::NotEnoughOsMemory)?;
+                let (old_id, address, size) = self.pcb_table[position];
+                self.data[address..address + size].copy_from_slice(&bytes[..]);
+                self.pcb_table[position] = (pcb.id, address, size);
+                self.pcbs[position] = pcb;
+                self.pcb_index.remove(&old_id);
+                self.pcb_index.insert(pcb.id, position);
+                return Ok(());
             }
         }
+        self.pcb_index.insert(pcb.id, self.pcbs.len());
+        self.pcbs.push(pcb);
         Ok(())
     }
 
-    pub fn last_pcb_id(&self) -> usize {
-        match self.pcb_table.last() {
-            Some((id, _, _)) => *id,
-            None => 0,
+    // Save the changes of a stored PCB
+    pub fn write_pcb(&mut self, pcb: PCB) {
+        if let Some(position) = self.pcb_index.get(&pcb.id) {
+            let (_, address, _) = self.pcb_table[*position];
+            self.data[address..address + PCB::RECORD_SIZE].copy_from_slice(&pcb.to_record());
+            self.pcbs[*position] = pcb;
         }
     }
 
-    pub fn running_process(&self) -> Option<((usize, usize, usize), PCB)> {
-        for (id, address, data_size) in &self.pcb_table {
-            let pcb = PCB::from(&self.data[*address..*address + *data_size]);
-            if pcb.process_state == ProcessState::Running {
-                return Some(((*id, *address, *data_size), pcb));
+    // Every stored PCB, in the order of the table
+    pub fn pcbs(&self) -> &[PCB] {
+        &self.pcbs
+    }
+
+    // Keep the decoded instructions of the process to avoid decoding them on every tick
+    pub fn cache_program(&mut self, pcb_id: usize, code_segment: usize, program: Vec<Instruction>) {
+        self.programs.insert(pcb_id, (code_segment, program));
+    }
+
+    pub fn set_source(&mut self, pcb_id: usize, source: SourceMap) {
+        self.sources.insert(pcb_id, source);
+    }
+
+    pub fn source(&self, pcb_id: usize) -> Option<&SourceMap> {
+        self.sources.get(&pcb_id)
+    }
+
+    // (line, text) of the source of the instruction at the address
+    pub fn source_line(&self, pcb_id: usize, pc: usize) -> Option<(usize, &str)> {
+        let offset = pc.checked_sub(self.pcb(pcb_id)?.code_segment)?;
+        self.source(pcb_id)?.line(offset / INSTRUCTION_SIZE)
+    }
+
+    // Instruction at the address, None when there is no instruction
+    pub fn instruction(&self, pcb_id: usize, pc: usize) -> Option<Instruction> {
+        // Use the cache when the address is one of the decoded instructions
+        if let Some((code_segment, program)) = self.programs.get(&pcb_id) {
+            // A jump out of the program doesn't run the bytes of another process
+            let offset = pc.checked_sub(*code_segment)?;
+            if offset >= program.len() * INSTRUCTION_SIZE {
+                return None;
+            }
+            if offset % INSTRUCTION_SIZE == 0 {
+                return program.get(offset / INSTRUCTION_SIZE).copied();
             }
         }
-        None
+        let bytes = self.data.get(pc + 1..pc + INSTRUCTION_SIZE)?;
+        // Verify that it's a valid instruction
+        Instruction::decode(bytes).ok()
+    }
+
+    // Move the stack of the process to a bigger block with room for at least `size` bytes, the
+    // content of the stack is kept. Nothing changes when there is no memory for the new block
+    pub fn grow_stack(&mut self, pcb_id: usize, size: usize) -> Result<PCB, Error> {
+        let mut pcb = self.pcb(pcb_id).ok_or(Error::NotEnoughUserMemory)?;
+        let new_size = size.max(pcb.stack_segment_size * 2);
+        let mut stack =
+            self.data[pcb.stack_segment..pcb.stack_segment + pcb.stack_segment_size].to_vec();
+        stack.resize(new_size, 0);
+
+        let (address, new_size) = self.store(stack)?;
+        self.free_memory(pcb.stack_segment)?;
+        // Both the new stack and the old one changed
+        self.changed.push(address);
+        pcb.sp = address + (pcb.sp.saturating_sub(pcb.stack_segment));
+        pcb.stack_segment = address;
+        pcb.stack_segment_size = new_size;
+        self.write_pcb(pcb);
+        Ok(pcb)
+    }
+
+    // Highest id given to a PCB, records are reused so the last one may not be the newest
+    pub fn last_pcb_id(&self) -> usize {
+        self.pcb_table.iter().map(|x| x.0).max().unwrap_or(0)
+    }
+
+    // Read the PCB of the process from the table
+    pub fn pcb(&self, id: usize) -> Option<PCB> {
+        self.pcb_index.get(&id).map(|x| self.pcbs[*x])
+    }
+
+    // Entry of the PCB on the table, (pcb_id, address, size)
+    pub fn pcb_entry(&self, id: usize) -> Option<(usize, usize, usize)> {
+        self.pcb_index.get(&id).map(|x| self.pcb_table[*x])
+    }
+
+    pub fn running_process(&self) -> Option<((usize, usize, usize), PCB)> {
+        self.pcb_table
+            .iter()
+            .zip(&self.pcbs)
+            .find(|(_, pcb)| pcb.process_state == ProcessState::Running)
+            .map(|(entry, pcb)| (*entry, *pcb))
+    }
+
+    // Size of the memory available to the processes
+    pub fn user_size(&self) -> usize {
+        self.data.len().saturating_sub(self.os_segment_size)
+    }
+
+    pub fn os_size(&self) -> usize {
+        self.os_segment_size
     }
 
+    // Bytes of the OS segment taken by the PCB records and the device region, the records of
+    // terminated processes are kept to be reused
+    pub fn os_used_size(&self) -> usize {
+        let records = self.pcb_table.iter().map(|x| x.2).sum::<usize>();
+        records + self.device.map_or(0, |x| x.1)
+    }
+
+    // Map of the memory computed from the tables, the OS segment has the PCB records and the user
+    // memory the code and stack segments of the processes
+    pub fn layout(&self) -> Vec<SegmentInfo> {
+        let records = self
+            .pcb_table
+            .iter()
+            .map(|(id, address, size)| SegmentInfo {
+                owner: Some(*id),
+                kind: SegmentKind::Pcb,
+                address: *address,
+                size: *size,
+            })
+            .chain(self.device.map(|(address, size)| SegmentInfo {
+                owner: None,
+                kind: SegmentKind::Device,
+                address,
+                size,
+            }))
+            .collect();
+        let mut layout = fill_gaps(records, 0..self.os_segment_size, SegmentKind::Os);
+
+        // Freed memory can be given to another process, so only live processes own segments
+        let blocks = self
+            .used
+            .iter()
+            .map(|(address, size)| {
+                let pcb = self.pcbs.iter().find(|x| {
+                    x.process_state != ProcessState::Terminated
+                        && (x.code_segment == *address || x.stack_segment == *address)
+                });
+                let kind = match pcb {
+                    Some(pcb) if pcb.code_segment == *address => SegmentKind::Code,
+                    Some(_) => SegmentKind::Stack,
+                    None => SegmentKind::Data,
+                };
+                SegmentInfo {
+                    owner: pcb.map(|x| x.id),
+                    kind,
+                    address: *address,
+                    size: *size,
+                }
+            })
+            .collect();
+        layout.append(&mut fill_gaps(
+            blocks,
+            self.os_segment_size..self.data.len(),
+            SegmentKind::Free,
+        ));
+        layout
+    }
+
+    // User memory that isn't on a used block
     pub fn free_size(&self) -> usize {
-        let mut data = self.data.clone();
-        data.retain(|x| *x == 0);
+        self.user_size().saturating_sub(self.used_size)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::emulator::{Operands, Operation, Register};
+
+    #[test]
+    fn cached_program() {
+        let mut memory = Memory::new(128, 64);
+        let program = vec![Instruction {
+            operation: Operation::MOV,
+            operands: Operands::V5(Register::AX, 5),
+        }];
+        let bytes = crate::emulator::to_bytes(program.clone());
+        let (address, size) = memory.store(bytes).unwrap();
+        memory.cache_program(1, address, program.clone());
+
+        assert_eq!(memory.instruction(1, address), Some(program[0]));
+        // Past the end of the program there is no instruction
+        assert_eq!(memory.instruction(1, address + size), None);
+
+        // Freeing the code drops the cache, the memory is read instead
+        memory.free_memory(address).unwrap();
+        assert!(memory.programs.is_empty());
+        assert_eq!(memory.instruction(1, address), None);
+        // Without a program the address may be past the end of memory
+        assert_eq!(memory.instruction(2, 126), None);
+        assert_eq!(memory.instruction(2, 500), None);
+    }
+
+    #[test]
+    fn changed_spaces() {
+        let mut memory = Memory::new(128, 64);
+        let (first, _) = memory.store(vec![1; 4]).unwrap();
+        let (second, _) = memory.store(vec![2; 4]).unwrap();
+        assert_eq!(memory.changed, vec![second]);
+
+        memory.free_memory(first).unwrap();
+        assert_eq!(memory.changed, vec![first]);
+        assert_eq!(memory.free_size(), memory.user_size() - 4);
+
+        // The freed space is reused
+        memory.store(vec![3; 4]).unwrap();
+        assert_eq!(memory.changed, vec![first]);
+        assert_eq!(memory.free_size(), memory.user_size() - 8);
+
+        // Nothing moved when the data doesn't fit
+        assert!(memory.store(vec![4; 64]).is_err());
+        assert!(memory.changed.is_empty());
+    }
+
+    #[test]
+    fn invalid_allocations() {
+        let mut memory = Memory::new(128, 64);
+        assert!(matches!(
+            memory.store(vec![]),
+            Err(Error::InvalidAllocation)
+        ));
+        assert!(memory.used.is_empty());
+
+        // A block that reuses a freed one goes last on the list, the next one still goes after
+        // the end of the used memory
+        let (first, _) = memory.store(vec![1; 4]).unwrap();
+        memory.store(vec![2; 8]).unwrap();
+        memory.free_memory(first).unwrap();
+        assert_eq!(memory.store(vec![3; 4]).unwrap(), (first, 4));
+        assert_eq!(memory.store(vec![4; 4]).unwrap(), (76, 4));
+
+        // A block that fills the remaining space exactly fits
+        assert!(memory.store(vec![5; 49]).is_err());
+        assert_eq!(memory.store(vec![5; 48]).unwrap(), (80, 48));
+        assert!(memory.store(vec![6; 1]).is_err());
+        let mut memory = Memory::new(128, 64);
+        assert!(memory.store(vec![1; 65]).is_err());
+        assert_eq!(memory.store(vec![1; 64]).unwrap(), (64, 64));
+    }
+
+    #[test]
+    fn grow_stack() {
+        let mut memory = Memory::new(128, 64);
+        let (address, size) = memory.store(vec![1, 2, 0, 0]).unwrap();
+        let mut pcb = PCB::new(1);
+        pcb.stack_segment(address, size);
+        pcb.sp = address + 2;
+        memory.store_pcb(pcb).unwrap();
+
+        let pcb = memory.grow_stack(1, 6).unwrap();
+        assert_eq!(pcb.stack_segment_size, 8);
+        assert_eq!(pcb.sp, pcb.stack_segment + 2);
+        assert_eq!(
+            memory.data[pcb.stack_segment..pcb.stack_segment + 3],
+            [1, 2, 0]
+        );
+        assert_eq!(memory.pcb(1), Some(pcb));
+
+        // No room for a bigger stack
+        assert!(memory.grow_stack(1, 100).is_err());
+        assert_eq!(memory.pcb(1), Some(pcb));
+    }
+
+    #[test]
+    fn layout() {
+        let mut memory = Memory::new(128, 64);
+        let (code, _) = memory.store(vec![1; 12]).unwrap();
+        let (stack, _) = memory.store(vec![0; 4]).unwrap();
+        let mut pcb = PCB::new(1);
+        pcb.code_segment(code, 12);
+        pcb.stack_segment(stack, 4);
+        memory.store_pcb(pcb).unwrap();
+        let (other, _) = memory.store(vec![2; 8]).unwrap();
+
+        let segment = |owner, kind, address, size| SegmentInfo {
+            owner,
+            kind,
+            address,
+            size,
+        };
+        assert_eq!(
+            memory.layout(),
+            vec![
+                segment(Some(1), SegmentKind::Pcb, 0, PCB::RECORD_SIZE),
+                segment(
+                    None,
+                    SegmentKind::Os,
+                    PCB::RECORD_SIZE,
+                    64 - PCB::RECORD_SIZE
+                ),
+                segment(Some(1), SegmentKind::Code, 64, 12),
+                segment(Some(1), SegmentKind::Stack, 76, 4),
+                segment(None, SegmentKind::Data, 80, 8),
+                segment(None, SegmentKind::Free, 88, 40),
+            ]
+        );
+
+        // The freed stack is a gap until a block of the same size reuses it
+        memory.free_memory(stack).unwrap();
+        pcb.process_state = ProcessState::Terminated;
+        memory.write_pcb(pcb);
+        let layout = memory.layout();
+        assert_eq!(layout[2], segment(None, SegmentKind::Data, 64, 12));
+        assert_eq!(layout[3], segment(None, SegmentKind::Free, 76, 4));
+        assert_eq!(layout[4].address, other);
+        memory.store(vec![3; 4]).unwrap();
+        assert_eq!(memory.layout()[3], segment(None, SegmentKind::Data, 76, 4));
+
+        // The segments cover the whole memory without overlapping
+        let layout = memory.layout();
+        assert!(layout.windows(2).all(|x| x[0].end() == x[1].address));
+        assert_eq!(layout.last().unwrap().end(), 128);
+    }
+
+    #[test]
+    fn pcb_lookup() {
+        let mut memory = Memory::new(512, 256);
+        for id in 1..=3 {
+            memory.store_pcb(PCB::new(id)).unwrap();
+        }
+
+        let mut pcb = memory.pcb(2).unwrap();
+        assert_eq!(memory.pcb_entry(2), Some(memory.pcb_table[1]));
+        pcb.ax = 7;
+        memory.write_pcb(pcb);
+
+        // The snapshot and the bytes on memory have the same PCB
+        let (_, address, size) = memory.pcb_entry(2).unwrap();
+        assert_eq!(size, PCB::RECORD_SIZE);
+        let record = memory.data[address..address + size].try_into().unwrap();
+        assert_eq!(PCB::from_record(record).unwrap().ax, 7);
+        assert_eq!(memory.pcb(2).unwrap().ax, 7);
+        assert_eq!(memory.pcb(4), None);
+    }
 
-        data.len().saturating_sub(self.os_segment_size)
+    #[test]
+    fn exact_os_segment() {
+        // Two records fill the OS segment exactly, the third one doesn't fit
+        let mut memory = Memory::new(128 + 2 * PCB::RECORD_SIZE, 2 * PCB::RECORD_SIZE);
+        memory.store_pcb(PCB::new(1)).unwrap();
+        memory.store_pcb(PCB::new(2)).unwrap();
+        assert_eq!(
+            memory.pcb_entry(2),
+            Some((2, PCB::RECORD_SIZE, PCB::RECORD_SIZE))
+        );
+        assert!(matches!(
+            memory.store_pcb(PCB::new(3)),
+            Err(Error::NotEnoughOsMemory)
+        ));
     }
 }
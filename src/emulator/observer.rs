@@ -0,0 +1,115 @@
+use serde::{Deserialize, Serialize};
+
+// Callbacks on the events of a run, for the tools that follow it without parsing the UI. Every
+// method does nothing by default, an observer only implements the events it wants
+pub trait Observer {
+    // Before a tick of the clock is executed
+    fn on_tick(&mut self, _tick: u64) {}
+
+    // The process got the CPU
+    fn on_dispatch(&mut self, _tick: u64, _p_id: usize, _cpu: usize) {}
+
+    // The process left the CPU back to the ready queue, for another process or a new scheduler
+    fn on_preempt(&mut self, _tick: u64, _p_id: usize, _cpu: usize) {}
+
+    // The process left the CPU to wait for an input
+    fn on_block(&mut self, _tick: u64, _p_id: usize, _cpu: usize) {}
+
+    // The process got its input and is ready again
+    fn on_unblock(&mut self, _tick: u64, _p_id: usize) {}
+
+    // The process is done, after INT 20H, a fault or being killed
+    fn on_terminate(&mut self, _tick: u64, _p_id: usize, _cpu: usize) {}
+
+    // The process failed, `fault` is the translation key of the fault. `on_terminate` follows
+    fn on_fault(&mut self, _tick: u64, _p_id: usize, _cpu: usize, _fault: &str) {}
+}
+
+// Event kept by the trace
+#[derive(Debug, Clone, PartialEq, Deserialize, Serialize)]
+pub enum TraceEvent {
+    Dispatch {
+        tick: u64,
+        p_id: usize,
+        cpu: usize,
+    },
+    Preempt {
+        tick: u64,
+        p_id: usize,
+        cpu: usize,
+    },
+    Block {
+        tick: u64,
+        p_id: usize,
+        cpu: usize,
+    },
+    Unblock {
+        tick: u64,
+        p_id: usize,
+    },
+    Terminate {
+        tick: u64,
+        p_id: usize,
+        cpu: usize,
+    },
+    Fault {
+        tick: u64,
+        p_id: usize,
+        cpu: usize,
+        fault: String,
+    },
+}
+
+// Records the events of the processes, the ticks are only counted
+#[derive(Debug, Clone, Default, Deserialize, Serialize)]
+pub struct Trace {
+    pub ticks: u64,
+    pub events: Vec<TraceEvent>,
+}
+
+impl Trace {
+    // Events that match the filter, like `trace.count(|x| matches!(x, TraceEvent::Block { .. }))`
+    pub fn count(&self, filter: impl Fn(&TraceEvent) -> bool) -> usize {
+        self.events.iter().filter(|x| filter(x)).count()
+    }
+
+    pub fn clear(&mut self) {
+        *self = Trace::default();
+    }
+}
+
+impl Observer for Trace {
+    fn on_tick(&mut self, _tick: u64) {
+        self.ticks += 1;
+    }
+
+    fn on_dispatch(&mut self, tick: u64, p_id: usize, cpu: usize) {
+        self.events.push(TraceEvent::Dispatch { tick, p_id, cpu });
+    }
+
+    fn on_preempt(&mut self, tick: u64, p_id: usize, cpu: usize) {
+        self.events.push(TraceEvent::Preempt { tick, p_id, cpu });
+    }
+
+    fn on_block(&mut self, tick: u64, p_id: usize, cpu: usize) {
+        self.events.push(TraceEvent::Block { tick, p_id, cpu });
+    }
+
+    fn on_unblock(&mut self, tick: u64, p_id: usize) {
+        self.events.push(TraceEvent::Unblock { tick, p_id });
+    }
+
+    fn on_terminate(&mut self, tick: u64, p_id: usize, cpu: usize) {
+        self.events.push(TraceEvent::Terminate { tick, p_id, cpu });
+    }
+
+    fn on_fault(&mut self, tick: u64, p_id: usize, cpu: usize, fault: &str) {
+        let fault = fault.to_string();
+        self.events.push(TraceEvent::Fault {
+            tick,
+            p_id,
+            cpu,
+            fault,
+        });
+    }
+}
@@ -1,8 +1,8 @@
 use serde::{Deserialize, Serialize};
 use std::default::Default;
-use std::io::Write;
 
 use crate::emulator::Operation;
+use crate::error::Error;
 
 #[derive(Debug, Clone, Copy, PartialEq, Deserialize, Serialize, Default)]
 pub enum ProcessState {
@@ -14,15 +14,17 @@ pub enum ProcessState {
     Terminated,
 }
 
-impl From<u8> for ProcessState {
-    fn from(i: u8) -> Self {
+impl TryFrom<u8> for ProcessState {
+    type Error = Error;
+
+    fn try_from(i: u8) -> Result<Self, Error> {
         match i {
-            1 => ProcessState::New,
-            2 => ProcessState::Ready,
-            3 => ProcessState::Running,
-            4 => ProcessState::Blocked,
-            5 => ProcessState::Terminated,
-            _ => todo!(),
+            1 => Ok(ProcessState::New),
+            2 => Ok(ProcessState::Ready),
+            3 => Ok(ProcessState::Running),
+            4 => Ok(ProcessState::Blocked),
+            5 => Ok(ProcessState::Terminated),
+            _ => Err(Error::InvalidPcbRecord),
         }
     }
 }
@@ -82,169 +84,83 @@ impl PCB {
     }
 }
 
-impl From<PCB> for Vec<u8> {
-    fn from(pcb: PCB) -> Vec<u8> {
-        let mut bytes: Vec<u8> = vec![];
-
-        // convert to bytes
-        let mut id_bytes = pcb.id.to_ne_bytes().to_vec();
-        // shrink the bytes
-        id_bytes.retain(|&x| x != 0);
-        bytes.push((id_bytes.len() + 1) as u8);
-        let _ = bytes.write(&id_bytes);
-
-        let mut code_segment_bytes = pcb.code_segment.to_ne_bytes().to_vec();
-        code_segment_bytes.retain(|&x| x != 0);
-        bytes.push((code_segment_bytes.len() + 1) as u8);
-        let _ = bytes.write(&code_segment_bytes);
-
-        let mut code_segment_size_bytes = pcb.code_segment_size.to_ne_bytes().to_vec();
-        code_segment_size_bytes.retain(|&x| x != 0);
-        bytes.push((code_segment_size_bytes.len() + 1) as u8);
-        let _ = bytes.write(&code_segment_size_bytes);
-
-        let mut stack_segment_bytes = pcb.stack_segment.to_ne_bytes().to_vec();
-        stack_segment_bytes.retain(|&x| x != 0);
-        bytes.push((stack_segment_bytes.len() + 1) as u8);
-        let _ = bytes.write(&stack_segment_bytes);
+// Layout of a PCB record, every record has the same size so a PCB can be rewritten in place:
+// version | id | code_segment | code_segment_size | stack_segment | stack_segment_size | pc | sp
+// (u32 little endian, memories are far smaller than 4 GB) | process_state | priority | ax | bx |
+// cx | dx | ac | ir | z
+const USIZE_FIELDS: usize = 7;
 
-        let mut stack_segment_size_bytes = pcb.stack_segment_size.to_ne_bytes().to_vec();
-        stack_segment_size_bytes.retain(|&x| x != 0);
-        bytes.push((stack_segment_size_bytes.len() + 1) as u8);
-        let _ = bytes.write(&stack_segment_size_bytes);
-
-        if pcb.pc == 0 {
-            bytes.push(2);
-            let _ = bytes.write(&[0]);
-        } else {
-            let mut pc_bytes = pcb.pc.to_ne_bytes().to_vec();
-            pc_bytes.retain(|&x| x != 0);
-            bytes.push((pc_bytes.len() + 1) as u8);
-            let _ = bytes.write(&pc_bytes);
-        }
-
-        if pcb.sp == 0 {
-            bytes.push(2);
-            let _ = bytes.write(&[0]);
-        } else {
-            let mut sp_bytes = pcb.sp.to_ne_bytes().to_vec();
-            sp_bytes.retain(|&x| x != 0);
-            bytes.push((sp_bytes.len() + 1) as u8);
-            let _ = bytes.write(&sp_bytes);
+impl PCB {
+    // Version of the record layout, records of other versions are rejected
+    pub const RECORD_VERSION: u8 = 1;
+    pub const RECORD_SIZE: usize = 1 + USIZE_FIELDS * 4 + 9;
+
+    pub fn to_record(&self) -> [u8; PCB::RECORD_SIZE] {
+        let mut record = [0; PCB::RECORD_SIZE];
+        record[0] = PCB::RECORD_VERSION;
+
+        let fields = [
+            self.id,
+            self.code_segment,
+            self.code_segment_size,
+            self.stack_segment,
+            self.stack_segment_size,
+            self.pc,
+            self.sp,
+        ];
+        for (i, field) in fields.iter().enumerate() {
+            record[1 + i * 4..5 + i * 4].copy_from_slice(&(*field as u32).to_le_bytes());
         }
 
-        bytes.push(pcb.process_state.into());
-
-        bytes.push(pcb.priority);
-
-        bytes.push(pcb.ax);
-        bytes.push(pcb.bx);
-        bytes.push(pcb.cx);
-        bytes.push(pcb.dx);
-        bytes.push(pcb.ac);
-        bytes.push(Operation::maybe_into(pcb.ir));
-        bytes.push(pcb.z.into());
-
-        bytes
+        let rest = 1 + USIZE_FIELDS * 4;
+        record[rest] = self.process_state.into();
+        record[rest + 1] = self.priority;
+        record[rest + 2] = self.ax;
+        record[rest + 3] = self.bx;
+        record[rest + 4] = self.cx;
+        record[rest + 5] = self.dx;
+        record[rest + 6] = self.ac;
+        record[rest + 7] = Operation::encode_ir(self.ir);
+        record[rest + 8] = self.z.into();
+        record
     }
-}
-
-impl From<&[u8]> for PCB {
-    fn from(bytes: &[u8]) -> PCB {
-        // Index accumulator
-        let mut len = bytes[0] as usize;
-
-        // Expand and convert back to [u8; 8]
-        let mut id_bytes = bytes[1..len].to_vec();
-        id_bytes.resize(8, 0);
-        let id_bytes: [u8; 8] = id_bytes.try_into().unwrap();
-        // Convert to usize
-        let id = usize::from_ne_bytes(id_bytes);
-
-        // Expand and convert back to [u8; 8]
-        // bytes[(len + 1)..(len+(bytes[len] as usize))] the indexies of the range of data we want
-        // len + 1 = the lenght of the previous data + 1 as the new index
-        // bytes[len] = holds the lenght of the next data
-        // len + bytes[len] = the range of where to index
-        let mut code_segment_bytes = bytes[(len + 1)..(len + (bytes[len] as usize))].to_vec();
-        code_segment_bytes.resize(8, 0);
-        let code_segment_bytes: [u8; 8] = code_segment_bytes.try_into().unwrap();
-        // Convert to usize
-        let code_segment = usize::from_ne_bytes(code_segment_bytes);
-
-        // Update index accumulator
-        len += bytes[len] as usize;
-
-        // Expand and convert back to [u8; 8]
-        let mut code_segment_size_bytes = bytes[(len + 1)..(len + (bytes[len] as usize))].to_vec();
-        code_segment_size_bytes.resize(8, 0);
-        let code_segment_size_bytes: [u8; 8] = code_segment_size_bytes.try_into().unwrap();
-        // Convert to usize
-        let code_segment_size = usize::from_ne_bytes(code_segment_size_bytes);
-
-        // Update index accumulator
-        len += bytes[len] as usize;
-
-        // Expand and convert back to [u8; 8]
-        let mut stack_segment_bytes = bytes[(len + 1)..(len + (bytes[len] as usize))].to_vec();
-        stack_segment_bytes.resize(8, 0);
-        let stack_segment_bytes: [u8; 8] = stack_segment_bytes.try_into().unwrap();
-        // Convert to usize
-        let stack_segment = usize::from_ne_bytes(stack_segment_bytes);
 
-        // Update index accumulator
-        len += bytes[len] as usize;
-
-        // Expand and convert back to [u8; 8]
-        let mut stack_segment_size_bytes = bytes[(len + 1)..(len + (bytes[len] as usize))].to_vec();
-        stack_segment_size_bytes.resize(8, 0);
-        let stack_segment_size_bytes: [u8; 8] = stack_segment_size_bytes.try_into().unwrap();
-        // Convert to usize
-        let stack_segment_size = usize::from_ne_bytes(stack_segment_size_bytes);
-
-        // Update index accumulator
-        len += bytes[len] as usize;
-
-        // Expand and convert back to [u8; 8]
-        let mut pc_bytes = bytes[(len + 1)..(len + (bytes[len] as usize))].to_vec();
-        pc_bytes.resize(8, 0);
-        let pc_bytes: [u8; 8] = pc_bytes.try_into().unwrap();
-        // Convert to usize
-        let pc = usize::from_ne_bytes(pc_bytes);
-
-        // Update index accumulator
-        len += bytes[len] as usize;
-
-        // Expand and convert back to [u8; 8]
-        let mut sp_bytes = bytes[(len + 1)..(len + (bytes[len] as usize))].to_vec();
-        sp_bytes.resize(8, 0);
-        let sp_bytes: [u8; 8] = sp_bytes.try_into().unwrap();
-        // Convert to usize
-        let sp = usize::from_ne_bytes(sp_bytes);
-
-        // Update index accumulator
-        len += bytes[len] as usize;
+    pub fn from_record(record: &[u8; PCB::RECORD_SIZE]) -> Result<PCB, Error> {
+        if record[0] != PCB::RECORD_VERSION {
+            return Err(Error::UnknownPcbVersion(record[0]));
+        }
 
-        let process_state = ProcessState::from(bytes[len]);
+        let mut fields = [0; USIZE_FIELDS];
+        for (i, field) in fields.iter_mut().enumerate() {
+            let bytes: [u8; 4] = record[1 + i * 4..5 + i * 4].try_into().unwrap();
+            *field = u32::from_le_bytes(bytes) as usize;
+        }
 
-        PCB {
-            id,
-            code_segment,
-            code_segment_size,
-            stack_segment,
-            stack_segment_size,
-            pc,
-            sp,
-            process_state,
-            priority: bytes[len + 1],
-            ax: bytes[len + 2],
-            bx: bytes[len + 3],
-            cx: bytes[len + 4],
-            dx: bytes[len + 5],
-            ac: bytes[len + 6],
-            ir: Operation::maybe_from(bytes[len + 7]),
-            z: bytes[len + 8] != 0,
+        let rest = 1 + USIZE_FIELDS * 4;
+        if record[rest + 8] > 1 {
+            return Err(Error::InvalidPcbRecord);
         }
+        let process_state = ProcessState::try_from(record[rest])?;
+        let ir = Operation::decode_ir(record[rest + 7])?;
+
+        Ok(PCB {
+            id: fields[0],
+            code_segment: fields[1],
+            code_segment_size: fields[2],
+            stack_segment: fields[3],
+            stack_segment_size: fields[4],
+            pc: fields[5],
+            sp: fields[6],
+            process_state,
+            priority: record[rest + 1],
+            ax: record[rest + 2],
+            bx: record[rest + 3],
+            cx: record[rest + 4],
+            dx: record[rest + 5],
+            ac: record[rest + 6],
+            ir,
+            z: record[rest + 8] == 1,
+        })
     }
 }
 
@@ -253,28 +169,87 @@ mod tests {
     use super::*;
 
     #[test]
-    fn from_into_operation() {
+    fn record_round_trip() {
         let pcb = PCB {
             id: 1,
             code_segment: 1000,
             code_segment_size: 94,
             stack_segment: 1094,
             stack_segment_size: 5,
-            process_state: ProcessState::New,
-            priority: 0,
-            ax: 0,
-            bx: 0,
-            cx: 0,
-            dx: 0,
-            ac: 0,
-            pc: 0,
-            sp: 0,
-            ir: None,
-            z: false,
+            ..Default::default()
         };
-        let pcb_u8: Vec<u8> = pcb.into();
+        let record = pcb.to_record();
+        assert_eq!(record.len(), PCB::RECORD_SIZE);
+        assert_eq!(PCB::from_record(&record).unwrap(), pcb);
+
+        // Extreme values of every field, zero bytes in the middle of a value included
+        let values = [0, 1, 255, 256, 0x0100_0001, 0x00ff_ff00, u32::MAX as usize];
+        let states = [
+            ProcessState::New,
+            ProcessState::Ready,
+            ProcessState::Running,
+            ProcessState::Blocked,
+            ProcessState::Terminated,
+        ];
+        for (i, value) in values.iter().enumerate() {
+            for (j, process_state) in states.iter().enumerate() {
+                let byte = [0, 1, 127, 128, 255][(i + j) % 5];
+                let pcb = PCB {
+                    id: *value,
+                    code_segment: values[(i + 1) % values.len()],
+                    code_segment_size: values[(i + 2) % values.len()],
+                    stack_segment: values[(i + 3) % values.len()],
+                    stack_segment_size: values[(i + 4) % values.len()],
+                    pc: values[(i + 5) % values.len()],
+                    sp: values[(i + 6) % values.len()],
+                    process_state: *process_state,
+                    priority: byte,
+                    ax: byte,
+                    bx: byte.wrapping_add(1),
+                    cx: byte.wrapping_sub(1),
+                    dx: !byte,
+                    ac: byte,
+                    ir: Operation::decode_ir(byte % 20).unwrap(),
+                    z: byte % 2 == 0,
+                };
+                assert_eq!(PCB::from_record(&pcb.to_record()).unwrap(), pcb);
+            }
+        }
+    }
 
-        let deserialize: PCB = PCB::from(&pcb_u8[..]);
-        assert_eq!(pcb, deserialize);
+    #[test]
+    fn ir_round_trip() {
+        // Every operation, and no instruction at all, survive the PCB record
+        let irs = Operation::ALL.into_iter().map(Some).chain([None]);
+        for ir in irs {
+            let pcb = PCB { ir, ..PCB::new(1) };
+            let record = pcb.to_record();
+            assert_eq!(PCB::from_record(&record).unwrap().ir, ir);
+        }
+    }
+
+    #[test]
+    fn invalid_records() {
+        let mut record = PCB::new(1).to_record();
+        record[0] = PCB::RECORD_VERSION + 1;
+        assert!(matches!(
+            PCB::from_record(&record),
+            Err(Error::UnknownPcbVersion(2))
+        ));
+
+        let mut record = PCB::new(1).to_record();
+        record[PCB::RECORD_SIZE - 9] = 9;
+        assert!(matches!(
+            PCB::from_record(&record),
+            Err(Error::InvalidPcbRecord)
+        ));
+
+        // Unknown IR codes aren't decoded as some other operation
+        let mut record = PCB::new(1).to_record();
+        record[PCB::RECORD_SIZE - 2] = 21;
+        assert!(matches!(
+            PCB::from_record(&record),
+            Err(Error::UnknownOperationCode(21))
+        ));
     }
 }
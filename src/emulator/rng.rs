@@ -0,0 +1,111 @@
+use rand::rngs::StdRng;
+use rand::{Rng, SeedableRng};
+use std::ops::RangeInclusive;
+
+// A value taken from the random generator
+#[derive(Debug, Clone, PartialEq)]
+pub struct Draw {
+    // Part of the emulator that asked for it
+    pub tag: &'static str,
+    pub range: RangeInclusive<u8>,
+    pub value: u8,
+    pub tick: u64,
+}
+
+// Seeded generator that keeps every value it gives while `audit` is on, two runs with the same
+// seed take the same draws on the same ticks
+#[derive(Debug, Clone)]
+pub struct AuditedRng {
+    rng: StdRng,
+    pub audit: bool,
+    // Tick of the next draws
+    tick: u64,
+    draws: Vec<Draw>,
+    // Draws since the run started, also counted without the audit
+    count: usize,
+}
+
+impl AuditedRng {
+    // A random seed is used when it's not set
+    pub fn seeded(seed: Option<u64>) -> Self {
+        Self::new(match seed {
+            Some(seed) => StdRng::seed_from_u64(seed),
+            None => StdRng::from_entropy(),
+        })
+    }
+
+    pub fn new(rng: StdRng) -> Self {
+        Self {
+            rng,
+            audit: false,
+            tick: 0,
+            draws: vec![],
+            count: 0,
+        }
+    }
+
+    pub fn set_tick(&mut self, tick: u64) {
+        self.tick = tick;
+    }
+
+    pub fn gen_range(&mut self, tag: &'static str, range: RangeInclusive<u8>) -> u8 {
+        let value = self.rng.gen_range(range.clone());
+        self.count += 1;
+        if self.audit {
+            self.draws.push(Draw {
+                tag,
+                range,
+                value,
+                tick: self.tick,
+            });
+        }
+        value
+    }
+
+    pub fn draws(&self) -> &[Draw] {
+        &self.draws
+    }
+
+    pub fn count(&self) -> usize {
+        self.count
+    }
+
+    // A new run starts counting from 0, the generator keeps its state
+    pub fn clear(&mut self) {
+        self.draws.clear();
+        self.count = 0;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn audit(seed: u64) -> Vec<Draw> {
+        let mut rng = AuditedRng::seeded(Some(seed));
+        rng.audit = true;
+        for tick in 0..5 {
+            rng.set_tick(tick);
+            rng.gen_range("arrival", 1..=5);
+        }
+        rng.draws().to_vec()
+    }
+
+    #[test]
+    fn audited_draws() {
+        // The same seed gives the same audit, another one a different one
+        assert_eq!(audit(7), audit(7));
+        assert_ne!(audit(7), audit(8));
+        assert_eq!(audit(7)[3].tick, 3);
+        assert_eq!(audit(7)[3].tag, "arrival");
+
+        // Without the audit the draws are only counted
+        let mut rng = AuditedRng::seeded(Some(7));
+        let value = rng.gen_range("arrival", 1..=5);
+        assert_eq!(value, audit(7)[0].value);
+        assert!(rng.draws().is_empty());
+        assert_eq!(rng.count(), 1);
+        rng.clear();
+        assert_eq!(rng.count(), 0);
+    }
+}
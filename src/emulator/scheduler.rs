@@ -1,5 +1,20 @@
+use crate::error::Error;
+use crate::i18n::{tr, trf, Lang};
 use serde::{Deserialize, Serialize};
 use std::fmt;
+use std::str::FromStr;
+
+// How SJF and SRT know the burst of a process
+#[derive(Debug, Clone, Copy, PartialEq, Default, Deserialize, Serialize)]
+pub enum BurstEstimation {
+    // Ticks of the instructions, or #BURST
+    #[default]
+    Exact,
+    // Only #BURST, processes without it go last
+    Declared,
+    // Exponential average of the CPU bursts of the process
+    Exponential,
+}
 
 #[derive(Debug, Clone, Copy, PartialEq, Deserialize, Serialize)]
 pub enum Scheduler {
@@ -8,6 +23,20 @@ pub enum Scheduler {
     SJF,
     RR,
     HRRN,
+    // The lowest #PRIORITY goes first. Non preemptive unless `priority_preemptive` is set, and
+    // with `priority_rr` the processes of the same priority take turns with the quantum
+    Priority,
+}
+
+impl Scheduler {
+    pub const ALL: [Scheduler; 6] = [
+        Scheduler::FCFS,
+        Scheduler::SRT,
+        Scheduler::SJF,
+        Scheduler::RR,
+        Scheduler::HRRN,
+        Scheduler::Priority,
+    ];
 }
 
 impl fmt::Display for Scheduler {
@@ -18,6 +47,92 @@ impl fmt::Display for Scheduler {
             Scheduler::SJF => write!(f, "SJF"),
             Scheduler::RR => write!(f, "RR"),
             Scheduler::HRRN => write!(f, "HRRN"),
+            Scheduler::Priority => write!(f, "Priority"),
+        }
+    }
+}
+
+impl FromStr for Scheduler {
+    type Err = Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_uppercase().as_str() {
+            "FCFS" => Ok(Scheduler::FCFS),
+            "SRT" => Ok(Scheduler::SRT),
+            "SJF" => Ok(Scheduler::SJF),
+            "RR" => Ok(Scheduler::RR),
+            "HRRN" => Ok(Scheduler::HRRN),
+            "PRIORITY" => Ok(Scheduler::Priority),
+            &_ => Err(Self::Err::ParseSchedulerError(s.to_string())),
+        }
+    }
+}
+
+// Key the scheduler sorted the waiting processes by
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Rule {
+    Arrival,
+    Burst,
+    Remaining,
+    Ratio,
+    Priority,
+    // Order of the PCB table, Round Robin
+    Queue,
+    // Got its input, it goes before everybody else
+    Boosted,
+}
+
+impl Rule {
+    fn key(self) -> &'static str {
+        match self {
+            Rule::Arrival => "rule_arrival",
+            Rule::Burst => "rule_burst",
+            Rule::Remaining => "rule_remaining",
+            Rule::Ratio => "rule_ratio",
+            Rule::Priority => "rule_priority",
+            Rule::Queue => "rule_queue",
+            Rule::Boosted => "rule_boosted",
+        }
+    }
+}
+
+// Why the scheduler picked a process, the values are the ones of the rule
+#[derive(Debug, Clone, PartialEq)]
+pub struct Decision {
+    pub scheduler: Scheduler,
+    pub rule: Rule,
+    // (pid, value)
+    pub chosen: (usize, String),
+    // The rest of the processes waiting for a CPU, in the order the rule sorts them
+    pub candidates: Vec<(usize, String)>,
+    pub not_arrived: Vec<usize>,
+}
+
+impl Decision {
+    // "SJF: picked P3 (burst 4) over P2 (burst 9), P5 not arrived"
+    pub fn text(&self, lang: Lang) -> String {
+        let rule = tr(lang, self.rule.key());
+        let value = |(id, value): &(usize, String)| match self.rule {
+            Rule::Boosted => format!("P{} ({})", id, rule),
+            _ => format!("P{} ({} {})", id, rule, value),
+        };
+        let mut text = trf(
+            lang,
+            "decision_picked",
+            &[&self.scheduler, &value(&self.chosen)],
+        );
+        if !self.candidates.is_empty() {
+            let candidates = self.candidates.iter().map(value).collect::<Vec<_>>();
+            text.push_str(&trf(lang, "decision_over", &[&candidates.join(", ")]));
+        }
+        if !self.not_arrived.is_empty() {
+            let ids = self
+                .not_arrived
+                .iter()
+                .map(|x| format!("P{}", x))
+                .collect::<Vec<_>>();
+            text.push_str(&trf(lang, "decision_not_arrived", &[&ids.join(", ")]));
         }
+        text
     }
 }
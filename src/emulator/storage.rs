@@ -1,10 +1,15 @@
+use crate::emulator::memory::{fill_gaps, SegmentInfo, SegmentKind};
 use crate::error::Error;
+use serde::{Deserialize, Serialize};
+use std::hash::{DefaultHasher, Hash, Hasher};
 
-#[derive(Debug, Default)]
+#[derive(Debug, Default, Clone, PartialEq, Deserialize, Serialize)]
 pub struct Storage {
     pub data: Vec<u8>,
     pub used: Vec<(String, usize, usize)>,
     pub freed: Vec<(String, usize, usize)>,
+    // Addresses of the files that the last store or free moved between used and freed
+    pub changed: Vec<usize>,
 }
 
 impl Storage {
@@ -13,15 +18,16 @@ impl Storage {
             data: vec![0; size],
             used: vec![],
             freed: vec![],
+            changed: vec![],
         }
     }
 
-    pub fn store_files(
-        &mut self,
-        file_name: &str,
-        size: usize,
-        data: Vec<u8>,
-    ) -> Result<(), Error> {
+    pub fn store_files(&mut self, file_name: &str, data: Vec<u8>) -> Result<(), Error> {
+        self.changed.clear();
+        let size = data.len();
+        if size == 0 {
+            return Err(Error::InvalidAllocation);
+        }
         // No memory space has been freed
         if !self.freed.is_empty() && !self.used.is_empty() {
             // Este problema lo vimos en clase XD no vimos solucion aun XD
@@ -32,28 +38,34 @@ impl Storage {
                     self.used
                         .push((file_name.to_string(), *address, *data_size));
                     let _ = self.freed.remove(i);
-                    break;
+                    self.changed.push(*address);
+                    return Ok(());
                 }
             }
         }
         // No memory has been used
-        else if self.used.is_empty() {
-            if self.data.len() > size {
+        if self.used.is_empty() {
+            if self.data.len() >= size {
                 self.data[0..size].copy_from_slice(&data[..]);
                 self.used.push((file_name.to_string(), 0, size));
+                self.changed.push(0);
             } else {
                 return Err(Error::NotEnoughStorage(file_name.to_string()));
             }
         } else {
-            // last used memory information
-            let (_, address, data_size) = &self.used.last().unwrap();
-
-            let next_address = address + data_size;
+            // End of the last used memory, freed spaces can be reused before it
+            let next_address = self
+                .used
+                .iter()
+                .map(|(_, address, data_size)| address + data_size)
+                .max()
+                .unwrap_or_default();
             let available_space = self.data.len() - next_address;
 
-            if available_space > size {
+            if available_space >= size {
                 self.data[next_address..next_address + size].copy_from_slice(&data[..]);
                 self.used.push((file_name.to_string(), next_address, size));
+                self.changed.push(next_address);
             } else {
                 return Err(Error::NotEnoughStorage(file_name.to_string()));
             }
@@ -61,4 +73,119 @@ impl Storage {
 
         Ok(())
     }
+
+    // Remove the file stored on the address
+    pub fn free_file(&mut self, address: usize) {
+        self.changed.clear();
+        if let Some(position) = self.used.iter().position(|x| x.1 == address) {
+            let file = self.used.remove(position);
+            self.changed.push(address);
+            self.data[file.1..file.1 + file.2].copy_from_slice(&vec![0; file.2]);
+            // Nothing is stored, start over
+            if self.used.is_empty() {
+                self.freed.clear();
+            } else {
+                self.freed.push(file);
+            }
+        }
+    }
+
+    // Bytes of the stored files
+    pub fn used_size(&self) -> usize {
+        self.used.iter().map(|x| x.2).sum()
+    }
+
+    // Map of the storage, one segment for each file and the free space between them
+    pub fn layout(&self) -> Vec<SegmentInfo> {
+        let files = self
+            .used
+            .iter()
+            .map(|(_, address, size)| SegmentInfo {
+                owner: None,
+                kind: SegmentKind::File,
+                address: *address,
+                size: *size,
+            })
+            .collect();
+        fill_gaps(files, 0..self.data.len(), SegmentKind::Free)
+    }
+
+    // Content of the file stored on the address
+    pub fn file(&self, address: usize) -> Option<&[u8]> {
+        self.used
+            .iter()
+            .find(|x| x.1 == address)
+            .map(|(_, address, size)| &self.data[*address..*address + *size])
+    }
+}
+
+// Name of a stored file without the quotes the file picker leaves, the one #AFTER uses
+pub fn bare_name(file_name: &str) -> &str {
+    file_name.trim_matches('"')
+}
+
+// Hash of the content of a file, tells if the stored copy changed after a process was loaded
+pub fn content_hash(data: &[u8]) -> u64 {
+    let mut hasher = DefaultHasher::new();
+    data.hash(&mut hasher);
+    hasher.finish()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn invalid_files() {
+        let mut storage = Storage::new(16);
+        assert!(matches!(
+            storage.store_files("empty", vec![]),
+            Err(Error::InvalidAllocation)
+        ));
+        assert!(storage.used.is_empty());
+
+        // Files that fill the remaining space exactly fit
+        assert!(storage.store_files("a", vec![1; 17]).is_err());
+        storage.store_files("a", vec![1; 12]).unwrap();
+        storage.store_files("b", vec![2; 4]).unwrap();
+        assert_eq!(storage.file(12), Some(&[2; 4][..]));
+        assert!(storage.store_files("c", vec![3; 1]).is_err());
+        let mut storage = Storage::new(16);
+        storage.store_files("a", vec![1; 16]).unwrap();
+    }
+
+    #[test]
+    fn replace_file() {
+        let mut storage = Storage::new(64);
+        storage.store_files("a", vec![1; 4]).unwrap();
+        storage.store_files("b", vec![2; 4]).unwrap();
+
+        // A bigger file doesn't fit on the freed space
+        storage.free_file(0);
+        storage.store_files("a", vec![3; 6]).unwrap();
+
+        assert_eq!(storage.file(8), Some(&[3; 6][..]));
+        assert_eq!(storage.file(4), Some(&[2; 4][..]));
+        assert_eq!(&storage.data[0..4], &[0; 4]);
+
+        // The freed space is reused by a file of the same size
+        storage.store_files("c", vec![4; 4]).unwrap();
+        assert_eq!(storage.file(0), Some(&[4; 4][..]));
+        assert!(storage.freed.is_empty());
+
+        let layout = storage
+            .layout()
+            .iter()
+            .map(|x| (x.kind, x.address, x.size))
+            .collect::<Vec<_>>();
+        assert_eq!(
+            layout,
+            vec![
+                (SegmentKind::File, 0, 4),
+                (SegmentKind::File, 4, 4),
+                (SegmentKind::File, 8, 6),
+                (SegmentKind::Free, 14, 50),
+            ]
+        );
+    }
 }
@@ -20,6 +20,18 @@ pub enum Error {
     NotEnoughUserMemory,
     #[error("Not enough space in OS memory.")]
     NotEnoughOsMemory,
+    #[error("An allocation needs at least 1 byte.")]
+    InvalidAllocation,
+    #[error("Not enough space in user memory for the stack.")]
+    NotEnoughStackMemory,
+    #[error("The program doesn't fit on the {0} bytes of user memory.")]
+    ProgramTooBig(usize),
+    #[error("The code on memory doesn't match instruction {0}.")]
+    CodeMismatch(usize),
+    #[error("{0} has {1} bytes, it's too big to be a program.")]
+    FileTooLarge(String, usize),
+    #[error("{0} is not a text file.")]
+    BinaryFile(String),
     #[error("File should contain valid utf8")]
     Utf8Error,
     #[error("Invalid Operation {1} on line {0},")]
@@ -30,8 +42,71 @@ pub enum Error {
     ParseRegisterError(String),
     #[error("Invalid Interupt code '{0}'.")]
     ParseInteruptError(String),
+    #[error("Invalid scheduler '{0}'.")]
+    ParseSchedulerError(String),
+    #[error("Invalid argument: {0}")]
+    InvalidArgument(String),
+    #[error("Invalid config file: {0}")]
+    InvalidConfigFile(String),
+    #[error("Invalid directive '{1}' on line {0}.")]
+    InvalidDirective(usize, String),
+    #[error("Unknown PCB record version {0}.")]
+    UnknownPcbVersion(u8),
+    #[error("Unknown recovery file version {0}.")]
+    UnknownRecoveryVersion(u64),
+    #[error("Invalid PCB record.")]
+    InvalidPcbRecord,
+    #[error("Unknown operation code {0}.")]
+    UnknownOperationCode(u8),
+    #[error("Unknown register code {0}.")]
+    UnknownRegisterCode(u8),
+    #[error("Unknown interupt code {0}.")]
+    UnknownInteruptCode(u8),
+    #[error("Unknown operands code {0}.")]
+    UnknownOperandsCode(u8),
+    #[error("Invalid instruction bytes {0:?}.")]
+    InvalidInstruction(Vec<u8>),
     #[error("Invalid number of operands for {1:?}: {2:?} on line: {0}.")]
     InvalidNumberOperands(usize, Operation, Vec<String>),
     #[error("Invalid operand '{2:?}' for {1:?} on line: {0}.")]
     InvalidOperand(usize, Operation, String),
+    #[error(
+        "Invalid value '{2}' for {1:?} on line: {0}, it must be between -128 and 255. Negative values wrap around, -5 is stored as 251."
+    )]
+    InvalidImmediate(usize, Operation, String),
+}
+
+// Problems found on the config file
+#[derive(Error, Debug, Clone, PartialEq)]
+pub enum ConfigError {
+    #[error("os_segment ({0}) must be smaller than memory ({1}).")]
+    OsSegmentTooLarge(usize, usize),
+    #[error("The user segment (memory - os_segment) has {0} bytes, it needs at least {1}.")]
+    UserSegmentTooSmall(usize, usize),
+    #[error("cpu_quantity must be at least 1.")]
+    NoCpus,
+    #[error("storage ({0}) must be at least {1} bytes.")]
+    StorageTooSmall(usize, usize),
+    #[error("quantum ({0}) must be between 1 and {1}.")]
+    InvalidQuantum(u8, u8),
+    #[error("tick_interval_ms must be at least 1.")]
+    InvalidTickInterval,
+    #[error("turbo_ticks must be at least 1.")]
+    InvalidTurboTicks,
+    #[error("stack_size must be at least 1.")]
+    InvalidStackSize,
+    #[error("The cost of {0} must be at least 1 tick.")]
+    InvalidCost(String),
+    #[error(
+        "The device region ({0}, {1} bytes) needs 2 bytes inside the first 256 of the OS segment."
+    )]
+    InvalidDeviceRegion(usize, usize),
+    #[error("burst_alpha ({0}) must be between 0 and 1.")]
+    InvalidBurstAlpha(f32),
+    #[error("The cache needs at least 1 line of 1 byte, it has {0} lines of {1} bytes.")]
+    InvalidCache(usize, usize),
+    #[error("The usage thresholds must be percents from 0 to 100, {0} isn't one.")]
+    InvalidUsageThreshold(u8),
+    #[error("The warning usage threshold ({0}) must not be above the critical one ({1}).")]
+    UnorderedUsageThresholds(u8, u8),
 }
@@ -0,0 +1,715 @@
+use serde::{Deserialize, Serialize};
+use std::fmt;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Deserialize, Serialize)]
+pub enum Lang {
+    #[default]
+    En,
+    Es,
+}
+
+impl Lang {
+    pub const ALL: [Lang; 2] = [Lang::En, Lang::Es];
+}
+
+impl fmt::Display for Lang {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Lang::En => write!(f, "English"),
+            Lang::Es => write!(f, "Español"),
+        }
+    }
+}
+
+// UI strings, each {} is replaced by an argument of `trf`
+const EN: &[(&str, &str)] = &[
+    // Menu bar
+    ("file", "File"),
+    ("open_folder", "Open folder"),
+    ("new_program", "New program"),
+    ("play", "Play"),
+    ("pause", "Pause"),
+    ("play_pause", "Play/Pause"),
+    ("next", "Next"),
+    ("back", "Back"),
+    ("step_cpu", "Step"),
+    ("run_to_event", "Run to event"),
+    ("run_n", "Run N"),
+    ("reset", "Reset"),
+    ("stats", "Stats"),
+    ("log", "Log"),
+    ("copy", "Copy"),
+    ("tick", "Tick: {}"),
+    ("turbo", "Turbo"),
+    ("turbo_executed", "{} instructions this frame"),
+    (
+        "status_states",
+        "New {} · Ready {} · Running {} · Blocked {} · Done {}",
+    ),
+    ("no_scheduler", "No scheduler"),
+    ("summary_title", "Run completed"),
+    ("summary_ticks", "Total ticks: {}"),
+    ("summary_draws", "Random draws: {}"),
+    ("summary_events", "Dispatches: {}, preemptions: {}, blocks: {}, faults: {}"),
+    ("rng_draw", "Random draw for {}: {} gave {} on tick {}"),
+    ("summary_wall_time", "Wall time: {} s"),
+    (
+        "summary_scheduler",
+        "{}: {} processes, average turnaround {} s, average execution {} s, average response ratio {}",
+    ),
+    ("close", "Close"),
+    ("run_again", "Run again"),
+    ("batch_title", "Results of the batch:"),
+    ("batch_ok", "{}: terminated on CPU {}, turnaround {} s"),
+    ("batch_fault", "{}: FAILED, {}"),
+    ("batch_not_read", "{} could not be read: {}"),
+    ("batch_not_stored", "{} was not stored: {}"),
+    ("batch_stopped", "The batch stopped after {} ticks with processes left"),
+    ("batch_timed_out", "{} (P{}): timed out"),
+    ("ascii", "ASCII"),
+    ("decimal", "Dec"),
+    ("completed_in", "Completed in {} ticks ({} s)"),
+    ("quantum", "Quantum"),
+    ("quantum_invalid", "1-99"),
+    // Panels
+    ("files", "Files"),
+    ("edit", "Edit"),
+    ("memory", "Memory"),
+    ("storage", "Storage"),
+    ("cpu", "CPU"),
+    ("display", "Display"),
+    ("cpu_output", "Output P{}: {}"),
+    ("no_output", "Output: -"),
+    ("process_output", "Process {} printed {} on CPU {}"),
+    ("queues", "Queues"),
+    ("pcb_list", "PCB List"),
+    ("history", "History"),
+    ("allocator", "Allocator"),
+    ("dump_log", "Dump to log"),
+    ("memory_used", "Memory used"),
+    ("memory_freed", "Memory freed"),
+    ("storage_used", "Storage used"),
+    ("storage_freed", "Storage freed"),
+    ("disassembly", "Disassembly P{}"),
+    ("flags_cmp", "Sets Z when both registers are equal"),
+    ("flags_arithmetic", "Sets Z when the result in AC is 0"),
+    ("flags_register", "Sets Z when the register is 0"),
+    ("flags_jump", "Reads Z to decide whether to jump"),
+    ("flags_none", "Doesn't touch Z"),
+    ("ready", "Ready"),
+    ("running", "Running"),
+    ("blocked", "Blocked"),
+    ("awaiting_input", "awaiting input"),
+    ("quantum_ticks", "({} ticks)"),
+    ("quantum_left", "q: {}/{}"),
+    // PCB tooltip
+    ("id", "ID: "),
+    ("priority", "Priority: "),
+    ("state", "State: "),
+    ("code_segment", "Code Segment: [{}; {}]"),
+    ("stack_segment", "Stack Segment: [{}; {}]"),
+    ("stack_usage", "Stack usage: {} / {}"),
+    ("stack_grown", "The stack of process {} grew to {} bytes"),
+    ("process_fault", "Process {} terminated by a fault: {} ({} this run)"),
+    ("fault_title", "Process fault"),
+    ("fault_stack_overflow", "stack overflow, there is no memory to grow the stack"),
+    ("fault_invalid_instruction", "invalid instruction"),
+    ("fault_memory_violation", "memory access outside of the device region"),
+    ("fault_code_write", "write on a code segment"),
+    ("fault_assertion", "failed assertion"),
+    ("exit_normal", "INT 20H"),
+    ("exit_killed", "a kill"),
+    ("post_mortem", "Post-mortem of P{}, terminated on tick {} by {}"),
+    ("post_mortem_stack", "SP {}, stack {}"),
+    ("post_mortem_history", "Last instructions:"),
+    ("assertion_failed", "P{} failed the ASSERT of line {}: {} should be {} but it's {}"),
+    ("fault_at_line", "{} at line {} of {}"),
+    ("source_line", "line {}: {}"),
+    ("source_changed", "source changed since run"),
+    ("code_written", "P{} wrote on {}, on the code of P{}"),
+    ("code_modified", "Code modified by a process"),
+    (
+        "process_runaway",
+        "Process {} executed {} instructions, {} times the length of its program, it may never terminate",
+    ),
+    ("runaway_title", "Process may never terminate"),
+    ("runaway_kill", "Kill the process?"),
+    ("process_killed", "Process {} killed on CPU {}"),
+    ("process_killed_queued", "Process {} killed while {}"),
+    ("stats_faults", "Faults, {}: {}"),
+    ("arrival", "Arrival: {}"),
+    ("burst", "Burst: {}"),
+    ("remaining_burst", "Remaining Burst: {}"),
+    ("estimated_burst", "Estimated burst: {}, ran {} ticks"),
+    ("execution_time", "Execution Time: {}"),
+    // Editor
+    ("program", "Program"),
+    ("name", "Name"),
+    ("assemble", "Assemble & store"),
+    ("cancel", "Cancel"),
+    ("program_needs_name", "The program needs a name."),
+    // Stats
+    ("stats_title", "System Statistics"),
+    ("selected_method", "Selected method: "),
+    ("no_method", "No method selected."),
+    ("stats_process", "\nProcess ID: {} ({}) on CPU {}"),
+    ("source_file", "File: {}"),
+    (
+        "stats_estimation",
+        "    Estimated burst: {} ticks, actual: {} ticks, error: {}",
+    ),
+    ("stats_no_estimate", "    No estimated burst, actual: {} ticks"),
+    ("stats_first_response", "    First response: {} ticks ready before its first CPU"),
+    ("average_first_response", "Average first response: {} ticks"),
+    ("stats_group", "{} {}: {} processes · turnaround {} · waiting {}"),
+    ("stats_arrival", "\n    Arrival time: {} seconds"),
+    ("stats_turnaround", "\n    Turnaround {}: {} seconds"),
+    (
+        "stats_execution",
+        "\n    Execution time: {} seconds\n\n     Tr / Ts: {}",
+    ),
+    ("total_time", "Total time: {} seconds"),
+    (
+        "cpu_usage",
+        "CPU {}: {}% usage, {} busy ticks, {} idle ticks, {} context switches",
+    ),
+    ("total_usage", "Total CPU usage: {}%, {} context switches"),
+    ("back", "Back"),
+    // Settings
+    ("settings", "Settings"),
+    ("os_segment", "OS segment"),
+    ("cpu_quantity", "CPUs"),
+    ("scheduler", "Scheduler"),
+    ("seed", "Seed (empty for random)"),
+    ("tick_interval", "Tick interval (ms)"),
+    ("apply", "Apply"),
+    ("save_config", "Save to config.json"),
+    ("settings_running", "Read only while the emulator is running."),
+    // Dialogs
+    ("error", "Error"),
+    ("memory_warning", "Memory Warning"),
+    ("reset_title", "Reset the emulator?"),
+    (
+        "reset_description",
+        "A soft reset keeps the stored files so they can run again, a full reset also clears the storage.",
+    ),
+    ("soft_reset", "Soft reset"),
+    ("switch_title", "Switch the scheduler now?"),
+    (
+        "switch_description",
+        "The running processes go back to the ready queue and {} picks the next ones.",
+    ),
+    ("switch_now", "Switch now"),
+    ("full_reset", "Full reset"),
+    ("close_title", "Close the emulator?"),
+    (
+        "close_description",
+        "The run and its statistics will be lost. Yes copies the log to the clipboard before closing, No closes without saving.",
+    ),
+    (
+        "file_too_big",
+        "{} doesn't fit on the {} bytes of user memory, it won't be loaded.",
+    ),
+    (
+        "code_mismatch",
+        "{} was stored with errors, instruction {} doesn't decode back, it won't be loaded.",
+    ),
+    ("expects_number", "{} expects a number:"),
+    ("input_range", "Type a number from 0 to 255"),
+    ("expects_number_range", "{} expects a number from {} to {}:"),
+    ("input_out_of_range", "Type a number from {} to {}"),
+    ("input_rejected", "P{} only accepts numbers from {} to {}, {} was rejected."),
+    ("boosted", "Boosted: goes first on its next dispatch"),
+    ("input_received", "P{} received {} on DX."),
+    (
+        "input_dropped",
+        "P{} ended while waiting for input, its input row was removed.",
+    ),
+    ("waiting_memory", "waiting for memory"),
+    ("segment_os", "OS"),
+    ("segment_pcb", "PCB"),
+    ("segment_code", "code"),
+    ("segment_stack", "stack"),
+    ("segment_data", "data"),
+    ("segment_device", "devices"),
+    ("display_register", "Display register"),
+    ("keyboard_register", "Keyboard register"),
+    ("segment_free", "free"),
+    ("freed", "Freed"),
+    ("arrives_at", "arrives at tick {} (in {})"),
+    ("waiting_for", "waiting for {}"),
+    ("dependency_cycle", "{} is not loaded, its #AFTER directives form a cycle: {}"),
+    ("dependency_missing", "{} is not loaded, its #AFTER names {} that is not stored"),
+    ("dma_loading", "loading {}/{} bytes"),
+    ("dma_start", "P{} started loading {} bytes of code with DMA"),
+    ("dma_end", "P{} finished loading its code after {} ticks"),
+    ("too_big", "too big"),
+    ("pending_loaded", "{} was loaded after waiting for memory"),
+    ("admission_title", "Some files couldn't be loaded"),
+    ("invalid_program", "{} was removed from storage: {}"),
+    (
+        "cant_allocate_stack",
+        "Not enough memory for the stack of {}, it will be loaded once some memory is freed.",
+    ),
+    (
+        "cant_store_pcb",
+        "Not enough OS memory for the PCB of {}, it will be loaded once a process terminates.",
+    ),
+    (
+        "no_event",
+        "No event happened after {} ticks, the execution was stopped.",
+    ),
+    // Log
+    ("processing_started", "Processing started."),
+    ("load_programs_first", "Load programs first."),
+    ("priority_changed", "Priority of P{} changed from {} to {}"),
+    ("cache_hit_rate", "Cache: {}% hits"),
+    ("cache_no_fetches", "Cache: no fetches"),
+    ("cache_totals", "Cache: {} hits, {} misses ({}% hit rate)"),
+    ("scheduler_decision", "{}"),
+    ("decision_picked", "{}: picked {}"),
+    ("decision_over", " over {}"),
+    ("decision_not_arrived", ", {} not arrived"),
+    ("rule_arrival", "arrival"),
+    ("rule_burst", "burst"),
+    ("rule_remaining", "remaining"),
+    ("rule_ratio", "ratio"),
+    ("rule_priority", "priority"),
+    ("rule_queue", "queue"),
+    ("rule_boosted", "got its input"),
+    ("admission_failed", "File not loaded: {}"),
+    ("process_arrived", "Process {} arrived on tick {}"),
+    ("input_scripted", "P{} read {} from its input script."),
+    ("free_memory_query", "P{} asked for the free memory: {} bytes."),
+    ("usage_warning", "The {} is {}% used, over the {}% threshold."),
+    ("tick_limit_title", "Tick limit reached"),
+    ("tick_limit", "The run reached the limit of {} ticks and was paused."),
+    ("tick_limit_process", "P{} {}: {}, PC {}, last {}"),
+    ("tick_limit_continue", "Continue"),
+    ("tick_limit_kill", "Kill the unfinished processes"),
+    ("tick_limit_copy", "Copy the log to the clipboard"),
+    ("region_user_memory", "user memory"),
+    ("region_os_segment", "OS segment"),
+    ("region_storage", "storage"),
+    (
+        "input_script_empty",
+        "P{} used every value of its input script, it waits for the user now.",
+    ),
+    ("scheduler_switched", "Scheduler switched to {} on tick {}"),
+    ("rr_free_cpu", "RR: assigning to a free CPU"),
+    ("scheduler_calls", "Scheduler ran {} times on tick {}, {} calls skipped"),
+    ("rr_no_free_cpu", "RR: no free CPU"),
+    ("pcb_before", "prev {}"),
+    ("pcb_after", "{}"),
+    (
+        "process_assigned",
+        "Assigning process with ID: {} to CPU {}",
+    ),
+    (
+        "process_terminated",
+        "Process with ID: {} finished on CPU {}",
+    ),
+    ("turnaround", "Turnaround for process {}: {} seconds"),
+    ("execution_ratio", "Execution time: {} seconds, Tr / Ts: {}"),
+    ("total_time_log", "Total time: {} seconds"),
+    (
+        "settings_loaded",
+        "The settings can only be applied when no file has been loaded, reset the emulator first.",
+    ),
+    ("config_saved", "The config was saved."),
+    (
+        "config_invalid",
+        "The config file is not valid, using the default config.",
+    ),
+    ("config_error", "Config: {}"),
+    ("ui_state_not_saved", "The UI preferences weren't saved: {}"),
+    ("recovery_machine", "The last session had {} bytes of memory, {} of storage and {} CPUs instead of {}, {} and {}, it wasn't restored"),
+    ("recovery_title", "Restore the last session?"),
+    ("recovery_description", "The emulator didn't close cleanly, on tick {} it had {} stored files and {} processes. Restore the session?"),
+    ("recovery_restored", "Restored the last session, saved on tick {}"),
+    ("recovery_refused", "The recovery of the last session was ignored: {}"),
+    ("recovery_not_saved", "The recovery file wasn't written: {}"),
+    ("allocator_dump", "{}: {}"),
+];
+
+const ES: &[(&str, &str)] = &[
+    // Menu bar
+    ("file", "Archivo"),
+    ("open_folder", "Abrir carpeta"),
+    ("new_program", "Nuevo programa"),
+    ("play", "Iniciar"),
+    ("pause", "Pausar"),
+    ("play_pause", "Iniciar/Pausar"),
+    ("next", "Siguiente"),
+    ("back", "Atrás"),
+    ("step_cpu", "Paso"),
+    ("run_to_event", "Ejecutar hasta evento"),
+    ("run_n", "Ejecutar N"),
+    ("reset", "Reiniciar"),
+    ("stats", "Estadísticas"),
+    ("log", "Registro"),
+    ("copy", "Copiar"),
+    ("tick", "Tick: {}"),
+    ("turbo", "Turbo"),
+    ("turbo_executed", "{} instrucciones en este cuadro"),
+    (
+        "status_states",
+        "Nuevos {} · Listos {} · Ejecutando {} · Bloqueados {} · Terminados {}",
+    ),
+    ("no_scheduler", "Sin planificador"),
+    ("summary_title", "Ejecución completada"),
+    ("summary_ticks", "Ticks totales: {}"),
+    ("summary_draws", "Valores aleatorios: {}"),
+    ("summary_events", "Asignaciones: {}, expropiaciones: {}, bloqueos: {}, fallos: {}"),
+    ("rng_draw", "Valor aleatorio para {}: {} dio {} en el tick {}"),
+    ("summary_wall_time", "Tiempo real: {} s"),
+    (
+        "summary_scheduler",
+        "{}: {} procesos, estancia promedio {} s, ejecución promedio {} s, proporción de respuesta promedio {}",
+    ),
+    ("close", "Cerrar"),
+    ("run_again", "Ejecutar de nuevo"),
+    ("batch_title", "Resultados del lote:"),
+    ("batch_ok", "{}: terminado en la CPU {}, tiempo de estancia {} s"),
+    ("batch_fault", "{}: FALLÓ, {}"),
+    ("batch_not_read", "No se pudo leer {}: {}"),
+    ("batch_not_stored", "{} no se almacenó: {}"),
+    ("batch_stopped", "El lote se detuvo tras {} ticks con procesos pendientes"),
+    ("batch_timed_out", "{} (P{}): se agotó el tiempo"),
+    ("ascii", "ASCII"),
+    ("decimal", "Dec"),
+    ("completed_in", "Completado en {} ticks ({} s)"),
+    ("quantum", "Quantum"),
+    ("quantum_invalid", "1-99"),
+    // Panels
+    ("files", "Archivos"),
+    ("edit", "Editar"),
+    ("memory", "Memoria"),
+    ("storage", "Almacenamiento"),
+    ("cpu", "CPU"),
+    ("display", "Pantalla"),
+    ("cpu_output", "Salida P{}: {}"),
+    ("no_output", "Salida: -"),
+    ("process_output", "El proceso {} imprimió {} en la CPU {}"),
+    ("queues", "Colas"),
+    ("pcb_list", "Lista de PCB"),
+    ("history", "Historial"),
+    ("allocator", "Asignador"),
+    ("dump_log", "Volcar al registro"),
+    ("memory_used", "Memoria usada"),
+    ("memory_freed", "Memoria liberada"),
+    ("storage_used", "Almacenamiento usado"),
+    ("storage_freed", "Almacenamiento liberado"),
+    ("disassembly", "Desensamblado P{}"),
+    ("flags_cmp", "Activa Z cuando ambos registros son iguales"),
+    ("flags_arithmetic", "Activa Z cuando el resultado en AC es 0"),
+    ("flags_register", "Activa Z cuando el registro es 0"),
+    ("flags_jump", "Lee Z para decidir si salta"),
+    ("flags_none", "No modifica Z"),
+    ("ready", "Listos"),
+    ("running", "Ejecutando"),
+    ("blocked", "Bloqueados"),
+    ("awaiting_input", "esperando entrada"),
+    ("quantum_ticks", "({} ticks)"),
+    ("quantum_left", "q: {}/{}"),
+    // PCB tooltip
+    ("id", "ID: "),
+    ("priority", "Prioridad: "),
+    ("state", "Estado: "),
+    ("code_segment", "Segmento de código: [{}; {}]"),
+    ("stack_segment", "Segmento de pila: [{}; {}]"),
+    ("stack_usage", "Uso de la pila: {} / {}"),
+    ("stack_grown", "La pila del proceso {} creció a {} bytes"),
+    ("process_fault", "Proceso {} terminado por un fallo: {} ({} en esta ejecución)"),
+    ("fault_title", "Fallo de proceso"),
+    ("fault_stack_overflow", "desbordamiento de pila, no hay memoria para crecer la pila"),
+    ("fault_invalid_instruction", "instrucción inválida"),
+    ("fault_memory_violation", "acceso a memoria fuera de la región de dispositivos"),
+    ("fault_code_write", "escritura en un segmento de código"),
+    ("fault_assertion", "aserción fallida"),
+    ("exit_normal", "INT 20H"),
+    ("exit_killed", "una terminación forzada"),
+    ("post_mortem", "Post-mortem de P{}, terminado en el tick {} por {}"),
+    ("post_mortem_stack", "SP {}, pila {}"),
+    ("post_mortem_history", "Últimas instrucciones:"),
+    ("assertion_failed", "P{} falló el ASSERT de la línea {}: {} debería ser {} pero es {}"),
+    ("fault_at_line", "{} en la línea {} de {}"),
+    ("source_line", "línea {}: {}"),
+    ("source_changed", "el código cambió desde la ejecución"),
+    ("code_written", "P{} escribió en {}, en el código de P{}"),
+    ("code_modified", "Código modificado por un proceso"),
+    (
+        "process_runaway",
+        "El proceso {} ejecutó {} instrucciones, {} veces el largo de su programa, puede que nunca termine",
+    ),
+    ("runaway_title", "El proceso puede que nunca termine"),
+    ("runaway_kill", "¿Terminar el proceso?"),
+    ("process_killed", "Proceso {} terminado a la fuerza en la CPU {}"),
+    ("process_killed_queued", "Proceso {} terminado a la fuerza en estado {}"),
+    ("stats_faults", "Fallos, {}: {}"),
+    ("arrival", "Llegada: {}"),
+    ("burst", "Ráfaga: {}"),
+    ("remaining_burst", "Ráfaga restante: {}"),
+    ("estimated_burst", "Ráfaga estimada: {}, ejecutó {} ticks"),
+    ("execution_time", "Tiempo de ejecución: {}"),
+    // Editor
+    ("program", "Programa"),
+    ("name", "Nombre"),
+    ("assemble", "Ensamblar y guardar"),
+    ("cancel", "Cancelar"),
+    ("program_needs_name", "El programa necesita un nombre."),
+    // Stats
+    ("stats_title", "Sección de Estadísticas del Sistema"),
+    ("selected_method", "Método seleccionado es: "),
+    ("no_method", "No hay método seleccionado."),
+    ("stats_process", "\nProceso con ID: {} ({}) en CPU {}"),
+    ("source_file", "Archivo: {}"),
+    (
+        "stats_estimation",
+        "    Ráfaga estimada: {} ticks, real: {} ticks, error: {}",
+    ),
+    ("stats_no_estimate", "    Sin ráfaga estimada, real: {} ticks"),
+    ("stats_first_response", "    Primera respuesta: {} ticks lista antes de su primer CPU"),
+    ("average_first_response", "Primera respuesta promedio: {} ticks"),
+    ("stats_group", "{} {}: {} procesos · estancia {} · espera {}"),
+    ("stats_arrival", "\n    Tiempo de llegada: {} segundos"),
+    ("stats_turnaround", "\n    Turnaround {}: {} segundos"),
+    (
+        "stats_execution",
+        "\n    Tiempo de ejecución: {} segundos\n\n     Tr / Ts: {}",
+    ),
+    ("total_time", "Tiempo total: {} segundos"),
+    (
+        "cpu_usage",
+        "CPU {}: {}% de uso, {} ticks ocupado, {} ticks libre, {} cambios de contexto",
+    ),
+    (
+        "total_usage",
+        "Uso total de CPU: {}%, {} cambios de contexto",
+    ),
+    ("back", "Volver"),
+    // Settings
+    ("settings", "Configuración"),
+    ("os_segment", "Segmento del SO"),
+    ("cpu_quantity", "CPUs"),
+    ("scheduler", "Planificador"),
+    ("seed", "Semilla (vacía para aleatoria)"),
+    ("tick_interval", "Intervalo de tick (ms)"),
+    ("apply", "Aplicar"),
+    ("save_config", "Guardar en config.json"),
+    ("settings_running", "Solo lectura mientras el emulador está en ejecución."),
+    // Dialogs
+    ("error", "Error"),
+    ("memory_warning", "Advertencia de memoria"),
+    ("reset_title", "¿Reiniciar el emulador?"),
+    (
+        "reset_description",
+        "El reinicio parcial conserva los archivos guardados para ejecutarlos de nuevo, el reinicio completo también borra el almacenamiento.",
+    ),
+    ("soft_reset", "Reinicio parcial"),
+    ("switch_title", "¿Cambiar el planificador ahora?"),
+    (
+        "switch_description",
+        "Los procesos en ejecución vuelven a la cola de listos y {} elige los siguientes.",
+    ),
+    ("switch_now", "Cambiar ahora"),
+    ("full_reset", "Reinicio completo"),
+    ("close_title", "¿Cerrar el emulador?"),
+    (
+        "close_description",
+        "La ejecución y sus estadísticas se perderán. Sí copia el registro al portapapeles antes de cerrar, No cierra sin guardar.",
+    ),
+    (
+        "file_too_big",
+        "{} no cabe en los {} bytes de memoria de usuario, no se cargará.",
+    ),
+    (
+        "code_mismatch",
+        "{} se guardó con errores, la instrucción {} no se decodifica igual, no se cargará.",
+    ),
+    ("expects_number", "{} espera un número:"),
+    ("input_range", "Escribe un número de 0 a 255"),
+    ("expects_number_range", "{} espera un número de {} a {}:"),
+    ("input_out_of_range", "Escribe un número de {} a {}"),
+    ("input_rejected", "P{} solo acepta números de {} a {}, se rechazó {}."),
+    ("boosted", "Impulsado: va primero en su próximo despacho"),
+    ("input_received", "P{} recibió {} en DX."),
+    (
+        "input_dropped",
+        "P{} terminó mientras esperaba una entrada, se quitó su fila de entrada.",
+    ),
+    ("waiting_memory", "esperando memoria"),
+    ("segment_os", "SO"),
+    ("segment_pcb", "PCB"),
+    ("segment_code", "código"),
+    ("segment_stack", "pila"),
+    ("segment_data", "datos"),
+    ("segment_device", "dispositivos"),
+    ("display_register", "Registro de pantalla"),
+    ("keyboard_register", "Registro de teclado"),
+    ("segment_free", "libre"),
+    ("freed", "Liberado"),
+    ("arrives_at", "llega en el tick {} (en {})"),
+    ("waiting_for", "esperando a {}"),
+    ("dependency_cycle", "{} no se carga, sus directivas #AFTER forman un ciclo: {}"),
+    ("dependency_missing", "{} no se carga, su #AFTER nombra a {} que no está guardado"),
+    ("dma_loading", "cargando {}/{} bytes"),
+    ("dma_start", "P{} empezó a cargar {} bytes de código con DMA"),
+    ("dma_end", "P{} terminó de cargar su código después de {} ticks"),
+    ("too_big", "muy grande"),
+    ("pending_loaded", "{} se cargó después de esperar memoria"),
+    ("admission_title", "Algunos archivos no se pudieron cargar"),
+    ("invalid_program", "{} se eliminó del almacenamiento: {}"),
+    (
+        "cant_allocate_stack",
+        "No hay memoria para la pila de {}, se cargará cuando se libere memoria.",
+    ),
+    (
+        "cant_store_pcb",
+        "No hay memoria del SO para el PCB de {}, se cargará cuando termine un proceso.",
+    ),
+    (
+        "no_event",
+        "Ningún evento ocurrió después de {} ticks, la ejecución se detuvo.",
+    ),
+    // Log
+    ("processing_started", "Procesamiento iniciado."),
+    ("load_programs_first", "Primero carga programas."),
+    ("priority_changed", "La prioridad de P{} cambió de {} a {}"),
+    ("cache_hit_rate", "Caché: {}% aciertos"),
+    ("cache_no_fetches", "Caché: sin lecturas"),
+    ("cache_totals", "Caché: {} aciertos, {} fallos ({}% de aciertos)"),
+    ("scheduler_decision", "{}"),
+    ("decision_picked", "{}: eligió {}"),
+    ("decision_over", " antes que {}"),
+    ("decision_not_arrived", ", {} sin llegar"),
+    ("rule_arrival", "llegada"),
+    ("rule_burst", "ráfaga"),
+    ("rule_remaining", "restante"),
+    ("rule_ratio", "ratio"),
+    ("rule_priority", "prioridad"),
+    ("rule_queue", "cola"),
+    ("rule_boosted", "recibió su entrada"),
+    ("admission_failed", "Archivo no cargado: {}"),
+    ("process_arrived", "El proceso {} llegó en el tick {}"),
+    ("input_scripted", "P{} leyó {} de su script de entrada."),
+    ("free_memory_query", "P{} consultó la memoria libre: {} bytes."),
+    ("usage_warning", "{} está usado al {}%, por encima del umbral del {}%."),
+    ("tick_limit_title", "Límite de ticks alcanzado"),
+    ("tick_limit", "La ejecución alcanzó el límite de {} ticks y se pausó."),
+    ("tick_limit_process", "P{} {}: {}, PC {}, últimas {}"),
+    ("tick_limit_continue", "Continuar"),
+    ("tick_limit_kill", "Terminar los procesos sin terminar"),
+    ("tick_limit_copy", "Copiar el log al portapapeles"),
+    ("region_user_memory", "La memoria de usuario"),
+    ("region_os_segment", "El segmento del SO"),
+    ("region_storage", "El almacenamiento"),
+    (
+        "input_script_empty",
+        "P{} usó todos los valores de su script de entrada, ahora espera al usuario.",
+    ),
+    ("scheduler_switched", "Planificador cambiado a {} en el tick {}"),
+    ("rr_free_cpu", "RR: asignando a CPU libre"),
+    ("scheduler_calls", "Planificador ejecutado {} veces en el tick {}, {} llamadas omitidas"),
+    ("rr_no_free_cpu", "RR: sin CPU libre"),
+    ("pcb_before", "prev {}"),
+    ("pcb_after", "{}"),
+    ("process_assigned", "Asignando proceso con ID: {} en CPU {}"),
+    (
+        "process_terminated",
+        "Proceso con ID: {} ha finalizado en CPU {}",
+    ),
+    ("turnaround", "Turnaround para el proceso {}: {} segundos"),
+    (
+        "execution_ratio",
+        "Tiempo de ejecución: {} segundos, Tr / Ts: {}",
+    ),
+    ("total_time_log", "Tiempo total: {} segundos"),
+    (
+        "settings_loaded",
+        "La configuración solo se puede aplicar sin archivos cargados, reinicie el emulador primero.",
+    ),
+    ("config_saved", "La configuración se guardó."),
+    (
+        "config_invalid",
+        "El archivo de configuración no es válido, se usa la configuración por defecto.",
+    ),
+    ("config_error", "Configuración: {}"),
+    ("ui_state_not_saved", "No se guardaron las preferencias: {}"),
+    ("recovery_machine", "La última sesión tenía {} bytes de memoria, {} de almacenamiento y {} CPUs en vez de {}, {} y {}, no se restauró"),
+    ("recovery_title", "¿Restaurar la última sesión?"),
+    ("recovery_description", "El emulador no se cerró correctamente, en el tick {} tenía {} archivos guardados y {} procesos. ¿Restaurar la sesión?"),
+    ("recovery_restored", "Se restauró la última sesión, guardada en el tick {}"),
+    ("recovery_refused", "Se ignoró la recuperación de la última sesión: {}"),
+    ("recovery_not_saved", "No se escribió el archivo de recuperación: {}"),
+    ("allocator_dump", "{}: {}"),
+];
+
+fn strings(lang: Lang) -> &'static [(&'static str, &'static str)] {
+    match lang {
+        Lang::En => EN,
+        Lang::Es => ES,
+    }
+}
+
+// Translated string of the key, unknown keys are shown as is
+pub fn tr(lang: Lang, key: &'static str) -> &'static str {
+    strings(lang)
+        .iter()
+        .find(|x| x.0 == key)
+        .map(|x| x.1)
+        .unwrap_or(key)
+}
+
+// Translated string with each {} replaced by the arguments in order
+pub fn trf(lang: Lang, key: &'static str, args: &[&dyn fmt::Display]) -> String {
+    let mut parts = tr(lang, key).split("{}");
+    let mut string = parts.next().unwrap_or_default().to_string();
+    for (index, part) in parts.enumerate() {
+        if let Some(arg) = args.get(index) {
+            string.push_str(&arg.to_string());
+        }
+        string.push_str(part);
+    }
+    string
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn every_key_translated() {
+        for lang in Lang::ALL {
+            for other in Lang::ALL {
+                for (key, _) in strings(other) {
+                    assert!(
+                        strings(lang).iter().any(|x| x.0 == *key),
+                        "{} missing on {}",
+                        key,
+                        lang
+                    );
+                }
+            }
+            // Same number of arguments on every language
+            for (key, string) in strings(lang) {
+                assert_eq!(
+                    string.matches("{}").count(),
+                    tr(Lang::En, key).matches("{}").count(),
+                    "{}",
+                    key
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn format_arguments() {
+        assert_eq!(
+            trf(Lang::Es, "process_assigned", &[&1, &0]),
+            "Asignando proceso con ID: 1 en CPU 0"
+        );
+        assert_eq!(trf(Lang::En, "tick", &[&12]), "Tick: 12");
+        assert_eq!(tr(Lang::En, "unknown"), "unknown");
+    }
+}
@@ -1,4 +1,7 @@
+pub mod cli;
+pub mod colors;
 pub mod config;
 pub mod emulator;
 pub mod error;
+pub mod i18n;
 pub mod parser;
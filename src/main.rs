@@ -1,73 +1,877 @@
 use iced::widget::{
-    button, column, container, pick_list, rich_text, row, scrollable, span, text, text_input,
-    tooltip, vertical_rule,
+    button, column, container, mouse_area, pick_list, progress_bar, rich_text, row, scrollable,
+    span, text, text_editor, text_input, tooltip, vertical_rule,
 };
-use iced::widget::{Container, Tooltip};
-use iced::{color, font, time, widget};
+use iced::widget::{Container, Row, Tooltip};
+use iced::{color, font, time, widget, window};
 use iced::{Element, Font, Subscription, Task, Theme};
-use rand::Rng;
-use std::fs::File;
-use std::io::BufReader;
-use std::path::PathBuf;
+use serde::{Deserialize, Serialize};
+use std::cmp::Reverse;
+use std::collections::{BTreeMap, HashMap, VecDeque};
+use std::hash::Hash;
+use std::path::{Path, PathBuf};
+use std::rc::Rc;
 use std::time::Duration;
 use std::time::Instant;
 
 use std::{env, mem};
 
-use proyecto_1::{config::Config, error::Error};
+use proyecto_1::i18n::{tr, trf, Lang};
+use proyecto_1::{
+    cli::{Args, USAGE},
+    colors::{self, Palette},
+    config::{Config, MAX_QUANTUM},
+    error::Error,
+};
 use proyecto_1::{emulator::*, parser::*};
 
 fn main() -> iced::Result {
+    let args = match Args::parse(env::args().skip(1)) {
+        Ok(args) if args.help => {
+            println!("{}", USAGE);
+            return Ok(());
+        }
+        Ok(args) => args,
+        Err(error) => {
+            eprintln!("{}\n\n{}", error, USAGE);
+            std::process::exit(2);
+        }
+    };
+
+    // The batch runs without opening the window
+    if args.batch.is_some() {
+        std::process::exit(run_batch(args));
+    }
+
     iced::application("Emulator", Emulator::update, Emulator::view)
         .subscription(Emulator::subscription)
         .theme(Emulator::theme)
-        .run_with(Emulator::new)
+        .exit_on_close_request(false)
+        .run_with(move || Emulator::new(args))
+}
+
+const MEMORY_SCROLL: &str = "memory";
+const STORAGE_SCROLL: &str = "storage";
+// Longest time a turbo frame can run before letting the UI render
+const TURBO_FRAME_TIME: Duration = Duration::from_millis(12);
+// Height of a row of the memory and storage views, every row has the same height so only the
+// visible ones are built
+const ROW_HEIGHT: f32 = 22.0;
+// Rows built above and below the visible ones
+const OVERSCAN_ROWS: usize = 4;
+// Widths the files and the memory panes can be resized to, and the step of their buttons
+const FILES_WIDTHS: (f32, f32) = (160.0, 400.0);
+const MEMORY_WIDTHS: (f32, f32) = (260.0, 640.0);
+const WIDTH_STEP: f32 = 20.0;
+
+// Visible part of a scrollable
+#[derive(Debug, Default, Clone, Copy, PartialEq)]
+struct ScrollView {
+    offset: f32,
+    height: f32,
+}
+
+impl ScrollView {
+    fn from_viewport(viewport: scrollable::Viewport) -> Self {
+        Self {
+            offset: viewport.absolute_offset().y,
+            height: viewport.bounds().height,
+        }
+    }
+
+    // Rows to build out of the total, the height is unknown until the first scroll event
+    fn rows(&self, total: usize) -> std::ops::Range<usize> {
+        let height = if self.height > 0.0 {
+            self.height
+        } else {
+            800.0
+        };
+        let first = ((self.offset / ROW_HEIGHT) as usize).saturating_sub(OVERSCAN_ROWS);
+        let last = ((self.offset + height) / ROW_HEIGHT).ceil() as usize + OVERSCAN_ROWS;
+        first.min(total)..last.min(total)
+    }
+}
+
+// Valid quantums go from 1 to MAX_QUANTUM ticks
+fn parse_quantum(input: &str) -> Option<u8> {
+    input
+        .parse::<u8>()
+        .ok()
+        .filter(|x| (1..=MAX_QUANTUM).contains(x))
+}
+
+// Add an entry to the log console, translated to the language of the config
+macro_rules! log {
+    ($emulator:expr, $level:expr, $key:expr $(, $arg:expr)* $(,)?) => {
+        $emulator.log.push(
+            $emulator.counter,
+            $level,
+            trf($emulator.config.lang, $key, &[$(&$arg),*]),
+        )
+    };
 }
 
-#[derive(Default, Debug, Clone)]
-struct Timing {
-    p_id: usize,                 // Process ID
-    c_id: Option<usize>,         // CPU ID (if assigned to a CPU)
-    burst: usize,                // Total burst time required
-    arrival: u8,                 // Arrival time of the process
-    start: Option<Instant>,      // Actual start time of the process
-    end_time: Option<Instant>,   // Time when process was terminated
-    execution: Option<Duration>, // Time when process was last executed
-    remaining_burst: usize,      // Remaining burst time (updated during execution)
+// Range of bytes colored on the memory or storage view, the label is shown as a tooltip
+#[derive(Debug, Clone)]
+struct Highlight {
+    start: usize,
+    end: usize,
+    color: iced::Color,
+    label: String,
+    on_press: Option<Message>,
 }
 
 #[derive(Default)]
 struct Emulator {
-    cpus: Vec<(CPU, Option<usize>)>,
+    cpus: Vec<Core>,
+    // Last instructions executed by each CPU
+    history: Vec<History>,
+    // Register values of each CPU before the last tick
+    last_registers: Vec<CPU>,
+    // Busy/idle ticks and context switches of each CPU
+    usage: Vec<CpuUsage>,
+    // CPUs with the history section expanded
+    show_history: Vec<bool>,
+    // Instruction cache, it only changes how long the fetches take
+    cache: Cache,
+    // Programs being copied to memory, their processes arrive once they are done
+    dma: Dma,
     stats_data: Vec<ProcessStats>,
     mode: Option<Mode>,
     memory: Memory,
     storage: Storage,
     config: Config,
     display_content: String,
+    // Last INT 10H value of the process running on each CPU, cpu -> (pcb_id, value)
+    cpu_output: HashMap<usize, (usize, u8)>,
+    // Processes that got their input and go first on their next dispatch
+    boosted: Vec<usize>,
+    // Number typed on the input row of each process waiting for input
+    input_values: HashMap<usize, String>,
     // List of processes waiting because of an interupt
     waiting_queue: Vec<(usize, usize, usize)>,
-    // (file_name, pcb_id)
-    loaded_files: Vec<(String, Option<usize>)>,
+    // (storage address, pcb_id), files are keyed by address because names can repeat
+    loaded_files: Vec<(usize, Option<usize>)>,
+    // Files that couldn't be loaded because there was no memory, they are loaded once some
+    // memory is freed. Keyed by storage address
+    pending_files: Vec<(usize, Admission)>,
     // Scheduler diagram
-    diagram: Vec<Timing>,
+    diagram: Diagram,
     theme: Theme,
     show_stats: bool,
     start_time: Option<Instant>,
     total_start_time: Option<Instant>,
+    // Ticks and wall time the run took, set once every process terminated
+    completed: Option<(u64, Duration)>,
+    // Summary shown once the run is completed, until it's closed
+    show_summary: bool,
     quantum: Option<u8>,
+    // Tick the quantum of the process on each CPU started on. A process gets `quantum` ticks
+    // from its dispatch, once they are used the tick reports QuantumExpired and the OS preempts
+    // that CPU between ticks, so the next process executes from the following tick on. Without
+    // another process ready the same one keeps the CPU with a new quantum
+    quantum_start: HashMap<usize, u64>,
+    // Quantum typed during a run, used from the next quantum boundary
+    next_quantum: Option<u8>,
+    // Scheduler selected during a run, waiting for the user to confirm the switch
+    switch_scheduler: Option<Scheduler>,
     counter: u64,
+    // Number of ticks for the run action
+    run_ticks: String,
+    // (pcb_id, instruction index)
+    breakpoints: Vec<(usize, usize)>,
+    // (cpu, address) of the breakpoint that paused the execution
+    breakpoint_hit: Option<(usize, usize)>,
+    // Process shown on the disassembly panel
+    selected_pcb: Option<usize>,
+    // Process that hit a breakpoint on the last tick
+    flash_pcb: Option<usize>,
+    // Processes that arrived on the last tick
+    arrived: Vec<usize>,
+    speed: Speed,
+    editor: Option<Editor>,
+    log: Log,
+    show_log: bool,
+    // Used and freed tables of the memory and the storage
+    show_allocator: bool,
+    // Tick the log was last copied on, a completed run is kept once its log was copied
+    log_copied: Option<u64>,
+    log_level: Level,
+    files_width: f32,
+    memory_width: f32,
+    memory_rows: RowMode,
+    storage_rows: RowMode,
+    // Columns of the hex views
+    show_ascii: bool,
+    decimal: bool,
+    confirm_reset: bool,
+    // Arrival of the processes without an #ARRIVAL directive
+    arrival_policy: ArrivalPolicy,
+    // Open settings view
+    settings: Option<Settings>,
+    // File the config was read from
+    config_path: Option<PathBuf>,
+    // Run many ticks on each frame of the automatic mode
+    turbo: bool,
+    // Instructions executed on the last turbo frame
+    turbo_executed: usize,
+    // Storage address of the file selected on the files panel or the storage view
+    selected_file: Option<usize>,
+    // Visible part of the memory and storage views
+    memory_view: ScrollView,
+    storage_view: ScrollView,
+    // Text of the quantum input, the last valid value is kept on `quantum`
+    quantum_input: String,
+    // Faults of each kind on this run, only the first one shows a dialog
+    faults: HashMap<Fault, usize>,
+    // State before each of the last ticks of the manual mode, newest last
+    snapshots: VecDeque<Snapshot>,
+    // Files of the batch that couldn't be run, set while running a folder
+    batch: Option<Vec<String>>,
+    // Tick the run continued from after it reached the tick limit, the next pause is `max_ticks`
+    // later
+    limit_extension: u64,
+    // Running without a window, the notifications go to stderr
+    headless: bool,
+    // Notifications of the session, the last one is the newest
+    notifications: Vec<Notification>,
+    // Tick of the last scheduler run, it only runs again on the same tick when something changed
+    scheduler_last: Option<u64>,
+    // A process was admitted, dispatched, terminated, blocked or unblocked since the last run
+    scheduler_dirty: bool,
+    // (tick, runs, skipped) of the scheduler calls on the current tick
+    scheduler_calls: (u64, usize, usize),
+    // Why the scheduler picked the last process it dispatched
+    decision: Option<Decision>,
+    // Origins collapsed on the stats view
+    collapsed_groups: Vec<String>,
+    // Final state of the terminated processes of the run
+    post_mortems: BTreeMap<usize, PostMortem>,
+    // (region, threshold) already warned, a threshold is warned again once the usage drops below it
+    warned_usage: Vec<(UsageRegion, u8)>,
+    // File the recovery is written to, it's deleted on a clean exit
+    recovery_path: Option<PathBuf>,
+    // Tick the recovery was last written on
+    recovery_tick: u64,
+    // Recovery left by a session that didn't close cleanly, waiting for the user to restore it
+    recovery: Option<Recovery>,
+    // Events of the processes since the last reset
+    trace: Trace,
+    // Observers of other tools, they get the events after the log and the trace. Going back to a
+    // snapshot doesn't undo what they got
+    observers: Vec<Box<dyn Observer>>,
 }
 
-#[derive(Debug, Clone)]
+// Machine state before a tick, restored by the back button. It's also the state kept on the
+// recovery file
+#[derive(Deserialize, Serialize)]
+struct Snapshot {
+    cpus: Vec<Core>,
+    history: Vec<History>,
+    last_registers: Vec<CPU>,
+    usage: Vec<CpuUsage>,
+    cache: Cache,
+    dma: Dma,
+    stats_data: Vec<ProcessStats>,
+    post_mortems: BTreeMap<usize, PostMortem>,
+    trace: Trace,
+    memory: Memory,
+    // Shared with the previous snapshot while the storage doesn't change
+    storage: Rc<Storage>,
+    display_content: String,
+    cpu_output: HashMap<usize, (usize, u8)>,
+    boosted: Vec<usize>,
+    waiting_queue: Vec<(usize, usize, usize)>,
+    loaded_files: Vec<(usize, Option<usize>)>,
+    pending_files: Vec<(usize, Admission)>,
+    diagram: Diagram,
+    completed: Option<(u64, Duration)>,
+    quantum: Option<u8>,
+    quantum_start: HashMap<usize, u64>,
+    next_quantum: Option<u8>,
+    counter: u64,
+    breakpoint_hit: Option<(usize, usize)>,
+    // The random generator isn't written to the recovery, the current policy is kept
+    #[serde(skip)]
+    arrival_policy: ArrivalPolicy,
+    faults: HashMap<Fault, usize>,
+    // Entries of the log, the newer ones are dropped
+    log_len: usize,
+}
+
+#[derive(Debug, Clone, Deserialize, Serialize)]
 struct ProcessStats {
     process_id: usize,
+    name: String,
     cpu_id: usize,
     turnaround_time: f64,
     execution_time: f64,
     response_ratio: f64,
-    arrival_time: f64, 
+    arrival_time: f64,
+    // Scheduler used when the process terminated
+    scheduler: Option<Scheduler>,
+    // Burst expected when it was first dispatched and the ticks it ran
+    estimated: Option<usize>,
+    ticks: usize,
+    // Ticks it waited ready before its first dispatch
+    first_response: Option<u64>,
+    // File the process was admitted from, its group on the stats view
+    origin: String,
+    // Fault that terminated it
+    fault: Option<Fault>,
+}
+
+// Averages of the processes that came from the same origin
+#[derive(Debug, Clone, PartialEq)]
+struct StatsGroup {
+    origin: String,
+    // Indexes on stats_data
+    processes: Vec<usize>,
+    turnaround_time: f64,
+    // Turnaround minus the time it executed
+    waiting_time: f64,
+}
+
+// Groups of the stats, in the order their first process terminated
+fn group_stats(stats: &[ProcessStats]) -> Vec<StatsGroup> {
+    let mut groups: Vec<StatsGroup> = vec![];
+    for (i, stat) in stats.iter().enumerate() {
+        match groups.iter_mut().find(|x| x.origin == stat.origin) {
+            Some(group) => group.processes.push(i),
+            None => groups.push(StatsGroup {
+                origin: stat.origin.clone(),
+                processes: vec![i],
+                turnaround_time: 0.0,
+                waiting_time: 0.0,
+            }),
+        }
+    }
+    for group in &mut groups {
+        let count = group.processes.len() as f64;
+        let stats = group.processes.iter().map(|x| &stats[*x]);
+        group.turnaround_time = stats.clone().map(|x| x.turnaround_time).sum::<f64>() / count;
+        group.waiting_time = stats
+            .map(|x| x.turnaround_time - x.execution_time)
+            .sum::<f64>()
+            / count;
+    }
+    groups
+}
+// Limit of ticks executed by a single run action
+const MAX_RUN_TICKS: usize = 10_000;
+// Instructions of each unfinished process shown when the tick limit is reached
+const TICK_LIMIT_HISTORY: usize = 3;
+
+// (cpu, (pcb_id, address, size)) selected by the scheduler and if it should run again
+type Dispatch = ((usize, (usize, usize, usize)), bool);
+
+// Events of a tick that need the OS to act
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum TickEvent {
+    Terminated(usize),
+    Blocked(usize),
+    QuantumExpired,
+    Breakpoint(usize),
+    Fault(usize, Fault),
+}
+
+// Errors of a process that terminate it
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Deserialize, Serialize)]
+enum Fault {
+    StackOverflow,
+    InvalidInstruction,
+    // Access outside of the device region
+    MemoryViolation,
+    // Write on a code segment
+    CodeWrite,
+    // An ASSERT found another value on the register
+    AssertionFailed,
+}
+
+impl Fault {
+    fn key(&self) -> &'static str {
+        match self {
+            Fault::StackOverflow => "fault_stack_overflow",
+            Fault::InvalidInstruction => "fault_invalid_instruction",
+            Fault::MemoryViolation => "fault_memory_violation",
+            Fault::CodeWrite => "fault_code_write",
+            Fault::AssertionFailed => "fault_assertion",
+        }
+    }
+}
+
+// How a notification is shown
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum Severity {
+    // Only logged
+    Info,
+    Warning,
+    Error,
+}
+
+// Something the user has to know about, the update logic creates it and `notify` shows it
+#[derive(Debug, Clone, PartialEq)]
+struct Notification {
+    severity: Severity,
+    title: String,
+    body: String,
+}
+
+impl Notification {
+    fn new(severity: Severity, title: impl Into<String>, body: impl Into<String>) -> Self {
+        Self {
+            severity,
+            title: title.into(),
+            body: body.into(),
+        }
+    }
+}
+
+// Parts of the memory and the storage with a usage warning
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum UsageRegion {
+    UserMemory,
+    OsSegment,
+    Storage,
+}
+
+impl UsageRegion {
+    fn key(&self) -> &'static str {
+        match self {
+            UsageRegion::UserMemory => "region_user_memory",
+            UsageRegion::OsSegment => "region_os_segment",
+            UsageRegion::Storage => "region_storage",
+        }
+    }
+}
+
+// Instructions of the history kept on a post-mortem
+const POST_MORTEM_HISTORY: usize = 10;
+
+// Final state of a terminated process, captured before its segments are freed
+#[derive(Debug, Clone, Deserialize, Serialize)]
+struct PostMortem {
+    tick: u64,
+    registers: CPU,
+    // Fault that terminated it, None after INT 20H
+    fault: Option<Fault>,
+    // Killed by the user, after a runaway or the tick limit
+    killed: bool,
+    stack: Vec<u8>,
+    // Last instructions it executed, newest first
+    history: Vec<HistoryEntry>,
+    // (address, instruction) around the final PC
+    disassembly: Vec<(usize, Instruction)>,
+}
+
+// Program written on the editor
+#[derive(Default)]
+struct Editor {
+    content: text_editor::Content,
+    name: String,
+    errors: Vec<String>,
+    // Storage address of the file being edited
+    address: Option<usize>,
+}
+
+// UI preferences saved between sessions
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+struct UiState {
+    // Name of the iced theme
+    theme: String,
+    show_stats: bool,
+    speed: Speed,
+    files_width: f32,
+    memory_width: f32,
+    memory_rows: RowMode,
+    storage_rows: RowMode,
+    show_ascii: bool,
+    decimal: bool,
+}
+
+impl Default for UiState {
+    fn default() -> Self {
+        Self {
+            theme: Theme::Dracula.to_string(),
+            show_stats: false,
+            speed: Speed::Normal,
+            files_width: 220.0,
+            memory_width: 320.0,
+            memory_rows: RowMode::Eight,
+            storage_rows: RowMode::Eight,
+            show_ascii: false,
+            decimal: false,
+        }
+    }
+}
+
+impl UiState {
+    fn path() -> Option<PathBuf> {
+        env::current_dir().ok().map(|x| x.join("ui_state.json"))
+    }
+
+    // Missing or corrupt files fallback to the default preferences
+    fn load(path: &Path) -> Self {
+        match std::fs::read(path) {
+            Ok(bytes) => serde_json::from_slice(&bytes).unwrap_or_default(),
+            Err(_) => Self::default(),
+        }
+    }
+
+    fn save(&self, path: &Path) -> Result<(), Error> {
+        let json = serde_json::to_string_pretty(self)
+            .map_err(|_| Error::IO(std::io::ErrorKind::InvalidData))?;
+        std::fs::write(path, json).map_err(|error| Error::IO(error.kind()))
+    }
+
+    fn theme(&self) -> Theme {
+        Theme::ALL
+            .iter()
+            .find(|x| x.to_string() == self.theme)
+            .cloned()
+            .unwrap_or(Theme::Dracula)
+    }
+}
+
+// State of the session, written every `autosnapshot_ticks` and when files are stored so a crash
+// doesn't lose it. The wall clock times aren't kept
+#[derive(Serialize, Deserialize)]
+struct Recovery {
+    version: u64,
+    // Tick it was written on
+    counter: u64,
+    snapshot: Snapshot,
+}
+
+// Changes whenever the fields of the recovery do
+const RECOVERY_VERSION: u64 = 3;
+
+impl Recovery {
+    fn path() -> PathBuf {
+        env::temp_dir().join("emulator_recovery.json")
+    }
+
+    // Files of an older version are refused before reading the rest of the fields
+    fn load(path: &Path) -> Result<Self, Error> {
+        let bytes = std::fs::read(path).map_err(|error| Error::IO(error.kind()))?;
+        let value: serde_json::Value = serde_json::from_slice(&bytes)
+            .map_err(|_| Error::IO(std::io::ErrorKind::InvalidData))?;
+        let version = value["version"].as_u64().unwrap_or_default();
+        if version != RECOVERY_VERSION {
+            return Err(Error::UnknownRecoveryVersion(version));
+        }
+        serde_json::from_value(value).map_err(|_| Error::IO(std::io::ErrorKind::InvalidData))
+    }
+
+    fn save(&self, path: &Path) -> Result<(), Error> {
+        let json =
+            serde_json::to_string(self).map_err(|_| Error::IO(std::io::ErrorKind::InvalidData))?;
+        std::fs::write(path, json).map_err(|error| Error::IO(error.kind()))
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum SettingsField {
+    Memory,
+    Storage,
+    OsSegment,
+    CpuQuantity,
+    Quantum,
+    Seed,
+    TickInterval,
+}
+
+impl SettingsField {
+    const ALL: [SettingsField; 7] = [
+        SettingsField::Memory,
+        SettingsField::Storage,
+        SettingsField::OsSegment,
+        SettingsField::CpuQuantity,
+        SettingsField::Quantum,
+        SettingsField::Seed,
+        SettingsField::TickInterval,
+    ];
+
+    // Translation key of the label
+    fn key(&self) -> &'static str {
+        match self {
+            SettingsField::Memory => "memory",
+            SettingsField::Storage => "storage",
+            SettingsField::OsSegment => "os_segment",
+            SettingsField::CpuQuantity => "cpu_quantity",
+            SettingsField::Quantum => "quantum",
+            SettingsField::Seed => "seed",
+            SettingsField::TickInterval => "tick_interval",
+        }
+    }
+}
+
+// Values of the settings view, they are validated before being used
+#[derive(Debug, Clone, Default, PartialEq)]
+struct Settings {
+    memory: String,
+    storage: String,
+    os_segment: String,
+    cpu_quantity: String,
+    scheduler: Option<Scheduler>,
+    quantum: String,
+    // Empty to use a random seed
+    seed: String,
+    tick_interval_ms: String,
+}
+
+impl Settings {
+    fn new(config: &Config) -> Self {
+        Self {
+            memory: config.memory.to_string(),
+            storage: config.storage.to_string(),
+            os_segment: config.os_segment.to_string(),
+            cpu_quantity: config.cpu_quantity.to_string(),
+            scheduler: config.scheduler,
+            quantum: config.quantum.to_string(),
+            seed: config.seed.map(|x| x.to_string()).unwrap_or_default(),
+            tick_interval_ms: config.tick_interval_ms.to_string(),
+        }
+    }
+
+    fn field(&self, field: SettingsField) -> &String {
+        match field {
+            SettingsField::Memory => &self.memory,
+            SettingsField::Storage => &self.storage,
+            SettingsField::OsSegment => &self.os_segment,
+            SettingsField::CpuQuantity => &self.cpu_quantity,
+            SettingsField::Quantum => &self.quantum,
+            SettingsField::Seed => &self.seed,
+            SettingsField::TickInterval => &self.tick_interval_ms,
+        }
+    }
+
+    fn field_mut(&mut self, field: SettingsField) -> &mut String {
+        match field {
+            SettingsField::Memory => &mut self.memory,
+            SettingsField::Storage => &mut self.storage,
+            SettingsField::OsSegment => &mut self.os_segment,
+            SettingsField::CpuQuantity => &mut self.cpu_quantity,
+            SettingsField::Quantum => &mut self.quantum,
+            SettingsField::Seed => &mut self.seed,
+            SettingsField::TickInterval => &mut self.tick_interval_ms,
+        }
+    }
+
+    // Copy of the config with the values of the settings, returns every problem found
+    fn config(&self, mut config: Config) -> Result<Config, Vec<String>> {
+        let mut errors = vec![];
+        let mut parse = |field: SettingsField| -> Option<u64> {
+            match self.field(field).parse::<u64>() {
+                Ok(value) => Some(value),
+                Err(_) => {
+                    errors.push(format!("{}: {}", field.key(), Error::ParseIntError));
+                    None
+                }
+            }
+        };
+
+        if let Some(memory) = parse(SettingsField::Memory) {
+            config.memory = memory as usize;
+        }
+        if let Some(storage) = parse(SettingsField::Storage) {
+            config.storage = storage as usize;
+        }
+        if let Some(os_segment) = parse(SettingsField::OsSegment) {
+            config.os_segment = os_segment as usize;
+        }
+        if let Some(cpu_quantity) = parse(SettingsField::CpuQuantity) {
+            config.cpu_quantity = cpu_quantity as usize;
+        }
+        if let Some(quantum) = parse(SettingsField::Quantum) {
+            config.quantum = quantum.min(u8::MAX as u64) as u8;
+        }
+        config.seed = if self.seed.is_empty() {
+            None
+        } else {
+            parse(SettingsField::Seed)
+        };
+        if let Some(tick_interval_ms) = parse(SettingsField::TickInterval) {
+            config.tick_interval_ms = tick_interval_ms;
+        }
+        config.scheduler = self.scheduler;
+
+        if let Err(config_errors) = config.validate() {
+            errors.extend(config_errors.iter().map(|x| x.to_string()));
+        }
+        if errors.is_empty() {
+            Ok(config)
+        } else {
+            Err(errors)
+        }
+    }
+}
+
+// Bytes on each row of the hex views
+#[derive(Debug, Default, Clone, Copy, PartialEq, Serialize, Deserialize)]
+enum RowMode {
+    #[default]
+    Eight,
+    Sixteen,
+    // Rows break on the instruction boundaries inside the code segments
+    Instructions,
+}
+
+impl RowMode {
+    const ALL: [RowMode; 3] = [RowMode::Eight, RowMode::Sixteen, RowMode::Instructions];
+}
+
+impl std::fmt::Display for RowMode {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        match self {
+            RowMode::Eight => write!(f, "8"),
+            RowMode::Sixteen => write!(f, "16"),
+            RowMode::Instructions => write!(f, "Instr."),
+        }
+    }
+}
+
+// Row of a hex view
+#[derive(Debug, Clone, PartialEq)]
+struct HexRow {
+    start: usize,
+    end: usize,
+    label: String,
+}
+
+// Rows of a hex view of `len` bytes. The fixed modes label the rows with their index, the
+// instruction mode labels the code rows with the instruction index and the rest with the address
+fn hex_rows(len: usize, mode: RowMode, layout: &[SegmentInfo]) -> Vec<HexRow> {
+    let fixed = |width: usize| {
+        (0..len.div_ceil(width))
+            .map(|index| HexRow {
+                start: index * width,
+                end: ((index + 1) * width).min(len),
+                label: format!("{:02X}", index),
+            })
+            .collect()
+    };
+    match mode {
+        RowMode::Eight => fixed(8),
+        RowMode::Sixteen => fixed(16),
+        RowMode::Instructions => {
+            let mut rows = vec![];
+            let mut address = 0;
+            while address < len {
+                let code = layout.iter().find(|x| {
+                    x.kind == SegmentKind::Code && x.address <= address && address < x.end()
+                });
+                let row = match code {
+                    Some(segment) => {
+                        let offset = address - segment.address;
+                        HexRow {
+                            start: address,
+                            end: (address + INSTRUCTION_SIZE - offset % INSTRUCTION_SIZE)
+                                .min(segment.end())
+                                .min(len),
+                            label: format!("#{}", offset / INSTRUCTION_SIZE),
+                        }
+                    }
+                    None => {
+                        // Plain rows stop at the start of the next code segment
+                        let next = layout
+                            .iter()
+                            .filter(|x| x.kind == SegmentKind::Code && x.address > address)
+                            .map(|x| x.address)
+                            .min()
+                            .unwrap_or(len);
+                        HexRow {
+                            start: address,
+                            end: (address + 8).min(next).min(len),
+                            label: format!("{:02X}", address),
+                        }
+                    }
+                };
+                address = row.end;
+                rows.push(row);
+            }
+            rows
+        }
+    }
+}
+
+// Rows and columns shown on a hex view
+#[derive(Debug, Clone)]
+struct HexFormat {
+    rows: Vec<HexRow>,
+    ascii: bool,
+    decimal: bool,
+}
+
+// Value of a byte on the hex views
+fn byte_text(byte: u8, decimal: bool) -> String {
+    if decimal {
+        format!("{:3}", byte)
+    } else {
+        format!("{:02X}", byte)
+    }
+}
+
+// Printable ASCII of the bytes, a dot for the rest
+fn ascii_text(bytes: &[u8]) -> String {
+    bytes
+        .iter()
+        .map(|x| {
+            if x.is_ascii_graphic() || *x == b' ' {
+                *x as char
+            } else {
+                '.'
+            }
+        })
+        .collect()
+}
+
+// Speed of the automatic mode
+#[derive(Debug, Default, Clone, Copy, PartialEq, Serialize, Deserialize)]
+enum Speed {
+    Quarter,
+    #[default]
+    Normal,
+    Fast,
+    Faster,
+    Max,
+}
+
+impl Speed {
+    const ALL: [Speed; 5] = [
+        Speed::Quarter,
+        Speed::Normal,
+        Speed::Fast,
+        Speed::Faster,
+        Speed::Max,
+    ];
+
+    // Time between ticks for the tick interval of the config
+    fn interval(&self, tick_interval_ms: u64) -> Duration {
+        let millis = match self {
+            Speed::Quarter => tick_interval_ms * 4,
+            Speed::Normal => tick_interval_ms,
+            Speed::Fast => tick_interval_ms / 4,
+            Speed::Faster => tick_interval_ms / 10,
+            Speed::Max => 1,
+        };
+        Duration::from_millis(millis.max(1))
+    }
+}
+
+impl std::fmt::Display for Speed {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        match self {
+            Speed::Quarter => write!(f, "0.25x"),
+            Speed::Normal => write!(f, "1x"),
+            Speed::Fast => write!(f, "4x"),
+            Speed::Faster => write!(f, "10x"),
+            Speed::Max => write!(f, "Max"),
+        }
+    }
 }
+
 #[derive(PartialEq)]
 enum Mode {
     Manual,
@@ -77,68 +881,246 @@ enum Mode {
 #[derive(Debug, Clone)]
 enum Message {
     Tick,
-    Input(String),
+    // Execute an instruction only on the CPU
+    TickCpu(usize),
+    // Restore the state before the last tick
+    StepBack,
+    Input(usize, String),
     Blocked(usize),
-    Unblock,
+    Fault(usize, Fault),
+    // Answer to the dialog of a process that may never terminate
+    Runaway(usize, rfd::MessageDialogResult),
+    TickLimit(rfd::MessageDialogResult),
+    Unblock(usize, u8),
     OpenFile,
     Scheduler,
     DialogResult(rfd::MessageDialogResult),
     FilePicked(Result<Vec<PathBuf>, Error>),
-    StoreFiles(Result<Vec<(String, Vec<u8>)>, Error>),
+    StoreFiles(Result<Folder, Error>),
+    OpenFolder,
+    FolderPicked(Result<PathBuf, Error>),
+    BatchFiles(Result<Folder, Error>),
     // (cpu, (pcb_id, address, size))
     Distpacher((usize, (usize, usize, usize))),
     Terminated(usize),
     ChangeMode,
     SchedulerSelected(Scheduler),
-    QuantumSelected(u8),
+    QuantumInput(String),
+    RunToEvent,
+    RunTicks,
+    RunTicksInput(String),
+    SelectPcb(usize),
+    // (pcb_id, new priority)
+    SetPriority(usize, u8),
+    SpeedSelected(Speed),
+    ToggleTurbo(bool),
+    NewProgram,
+    // Storage address of the file
+    EditFile(usize),
+    EditorAction(text_editor::Action),
+    EditorName(String),
+    AssembleProgram,
+    CloseEditor,
+    ToggleLog,
+    ToggleAllocator,
+    // Write the allocator tables to the log
+    DumpAllocator,
+    ThemeSelected(Theme),
+    LangSelected(Lang),
+    CloseRequested(window::Id),
+    // Answer to the close confirmation, yes copies the log before closing
+    CloseConfirmed(window::Id, rfd::MessageDialogResult),
+    // Scroll the memory view to the code segment of the process
+    ScrollToProcess(usize),
+    // Select the file stored on the address and scroll the storage view to it
+    SelectFile(usize),
+    MemoryScrolled(scrollable::Viewport),
+    StorageScrolled(scrollable::Viewport),
+    MemoryRowsSelected(RowMode),
+    StorageRowsSelected(RowMode),
+    // New width of the files and the memory panes
+    FilesWidth(f32),
+    MemoryWidth(f32),
+    ToggleAscii(bool),
+    ToggleDecimal(bool),
+    LogLevelSelected(Level),
+    CopyLog,
+    // (pcb_id, instruction index)
+    ToggleBreakpoint(usize, usize),
     StatsPressed,
     ResetPressed,
-    TickScheduler,
+    CancelReset,
+    // Switch to the scheduler selected during the run
+    ConfirmSwitch,
+    CancelSwitch,
+    // Keep the stored files so they can be run again
+    SoftReset,
+    // Buttons of the end of run summary
+    SummaryStats,
+    CloseSummary,
+    FullReset,
+    ToggleHistory(usize),
+    // Origin of the group of the stats view
+    ToggleGroup(String),
+    // Answer to the dialog that offers the recovery of the last session
+    Recover(rfd::MessageDialogResult),
+    ToggleSettings,
+    SettingsInput(SettingsField, String),
+    SettingsScheduler(Scheduler),
+    ApplySettings,
+    SaveSettings,
 }
 
 impl Emulator {
-    fn new() -> (Self, Task<Message>) {
+    fn new(args: Args) -> (Self, Task<Message>) {
         // Read the config file, if no file is found in the proyect root create a defualt config
-        let config: Config = match env::current_dir() {
-            Ok(mut path) => {
-                path.push("config.json");
-                match File::open(path) {
-                    Ok(file) => {
-                        let reader = BufReader::new(file);
-                        let mut config: Config = match serde_json::from_reader(reader) {
-                            Ok(config) => config,
-                            Err(_) => Config::default(),
-                        };
-                        config.scheduler = Some(Scheduler::FCFS);
-                        config
-                    }
-                    Err(_) => Config::default(),
-                }
-            }
-            Err(_) => Config::default(),
-        };
-
-        (
-            Self {
-                show_stats: false,
-                storage: Storage::new(config.storage),
-                memory: Memory::new(config.memory, config.os_segment),
-                cpus: vec![(CPU::new(), None); config.cpu_quantity],
-                mode: None,
-                display_content: "".to_string(),
-                theme: iced::Theme::Dracula,
-                waiting_queue: vec![],
-                loaded_files: vec![],
-                diagram: vec![],
-                config,
-                start_time: None,
-                total_start_time: None,
-                quantum: Some(1),
-                counter: 0, 
-                stats_data: Vec::new(),
+        let path = match &args.config {
+            Some(path) => Ok(path.clone()),
+            None => env::current_dir().map(|x| Config::find(&x)),
+        };
+        let config_path = path.as_ref().ok().cloned();
+        // Problems of the config file
+        let mut config_errors = vec![];
+        let mut config: Config = match path {
+            Ok(path) if path.exists() => match Config::load(&path) {
+                Ok(mut config) => {
+                    config.scheduler.get_or_insert(Scheduler::FCFS);
+                    config
+                }
+                Err(error) => {
+                    config_errors.push(error.to_string());
+                    Config::default()
+                }
             },
-            Task::none(),
-        )
+            _ => Config::default(),
+        };
+        // Command line values override the file
+        args.apply(&mut config);
+        // Invalid configs would break the memory, use the default one instead
+        let config = match config.validate() {
+            Ok(()) => config,
+            Err(errors) => {
+                config_errors.extend(errors.iter().map(|x| x.to_string()));
+                Config::default()
+            }
+        };
+
+        let ui_state = UiState::path()
+            .map(|path| UiState::load(&path))
+            .unwrap_or_default();
+
+        let mut emulator = Self {
+            show_stats: ui_state.show_stats,
+            storage: Storage::new(config.storage),
+            memory: Memory::from_config(&config),
+            cpus: vec![Core::default(); config.cpu_quantity],
+            history: vec![History::new(); config.cpu_quantity],
+            last_registers: vec![CPU::new(); config.cpu_quantity],
+            usage: vec![CpuUsage::new(); config.cpu_quantity],
+            show_history: vec![false; config.cpu_quantity],
+            cache: Cache::new(config.cache),
+            dma: Dma::default(),
+            mode: None,
+            display_content: "".to_string(),
+            cpu_output: HashMap::new(),
+            input_values: HashMap::new(),
+            boosted: vec![],
+            theme: ui_state.theme(),
+            waiting_queue: vec![],
+            loaded_files: vec![],
+            pending_files: vec![],
+            diagram: Diagram::default(),
+            config,
+            start_time: None,
+            total_start_time: None,
+            completed: None,
+            show_summary: false,
+            quantum: Some(config.quantum),
+            quantum_start: HashMap::new(),
+            next_quantum: None,
+            switch_scheduler: None,
+            counter: 0,
+            run_ticks: "10".to_string(),
+            breakpoints: vec![],
+            breakpoint_hit: None,
+            selected_pcb: None,
+            selected_file: None,
+            flash_pcb: None,
+            arrived: vec![],
+            speed: ui_state.speed,
+            files_width: ui_state.files_width.clamp(FILES_WIDTHS.0, FILES_WIDTHS.1),
+            memory_width: ui_state
+                .memory_width
+                .clamp(MEMORY_WIDTHS.0, MEMORY_WIDTHS.1),
+            memory_rows: ui_state.memory_rows,
+            storage_rows: ui_state.storage_rows,
+            show_ascii: ui_state.show_ascii,
+            decimal: ui_state.decimal,
+            confirm_reset: false,
+            quantum_input: config.quantum.to_string(),
+            faults: HashMap::new(),
+            snapshots: VecDeque::new(),
+            batch: None,
+            limit_extension: 0,
+            headless: args.batch.is_some(),
+            notifications: vec![],
+            scheduler_last: None,
+            scheduler_dirty: false,
+            scheduler_calls: (0, 0, 0),
+            decision: None,
+            collapsed_groups: vec![],
+            post_mortems: BTreeMap::new(),
+            warned_usage: vec![],
+            // The batch has no dialog to offer the recovery
+            recovery_path: args.batch.is_none().then(Recovery::path),
+            recovery_tick: 0,
+            recovery: None,
+            trace: Trace::default(),
+            observers: vec![],
+            editor: None,
+            log: Log::new(),
+            show_log: false,
+            show_allocator: false,
+            log_copied: None,
+            log_level: Level::Info,
+            stats_data: Vec::new(),
+            arrival_policy: ArrivalPolicy::seeded(config.seed),
+            settings: None,
+            config_path,
+            turbo: false,
+            turbo_executed: 0,
+            memory_view: ScrollView::default(),
+            storage_view: ScrollView::default(),
+        };
+
+        // Store the programs given on the command line
+        let files = if args.files.is_empty() {
+            Task::none()
+        } else {
+            Task::perform(
+                read_files(args.files, emulator.config.max_file_size),
+                Message::StoreFiles,
+            )
+        };
+        let files = emulator.recover().chain(files);
+
+        if config_errors.is_empty() {
+            return (emulator, files);
+        }
+        let lang = emulator.config.lang;
+        log!(emulator, Level::Warning, "config_invalid");
+        for error in &config_errors {
+            log!(emulator, Level::Warning, "config_error", error);
+        }
+        emulator.show_log = true;
+        let notification = Notification::new(
+            Severity::Warning,
+            tr(lang, "config_invalid"),
+            config_errors.join("\n"),
+        );
+        let dialog = emulator.notify(notification);
+
+        (emulator, dialog.chain(files))
     }
 
     fn update(&mut self, message: Message) -> Task<Message> {
@@ -146,1357 +1128,8282 @@ impl Emulator {
             // Open the file picker
             Message::OpenFile => Task::perform(pick_file(), Message::FilePicked),
             // Reads the contents of the selected files
-            Message::FilePicked(Ok(paths)) => Task::perform(read_files(paths), Message::StoreFiles),
+            Message::FilePicked(Ok(paths)) => Task::perform(
+                read_files(paths, self.config.max_file_size),
+                Message::StoreFiles,
+            ),
+            Message::OpenFolder => Task::perform(pick_folder(), Message::FolderPicked),
+            Message::FolderPicked(Ok(dir)) => {
+                let max_file_size = self.config.max_file_size;
+                Task::perform(
+                    async move { read_folder(&dir, max_file_size) },
+                    Message::BatchFiles,
+                )
+            }
+            Message::FolderPicked(Err(error)) => Task::done(Message::FilePicked(Err(error))),
+            // The batch runs on turbo until every process terminates, then the summary is shown
+            Message::BatchFiles(Ok((files, failures))) => {
+                self.store_batch(files, failures);
+                self.turbo = true;
+                self.mode = Some(Mode::Manual);
+                Task::done(Message::ChangeMode).chain(Task::done(Message::Scheduler))
+            }
+            Message::BatchFiles(Err(error)) => Task::done(Message::StoreFiles(Err(error))),
             Message::FilePicked(Err(error)) => {
-                let dialog = rfd::AsyncMessageDialog::new()
-                    .set_level(rfd::MessageLevel::Error)
-                    .set_title("Error")
-                    .set_description(format!("{}", error))
-                    .set_buttons(rfd::MessageButtons::Ok)
-                    .show();
-
-                Task::perform(dialog, Message::DialogResult)
+                let title = tr(self.config.lang, "error");
+                self.notify(Notification::new(Severity::Error, title, error.to_string()))
             }
-            Message::StatsPressed => {
-                self.show_stats = !self.show_stats;
+            Message::ToggleSettings => {
+                self.settings = match self.settings {
+                    Some(_) => None,
+                    None => Some(Settings::new(&self.config)),
+                };
                 Task::none()
             }
-            Message::ResetPressed => {
-                self.storage = Storage::new(self.config.storage);
-                self.memory = Memory::new(self.config.memory, self.config.os_segment);
-                self.cpus = vec![(CPU::new(), None); self.config.cpu_quantity];
-                self.mode = None;
-                self.display_content = "".to_string();
-                self.waiting_queue = vec![];
-                self.loaded_files = vec![];
-                self.diagram = vec![];
-                self.start_time = None;
-                self.total_start_time = None;
-                self.counter = 0;
-
+            Message::SettingsInput(field, mut input) => {
+                input.retain(|c| c.is_numeric());
+                if let Some(settings) = &mut self.settings {
+                    *settings.field_mut(field) = input;
+                }
                 Task::none()
             }
-            // Saves the files content to storage
-            Message::StoreFiles(Ok(files)) => {
-                for (file_name, data) in files {
-                    let result = self.storage.store_files(&file_name, data.len(), data);
-                    if let Err(error) = result {
-                        let dialog = rfd::AsyncMessageDialog::new()
-                            .set_level(rfd::MessageLevel::Warning)
-                            .set_title("Memory Warning")
-                            .set_description(format!("{}", error))
-                            .set_buttons(rfd::MessageButtons::Ok)
-                            .show();
-
-                        return Task::perform(dialog, Message::DialogResult);
+            Message::SettingsScheduler(scheduler) => {
+                if let Some(settings) = &mut self.settings {
+                    settings.scheduler = Some(scheduler);
+                }
+                Task::none()
+            }
+            Message::ApplySettings => {
+                if let Some(Ok(config)) = self.settings.as_ref().map(|x| x.config(self.config)) {
+                    if self.apply_config(config) {
+                        self.settings = None;
                     }
                 }
-                Task::done(Message::Scheduler)
+                Task::none()
             }
-            Message::StoreFiles(Err(error)) => {
-                let dialog = rfd::AsyncMessageDialog::new()
-                    .set_level(rfd::MessageLevel::Error)
-                    .set_title("Error")
-                    .set_description(format!("{}", error))
-                    .set_buttons(rfd::MessageButtons::Ok)
-                    .show();
-
-                Task::perform(dialog, Message::DialogResult)
+            Message::SaveSettings => {
+                let Some(Ok(config)) = self.settings.as_ref().map(|x| x.config(self.config)) else {
+                    return Task::none();
+                };
+                let result = match &self.config_path {
+                    Some(path) => config.save(path),
+                    None => Err(Error::NotFile),
+                };
+                let lang = self.config.lang;
+                let notification = match result {
+                    Ok(()) => Notification::new(
+                        Severity::Info,
+                        tr(lang, "settings"),
+                        tr(lang, "config_saved"),
+                    ),
+                    Err(error) => {
+                        Notification::new(Severity::Error, tr(lang, "error"), error.to_string())
+                    }
+                };
+                self.notify(notification)
             }
-            Message::DialogResult(_result) => Task::none(),
-            Message::ChangeMode => {
-                if self.mode == Some(Mode::Manual) {
-                    self.mode = Some(Mode::Automatic);
-                } else {
-                    self.mode = Some(Mode::Manual);
+            Message::StatsPressed => {
+                self.show_stats = !self.show_stats;
+                Task::none()
+            }
+            Message::Recover(answer) => {
+                let Some(recovery) = self.recovery.take() else {
+                    return Task::none();
+                };
+                if answer != rfd::MessageDialogResult::Yes {
+                    self.remove_recovery();
+                    return Task::none();
                 }
-                // Solo iniciar el tiempo total si no ha empezado
-                if self.total_start_time.is_none() {
-                    self.total_start_time = Some(Instant::now());
-                    self.start_time = Some(Instant::now());
-                    println!("Procesamiento iniciado.");
+                // A machine of another size doesn't fit the addresses of the segments and files
+                let mut snapshot = recovery.snapshot;
+                let saved = (
+                    snapshot.memory.data.len(),
+                    snapshot.storage.data.len(),
+                    snapshot.cpus.len(),
+                );
+                let current = (
+                    self.memory.data.len(),
+                    self.storage.data.len(),
+                    self.cpus.len(),
+                );
+                if saved != current {
+                    log!(
+                        self,
+                        Level::Warning,
+                        "recovery_machine",
+                        saved.0,
+                        saved.1,
+                        saved.2,
+                        current.0,
+                        current.1,
+                        current.2
+                    );
+                    self.remove_recovery();
+                    return Task::none();
+                }
+                snapshot.arrival_policy = self.arrival_policy.clone();
+                snapshot.log_len = self.log.len();
+                self.restore(snapshot);
+                // The wall clock starts again on the restored tick
+                let now = Instant::now();
+                for core in self.cpus.iter_mut().filter(|x| x.assigned.is_some()) {
+                    core.cpu.start_time = Some(now);
                 }
-
-                // Iniciar el tiempo de cada proceso en estado `Ready`
                 for timing in self.diagram.iter_mut() {
-                    if timing.start.is_none() && timing.c_id.is_none() {
-                        timing.start = Some(Instant::now());
+                    if timing.dispatched_at.is_some() {
+                        timing.start = Some(now);
+                    }
+                }
+                self.check_usage();
+                log!(self, Level::Info, "recovery_restored", recovery.counter);
+                Task::done(Message::Scheduler)
+            }
+            Message::ToggleGroup(origin) => {
+                match self.collapsed_groups.iter().position(|x| *x == origin) {
+                    Some(i) => {
+                        self.collapsed_groups.remove(i);
                     }
+                    None => self.collapsed_groups.push(origin),
                 }
                 Task::none()
             }
-            // The Scheduler of the OS, it will select the next process to execute and send it to the distpacher
-            Message::Scheduler => {
-                if let Some(task) = create_pcbs(
-                    &mut self.storage,
-                    &mut self.memory,
-                    &mut self.loaded_files,
-                    &mut self.diagram,
-                ) {
-                    return task;
+            Message::ToggleHistory(cpu_index) => {
+                if let Some(show) = self.show_history.get_mut(cpu_index) {
+                    *show = !*show;
                 }
-                // Uses the scheduler algo selected on config
-                let mut rng = rand::thread_rng();
-                match self.config.scheduler {
-                    Some(Scheduler::FCFS) => {
-                        // Select the pcb from the table and send to distpacher
-                        for (pcb_id, address, size) in self.memory.pcb_table.iter() {
-                            let pcb = PCB::from(&self.memory.data[*address..*address + *size]);
-                            if pcb.process_state == ProcessState::New
-                                || pcb.process_state == ProcessState::Ready
-                            {
-                                let mut list = vec![0; self.config.cpu_quantity];
-                                // Repeat until all CPUs have been checked
-                                while list.iter().sum::<usize>() < self.config.cpu_quantity {
-                                    let r_i = rng.gen_range(0..self.config.cpu_quantity);
-                                    // Assign the process to free CPU
-                                    if let Some((_, p)) = self.cpus.get(r_i) {
-                                        if p.is_none() {
-                                        
-                                            return Task::done(Message::Distpacher((
-                                                r_i,
-                                                (*pcb_id, *address, *size),
-                                            )))
-                                            .chain(Task::done(Message::Scheduler));
-                                        } else {
-                                            list[r_i] = 1;
-                                        }
-                                    }
-                                }
-                            }
-                        }
-                        Task::none()
-                    }
-                    Some(Scheduler::SRT) => {
-                        // Sort the pcbs by arrival and burst time
-                        self.diagram.sort_by_key(|a| a.remaining_burst);
-                        // Select the pcb from the table and send to distpacher
-                        for pcb_timing in self.diagram.iter() {
-                            if pcb_timing.c_id.is_none() {
-                                if let Some((pcb_id, address, size)) = self
-                                    .memory
-                                    .pcb_table
-                                    .iter()
-                                    .find(|x| x.0 == pcb_timing.p_id)
-                                {
-                                    // Read the PCB from memory
-                                    let pcb =
-                                        PCB::from(&self.memory.data[*address..*address + *size]);
-                                    if pcb.process_state == ProcessState::New
-                                        || pcb.process_state == ProcessState::Ready
-                                    {
-                                        if self.cpus.iter().any(|x| x.1.is_none()) {
-                                            let mut list = vec![0; self.config.cpu_quantity];
-                                            // Repeat until all CPUs have been checked
-                                            while list.iter().sum::<usize>()
-                                                < self.config.cpu_quantity
-                                            {
-                                                let r_i =
-                                                    rng.gen_range(0..self.config.cpu_quantity);
-                                                // Assign the process to free CPU
-                                                if let Some((_, p)) = self.cpus.get(r_i) {
-                                                    if p.is_none() {
-                                                        return Task::done(Message::Distpacher((
-                                                            r_i,
-                                                            (*pcb_id, *address, *size),
-                                                        )))
-                                                        .chain(Task::done(Message::Scheduler));
-                                                    } else {
-                                                        list[r_i] = 1;
-                                                    }
-                                                }
-                                            }
-                                        } else {
-                                            let r_i = rng.gen_range(0..self.config.cpu_quantity);
-                                            if let Some((_, p)) = self.cpus.get(r_i) {
-                                                if let Some(old_timing) = self
-                                                    .diagram
-                                                    .iter()
-                                                    .find(|x| x.p_id == p.unwrap())
-                                                {
-                                                    if old_timing.remaining_burst
-                                                        > pcb_timing.remaining_burst
-                                                    {
-                                                        return Task::done(Message::Distpacher((
-                                                            r_i,
-                                                            (*pcb_id, *address, *size),
-                                                        )))
-                                                        .chain(Task::done(Message::Scheduler));
-                                                    }
-                                                }
-                                            }
-                                        }
-                                    }
-                                }
-                            }
-                        }
-                        Task::none()
-                    }
-                    Some(Scheduler::SJF) => {
-                        // Sort the pcbs by arrival and burst time
-                        self.diagram.sort_by_key(|a| a.burst);
-                        // Select the pcb from the table and send to distpacher
-                        for pcb_timing in self.diagram.iter_mut() {
-                            if let Some((pcb_id, address, size)) = self
-                                .memory
-                                .pcb_table
-                                .iter()
-                                .find(|x| x.0 == pcb_timing.p_id)
-                            {
-                                // Read the PCB from memory
-                                let pcb = PCB::from(&self.memory.data[*address..*address + *size]);
-                                if pcb.process_state == ProcessState::New
-                                    || pcb.process_state == ProcessState::Ready
-                                {
-                                    let mut list = vec![0; self.config.cpu_quantity];
-                                    // Repeat until all CPUs have been checked
-                                    while list.iter().sum::<usize>() < self.config.cpu_quantity {
-                                        let r_i = rng.gen_range(0..self.config.cpu_quantity);
-                                        // Assign the process to free CPU
-                                        if let Some((_, p)) = self.cpus.get(r_i) {
-                                            if p.is_none() {
-                                                return Task::done(Message::Distpacher((
-                                                    r_i,
-                                                    (*pcb_id, *address, *size),
-                                                )))
-                                                .chain(Task::done(Message::Scheduler));
-                                            } else {
-                                                list[r_i] = 1;
-                                            }
-                                        }
-                                    }
-                                }
-                            }
-                        }
-                        Task::none()
-                    }
-                    Some(Scheduler::RR) => {
-                        for (pcb_id, address, size) in self.memory.pcb_table.iter() {
-                            let pcb = PCB::from(&self.memory.data[*address..*address + *size]);
-                            if pcb.process_state == ProcessState::New
-                                || pcb.process_state == ProcessState::Ready
-                            {
-                                if self.cpus.iter().any(|x| x.1.is_none()) {
-                                    println!("======== 1 ========");
-                                    let mut list = vec![0; self.config.cpu_quantity];
-                                    // Repeat until all CPUs have been checked
-                                    while list.iter().sum::<usize>() < self.config.cpu_quantity {
-                                        let r_i = rng.gen_range(0..self.config.cpu_quantity);
-                                        // Assign the process to free CPU
-                                        if let Some((_, p)) = self.cpus.get(r_i) {
-                                            if p.is_none() {
-                                                return Task::done(Message::Distpacher((
-                                                    r_i,
-                                                    (*pcb_id, *address, *size),
-                                                )))
-                                                .chain(Task::done(Message::Scheduler));
-                                            } else {
-                                                list[r_i] = 1;
-                                            }
-                                        }
-                                    }
-                                } else {
-                                    println!("======== 2 ========");
-                                    let r_i = rng.gen_range(0..self.config.cpu_quantity);
-                                    if self.counter % (self.quantum.unwrap() as u64) == 0 && self.counter != 0 {
-                                        return Task::done(Message::Distpacher((
-                                            r_i,
-                                            (*pcb_id, *address, *size),
-                                        )));
-                                    }
-                                }
-                            }
-                        }
-                        Task::none()
+                Task::none()
+            }
+            Message::ResetPressed => {
+                self.confirm_reset = true;
+                Task::none()
+            }
+            Message::CancelReset => {
+                self.confirm_reset = false;
+                Task::none()
+            }
+            Message::ConfirmSwitch => match self.switch_scheduler.take() {
+                Some(scheduler) => self.switch_policy(scheduler),
+                None => Task::none(),
+            },
+            Message::CancelSwitch => {
+                self.switch_scheduler = None;
+                Task::none()
+            }
+            Message::SummaryStats => {
+                self.show_summary = false;
+                self.show_stats = true;
+                Task::none()
+            }
+            Message::CloseSummary => {
+                self.show_summary = false;
+                Task::none()
+            }
+            Message::SoftReset => {
+                self.reset();
+                Task::none()
+            }
+            Message::FullReset => {
+                self.reset();
+                self.storage = Storage::new(self.config.storage);
+                self.warned_usage.clear();
+                Task::none()
+            }
+            // Saves the files content to storage, the files that can't be read or stored are
+            // reported together without stopping the rest
+            Message::StoreFiles(Ok((files, failures))) => {
+                let lang = self.config.lang;
+                let mut problems = failures
+                    .iter()
+                    .map(|(file_name, error)| trf(lang, "batch_not_read", &[file_name, error]))
+                    .collect::<Vec<_>>();
+                for (file_name, data) in files {
+                    if let Err(error) = self.store_file(file_name, data) {
+                        problems.push(error.to_string());
                     }
-                    Some(Scheduler::HRRN) => Task::none(),
-                    None => Task::none(),
                 }
+                if problems.is_empty() {
+                    return Task::done(Message::Scheduler);
+                }
+                let notification = Notification::new(
+                    Severity::Warning,
+                    tr(lang, "memory_warning"),
+                    problems.join("\n"),
+                );
+                Task::batch([self.notify(notification), Task::done(Message::Scheduler)])
             }
-            Message::Distpacher((cpu_index, (pcb_id, address, size))) => {
-                if let Some((cpu, p)) = self.cpus.get_mut(cpu_index) {
-                   
-                    if let Some(p_id) = p {
-                        // Context switch
-                        // Store CPU content on the PCB
-                        if let Some((_, old_address, old_size)) =
-                            self.memory.pcb_table.iter().find(|x| x.0 == *p_id)
-                        {
-                            let mut pcb = PCB::from(
-                                &self.memory.data[*old_address..*old_address + *old_size],
-                            );
-                            println!("prev {:?}", &pcb);
-                            pcb.ax = cpu.ax;
-                            cpu.bx = pcb.bx;
-                            pcb.cx = cpu.cx;
-                            pcb.dx = cpu.dx;
-                            pcb.ac = cpu.ac;
-                            pcb.pc = cpu.pc;
-                            pcb.sp = cpu.sp;
-                            pcb.ir = cpu.ir;
-                            pcb.z = cpu.z;
-
-                            pcb.process_state = ProcessState::Ready;
-                            println!("{:?}", &pcb);
-                            // Save changes
-                            let bytes: Vec<u8> = pcb.into();
-                            self.memory.data[*old_address..*old_address + bytes.len()]
-                                .copy_from_slice(&bytes[..]);
-
-                            if let Some(timing) = self.diagram.iter_mut().find(|x| x.p_id == *p_id)
-                            {
-                                timing.c_id = None;
-                            }
-                        }
-                    }
-
-                    // Context switch, load registers to the CPU
-                    let mut pcb = PCB::from(&self.memory.data[address..address + size]);
-                    cpu.ax = pcb.ax;
-                    cpu.bx = pcb.bx;
-                    cpu.cx = pcb.cx;
-                    cpu.dx = pcb.dx;
-                    cpu.ac = pcb.ac;
-                    cpu.pc = pcb.pc;
-                    cpu.sp = pcb.sp;
-                    cpu.ir = pcb.ir;
-                    cpu.z = pcb.z;
-
-                    pcb.process_state = ProcessState::Running;
-
-                    // Save changes
-                    let bytes: Vec<u8> = pcb.into();
-                    self.memory.data[address..address + bytes.len()].copy_from_slice(&bytes[..]);
-
-                    // Inicia el temporizador del CPU y el tiempo individual del proceso si aún no ha comenzado
-                    cpu.start_time = Some(Instant::now());
-
-                    if let Some(timing) = self.diagram.iter_mut().find(|x| x.p_id == pcb_id) {
-                        timing.c_id = Some(cpu_index);
-                        if timing.start.is_none() {
-                            timing.start = Some(Instant::now());
-                        }
-                    }
-
-                    // Updates times
-                    //if let Some(timing) = self.diagram.iter_mut().find(|x| x.p_id == pcb_id) {
-                        //timing.c_id = Some(cpu_index);
-                        //timing.start = Some(Instant::now());
-                    //}
+            Message::StoreFiles(Err(error)) => {
+                let title = tr(self.config.lang, "error");
+                self.notify(Notification::new(Severity::Error, title, error.to_string()))
+            }
+            Message::DialogResult(_result) => Task::none(),
+            Message::ChangeMode => {
+                // Nothing to run, the clocks don't start over an empty machine
+                if self.mode.is_none() && self.storage.used.is_empty() {
+                    log!(self, Level::Info, "load_programs_first");
+                    return Task::none();
+                }
+                // Load the stored files after a reset
+                let start = self.mode.is_none();
+                if self.mode == Some(Mode::Manual) {
+                    self.mode = Some(Mode::Automatic);
+                } else {
+                    self.mode = Some(Mode::Manual);
+                }
+                // Solo iniciar el tiempo total si no ha empezado
+                if self.total_start_time.is_none() {
+                    self.total_start_time = Some(Instant::now());
+                    self.start_time = Some(Instant::now());
+                    log!(self, Level::Info, "processing_started");
+                }
 
-                    if self.mode.is_none() {
-                        self.mode = Some(Mode::Manual);
+                // Iniciar el tiempo de cada proceso en estado `Ready`
+                for timing in self.diagram.iter_mut() {
+                    if timing.start.is_none() && timing.c_id.is_none() {
+                        timing.start = Some(Instant::now());
                     }
-
-                    // Update the CPU running process id
-                    *p = Some(pcb_id);
-
-                    // Mostrar mensaje en consola al iniciar el procesamiento de un proceso
-                    println!("Asignando proceso con ID: {} en CPU {}", pcb_id, cpu_index);
-                    
                 }
+                if start {
+                    self.scheduler_dirty = true;
+                    return Task::done(Message::Scheduler);
+                }
+                Task::none()
+            }
+            // The Scheduler of the OS, it will select the next process to execute and send it to the distpacher
+            Message::Scheduler => {
+                if !self.scheduler_due() {
+                    return Task::none();
+                }
+                if let Some(task) = self.admit() {
+                    return task;
+                }
+                match self.select_process() {
+                    Some((dispatch, true)) => Task::done(Message::Distpacher(dispatch))
+                        .chain(Task::done(Message::Scheduler)),
+                    Some((dispatch, false)) => Task::done(Message::Distpacher(dispatch)),
+                    None => Task::none(),
+                }
+            }
+            Message::Distpacher(dispatch) => {
+                self.dispatch(dispatch);
                 Task::none()
             }
             // Runs when a running process is done
             Message::Terminated(cpu_index) => {
-                // Select the running process
-                if let Some((cpu, id)) = self.cpus.get_mut(cpu_index) {
-                    if let Some(p_id) = id {
-                        if let Some((_, address, size)) =
-                            self.memory.pcb_table.iter().find(|x| x.0 == *p_id)
-                        {
-                            let mut pcb = PCB::from(&self.memory.data[*address..*address + *size]);
-                            // Mostrar mensaje en consola cuando el proceso finaliza
-                            println!(
-                                "Proceso con ID: {} ha finalizado en CPU {}",
-                                p_id, cpu_index
-                            );
-
-                        
-                            if let Some(start_time) = cpu.start_time {
-                                let duration = start_time.elapsed(); // Calcula el tiempo de ejecución
-                                if let Some(timing) = self.diagram.iter_mut().find(|x| x.p_id == *p_id) {
-                                    timing.execution = Some(duration); // Asigna `duration` a `timing.execution
-                                    timing.end_time = Some(Instant::now());
-                
-                                    let arrival_time = timing.arrival as f64;
-                                    let turnaround_time = timing.end_time.unwrap().duration_since(timing.start.unwrap());
-                                    let execution_time = timing.execution.unwrap();
-                                    let response_ratio = turnaround_time.as_secs_f64() / execution_time.as_secs_f64();
-
-                                    // Almacena los datos de estadísticas en stats_data
-                                    self.stats_data.push(ProcessStats {
-                                        process_id: *p_id,
-                                        cpu_id: cpu_index,
-                                        arrival_time,
-                                        turnaround_time: turnaround_time.as_secs_f64(),
-                                        execution_time: execution_time.as_secs_f64(),
-                                        response_ratio,
-                                    });
-
-                                    // Calcula el tiempo de estancia (Turnaround Time) como tiempo final - tiempo de llegada
-                                    if let Some(turnaround_time) = timing.end_time.unwrap().checked_duration_since(timing.start.unwrap()) {
-                                        println!(
-                                            "Turnaround para el proceso {}: {:.2} segundos",
-                                            p_id, turnaround_time.as_secs_f64()
-                                        );
-                
-                                        // Calcula T_r / T_s si `execution` está definido
-                                        if let Some(execution_time) = timing.execution {
-                                            let response_ratio = turnaround_time.as_secs_f64() / execution_time.as_secs_f64();
-                                            println!(
-                                                "Tiempo de ejecución: {:.2} segundos, Tr / Ts: {:.2}",
-                                                execution_time.as_secs_f64(),
-                                                response_ratio
-                                            );
-                                        }
-                                    }
-                                }
-                            }
-                            cpu.start_time = None; // Limpia el tiempo de inicio del proceso
-
-                            
-                            // Update PCB
-                            pcb.process_state = ProcessState::Terminated;
-                            pcb.ax = cpu.ax;
-                            pcb.bx = cpu.bx;
-                            pcb.cx = cpu.cx;
-                            pcb.dx = cpu.dx;
-                            pcb.ac = cpu.ac;
-                            pcb.pc = cpu.pc;
-                            pcb.sp = cpu.sp;
-                            pcb.ir = cpu.ir;
-                            pcb.z = cpu.z;
-                            // Save changes
-                            let bytes: Vec<u8> = pcb.into();
-                            self.memory.data[*address..*address + *size]
-                                .copy_from_slice(&bytes[..]);
-
-                            // Free memory
-                            let _ = self.memory.free_memory(pcb.code_segment);
-                            let _ = self.memory.free_memory(pcb.stack_segment);
-
-                            // Remove from pcb_table
-                            //self.memory.pcb_table.retain(|x| x.0 != *p_id);
-
-                            if let Some((_, p_id)) =
-                                self.loaded_files.iter_mut().find(|x| x.1 == Some(*p_id))
-                            {
-                                *p_id = None;
-                            }
-
-                            *id = None;
-                            *cpu = CPU::new();
-
-                            // Verificar si todos los procesos han terminado
-                            if self.cpus.iter().map(|x| x.1.is_none()).all(|x| x) {
-                                // Calcula el tiempo total acumulado sumando los tiempos de estancia de cada proceso
-                                let tiempo_total_acumulado: Duration = self.diagram.iter().filter_map(|timing| {
-                                    if let (Some(end_time), Some(start_time)) = (timing.end_time, timing.start) {
-                                        Some(end_time.duration_since(start_time))
-                                    } else {
-                                        None // Ignora procesos que no tienen tiempo de inicio o fin definido
-                                    }
-                                }).sum();
-
-                                // Imprime el tiempo total acumulado en segundos
-                                println!("Tiempo total: {:.2?} segundos", tiempo_total_acumulado.as_secs_f64());
-                            }
-                        }
-                    }
-                }
+                self.terminate(cpu_index);
                 Task::done(Message::Scheduler)
             }
             Message::Blocked(cpu_index) => {
-                // Select the running process
-                if let Some((cpu, Some(p_id))) = self.cpus.get(cpu_index) {
-                    if let Some((id, address, size)) =
-                        self.memory.pcb_table.iter().find(|x| x.0 == *p_id)
-                    {
-                        let mut pcb = PCB::from(&self.memory.data[*address..*address + *size]);
-                        // Update PCB
-                        pcb.process_state = ProcessState::Blocked;
-                        pcb.ax = cpu.ax;
-                        pcb.bx = cpu.bx;
-                        pcb.cx = cpu.cx;
-                        pcb.dx = cpu.dx;
-                        pcb.ac = cpu.ac;
-                        pcb.pc = cpu.pc;
-                        pcb.sp = cpu.sp;
-                        pcb.ir = cpu.ir;
-                        pcb.z = cpu.z;
-                        // Save changes
-                        let bytes: Vec<u8> = pcb.into();
-                        self.memory.data[*address..*address + *size].copy_from_slice(&bytes[..]);
-                        self.waiting_queue.push((*id, *address, *size));
+                self.block(cpu_index);
+                Task::done(Message::Scheduler)
+            }
+            Message::Fault(cpu_index, fault) => {
+                let message = self.fault(cpu_index, fault);
+                self.fault_dialog(message)
+                    .chain(Task::done(Message::Scheduler))
+            }
+            Message::Runaway(p_id, rfd::MessageDialogResult::Yes) => {
+                if self.kill(p_id) {
+                    Task::done(Message::Scheduler)
+                } else {
+                    Task::none()
+                }
+            }
+            Message::Runaway(..) => Task::none(),
+            Message::TickLimit(rfd::MessageDialogResult::Custom(answer)) => {
+                let lang = self.config.lang;
+                if answer == tr(lang, "tick_limit_continue") {
+                    self.limit_extension = self.counter;
+                    self.mode = Some(Mode::Automatic);
+                    Task::none()
+                } else if answer == tr(lang, "tick_limit_kill") {
+                    // Every process the dialog listed, on a CPU or not
+                    let unfinished = self
+                        .memory
+                        .pcbs()
+                        .iter()
+                        .filter(|x| x.process_state != ProcessState::Terminated)
+                        .map(|x| x.id)
+                        .collect::<Vec<_>>();
+                    for p_id in unfinished {
+                        if !self.kill(p_id) {
+                            self.kill_queued(p_id);
+                        }
                     }
+                    Task::done(Message::Scheduler)
+                } else {
+                    Task::done(Message::CopyLog)
                 }
-                Task::none()
             }
-            Message::Unblock => {
-                // Take the first process from the waiting queue if it's not empty
-                if let Some((_, address, size)) = self.waiting_queue.first() {
-                    // Tak the value from the display and store it on dx
-                    if let Ok(num) = self.display_content.parse::<u8>() {
-                        let mut pcb = PCB::from(&self.memory.data[*address..*address + *size]);
-
-                        pcb.dx = num;
-                        pcb.process_state = ProcessState::Ready;
-                        pcb.pc += 6;
-
-                        let bytes: Vec<u8> = pcb.into();
-                        self.memory.data[*address..*address + *size].copy_from_slice(&bytes[..]);
+            Message::TickLimit(_) => Task::none(),
+            Message::Unblock(id, num) => {
+                // The value goes to the process that asked for it, wherever it is on the queue
+                let Some(index) = self.waiting_queue.iter().position(|x| x.0 == id) else {
+                    return Task::none();
+                };
+                let pcb = self.memory.pcb(id);
+                let Some(mut pcb) = pcb.filter(|x| x.process_state != ProcessState::Terminated)
+                else {
+                    self.drop_input(id);
+                    return Task::none();
+                };
+                // The value has to be on the range declared by the program
+                if let Some((low, high)) = self.diagram.get(id).and_then(|x| x.input_range) {
+                    if !(low..=high).contains(&num) {
+                        log!(self, Level::Warning, "input_rejected", id, low, high, num);
+                        return Task::none();
+                    }
+                }
+                log!(self, Level::Info, "input_received", id, num);
+                pcb.dx = num;
+                self.memory.write_keyboard(num);
+                pcb.process_state = ProcessState::Ready;
+                pcb.pc += 6;
 
-                        self.waiting_queue.remove(0);
+                self.memory.write_pcb(pcb);
+                let counter = self.counter;
+                if let Some(timing) = self.diagram.get_mut(id) {
+                    timing.queued = Some(counter);
+                }
 
-                        return Task::done(Message::Scheduler);
-                    }
+                self.waiting_queue.remove(index);
+                self.input_values.remove(&id);
+                let tick = self.counter;
+                self.observe(|x| x.on_unblock(tick, id));
+                if self.config.io_boost {
+                    self.boosted.push(id);
                 }
+                self.scheduler_dirty = true;
+
+                Task::done(Message::Scheduler)
+            }
+            Message::StepBack => {
+                self.step_back();
                 Task::none()
             }
-            Message::Tick => {
-                for (cpu_i, (cpu, p)) in self.cpus.iter_mut().enumerate() {
-                    if p.is_some() {
-                        // Fetch instruction from memory
-                        let bytes = &self.memory.data[cpu.pc + 1..cpu.pc + 6];
-
-                        // Verify that it's a valid instruction
-                        if bytes[0] == 0 {
-                            //self.mode = None;
-                            return Task::done(Message::Terminated(cpu_i));
+            Message::TickCpu(cpu_index) => {
+                self.snapshot();
+                let events = self.tick_cpu(cpu_index);
+                let mut tasks = vec![self.runaway()];
+                for event in events {
+                    tasks.push(match event {
+                        TickEvent::Terminated(cpu_index) => {
+                            Task::done(Message::Terminated(cpu_index))
                         }
-                        let instruction = Instruction::from(bytes);
-
-                        // Decode and Execute
-                        cpu.ir = Some(instruction.operation);
-                        match instruction.operation {
-                            Operation::LOAD => {
-                                if let Operands::V2(r) = instruction.operands {
-                                    match r {
-                                        Register::AX => cpu.ac = cpu.ax,
-                                        Register::BX => cpu.ac = cpu.bx,
-                                        Register::CX => cpu.ac = cpu.cx,
-                                        Register::DX => cpu.ac = cpu.dx,
-                                    }
-                                }
-                            }
-                            Operation::STORE => {
-                                if let Operands::V2(r) = instruction.operands {
-                                    match r {
-                                        Register::AX => cpu.ax = cpu.ac,
-                                        Register::BX => cpu.bx = cpu.ac,
-                                        Register::CX => cpu.cx = cpu.ac,
-                                        Register::DX => cpu.dx = cpu.ac,
-                                    }
-                                }
-                            }
-                            Operation::MOV => match instruction.operands {
-                                Operands::V5(r, num) => match r {
-                                    Register::AX => cpu.ax = num,
-                                    Register::BX => cpu.bx = num,
-                                    Register::CX => cpu.cx = num,
-                                    Register::DX => cpu.dx = num,
-                                },
-                                Operands::V6(r1, r2) => match r1 {
-                                    Register::AX => match r2 {
-                                        Register::BX => cpu.ax = cpu.bx,
-                                        Register::CX => cpu.ax = cpu.cx,
-                                        Register::DX => cpu.ax = cpu.dx,
-                                        _ => {}
-                                    },
-                                    Register::BX => match r2 {
-                                        Register::AX => cpu.bx = cpu.ax,
-                                        Register::CX => cpu.bx = cpu.cx,
-                                        Register::DX => cpu.bx = cpu.dx,
-                                        _ => {}
-                                    },
-                                    Register::CX => match r2 {
-                                        Register::AX => cpu.cx = cpu.ax,
-                                        Register::BX => cpu.cx = cpu.bx,
-                                        Register::DX => cpu.cx = cpu.dx,
-                                        _ => {}
-                                    },
-                                    Register::DX => match r2 {
-                                        Register::AX => cpu.dx = cpu.ax,
-                                        Register::BX => cpu.dx = cpu.bx,
-                                        Register::CX => cpu.dx = cpu.cx,
-                                        _ => {}
-                                    },
-                                },
-                                _ => {}
-                            },
-                            Operation::ADD => {
-                                if let Operands::V2(r) = instruction.operands {
-                                    match r {
-                                        Register::AX => cpu.ac += cpu.ax,
-                                        Register::BX => cpu.ac += cpu.bx,
-                                        Register::CX => cpu.ac += cpu.cx,
-                                        Register::DX => cpu.ac += cpu.dx,
-                                    }
-                                }
-                            }
-                            Operation::SUB => {
-                                if let Operands::V2(r) = instruction.operands {
-                                    match r {
-                                        Register::AX => cpu.ac -= cpu.ax,
-                                        Register::BX => cpu.ac -= cpu.bx,
-                                        Register::CX => cpu.ac -= cpu.cx,
-                                        Register::DX => cpu.ac -= cpu.dx,
-                                    }
-                                }
-                            }
-                            Operation::INC => match instruction.operands {
-                                Operands::V0 => cpu.ac += 1,
-                                Operands::V2(r) => match r {
-                                    Register::AX => cpu.ac += cpu.ax,
-                                    Register::BX => cpu.ac += cpu.bx,
-                                    Register::CX => cpu.ac += cpu.cx,
-                                    Register::DX => cpu.ac += cpu.dx,
-                                },
-                                _ => {}
-                            },
-                            Operation::DEC => match instruction.operands {
-                                Operands::V0 => cpu.ac -= 1,
-                                Operands::V2(r) => match r {
-                                    Register::AX => cpu.ac -= cpu.ax,
-                                    Register::BX => cpu.ac -= cpu.bx,
-                                    Register::CX => cpu.ac -= cpu.cx,
-                                    Register::DX => cpu.ac -= cpu.dx,
-                                },
-                                _ => {}
-                            },
-                            Operation::SWAP => {
-                                if let Operands::V6(r1, r2) = instruction.operands {
-                                    match r1 {
-                                        Register::AX => match r2 {
-                                            Register::BX => mem::swap(&mut cpu.ax, &mut cpu.bx),
-                                            Register::CX => mem::swap(&mut cpu.ax, &mut cpu.cx),
-                                            Register::DX => mem::swap(&mut cpu.ax, &mut cpu.dx),
-                                            _ => {}
-                                        },
-                                        Register::BX => match r2 {
-                                            Register::AX => mem::swap(&mut cpu.bx, &mut cpu.ax),
-                                            Register::CX => mem::swap(&mut cpu.bx, &mut cpu.cx),
-                                            Register::DX => mem::swap(&mut cpu.bx, &mut cpu.dx),
-                                            _ => {}
-                                        },
-                                        Register::CX => match r2 {
-                                            Register::AX => mem::swap(&mut cpu.cx, &mut cpu.ax),
-                                            Register::BX => mem::swap(&mut cpu.cx, &mut cpu.bx),
-                                            Register::DX => mem::swap(&mut cpu.cx, &mut cpu.dx),
-                                            _ => {}
-                                        },
-                                        Register::DX => match r2 {
-                                            Register::AX => mem::swap(&mut cpu.dx, &mut cpu.ax),
-                                            Register::BX => mem::swap(&mut cpu.dx, &mut cpu.bx),
-                                            Register::CX => mem::swap(&mut cpu.dx, &mut cpu.cx),
-                                            _ => {}
-                                        },
-                                    }
-                                }
-                            }
-                            Operation::INT => {
-                                if let Operands::V3(i) = instruction.operands {
-                                    match i {
-                                        Interupt::H20 => {
-                                            //self.mode = None;
-                                            return Task::done(Message::Terminated(cpu_i));
-                                        }
-                                        Interupt::H10 => self.display_content = cpu.dx.to_string(),
-                                        Interupt::H09 => {
-                                            //self.mode = None;
-                                            return Task::done(Message::Blocked(cpu_i));
-                                        }
-                                    }
-                                }
-                            }
-                            Operation::JMP => {
-                                if let Operands::V1(s, num) = instruction.operands {
-                                    match s {
-                                        0 => cpu.pc += (7 * num) as usize,
-                                        1 => cpu.pc -= (7 * num) as usize,
-                                        _ => {}
-                                    }
-                                }
-                            }
-                            Operation::JE => {
-                                if cpu.z {
-                                    if let Operands::V1(s, num) = instruction.operands {
-                                        match s {
-                                            0 => cpu.pc += (7 * num) as usize,
-                                            1 => cpu.pc -= (7 * num) as usize,
-                                            _ => {}
-                                        }
-                                    }
-                                }
-                            }
-                            Operation::JNE => {
-                                if !cpu.z {
-                                    if let Operands::V1(s, num) = instruction.operands {
-                                        match s {
-                                            0 => cpu.pc += (7 * num) as usize,
-                                            1 => cpu.pc -= (7 * num) as usize,
-                                            _ => {}
-                                        }
-                                    }
-                                }
-                            }
-                            Operation::PUSH => {
-                                if let Operands::V2(r) = instruction.operands {
-                                    match r {
-                                        Register::AX => {
-                                            self.memory.data[cpu.sp] = cpu.ax;
-                                            cpu.sp += 1;
-                                        }
-                                        Register::BX => {
-                                            self.memory.data[cpu.sp] = cpu.bx;
-                                            cpu.sp += 1;
-                                        }
-                                        Register::CX => {
-                                            self.memory.data[cpu.sp] = cpu.cx;
-                                            cpu.sp += 1;
-                                        }
-                                        Register::DX => {
-                                            self.memory.data[cpu.sp] = cpu.dx;
-                                            cpu.sp += 1;
-                                        }
-                                    }
-                                }
-                            }
-                            Operation::POP => {
-                                if let Operands::V2(r) = instruction.operands {
-                                    match r {
-                                        Register::AX => {
-                                            cpu.ax = self.memory.data[cpu.sp];
-                                            cpu.sp -= 1;
-                                        }
-                                        Register::BX => {
-                                            cpu.bx = self.memory.data[cpu.sp];
-                                            cpu.sp -= 1;
-                                        }
-                                        Register::CX => {
-                                            cpu.cx = self.memory.data[cpu.sp];
-                                            cpu.sp -= 1;
-                                        }
-                                        Register::DX => {
-                                            cpu.dx = self.memory.data[cpu.sp];
-                                            cpu.sp -= 1;
-                                        }
-                                    }
-                                }
-                            }
-                            Operation::PARAM => {
-                                if let Operands::V4(p1, p2, p3) = instruction.operands {
-                                    if p1 != 0 {
-                                        self.memory.data[cpu.sp] = p1;
-                                        cpu.sp += 1;
-                                    }
-                                    if p2 != 0 {
-                                        self.memory.data[cpu.sp] = p2;
-                                        cpu.sp += 1;
-                                    }
-                                    if p3 != 0 {
-                                        self.memory.data[cpu.sp] = p3;
-                                        cpu.sp += 1;
-                                    }
-                                }
-                            }
-                            Operation::CMP => {
-                                if let Operands::V6(r1, r2) = instruction.operands {
-                                    match r1 {
-                                        Register::AX => match r2 {
-                                            Register::BX => cpu.z = cpu.ax == cpu.bx,
-                                            Register::CX => cpu.z = cpu.ax == cpu.cx,
-                                            Register::DX => cpu.z = cpu.ax == cpu.dx,
-                                            _ => {}
-                                        },
-                                        Register::BX => match r2 {
-                                            Register::AX => cpu.z = cpu.bx == cpu.ax,
-                                            Register::CX => cpu.z = cpu.bx == cpu.cx,
-                                            Register::DX => cpu.z = cpu.bx == cpu.dx,
-                                            _ => {}
-                                        },
-                                        Register::CX => match r2 {
-                                            Register::AX => cpu.z = cpu.cx == cpu.ax,
-                                            Register::BX => cpu.z = cpu.cx == cpu.bx,
-                                            Register::DX => cpu.z = cpu.cx == cpu.dx,
-                                            _ => {}
-                                        },
-                                        Register::DX => match r2 {
-                                            Register::AX => cpu.z = cpu.dx == cpu.ax,
-                                            Register::BX => cpu.z = cpu.dx == cpu.bx,
-                                            Register::CX => cpu.z = cpu.dx == cpu.cx,
-                                            _ => {}
-                                        },
-                                    }
-                                }
-                            }
+                        TickEvent::Blocked(cpu_index) => Task::done(Message::Blocked(cpu_index)),
+                        TickEvent::Fault(cpu_index, fault) => {
+                            Task::done(Message::Fault(cpu_index, fault))
                         }
-
-                        if let Some(timing) = self.diagram.iter_mut().find(|x| Some(x.p_id) == *p) {
-                            timing.remaining_burst -= 1;
-                            timing.execution = Some(timing.start.unwrap().elapsed());
+                        TickEvent::Breakpoint(cpu_index) => {
+                            self.breakpoint(cpu_index);
+                            Task::none()
                         }
-
-                        cpu.pc += 6;
-                    }
+                        TickEvent::QuantumExpired => Task::none(),
+                    });
                 }
-                self.counter += 1;
-
-                if let Some(quantum) = self.quantum {
-                    if self.counter % (quantum as u64) == 0 && self.counter != 0 {
-                        //println!("======== 3 ========");
-                        return Task::done(Message::Scheduler);
-                    }
+                Task::batch(tasks)
+            }
+            Message::Tick if self.turbo => self.turbo(),
+            // Automatic mode, run and turbo handle the events of a tick in the same `step`
+            Message::Tick => {
+                let (_, task) = self.step();
+                Task::batch([task, self.tick_limit()])
+            }
+            Message::NewProgram => {
+                self.editor = Some(Editor::default());
+                Task::none()
+            }
+            Message::EditFile(address) => {
+                if let Some((file_name, _, _)) = self.storage.used.iter().find(|x| x.1 == address) {
+                    let content =
+                        String::from_utf8_lossy(self.storage.file(address).unwrap_or(&[]))
+                            .to_string();
+                    self.editor = Some(Editor {
+                        content: text_editor::Content::with_text(&content),
+                        name: file_name.trim_matches('"').to_string(),
+                        errors: vec![],
+                        address: Some(address),
+                    });
                 }
                 Task::none()
             }
-            Message::Input(mut input) => {
-                input.retain(|c| c.is_numeric());
-                if input.len() <= 3 {
-                    self.display_content = input;
+            Message::EditorAction(action) => {
+                if let Some(editor) = &mut self.editor {
+                    editor.content.perform(action);
                 }
                 Task::none()
             }
-            Message::SchedulerSelected(scheduler) => {
-                if self.mode.is_none() {
-                    self.config.scheduler = Some(scheduler);
-                } else {
-                    println!("No se puede cambiar el planificador mientras el emulador está en ejecución.");
-                    rfd::MessageDialog::new()
-                        .set_level(rfd::MessageLevel::Warning)
-                        .set_title("Cambio de Planificador")
-                        .set_description("No se puede cambiar el planificador mientras el emulador está en ejecución.")
-                        .set_buttons(rfd::MessageButtons::Ok)
-                        .show();
+            Message::EditorName(name) => {
+                if let Some(editor) = &mut self.editor {
+                    editor.name = name;
                 }
                 Task::none()
             }
-            Message::QuantumSelected(quantum) => {
-                match self.config.scheduler {
-                    Some(Scheduler::RR) => {
-                        self.quantum = Some(quantum);
-                    }
-                    _ => {}
+            Message::AssembleProgram => self.assemble_program(),
+            Message::CloseEditor => {
+                self.editor = None;
+                Task::none()
+            }
+            Message::ScrollToProcess(pcb_id) => match self.memory.pcb(pcb_id) {
+                Some(pcb) => {
+                    let y = (pcb.code_segment / 8) as f32 * ROW_HEIGHT;
+                    scrollable::scroll_to(
+                        scrollable::Id::new(MEMORY_SCROLL),
+                        scrollable::AbsoluteOffset { x: 0.0, y },
+                    )
                 }
+                None => Task::none(),
+            },
+            Message::SelectFile(address) => {
+                self.selected_file = Some(address);
+                let y = (address / 8) as f32 * ROW_HEIGHT;
+                scrollable::scroll_to(
+                    scrollable::Id::new(STORAGE_SCROLL),
+                    scrollable::AbsoluteOffset { x: 0.0, y },
+                )
+            }
+            Message::MemoryScrolled(viewport) => {
+                self.memory_view = ScrollView::from_viewport(viewport);
                 Task::none()
             }
-            Message::TickScheduler => {
-                Task::done(Message::Tick).chain(Task::done(Message::Scheduler))
+            Message::StorageScrolled(viewport) => {
+                self.storage_view = ScrollView::from_viewport(viewport);
+                Task::none()
+            }
+            Message::MemoryRowsSelected(mode) => {
+                self.memory_rows = mode;
+                Task::none()
+            }
+            Message::FilesWidth(width) => {
+                self.files_width = width.clamp(FILES_WIDTHS.0, FILES_WIDTHS.1);
+                Task::none()
+            }
+            Message::MemoryWidth(width) => {
+                self.memory_width = width.clamp(MEMORY_WIDTHS.0, MEMORY_WIDTHS.1);
+                Task::none()
+            }
+            Message::StorageRowsSelected(mode) => {
+                self.storage_rows = mode;
+                Task::none()
+            }
+            Message::ToggleAscii(show) => {
+                self.show_ascii = show;
+                Task::none()
+            }
+            Message::ToggleDecimal(decimal) => {
+                self.decimal = decimal;
+                Task::none()
+            }
+            Message::LangSelected(lang) => {
+                self.config.lang = lang;
+                Task::none()
+            }
+            Message::ThemeSelected(theme) => {
+                self.theme = theme;
+                Task::none()
+            }
+            Message::CloseRequested(id) => {
+                if !self.unsaved() {
+                    return self.close(id);
+                }
+                let lang = self.config.lang;
+                let dialog = rfd::AsyncMessageDialog::new()
+                    .set_level(rfd::MessageLevel::Warning)
+                    .set_title(tr(lang, "close_title"))
+                    .set_description(tr(lang, "close_description"))
+                    .set_buttons(rfd::MessageButtons::YesNoCancel)
+                    .show();
+                Task::perform(dialog, move |x| Message::CloseConfirmed(id, x))
+            }
+            Message::CloseConfirmed(id, rfd::MessageDialogResult::Yes) => {
+                let log = self.log.to_text(self.log_level);
+                iced::clipboard::write(log).chain(self.close(id))
+            }
+            Message::CloseConfirmed(id, rfd::MessageDialogResult::No) => self.close(id),
+            Message::CloseConfirmed(..) => Task::none(),
+            Message::ToggleLog => {
+                self.show_log = !self.show_log;
+                Task::none()
+            }
+            Message::ToggleAllocator => {
+                self.show_allocator = !self.show_allocator;
+                Task::none()
+            }
+            Message::DumpAllocator => {
+                for (table, rows) in self.allocator_tables() {
+                    let rows = rows
+                        .iter()
+                        .map(|x| x.line.clone())
+                        .collect::<Vec<_>>()
+                        .join(", ");
+                    log!(
+                        self,
+                        Level::Info,
+                        "allocator_dump",
+                        tr(self.config.lang, table),
+                        rows
+                    );
+                }
+                Task::none()
+            }
+            Message::LogLevelSelected(level) => {
+                self.log_level = level;
+                Task::none()
+            }
+            Message::CopyLog => {
+                self.log_copied = Some(self.counter);
+                iced::clipboard::write(self.log.to_text(self.log_level))
+            }
+            Message::ToggleTurbo(turbo) => {
+                self.turbo = turbo;
+                self.turbo_executed = 0;
+                Task::none()
+            }
+            Message::SpeedSelected(speed) => {
+                self.speed = speed;
+                Task::none()
+            }
+            Message::SelectPcb(pcb_id) => {
+                if self.selected_pcb == Some(pcb_id) {
+                    self.selected_pcb = None;
+                } else {
+                    self.selected_pcb = Some(pcb_id);
+                }
+                Task::none()
+            }
+            Message::SetPriority(pcb_id, priority) => {
+                self.set_priority(pcb_id, priority);
+                Task::none()
+            }
+            Message::ToggleBreakpoint(pcb_id, index) => {
+                if let Some(position) = self.breakpoints.iter().position(|x| *x == (pcb_id, index))
+                {
+                    self.breakpoints.remove(position);
+                } else {
+                    self.breakpoints.push((pcb_id, index));
+                }
+                Task::none()
+            }
+            Message::RunToEvent => {
+                let (task, event) = self.run(MAX_RUN_TICKS, true);
+                if event || self.cpus.iter().all(|x| x.is_free()) {
+                    return task;
+                }
+                let lang = self.config.lang;
+                let notification = Notification::new(
+                    Severity::Warning,
+                    tr(lang, "run_to_event"),
+                    trf(lang, "no_event", &[&MAX_RUN_TICKS]),
+                );
+                task.chain(self.notify(notification))
+            }
+            Message::RunTicks => match self.run_ticks.parse::<usize>() {
+                Ok(ticks) => self.run(ticks.min(MAX_RUN_TICKS), false).0,
+                Err(_) => Task::none(),
+            },
+            Message::RunTicksInput(mut input) => {
+                input.retain(|c| c.is_numeric());
+                if input.len() <= 5 {
+                    self.run_ticks = input;
+                }
+                Task::none()
+            }
+            Message::Input(id, mut input) => {
+                input.retain(|c| c.is_numeric());
+                if input.len() <= 3 {
+                    self.input_values.insert(id, input);
+                }
+                Task::none()
+            }
+            Message::SchedulerSelected(scheduler) => {
+                if self.mode.is_none() {
+                    self.config.scheduler = Some(scheduler);
+                } else if self.config.scheduler != Some(scheduler) {
+                    // Switching in the middle of a run needs a confirmation
+                    self.switch_scheduler = Some(scheduler);
+                }
+                Task::none()
+            }
+            Message::QuantumInput(mut input) => {
+                if !self.uses_quantum() {
+                    return Task::none();
+                }
+                input.retain(|c| c.is_numeric());
+                if input.len() <= 3 {
+                    if let Some(quantum) = parse_quantum(&input) {
+                        // In the middle of a run the current quantum is finished first
+                        if self.mode.is_some() {
+                            self.next_quantum = Some(quantum);
+                        } else {
+                            self.quantum = Some(quantum);
+                        }
+                    }
+                    self.quantum_input = input;
+                }
+                Task::none()
             }
         }
-    }
-
-    fn view(&self) -> iced::Element<'_, Message> {
-        let mut play_button = if self.mode == Some(Mode::Manual) {
-            button("Play")
-        } else if self.mode == Some(Mode::Automatic) {
-            button("Pause")
-        } else {
-            button("Play/Pause")
-        };
-
-        let mut next_button = button("Next");
-        let stats_button = button("Stats").on_press(Message::StatsPressed);
-        let reset_button = button("Reset").on_press(Message::ResetPressed);
-        if self.mode == Some(Mode::Manual) {
-            next_button = next_button.on_press(Message::Tick);
-        }
-        if self.mode.is_some() {
-            play_button = play_button.on_press(Message::ChangeMode);
-            
-        }
-        //Stats display
-        if self.show_stats {
-            let scheduler_text = match self.config.scheduler {
-                Some(Scheduler::RR) => rich_text([
-                    span("Método seleccionado es: "),
-                    span("Round Robin").size(22).color(color!(0x9E69E3)),
-                    span(format!(" (Quantum: {})", self.quantum.unwrap_or_default())).size(18).color(color!(0xFFD700)), // Muestra el quantum
-                ]),
-                Some(scheduler) => rich_text([
-                    span("Método seleccionado es: "),
-                    span(scheduler.to_string()).size(22).color(color!(0x9E69E3)),
-                ]),
-                None => rich_text([span("No hay método seleccionado.")]),
-            };
-        
-        
-            // Inicia la construcción del bloque de estadísticas
-            let mut stats_view = column![
-                container(text("Sección de Estadísticas del Sistema").size(30),)
-                    .padding(10)
-                    .style(container::rounded_box)
-                    .width(iced::Length::Fill)
-                    .center_x(iced::Length::Fill),
-                widget::Space::with_height(iced::Length::Fixed(20.0)),
-                scheduler_text,
-                widget::Space::with_height(iced::Length::Fixed(20.0)),
-            ];
-        
-            // Añade cada estadística individualmente en el `stats_view`
-            for stat in &self.stats_data {
-                stats_view = stats_view.push(column![
-                    text(format!("\nProceso con ID: {} en CPU {}", stat.process_id, stat.cpu_id)),
-                    text(format!("\n    Tiempo de llegada: {:.2} segundos", stat.arrival_time)),
-                    text(format!("\n    Turnaround {}: {:.2} segundos", stat.process_id, stat.turnaround_time)),
-                    text(format!("\n    Tiempo de ejecución: {:.2} segundos\n\n     Tr / Ts: {:.2}", stat.execution_time, stat.response_ratio)),
-                    widget::Space::with_height(iced::Length::Fixed(10.0)), // Espacio entre procesos
-                ]);
-            }
-        
-            // Suma el tiempo total de turnaround y añade al final del `stats_view`
-            let tiempo_total: f64 = self.stats_data.iter().map(|stat| stat.turnaround_time).sum();
-            stats_view = stats_view.push(text(format!("Tiempo total: {:.2} segundos", tiempo_total)));
-        
-            // Añade el botón para regresar
-            stats_view = stats_view.push(row![
-                widget::Space::with_width(iced::Length::Fill),
-                button("Volver")
-                    .on_press(Message::StatsPressed)
-                    .width(iced::Length::Shrink),
-            ]);
-        
-            // Retorna el `stats_view` dentro de un contenedor `scrollable`
-            return container(scrollable(stats_view))
-                .width(iced::Length::Fill)
-                .height(iced::Length::Fill)
-                .into();
-        }
-        
-        
-        // Menu bar
-        let menu_bar = row![
-            button("File").on_press(Message::OpenFile),
-            play_button,
-            next_button,
-            reset_button,
-            stats_button,
-            pick_list(
-                [
-                    Scheduler::FCFS,
-                    Scheduler::SRT,
-                    Scheduler::SJF,
-                    Scheduler::RR,
-                    Scheduler::HRRN,
+    }
+
+    // Store each program of the file on its own
+    fn store_file(&mut self, file_name: String, data: Vec<u8>) -> Result<(), Error> {
+        let programs = match split_programs(&data) {
+            Ok(programs) => programs
+                .into_iter()
+                .map(|(program, source)| match program {
+                    Some(program) => (program_name(&file_name, &program), source.into_bytes()),
+                    None => (file_name.clone(), source.into_bytes()),
+                })
+                .collect(),
+            // Invalid files are reported when their PCB is created
+            Err(_) => vec![(file_name, data)],
+        };
+        self.scheduler_dirty = true;
+        let stored = programs
+            .into_iter()
+            .try_for_each(|(file_name, data)| self.storage.store_files(&file_name, data));
+        self.check_usage();
+        self.save_recovery();
+        stored?;
+        self.restart_clock();
+        Ok(())
+    }
+
+    // Files stored after every process terminated start a new run, its time doesn't include
+    // the idle time since the last one completed
+    fn restart_clock(&mut self) {
+        if self.completed.take().is_none() {
+            return;
+        }
+        self.show_summary = false;
+        let now = self.mode.as_ref().map(|_| Instant::now());
+        self.total_start_time = now;
+        self.start_time = now;
+    }
+
+    // Store the files of a folder, the ones that can't be stored don't stop the rest and are
+    // reported on the summary
+    fn store_batch(&mut self, files: Vec<(String, Vec<u8>)>, failures: Vec<(String, Error)>) {
+        let lang = self.config.lang;
+        let mut batch = failures
+            .iter()
+            .map(|(file_name, error)| trf(lang, "batch_not_read", &[file_name, error]))
+            .collect::<Vec<_>>();
+        for (file_name, data) in files {
+            if let Err(error) = self.store_file(file_name.clone(), data) {
+                batch.push(trf(lang, "batch_not_stored", &[&file_name, &error]));
+            }
+        }
+        self.batch = Some(batch);
+    }
+
+    // Run the stored files until every process terminates, without the message loop
+    fn run_batch(&mut self) {
+        self.mode = Some(Mode::Automatic);
+        self.total_start_time = Some(Instant::now());
+        self.start_time = Some(Instant::now());
+        log!(self, Level::Info, "processing_started");
+        // The admission failures are only reported, there is no dialog to wait for
+        while self.admit().is_some() {}
+        let _ = self.schedule();
+        // The same limit as the automatic mode stops a program that never terminates
+        while !self.limit_reached() {
+            // Nothing left to execute, like processes waiting for an input that never comes
+            if self.completed.is_some()
+                || (self.cpus.iter().all(|x| x.is_free()) && !self.arriving())
+            {
+                break;
+            }
+            let _ = self.step();
+        }
+        let pcbs = self.memory.pcbs();
+        if pcbs
+            .iter()
+            .any(|x| x.process_state != ProcessState::Terminated)
+        {
+            let lang = self.config.lang;
+            let mut lines = vec![trf(lang, "batch_stopped", &[&self.counter])];
+            if self.limit_reached() {
+                for pcb in pcbs
+                    .iter()
+                    .filter(|x| x.process_state != ProcessState::Terminated)
+                {
+                    let name = self.diagram.get(pcb.id).map_or("", |x| &x.name);
+                    lines.push(trf(lang, "batch_timed_out", &[&short_name(name), &pcb.id]));
+                }
+            }
+            if let Some(batch) = &mut self.batch {
+                batch.extend(lines);
+            }
+        }
+    }
+
+    // Result of each program of the batch
+    fn batch_results(&self) -> Vec<String> {
+        let lang = self.config.lang;
+        let Some(failures) = &self.batch else {
+            return vec![];
+        };
+        let mut lines = vec![tr(lang, "batch_title").to_string()];
+        for stats in &self.stats_data {
+            // Faults like a failed ASSERT make the program fail
+            if let Some(fault) = stats.fault {
+                let name = short_name(&stats.name);
+                lines.push(trf(lang, "batch_fault", &[&name, &tr(lang, fault.key())]));
+                continue;
+            }
+            lines.push(trf(
+                lang,
+                "batch_ok",
+                &[
+                    &short_name(&stats.name),
+                    &stats.cpu_id,
+                    &format!("{:.2}", stats.turnaround_time),
                 ],
-                self.config.scheduler,
-                Message::SchedulerSelected
+            ));
+        }
+        lines.extend(failures.iter().cloned());
+        lines
+    }
+
+    // A program of the batch terminated with a fault or timed out
+    fn batch_failed(&self) -> bool {
+        let unfinished = self
+            .memory
+            .pcbs()
+            .iter()
+            .any(|x| x.process_state != ProcessState::Terminated);
+        self.stats_data.iter().any(|x| x.fault.is_some()) || (unfinished && self.limit_reached())
+    }
+
+    // A run is in progress, or it completed and its log was never copied
+    fn unsaved(&self) -> bool {
+        match self.completed {
+            Some((ticks, _)) => self.log_copied.is_none_or(|x| x < ticks),
+            None => self.counter > 0,
+        }
+    }
+
+    // Save the UI preferences and close the window
+    fn close(&mut self, id: window::Id) -> Task<Message> {
+        if let Some(path) = UiState::path() {
+            if let Err(error) = self.ui_state().save(&path) {
+                log!(self, Level::Warning, "ui_state_not_saved", error);
+            }
+        }
+        // Nothing to recover after a clean exit
+        self.remove_recovery();
+        window::close(id)
+    }
+
+    // Clear the state of the execution, the stored files are loaded again by the scheduler
+    fn reset(&mut self) {
+        self.memory = Memory::from_config(&self.config);
+        self.quantum_start.clear();
+        self.cpus = vec![Core::default(); self.config.cpu_quantity];
+        self.history = vec![History::new(); self.config.cpu_quantity];
+        self.last_registers = vec![CPU::new(); self.config.cpu_quantity];
+        self.usage = vec![CpuUsage::new(); self.config.cpu_quantity];
+        self.show_history = vec![false; self.config.cpu_quantity];
+        self.cache = Cache::new(self.config.cache);
+        self.dma.clear();
+        self.post_mortems.clear();
+        self.trace.clear();
+        self.limit_extension = 0;
+        // The stored files stay
+        self.warned_usage.retain(|x| x.0 == UsageRegion::Storage);
+        if let Some(rng) = self.arrival_policy.rng_mut() {
+            rng.clear();
+        }
+        self.mode = None;
+        self.display_content = "".to_string();
+        self.cpu_output.clear();
+        self.input_values.clear();
+        self.boosted.clear();
+        self.waiting_queue = vec![];
+        self.loaded_files = vec![];
+        self.pending_files = vec![];
+        self.diagram = Diagram::default();
+        self.stats_data = vec![];
+        self.start_time = None;
+        self.total_start_time = None;
+        self.completed = None;
+        self.show_summary = false;
+        self.faults.clear();
+        self.snapshots.clear();
+        self.batch = None;
+        self.scheduler_last = None;
+        self.scheduler_calls = (0, 0, 0);
+        self.decision = None;
+        self.log_copied = None;
+        self.counter = 0;
+        self.breakpoints = vec![];
+        self.breakpoint_hit = None;
+        self.selected_pcb = None;
+        self.selected_file = None;
+        self.flash_pcb = None;
+        self.arrived = vec![];
+        self.log.clear();
+        self.confirm_reset = false;
+    }
+
+    // Use a new config, the memory, storage and CPUs are created again so it's only allowed
+    // when nothing has been loaded
+    fn apply_config(&mut self, config: Config) -> bool {
+        if self.mode.is_some() || !self.storage.used.is_empty() || !self.memory.pcb_table.is_empty()
+        {
+            log!(self, Level::Warning, "settings_loaded");
+            self.show_log = true;
+            return false;
+        }
+        self.config = config;
+        self.reset();
+        self.storage = Storage::new(self.config.storage);
+        self.warned_usage.clear();
+        self.quantum = Some(self.config.quantum);
+        self.quantum_input = self.config.quantum.to_string();
+        self.next_quantum = None;
+        self.switch_scheduler = None;
+        self.arrival_policy = ArrivalPolicy::seeded(self.config.seed);
+        true
+    }
+
+    // Current UI preferences
+    fn ui_state(&self) -> UiState {
+        UiState {
+            theme: self.theme.to_string(),
+            show_stats: self.show_stats,
+            speed: self.speed,
+            files_width: self.files_width,
+            memory_width: self.memory_width,
+            memory_rows: self.memory_rows,
+            storage_rows: self.storage_rows,
+            show_ascii: self.show_ascii,
+            decimal: self.decimal,
+        }
+    }
+
+    // Processes waiting for a CPU in the order the scheduler picks them
+    fn ready_queue(&self) -> Vec<usize> {
+        let mut queue = self
+            .memory
+            .pcbs()
+            .iter()
+            .filter_map(|pcb| {
+                let ready = pcb.process_state == ProcessState::Ready;
+                // Processes on a CPU are dispatched again only after a context switch
+                if ready && !self.cpus.iter().any(|x| x.assigned == Some(pcb.id)) {
+                    Some(pcb.id)
+                } else {
+                    None
+                }
+            })
+            .collect::<Vec<usize>>();
+
+        let timing = |id: &usize| self.diagram.get(*id);
+        let estimation = self.config.burst_estimation;
+        match self.config.scheduler {
+            Some(Scheduler::FCFS) => {
+                queue.sort_by_key(|id| timing(id).map(|x| (x.arrival, x.p_id)))
+            }
+            Some(Scheduler::SRT) => {
+                queue.sort_by_key(|id| timing(id).map(|x| x.expected_remaining(estimation)))
+            }
+            Some(Scheduler::SJF) => queue.sort_by_key(|id| {
+                timing(id).map(|x| (x.expected_burst(estimation), x.arrival, x.p_id))
+            }),
+            Some(Scheduler::Priority) => queue.sort_by_key(|id| {
+                let priority = self.memory.pcb(*id).map(|x| x.priority);
+                (priority, timing(id).map(|x| x.queue_key()))
+            }),
+            Some(Scheduler::RR) => queue.sort_by_key(|id| timing(id).map(|x| x.queue_key())),
+            _ => {}
+        }
+        queue
+    }
+
+    // Running processes with their CPU and the ticks spent on the current quantum
+    fn running_queue(&self) -> Vec<(usize, usize, Option<u64>)> {
+        self.cpus
+            .iter()
+            .enumerate()
+            .filter_map(|(cpu_index, core)| {
+                core.assigned.map(|id| {
+                    (
+                        cpu_index,
+                        id,
+                        self.quantum
+                            .filter(|_| self.uses_quantum())
+                            .and_then(|_| self.quantum_start.get(&cpu_index))
+                            .map(|start| self.counter - start),
+                    )
+                })
+            })
+            .collect()
+    }
+
+    // Show the last executed instruction with its operands when it's known, after a context
+    // switch only the operation restored from the PCB is
+    fn ir_label(&self, cpu_i: usize) -> String {
+        let Core { cpu, assigned, .. } = &self.cpus[cpu_i];
+        match self.history[cpu_i].newest_first().next() {
+            Some(entry) if Some(entry.pid) == *assigned => {
+                self.source_label(entry.pid, entry.pc, entry.instruction)
+            }
+            _ => match cpu.ir {
+                Some(operation) => format!("{}", operation),
+                None => "None".to_string(),
+            },
+        }
+    }
+
+    // "line 12: JNE -3" when the source of the process is known
+    fn source_label(&self, pid: usize, pc: usize, instruction: Instruction) -> String {
+        match self.memory.source_line(pid, pc) {
+            Some((line, _)) => trf(self.config.lang, "source_line", &[&line, &instruction]),
+            None => instruction.to_string(),
+        }
+    }
+
+    // (ticks left, quantum) of the process on the CPU, only Round Robin uses the quantum
+    fn quantum_left(&self, cpu_index: usize) -> Option<(u64, u64)> {
+        let quantum = self.quantum.filter(|_| self.uses_quantum());
+        quantum_left(quantum, self.quantum_start.get(&cpu_index), self.counter)
+    }
+
+    // Blocked processes with the reason they are waiting
+    fn blocked_queue(&self) -> Vec<(usize, &'static str)> {
+        self.memory
+            .pcbs()
+            .iter()
+            .filter_map(|pcb| {
+                if self.waiting_queue.iter().any(|x| x.0 == pcb.id) {
+                    Some((pcb.id, "awaiting_input"))
+                } else if pcb.process_state == ProcessState::Blocked {
+                    Some((pcb.id, "blocked"))
+                } else {
+                    None
+                }
+            })
+            .collect()
+    }
+
+    // Select the next process to execute and keep why it was picked
+    fn select_process(&mut self) -> Option<Dispatch> {
+        let dispatch = self.pick_process()?;
+        let decision = self.explain(dispatch.0 .1 .0);
+        log!(
+            self,
+            Level::Debug,
+            "scheduler_decision",
+            decision.text(self.config.lang)
+        );
+        self.decision = Some(decision);
+        Some(dispatch)
+    }
+
+    // Processes waiting for a CPU with the key of the scheduler, to explain why `chosen` goes
+    // before the rest of them
+    fn explain(&self, chosen: usize) -> Decision {
+        let scheduler = self.config.scheduler.unwrap_or(Scheduler::FCFS);
+        let rule = match scheduler {
+            _ if self.boosted.contains(&chosen) => Rule::Boosted,
+            Scheduler::FCFS => Rule::Arrival,
+            Scheduler::SJF => Rule::Burst,
+            Scheduler::SRT => Rule::Remaining,
+            Scheduler::RR => Rule::Queue,
+            Scheduler::HRRN => Rule::Ratio,
+            Scheduler::Priority => Rule::Priority,
+        };
+        let estimation = self.config.burst_estimation;
+        // Processes without #BURST go last with the declared estimation
+        let expected = |x: usize| match x {
+            usize::MAX => (f32::MAX, "?".to_string()),
+            x => (x as f32, x.to_string()),
+        };
+        let ready = self.ready_queue();
+        let mut candidates = vec![];
+        let mut not_arrived = vec![];
+        for timing in self.diagram.iter() {
+            let id = timing.p_id;
+            let state = self.memory.pcb(id).map(|x| x.process_state);
+            let waiting = matches!(state, Some(ProcessState::Ready | ProcessState::New))
+                && !self.cpus.iter().any(|x| x.assigned == Some(id));
+            if id != chosen && !waiting {
+                continue;
+            }
+            if id != chosen && timing.arrival as u64 > self.counter {
+                not_arrived.push(id);
+                continue;
+            }
+            let (order, value) = match rule {
+                Rule::Arrival => (timing.arrival as f32, timing.arrival.to_string()),
+                Rule::Burst => expected(timing.expected_burst(estimation)),
+                Rule::Remaining => expected(timing.expected_remaining(estimation)),
+                Rule::Ratio => {
+                    let ratio = timing.response_ratio(self.counter, estimation);
+                    (-ratio, format!("{:.2}", ratio))
+                }
+                Rule::Priority => {
+                    let priority = self.memory.pcb(id).map_or(0, |x| x.priority);
+                    (priority as f32, priority.to_string())
+                }
+                Rule::Queue => {
+                    let position = ready.iter().position(|x| *x == id);
+                    expected(position.map_or(usize::MAX, |x| x + 1))
+                }
+                Rule::Boosted => (0.0, String::new()),
+            };
+            candidates.push((order, id, value));
+        }
+        candidates.sort_by(|a, b| a.0.total_cmp(&b.0).then(a.1.cmp(&b.1)));
+        let chosen = match candidates.iter().position(|x| x.1 == chosen) {
+            Some(index) => candidates.remove(index),
+            None => (0.0, chosen, String::new()),
+        };
+        if rule == Rule::Boosted {
+            candidates.clear();
+        }
+        Decision {
+            scheduler,
+            rule,
+            chosen: (chosen.1, chosen.2),
+            candidates: candidates.into_iter().map(|x| (x.1, x.2)).collect(),
+            not_arrived,
+        }
+    }
+
+    // Select the next process to execute with the scheduler algo, returns the cpu and pcb to
+    // send to the distpacher and if the scheduler should run again after the dispatch. The CPU
+    // doesn't depend on the RNG or on the order of the tasks: the first process of the
+    // scheduler goes to the lowest free CPU, and the next run gives the next process the next
+    // free CPU. Without a free CPU, SRT preempts the CPU with the longest remaining burst and
+    // RR the first CPU whose quantum ended, the lowest index on ties
+    fn pick_process(&mut self) -> Option<Dispatch> {
+        if let Some(dispatch) = self.select_boosted() {
+            return Some(dispatch);
+        }
+        let ready = self.ready_queue();
+        let expired = self.expired_cpu();
+        let free = self.free_cpu();
+        // Uses the scheduler algo selected on config
+        match self.config.scheduler {
+            Some(Scheduler::FCFS) => {
+                // The ready queue is ordered by arrival, the first one goes to the distpacher
+                let entry = ready.iter().find_map(|x| self.memory.pcb_entry(*x))?;
+                Some(((free?, entry), true))
+            }
+            Some(Scheduler::SRT) => {
+                // Sort the pcbs by arrival and burst time
+                let estimation = self.config.burst_estimation;
+                let order = self
+                    .diagram
+                    .order_by_key(|a| a.expected_remaining(estimation));
+                // Select the pcb from the table and send to distpacher
+                for pcb_timing in order.iter().filter_map(|x| self.diagram.get(*x)) {
+                    if pcb_timing.c_id.is_some() {
+                        continue;
+                    }
+                    let Some(entry) = self.memory.pcb_entry(pcb_timing.p_id) else {
+                        continue;
+                    };
+                    // Read the PCB from memory
+                    let pcb = self.memory.pcb(entry.0).unwrap_or_default();
+                    if pcb.process_state != ProcessState::Ready {
+                        continue;
+                    }
+                    if let Some(cpu_index) = free {
+                        return Some(((cpu_index, entry), true));
+                    }
+                    // The process with the longest remaining burst leaves its CPU
+                    let remaining = pcb_timing.expected_remaining(estimation);
+                    let longest = self
+                        .cpus
+                        .iter()
+                        .enumerate()
+                        .filter_map(|(cpu_index, x)| {
+                            let timing = self.diagram.get(x.assigned?)?;
+                            Some((timing.expected_remaining(estimation), cpu_index))
+                        })
+                        .max_by_key(|(remaining, cpu_index)| (*remaining, Reverse(*cpu_index)));
+                    if let Some((old_remaining, cpu_index)) = longest {
+                        if old_remaining > remaining {
+                            return Some(((cpu_index, entry), true));
+                        }
+                    }
+                }
+                None
+            }
+            Some(Scheduler::SJF) => {
+                // Non preemptive, a job only starts on a free CPU
+                let cpu_index = free?;
+                // Shortest burst first, ties by arrival and then pid. The order is a temporary
+                // list, the diagram keeps the creation order
+                let estimation = self.config.burst_estimation;
+                let order = self
+                    .diagram
+                    .order_by_key(|a| (a.expected_burst(estimation), a.arrival, a.p_id));
+                let counter = self.counter;
+                // Select the pcb from the table and send to distpacher
+                for p_id in order {
+                    let arrived = self
+                        .diagram
+                        .get(p_id)
+                        .is_some_and(|x| x.arrival as u64 <= counter);
+                    let running = self.cpus.iter().any(|x| x.assigned == Some(p_id));
+                    if !arrived || running {
+                        continue;
+                    }
+                    if let Some(entry) = self.memory.pcb_entry(p_id) {
+                        // Read the PCB from memory
+                        let pcb = self.memory.pcb(entry.0).unwrap_or_default();
+                        if matches!(pcb.process_state, ProcessState::Ready | ProcessState::New) {
+                            return Some(((cpu_index, entry), true));
+                        }
+                    }
+                }
+                None
+            }
+            Some(Scheduler::RR) => {
+                // The head of the ready queue, the processes that arrive, leave a CPU or get
+                // their input go to its tail
+                let entry = ready.iter().find_map(|x| self.memory.pcb_entry(*x))?;
+                if let Some(cpu_index) = free {
+                    log!(self, Level::Debug, "rr_free_cpu");
+                    return Some(((cpu_index, entry), true));
+                }
+                log!(self, Level::Debug, "rr_no_free_cpu");
+                // Preempt only a CPU whose quantum ended, the rest of the expired ones are
+                // checked on the next pass
+                Some(((expired?, entry), true))
+            }
+            Some(Scheduler::Priority) => {
+                let levels = self.priority_levels();
+                let (&priority, level) = levels.iter().next()?;
+                let entry = self.memory.pcb_entry(*level.front()?)?;
+                if let Some(cpu_index) = free {
+                    return Some(((cpu_index, entry), true));
+                }
+                let running = |cpu_index: usize| {
+                    let pcb = self.memory.pcb(self.cpus[cpu_index].assigned?)?;
+                    Some(pcb.priority)
+                };
+                // The CPU with the lowest priority goes to a strictly higher one
+                if self.config.priority_preemptive {
+                    let lowest = (0..self.cpus.len())
+                        .filter_map(|cpu_index| Some((running(cpu_index)?, cpu_index)))
+                        .max_by_key(|(priority, cpu_index)| (*priority, Reverse(*cpu_index)));
+                    if let Some((old_priority, cpu_index)) = lowest {
+                        if old_priority > priority {
+                            return Some(((cpu_index, entry), true));
+                        }
+                    }
+                }
+                // A process that used its quantum lets the next one of its level run, a lower
+                // level keeps waiting
+                if self.config.priority_rr {
+                    let quantum = self.quantum? as u64;
+                    let cpu_index = (0..self.cpus.len()).find(|cpu_index| {
+                        self.quantum_start
+                            .get(cpu_index)
+                            .is_some_and(|start| self.counter - start >= quantum)
+                            && running(*cpu_index).is_some_and(|x| x >= priority)
+                    })?;
+                    return Some(((cpu_index, entry), true));
+                }
+                None
+            }
+            Some(Scheduler::HRRN) => {
+                // Non preemptive like SJF, the highest (waiting + burst) / burst first
+                let cpu_index = free?;
+                let estimation = self.config.burst_estimation;
+                let counter = self.counter;
+                let mut order = self
+                    .diagram
+                    .iter()
+                    .filter(|x| {
+                        let state = self.memory.pcb(x.p_id).map(|x| x.process_state);
+                        x.arrival as u64 <= counter
+                            && matches!(state, Some(ProcessState::Ready | ProcessState::New))
+                            && !self.cpus.iter().any(|cpu| cpu.assigned == Some(x.p_id))
+                    })
+                    .map(|x| (-x.response_ratio(counter, estimation), x.arrival, x.p_id))
+                    .collect::<Vec<_>>();
+                // Ties by arrival and then pid
+                order.sort_by(|a, b| a.0.total_cmp(&b.0).then((a.1, a.2).cmp(&(b.1, b.2))));
+                let entry = self.memory.pcb_entry(order.first()?.2)?;
+                Some(((cpu_index, entry), true))
+            }
+            None => None,
+        }
+    }
+
+    // Lowest CPU without a process
+    fn free_cpu(&self) -> Option<usize> {
+        self.cpus.iter().position(|x| x.is_free())
+    }
+
+    // A process that just got its input goes before the rest of the ready queue, whatever the
+    // scheduler. It takes a free CPU or, on Round Robin, the CPU whose quantum just ended
+    fn select_boosted(&mut self) -> Option<Dispatch> {
+        let id = *self.boosted.iter().find(|id| {
+            self.memory.pcb(**id).map(|x| x.process_state) == Some(ProcessState::Ready)
+        })?;
+        let entry = self.memory.pcb_entry(id)?;
+        if let Some(cpu_index) = self.free_cpu() {
+            return Some(((cpu_index, entry), true));
+        }
+        if self.uses_quantum() {
+            if let Some(cpu_index) = self.expired_cpu() {
+                return Some(((cpu_index, entry), true));
+            }
+        }
+        None
+    }
+
+    // Round Robin, and Priority when the processes of a level take turns
+    fn uses_quantum(&self) -> bool {
+        match self.config.scheduler {
+            Some(Scheduler::RR) => true,
+            Some(Scheduler::Priority) => self.config.priority_rr,
+            _ => false,
+        }
+    }
+
+    // Ready processes by priority, the lowest value first. Each level is a FIFO queue, a process
+    // that leaves its CPU goes after the ones that were already waiting
+    fn priority_levels(&self) -> BTreeMap<u8, VecDeque<usize>> {
+        let counter = self.counter;
+        let mut waiting = self
+            .diagram
+            .iter()
+            .filter(|x| {
+                let state = self.memory.pcb(x.p_id).map(|x| x.process_state);
+                x.arrival as u64 <= counter
+                    && matches!(state, Some(ProcessState::Ready | ProcessState::New))
+                    && !self.cpus.iter().any(|cpu| cpu.assigned == Some(x.p_id))
+            })
+            .collect::<Vec<_>>();
+        waiting.sort_by_key(|x| x.queue_key());
+        let mut levels = BTreeMap::<u8, VecDeque<usize>>::new();
+        for timing in waiting {
+            let priority = self.memory.pcb(timing.p_id).map_or(0, |x| x.priority);
+            levels.entry(priority).or_default().push_back(timing.p_id);
+        }
+        levels
+    }
+
+    // First CPU whose process used its whole quantum
+    fn expired_cpu(&self) -> Option<usize> {
+        let quantum = self.quantum? as u64;
+        // The process of a halted CPU is done, it doesn't have a quantum left to use
+        (0..self.cpus.len()).find(|cpu_index| {
+            self.cpus[*cpu_index].state != CoreState::Halted
+                && self
+                    .quantum_start
+                    .get(cpu_index)
+                    .is_some_and(|start| self.counter - start >= quantum)
+        })
+    }
+
+    // The processes that used their quantum without anybody to take their CPU start a new one
+    fn renew_quanta(&mut self) {
+        let Some(quantum) = self.quantum else {
+            return;
+        };
+        for start in self.quantum_start.values_mut() {
+            if self.counter - *start >= quantum as u64 {
+                *start = self.counter;
+            }
+        }
+    }
+
+    // Load the process to the CPU
+    fn dispatch(&mut self, (cpu_index, (pcb_id, _, _)): (usize, (usize, usize, usize))) {
+        self.scheduler_dirty = true;
+        self.switch_out(cpu_index);
+        self.quantum_start.insert(cpu_index, self.counter);
+        // The boost only lasts one dispatch
+        self.boosted.retain(|x| *x != pcb_id);
+        // The output of the previous process isn't shown for the new one
+        if self
+            .cpus
+            .get(cpu_index)
+            .is_some_and(|x| x.assigned != Some(pcb_id))
+        {
+            self.cpu_output.remove(&cpu_index);
+        }
+        if let Some(core) = self.cpus.get_mut(cpu_index) {
+            // Context switch, load registers to the CPU and update the CPU running process id
+            let mut pcb = self.memory.pcb(pcb_id).unwrap_or_default();
+            core.assign(&pcb);
+            let cpu = &mut core.cpu;
+
+            pcb.process_state = ProcessState::Running;
+
+            // Save changes
+            self.memory.write_pcb(pcb);
+
+            // Inicia el temporizador del CPU y el tiempo individual del proceso si aún no ha comenzado
+            cpu.start_time = Some(Instant::now());
+
+            let estimation = self.config.burst_estimation;
+            let counter = self.counter;
+            if let Some(timing) = self.diagram.get_mut(pcb_id) {
+                timing.c_id = Some(cpu_index);
+                if timing.start.is_none() {
+                    timing.start = Some(Instant::now());
+                    timing.estimated = Some(timing.expected_burst(estimation));
+                    // SJF and HRRN can dispatch a process that arrived before it was marked ready
+                    timing.ready_at.get_or_insert(counter);
+                    timing.dispatched_at = Some(counter);
+                }
+            }
+
+            // Updates times
+            //if let Some(timing) = self.diagram.iter_mut().find(|x| x.p_id == pcb_id) {
+            //timing.c_id = Some(cpu_index);
+            //timing.start = Some(Instant::now());
+            //}
+
+            if self.mode.is_none() {
+                self.mode = Some(Mode::Manual);
+            }
+
+            // Loading a process is not an instruction, don't highlight its registers
+            if let Some(last) = self.last_registers.get_mut(cpu_index) {
+                *last = *cpu;
+            }
+            if let Some(usage) = self.usage.get_mut(cpu_index) {
+                usage.context_switches += 1;
+            }
+
+            // Mostrar mensaje en consola al iniciar el procesamiento de un proceso
+            let tick = self.counter;
+            self.observe(|x| x.on_dispatch(tick, pcb_id, cpu_index));
+        }
+    }
+
+    // Context switch, store the registers of the process running on the CPU on its PCB and put
+    // it back on the ready queue
+    fn switch_out(&mut self, cpu_index: usize) {
+        if let Some(Core {
+            cpu,
+            assigned: Some(p_id),
+            ..
+        }) = self.cpus.get(cpu_index)
+        {
+            // Store CPU content on the PCB
+            if let Some(mut pcb) = self.memory.pcb(*p_id) {
+                log!(self, Level::Debug, "pcb_before", format!("{:?}", &pcb));
+                save_context(cpu, &mut pcb);
+
+                pcb.process_state = ProcessState::Ready;
+                log!(self, Level::Debug, "pcb_after", format!("{:?}", &pcb));
+                // Save changes
+                self.memory.write_pcb(pcb);
+
+                if let Some(timing) = self.diagram.get_mut(*p_id) {
+                    timing.c_id = None;
+                    timing.queued = Some(self.counter);
+                    timing.end_burst(self.config.burst_alpha);
+                }
+                let (tick, p_id) = (self.counter, *p_id);
+                self.observe(|x| x.on_preempt(tick, p_id, cpu_index));
+            }
+        }
+    }
+
+    // A process that ended while waiting for input loses its input row
+    fn drop_input(&mut self, id: usize) {
+        self.input_values.remove(&id);
+        if let Some(index) = self.waiting_queue.iter().position(|x| x.0 == id) {
+            self.waiting_queue.remove(index);
+            log!(self, Level::Warning, "input_dropped", id);
+        }
+    }
+
+    // Change the scheduler in the middle of a run, the running processes go back to the ready
+    // queue and the new scheduler picks the processes for the free CPUs
+    fn switch_policy(&mut self, scheduler: Scheduler) -> Task<Message> {
+        self.scheduler_dirty = true;
+        for cpu_index in 0..self.cpus.len() {
+            let Some(p_id) = self.cpus[cpu_index].assigned else {
+                continue;
+            };
+            // Only the running processes go back to the ready queue
+            if self.memory.pcb(p_id).map(|x| x.process_state) != Some(ProcessState::Running) {
+                continue;
+            }
+            self.switch_out(cpu_index);
+            self.quantum_start.remove(&cpu_index);
+            self.cpus[cpu_index].release();
+            self.last_registers[cpu_index] = CPU::new();
+            self.history[cpu_index].clear();
+        }
+        if let Some(quantum) = self.next_quantum.take() {
+            self.quantum = Some(quantum);
+        }
+        self.config.scheduler = Some(scheduler);
+        let counter = self.counter;
+        log!(self, Level::Info, "scheduler_switched", scheduler, counter);
+        self.schedule()
+    }
+
+    // Runs when a running process is done
+    fn terminate(&mut self, cpu_index: usize) {
+        self.scheduler_dirty = true;
+        self.cpu_output.remove(&cpu_index);
+        // Mostrar mensaje en consola cuando el proceso finaliza
+        let running = self.cpus.get(cpu_index).and_then(|x| x.assigned);
+        if let Some(p_id) = running.filter(|x| self.memory.pcb(*x).is_some()) {
+            let tick = self.counter;
+            self.observe(|x| x.on_terminate(tick, p_id, cpu_index));
+        }
+        // Select the running process
+        if let Some(Core {
+            cpu,
+            assigned: Some(p_id),
+            ..
+        }) = self.cpus.get_mut(cpu_index)
+        {
+            if let Some(mut pcb) = self.memory.pcb(*p_id) {
+                // Breakpoints only live as long as the process
+                self.breakpoints.retain(|x| x.0 != *p_id);
+                if self.breakpoint_hit.map(|x| x.0) == Some(cpu_index) {
+                    self.breakpoint_hit = None;
+                }
+
+                if let Some(start_time) = cpu.start_time {
+                    let duration = start_time.elapsed(); // Calcula el tiempo de ejecución
+                    if let Some(timing) = self.diagram.get_mut(*p_id) {
+                        timing.execution = Some(duration); // Asigna `duration` a `timing.execution
+                        timing.end_time = Some(Instant::now());
+
+                        let arrival_time = timing.arrival as f64;
+                        let turnaround_time = timing
+                            .end_time
+                            .unwrap()
+                            .duration_since(timing.start.unwrap());
+                        let execution_time = timing.execution.unwrap();
+                        let response_ratio =
+                            turnaround_time.as_secs_f64() / execution_time.as_secs_f64();
+
+                        // Almacena los datos de estadísticas en stats_data
+                        self.stats_data.push(ProcessStats {
+                            process_id: *p_id,
+                            name: timing.name.clone(),
+                            cpu_id: cpu_index,
+                            arrival_time,
+                            turnaround_time: turnaround_time.as_secs_f64(),
+                            execution_time: execution_time.as_secs_f64(),
+                            response_ratio,
+                            scheduler: self.config.scheduler,
+                            estimated: timing.estimated,
+                            ticks: timing.ticks,
+                            first_response: timing.first_response(),
+                            origin: timing.name.clone(),
+                            fault: None,
+                        });
+                        timing.end_burst(self.config.burst_alpha);
+
+                        // Calcula el tiempo de estancia (Turnaround Time) como tiempo final - tiempo de llegada
+                        if let Some(turnaround_time) = timing
+                            .end_time
+                            .unwrap()
+                            .checked_duration_since(timing.start.unwrap())
+                        {
+                            log!(
+                                self,
+                                Level::Info,
+                                "turnaround",
+                                p_id,
+                                format!("{:.2}", turnaround_time.as_secs_f64())
+                            );
+
+                            // Calcula T_r / T_s si `execution` está definido
+                            if let Some(execution_time) = timing.execution {
+                                let response_ratio =
+                                    turnaround_time.as_secs_f64() / execution_time.as_secs_f64();
+                                log!(
+                                    self,
+                                    Level::Info,
+                                    "execution_ratio",
+                                    format!("{:.2}", execution_time.as_secs_f64()),
+                                    format!("{:.2}", response_ratio)
+                                );
+                            }
+                        }
+                    }
+                }
+                cpu.start_time = None; // Limpia el tiempo de inicio del proceso
+
+                // Update PCB
+                pcb.process_state = ProcessState::Terminated;
+                save_context(cpu, &mut pcb);
+                let post_mortem = post_mortem(
+                    &self.memory,
+                    &pcb,
+                    cpu,
+                    &self.history[cpu_index],
+                    self.counter,
+                );
+                self.post_mortems.insert(pcb.id, post_mortem);
+                // Save changes
+                self.memory.write_pcb(pcb);
+
+                // Free memory
+                let _ = self.memory.free_memory(pcb.code_segment);
+                let _ = self.memory.free_memory(pcb.stack_segment);
+
+                // Remove from pcb_table
+                //self.memory.pcb_table.retain(|x| x.0 != *p_id);
+
+                if let Some((_, p_id)) = self.loaded_files.iter_mut().find(|x| x.1 == Some(*p_id)) {
+                    *p_id = None;
+                }
+
+                self.cpus[cpu_index].release();
+                self.quantum_start.remove(&cpu_index);
+                if let Some(last) = self.last_registers.get_mut(cpu_index) {
+                    *last = CPU::new();
+                }
+                // The CPU is now idle
+                if let Some(history) = self.history.get_mut(cpu_index) {
+                    history.clear();
+                }
+                self.drop_input(pcb.id);
+                self.check_usage();
+
+                self.check_completed();
+
+                // Verificar si todos los procesos han terminado
+                if self.cpus.iter().all(|x| x.is_free()) {
+                    // Calcula el tiempo total acumulado sumando los tiempos de estancia de cada proceso
+                    let tiempo_total_acumulado: Duration = self
+                        .diagram
+                        .iter()
+                        .filter_map(|timing| {
+                            if let (Some(end_time), Some(start_time)) =
+                                (timing.end_time, timing.start)
+                            {
+                                Some(end_time.duration_since(start_time))
+                            } else {
+                                None // Ignora procesos que no tienen tiempo de inicio o fin definido
+                            }
+                        })
+                        .sum();
+
+                    // Imprime el tiempo total acumulado en segundos
+                    log!(
+                        self,
+                        Level::Info,
+                        "total_time_log",
+                        format!("{:.2?}", tiempo_total_acumulado.as_secs_f64())
+                    );
+                }
+            }
+        }
+    }
+
+    // Freeze the clock once every stored file was run
+    fn check_completed(&mut self) {
+        let pcbs = self.memory.pcbs();
+        let done = self.completed.is_none()
+            && !pcbs.is_empty()
+            && pcbs
+                .iter()
+                .all(|x| x.process_state == ProcessState::Terminated)
+            && self.storage.used.iter().all(|(_, address, _)| {
+                self.loaded_files.iter().any(|x| x.0 == *address)
+                    || self.pending_files.contains(&(*address, Admission::Failed))
+            });
+        if !done {
+            return;
+        }
+        let elapsed = self
+            .total_start_time
+            .map(|x| x.elapsed())
+            .unwrap_or_default();
+        self.completed = Some((self.counter, elapsed));
+        self.show_summary = true;
+        let counter = self.counter;
+        log!(
+            self,
+            Level::Info,
+            "completed_in",
+            counter,
+            format!("{:.2}", elapsed.as_secs_f64())
+        );
+    }
+
+    // Lines of the end of run summary, the averages are grouped by the scheduler that was used
+    // when each process terminated
+    fn summary(&self) -> Vec<String> {
+        let lang = self.config.lang;
+        let Some((ticks, elapsed)) = self.completed else {
+            return vec![];
+        };
+        let mut lines = vec![
+            trf(lang, "summary_ticks", &[&ticks]),
+            trf(
+                lang,
+                "summary_wall_time",
+                &[&format!("{:.2}", elapsed.as_secs_f64())],
+            ),
+        ];
+        // Values the run took from the random generator
+        if let Some(rng) = self.arrival_policy.rng() {
+            lines.push(trf(lang, "summary_draws", &[&rng.count()]));
+        }
+        let count = |kind: fn(&TraceEvent) -> bool| self.trace.count(kind);
+        lines.push(trf(
+            lang,
+            "summary_events",
+            &[
+                &count(|x| matches!(x, TraceEvent::Dispatch { .. })),
+                &count(|x| matches!(x, TraceEvent::Preempt { .. })),
+                &count(|x| matches!(x, TraceEvent::Block { .. })),
+                &count(|x| matches!(x, TraceEvent::Fault { .. })),
+            ],
+        ));
+        let mut schedulers = vec![];
+        for stats in &self.stats_data {
+            if !schedulers.contains(&stats.scheduler) {
+                schedulers.push(stats.scheduler);
+            }
+        }
+        for scheduler in schedulers {
+            let stats = self
+                .stats_data
+                .iter()
+                .filter(|x| x.scheduler == scheduler)
+                .collect::<Vec<_>>();
+            let average = |value: fn(&ProcessStats) -> f64| {
+                stats.iter().map(|x| value(x)).sum::<f64>() / stats.len() as f64
+            };
+            let name = match scheduler {
+                Some(scheduler) => scheduler.to_string(),
+                None => tr(lang, "no_scheduler").to_string(),
+            };
+            lines.push(trf(
+                lang,
+                "summary_scheduler",
+                &[
+                    &name,
+                    &stats.len(),
+                    &format!("{:.2}", average(|x| x.turnaround_time)),
+                    &format!("{:.2}", average(|x| x.execution_time)),
+                    &format!("{:.2}", average(|x| x.response_ratio)),
+                ],
+            ));
+        }
+        lines.extend(self.batch_results());
+        lines
+    }
+
+    // Status shown on the menu bar: clock, processes on each state and the scheduler
+    fn status(&self) -> String {
+        let lang = self.config.lang;
+        let clock = match self.completed {
+            Some((ticks, elapsed)) => trf(
+                lang,
+                "completed_in",
+                &[&ticks, &format!("{:.2}", elapsed.as_secs_f64())],
+            ),
+            None => {
+                let elapsed = self
+                    .total_start_time
+                    .map(|x| x.elapsed())
+                    .unwrap_or_default();
+                format!(
+                    "{} · {:.1} s",
+                    trf(lang, "tick", &[&self.counter]),
+                    elapsed.as_secs_f64()
+                )
+            }
+        };
+        let count = |state| {
+            self.memory
+                .pcbs()
+                .iter()
+                .filter(|x| x.process_state == state)
+                .count()
+        };
+        let states = trf(
+            lang,
+            "status_states",
+            &[
+                &count(ProcessState::New),
+                &self.ready_queue().len(),
+                &self.running_queue().len(),
+                &self.blocked_queue().len(),
+                &count(ProcessState::Terminated),
+            ],
+        );
+        let scheduler = match (self.config.scheduler, self.quantum) {
+            (Some(Scheduler::RR), Some(quantum)) => format!("RR (Q {})", quantum),
+            (Some(scheduler), _) => scheduler.to_string(),
+            (None, _) => tr(lang, "no_scheduler").to_string(),
+        };
+        format!("{} · {} · {}", clock, states, scheduler)
+    }
+
+    // Runs when a running process waits for input
+    fn block(&mut self, cpu_index: usize) {
+        self.scheduler_dirty = true;
+        // Select the running process
+        if let Some(Core {
+            cpu,
+            assigned: Some(p_id),
+            ..
+        }) = self.cpus.get(cpu_index)
+        {
+            if let Some(entry) = self.memory.pcb_entry(*p_id) {
+                let p_id = *p_id;
+                let mut pcb = self.memory.pcb(p_id).unwrap_or_default();
+                // Update PCB
+                pcb.process_state = ProcessState::Blocked;
+                save_context(cpu, &mut pcb);
+                // Save changes
+                self.memory.write_pcb(pcb);
+                self.waiting_queue.push(entry);
+                let tick = self.counter;
+                self.observe(|x| x.on_block(tick, p_id, cpu_index));
+
+                // The CPU is free for another process while this one waits
+                self.cpus[cpu_index].release();
+                self.quantum_start.remove(&cpu_index);
+                self.cpu_output.remove(&cpu_index);
+                self.last_registers[cpu_index] = CPU::new();
+                self.history[cpu_index].clear();
+                if let Some(timing) = self.diagram.get_mut(p_id) {
+                    timing.c_id = None;
+                    timing.end_burst(self.config.burst_alpha);
+                }
+            }
+        }
+    }
+
+    // Validate the program on the editor and store it like a picked file
+    fn assemble_program(&mut self) -> Task<Message> {
+        let Some(editor) = &mut self.editor else {
+            return Task::none();
+        };
+        let content = editor.content.text();
+        let content = content.trim();
+
+        editor.errors = check_file(content.as_bytes())
+            .iter()
+            .map(|x| x.to_string())
+            .collect();
+        if editor.name.trim().is_empty() {
+            editor
+                .errors
+                .push(tr(self.config.lang, "program_needs_name").to_string());
+        }
+        if !editor.errors.is_empty() {
+            return Task::none();
+        }
+
+        let file_name = format!("{:?}", editor.name.trim());
+        // Replace the stored copy of the file being edited
+        let mut previous = None;
+        if let Some(address) = editor.address {
+            if let Some((old_name, _, _)) = self.storage.used.iter().find(|x| x.1 == address) {
+                // Load the file again once it's stored
+                let old_name = old_name.clone();
+                self.loaded_files.retain(|x| x.0 != address);
+                self.pending_files.retain(|x| x.0 != address);
+                previous = self.storage.file(address).map(|x| (old_name, x.to_vec()));
+            }
+            self.storage.free_file(address);
+        }
+
+        let data = content.as_bytes().to_vec();
+        match self.storage.store_files(&file_name, data) {
+            Ok(_) => {
+                // The processes of the old copy follow the file if it moved
+                if let (Some(old), Some(new)) = (editor.address, self.storage.changed.first()) {
+                    for timing in self.diagram.iter_mut() {
+                        if timing.source == Some(old) {
+                            timing.source = Some(*new);
+                        }
+                    }
+                }
+                self.editor = None;
+                self.check_usage();
+                self.save_recovery();
+                self.scheduler_dirty = true;
+                Task::done(Message::Scheduler)
+            }
+            Err(error) => {
+                // Keep the previous version of the file
+                if let Some((old_name, old_data)) = previous {
+                    let _ = self.storage.store_files(&old_name, old_data);
+                }
+                editor.errors.push(error.to_string());
+                Task::none()
+            }
+        }
+    }
+
+    // Memory map shown next to the hex view, one line for each segment
+    fn memory_map(&self) -> Vec<String> {
+        self.memory
+            .layout()
+            .iter()
+            .map(|segment| segment_line(segment, &self.segment_label(segment)))
+            .collect()
+    }
+
+    // "P1 code"
+    fn segment_label(&self, segment: &SegmentInfo) -> String {
+        let lang = self.config.lang;
+        let kind = match segment.kind {
+            SegmentKind::Os => "segment_os",
+            SegmentKind::Pcb => "segment_pcb",
+            SegmentKind::Code => "segment_code",
+            SegmentKind::Stack => "segment_stack",
+            SegmentKind::Free => "segment_free",
+            SegmentKind::Device => "segment_device",
+            _ => "segment_data",
+        };
+        match segment.owner {
+            Some(p_id) => format!("P{} {}", p_id, tr(lang, kind)),
+            None => tr(lang, kind).to_string(),
+        }
+    }
+
+    // Rows of the used and freed tables of the memory and the storage, the rows that the last
+    // store or free moved are marked as changed
+    fn allocator_tables(&self) -> Vec<(&'static str, Vec<AllocationRow>)> {
+        let layout = self.memory.layout();
+        let memory_row = |(address, size): &(usize, usize), owned: bool| {
+            // Only the used spaces have an owner, a freed one may still match a live segment
+            let label = layout
+                .iter()
+                .find(|x| owned && x.address == *address)
+                .map(|x| self.segment_label(x))
+                .unwrap_or_default();
+            AllocationRow::new(*address, *size, &label, &self.memory.changed)
+        };
+        let storage_row = |(file_name, address, size): &(String, usize, usize)| {
+            AllocationRow::new(*address, *size, file_name, &self.storage.changed)
+        };
+        vec![
+            (
+                "memory_used",
+                self.memory
+                    .used
+                    .iter()
+                    .map(|x| memory_row(x, true))
+                    .collect(),
+            ),
+            (
+                "memory_freed",
+                self.memory
+                    .freed
+                    .iter()
+                    .map(|x| memory_row(x, false))
+                    .collect(),
+            ),
+            (
+                "storage_used",
+                self.storage.used.iter().map(storage_row).collect(),
+            ),
+            (
+                "storage_freed",
+                self.storage.freed.iter().map(storage_row).collect(),
+            ),
+        ]
+    }
+
+    fn storage_map(&self) -> Vec<String> {
+        self.storage
+            .layout()
+            .iter()
+            .map(|segment| {
+                let label = match self.file_name(segment.address) {
+                    Some(file_name) if segment.kind == SegmentKind::File => file_name,
+                    _ => tr(self.config.lang, "segment_free").to_string(),
+                };
+                segment_line(segment, &label)
+            })
+            .collect()
+    }
+
+    // Registers of the device region with their current value, the code bytes written by a
+    // process in red and the segments of each process with its color
+    fn memory_highlights(&self) -> Vec<Highlight> {
+        let lang = self.config.lang;
+        let mut highlights = vec![];
+        if let Some((address, _)) = self.memory.device() {
+            highlights = ["display_register", "keyboard_register"]
+                .iter()
+                .enumerate()
+                .map(|(offset, key)| Highlight {
+                    start: address + offset,
+                    end: address + offset + 1,
+                    color: color!(0xf1fa8c),
+                    label: format!("{}: {}", tr(lang, key), self.memory.data[address + offset]),
+                    on_press: None,
+                })
+                .collect();
+        }
+        highlights.extend(self.memory.written_code.iter().map(|address| Highlight {
+            start: *address,
+            end: address + 1,
+            color: color!(0xff5555),
+            label: tr(lang, "code_modified").to_string(),
+            on_press: None,
+        }));
+        highlights.extend(self.memory.layout().iter().filter_map(|segment| {
+            Some(Highlight {
+                start: segment.address,
+                end: segment.end(),
+                color: process_color(self.config.palette, segment.owner?),
+                label: self.segment_label(segment),
+                on_press: None,
+            })
+        }));
+        highlights
+    }
+
+    // Loaded files take the color of their process, the freed ranges are grey
+    fn storage_highlights(&self) -> Vec<Highlight> {
+        let mut highlights = self
+            .storage
+            .used
+            .iter()
+            .map(|(file_name, address, size)| Highlight {
+                start: *address,
+                end: address + size,
+                color: self.file_color(file_name, *address),
+                label: file_name.clone(),
+                on_press: Some(Message::SelectFile(*address)),
+            })
+            .collect::<Vec<Highlight>>();
+        highlights.extend(
+            self.storage
+                .freed
+                .iter()
+                .map(|(_, address, size)| Highlight {
+                    start: *address,
+                    end: address + size,
+                    color: color!(0x6272a4),
+                    label: tr(self.config.lang, "freed").to_string(),
+                    on_press: None,
+                }),
+        );
+        highlights
+    }
+
+    fn file_color(&self, file_name: &str, address: usize) -> iced::Color {
+        let palette = self.config.palette;
+        match self.loaded_files.iter().find(|x| x.0 == address) {
+            Some((_, Some(p_id))) => process_color(palette, *p_id),
+            _ => to_color(colors::file_color(palette, file_name, address)),
+        }
+    }
+
+    // (hash that ran, hash stored) when the file stored on the address changed after its newest
+    // process was loaded. Loading the file again clears it
+    fn stale_source(&self, address: usize) -> Option<(u64, u64)> {
+        let newest = self
+            .diagram
+            .iter()
+            .filter(|x| x.source == Some(address))
+            .max_by_key(|x| x.p_id)?;
+        let stored = content_hash(self.storage.file(address)?);
+        (newest.source_hash != stored).then_some((newest.source_hash, stored))
+    }
+
+    // Only the newest process of a file is compared with the stored copy
+    fn stale_pcb(&self, pid: usize) -> Option<(u64, u64)> {
+        let address = self.diagram.get(pid)?.source?;
+        let newest = self
+            .diagram
+            .iter()
+            .filter(|x| x.source == Some(address))
+            .map(|x| x.p_id)
+            .max();
+        (newest == Some(pid))
+            .then(|| self.stale_source(address))
+            .flatten()
+    }
+
+    // Processes that haven't terminated, for the legends
+    fn live_processes(&self) -> Vec<usize> {
+        self.memory
+            .pcbs()
+            .iter()
+            .filter(|x| x.process_state != ProcessState::Terminated)
+            .map(|x| x.id)
+            .collect()
+    }
+
+    // Name of the stored file at the address
+    fn file_name(&self, address: usize) -> Option<String> {
+        self.storage
+            .used
+            .iter()
+            .find(|x| x.1 == address)
+            .map(|x| x.0.clone())
+    }
+
+    // The scheduler runs once per tick, calls on the same tick only run when something changed
+    // since the last run. The calls of each tick are logged once the clock moves
+    fn scheduler_due(&mut self) -> bool {
+        let (tick, runs, skipped) = self.scheduler_calls;
+        if tick != self.counter {
+            if runs + skipped > 1 {
+                log!(self, Level::Debug, "scheduler_calls", runs, tick, skipped);
+            }
+            self.scheduler_calls = (self.counter, 0, 0);
+        }
+        let due = self.scheduler_dirty || self.scheduler_last != Some(self.counter);
+        if due {
+            self.scheduler_calls.1 += 1;
+            self.scheduler_last = Some(self.counter);
+            self.scheduler_dirty = false;
+        } else {
+            self.scheduler_calls.2 += 1;
+        }
+        due
+    }
+
+    // Create the PCBs of the stored files that are not loaded yet
+    fn admit(&mut self) -> Option<Task<Message>> {
+        let waiting = self
+            .pending_files
+            .iter()
+            .filter(|x| x.1 == Admission::Waiting)
+            .map(|x| x.0)
+            .collect::<Vec<usize>>();
+        let loaded = self.loaded_files.len();
+        // The draws of the random arrivals are audited with the tick they were taken on
+        let (audit, counter) = (self.config.audit_rng, self.counter);
+        let draws = self.arrival_policy.rng_mut().map(|rng| {
+            rng.audit = audit;
+            rng.set_tick(counter);
+            rng.draws().len()
+        });
+        let failures = create_pcbs(
+            &mut self.storage,
+            &mut self.memory,
+            &mut self.loaded_files,
+            &mut self.pending_files,
+            &mut self.diagram,
+            &mut self.arrival_policy,
+            &self.config,
+        );
+        self.check_usage();
+        if let Some(draws) = draws {
+            let new = self
+                .arrival_policy
+                .rng()
+                .map_or(vec![], |x| x.draws()[draws..].to_vec());
+            for draw in new {
+                let range = format!("{}..={}", draw.range.start(), draw.range.end());
+                log!(
+                    self,
+                    Level::Debug,
+                    "rng_draw",
+                    draw.tag,
+                    range,
+                    draw.value,
+                    draw.tick
+                );
+            }
+        }
+        // With DMA the code is copied over the next ticks
+        if self.config.dma_bytes_per_tick > 0 {
+            let new = self.loaded_files[loaded..]
+                .iter()
+                .filter_map(|x| x.1)
+                .collect::<Vec<_>>();
+            for p_id in new {
+                self.dma.start(&mut self.memory, p_id, self.counter);
+                let size = self.dma.progress(p_id).map(|x| x.1).unwrap_or_default();
+                log!(self, Level::Info, "dma_start", p_id, size);
+            }
+        }
+        self.arrive();
+        for address in waiting {
+            if self.loaded_files.iter().any(|x| x.0 == address) {
+                if let Some(file_name) = self.file_name(address) {
+                    log!(self, Level::Info, "pending_loaded", file_name);
+                }
+            }
+        }
+        if self.loaded_files.len() > loaded {
+            self.scheduler_dirty = true;
+        }
+        if failures.is_empty() {
+            return None;
+        }
+        // The scheduler runs again once the dialog is closed
+        self.scheduler_dirty = true;
+
+        // All the problems of this pass are reported on a single dialog, the scheduler runs again
+        // once it's closed
+        for failure in &failures {
+            log!(self, Level::Warning, "admission_failed", failure);
+        }
+        if let Some(batch) = &mut self.batch {
+            batch.extend(failures.iter().cloned());
+        }
+        let notification = Notification::new(
+            Severity::Warning,
+            tr(self.config.lang, "admission_title"),
+            failures.join("\n"),
+        );
+        Some(
+            self.notify(notification)
+                .chain(Task::done(Message::Scheduler)),
+        )
+    }
+
+    // New processes become Ready once the tick counter reaches their arrival, returns if any
+    // process arrived
+    fn arrive(&mut self) -> bool {
+        let arrived = self.arrived.len();
+        for index in 0..self.memory.pcbs().len() {
+            let mut pcb = self.memory.pcbs()[index];
+            // Its code is still being loaded
+            if pcb.process_state != ProcessState::New || self.dma.progress(pcb.id).is_some() {
+                continue;
+            }
+            if let Some(timing) = self.diagram.get(pcb.id) {
+                if timing.arrival as u64 <= self.counter && self.waiting_for(pcb.id).is_none() {
+                    pcb.process_state = ProcessState::Ready;
+                    self.memory.write_pcb(pcb);
+                    self.arrived.push(pcb.id);
+                    let counter = self.counter;
+                    if let Some(timing) = self.diagram.get_mut(pcb.id) {
+                        timing.ready_at = Some(counter);
+                        timing.queued = Some(counter);
+                    }
+                    log!(self, Level::Info, "process_arrived", pcb.id, counter);
+                }
+            }
+        }
+        self.scheduler_dirty |= self.arrived.len() > arrived;
+        self.arrived.len() > arrived
+    }
+
+    // File of the #AFTER directive of the process while none of its processes has terminated
+    // without a fault
+    fn waiting_for(&self, p_id: usize) -> Option<&str> {
+        let after = self.diagram.get(p_id)?.after.as_deref()?;
+        // Only a process that ended with INT 20H lets it arrive
+        let done = self.post_mortems.iter().any(|(id, post_mortem)| {
+            post_mortem.fault.is_none()
+                && !post_mortem.killed
+                && self
+                    .diagram
+                    .get(*id)
+                    .is_some_and(|x| bare_name(&x.name) == after)
+        });
+        (!done).then_some(after)
+    }
+
+    // Copy the next bytes of the programs being loaded
+    fn transfer(&mut self) {
+        let bytes = match self.config.dma_bytes_per_tick {
+            0 => usize::MAX,
+            bytes => bytes,
+        };
+        for transfer in self.dma.tick(&mut self.memory, bytes) {
+            let ticks = self.counter - transfer.started;
+            log!(self, Level::Info, "dma_end", transfer.p_id, ticks);
+        }
+    }
+
+    // Processes that haven't arrived yet
+    fn arriving(&self) -> bool {
+        self.memory
+            .pcbs()
+            .iter()
+            .any(|x| x.process_state == ProcessState::New)
+    }
+
+    // Run the scheduler and the distpacher without going through the message loop, the task
+    // returned is only used to give feedback to the user
+    fn schedule(&mut self) -> Task<Message> {
+        loop {
+            if !self.scheduler_due() {
+                return Task::none();
+            }
+            if let Some(task) = self.admit() {
+                return task;
+            }
+            match self.select_process() {
+                Some((dispatch, again)) => {
+                    self.dispatch(dispatch);
+                    if !again {
+                        return Task::none();
+                    }
+                }
+                None => return Task::none(),
+            }
+        }
+    }
+
+    // (region, used bytes, size) of the memory and the storage
+    fn usage(&self) -> [(UsageRegion, usize, usize); 3] {
+        [
+            (
+                UsageRegion::UserMemory,
+                self.memory.user_size() - self.memory.free_size(),
+                self.memory.user_size(),
+            ),
+            (
+                UsageRegion::OsSegment,
+                self.memory.os_used_size(),
+                self.memory.os_size(),
+            ),
+            (
+                UsageRegion::Storage,
+                self.storage.used_size(),
+                self.storage.data.len(),
+            ),
+        ]
+    }
+
+    // Warn once when the usage of a region reaches a threshold
+    fn check_usage(&mut self) {
+        let thresholds = self.config.usage_thresholds;
+        for (region, used, size) in self.usage() {
+            let percent = used * 100 / size.max(1);
+            for threshold in thresholds.into_iter().filter(|x| *x > 0) {
+                let warned = self.warned_usage.contains(&(region, threshold));
+                if percent >= threshold as usize && !warned {
+                    self.warned_usage.push((region, threshold));
+                    let name = tr(self.config.lang, region.key());
+                    log!(
+                        self,
+                        Level::Warning,
+                        "usage_warning",
+                        name,
+                        percent,
+                        threshold
+                    );
+                } else if percent < threshold as usize && warned {
+                    self.warned_usage.retain(|x| *x != (region, threshold));
+                }
+            }
+        }
+    }
+
+    // Execute a tick and let the OS handle its events in the order of the CPUs
+    fn step(&mut self) -> (Vec<TickEvent>, Task<Message>) {
+        self.snapshot();
+        self.autosnapshot();
+        let events = self.tick();
+        // The stacks may have grown
+        self.check_usage();
+        self.transfer();
+        // The processes that just arrived can use the free CPUs
+        let mut schedule = self.arrive();
+        let mut tasks = vec![self.runaway()];
+        for event in &events {
+            match *event {
+                TickEvent::Terminated(cpu_index) => self.terminate(cpu_index),
+                TickEvent::Blocked(cpu_index) => self.block(cpu_index),
+                TickEvent::Fault(cpu_index, fault) => {
+                    let message = self.fault(cpu_index, fault);
+                    tasks.push(self.fault_dialog(message));
+                }
+                TickEvent::QuantumExpired => {}
+                TickEvent::Breakpoint(cpu_index) => {
+                    self.breakpoint(cpu_index);
+                    continue;
+                }
+            }
+            schedule = true;
+        }
+        // The CPUs freed on this tick are given away once every event is handled
+        if schedule {
+            tasks.push(self.schedule());
+        }
+        if events.contains(&TickEvent::QuantumExpired) {
+            self.renew_quanta();
+        }
+        (events, Task::batch(tasks))
+    }
+
+    // Warn once about the running processes that executed `runaway_factor` times the length of
+    // their program, a loop without an exit condition never terminates on its own
+    fn runaway(&mut self) -> Task<Message> {
+        let factor = self.config.runaway_factor;
+        if factor == 0 {
+            return Task::none();
+        }
+        let lang = self.config.lang;
+        // (pid, executed instructions) of the processes warned on this tick
+        let mut runaways = vec![];
+        for Core { assigned: p, .. } in &self.cpus {
+            let Some(timing) = p.and_then(|x| self.diagram.get_mut(x)) else {
+                continue;
+            };
+            if timing.runaway || timing.executed <= timing.length * factor {
+                continue;
+            }
+            timing.runaway = true;
+            runaways.push((timing.p_id, timing.executed));
+        }
+        let mut tasks = vec![];
+        for (p_id, executed) in runaways {
+            let message = trf(lang, "process_runaway", &[&p_id, &executed, &factor]);
+            self.log.push(self.counter, Level::Warning, message.clone());
+            if !self.config.runaway_pause {
+                continue;
+            }
+            // Nobody answers the dialog of a headless run, it goes on
+            if self.mode == Some(Mode::Automatic) && !self.headless {
+                self.mode = Some(Mode::Manual);
+            }
+            let notification = Notification::new(
+                Severity::Warning,
+                tr(lang, "runaway_title"),
+                format!("{}\n{}", message, tr(lang, "runaway_kill")),
+            );
+            tasks.push(
+                self.ask(notification, rfd::MessageButtons::YesNo, move |x| {
+                    Message::Runaway(p_id, x)
+                }),
+            );
+        }
+        Task::batch(tasks)
+    }
+
+    fn limit_reached(&self) -> bool {
+        let max_ticks = self.config.max_ticks;
+        max_ticks > 0 && self.counter >= self.limit_extension + max_ticks
+    }
+
+    // Pause the automatic mode once the run reaches the tick limit, the dialog lists the
+    // processes that haven't terminated and asks to continue, kill them or copy the log to the
+    // clipboard
+    fn tick_limit(&mut self) -> Task<Message> {
+        if self.mode != Some(Mode::Automatic) || !self.limit_reached() {
+            return Task::none();
+        }
+        self.mode = Some(Mode::Manual);
+        log!(self, Level::Warning, "tick_limit", self.counter);
+        let lang = self.config.lang;
+        let mut lines = vec![trf(lang, "tick_limit", &[&self.counter])];
+        lines.extend(self.unfinished());
+        let notification = Notification::new(
+            Severity::Warning,
+            tr(lang, "tick_limit_title"),
+            lines.join("\n"),
+        );
+        let buttons = rfd::MessageButtons::YesNoCancelCustom(
+            tr(lang, "tick_limit_continue").to_string(),
+            tr(lang, "tick_limit_kill").to_string(),
+            tr(lang, "tick_limit_copy").to_string(),
+        );
+        self.ask(notification, buttons, Message::TickLimit)
+    }
+
+    // "P1 a.asm: Running, PC 0x7E, MOV AX, 1; INC" for every process that hasn't terminated,
+    // newest instruction first
+    fn unfinished(&self) -> Vec<String> {
+        let lang = self.config.lang;
+        let pcbs = self.memory.pcbs();
+        let unfinished = pcbs
+            .iter()
+            .filter(|x| x.process_state != ProcessState::Terminated);
+        unfinished
+            .map(|pcb| {
+                let pc = match self.cpus.iter().find(|x| x.assigned == Some(pcb.id)) {
+                    Some(core) => core.cpu.pc,
+                    None => pcb.pc,
+                };
+                let last = self
+                    .history
+                    .iter()
+                    .flat_map(|x| x.newest_first())
+                    .filter(|x| x.pid == pcb.id)
+                    .take(TICK_LIMIT_HISTORY)
+                    .map(|x| x.instruction.to_string())
+                    .collect::<Vec<_>>();
+                let name = self.diagram.get(pcb.id).map_or("", |x| &x.name);
+                trf(
+                    lang,
+                    "tick_limit_process",
+                    &[
+                        &pcb.id,
+                        &short_name(name),
+                        &format!("{:?}", pcb.process_state),
+                        &format!("0x{:02X}", pc),
+                        &last.join("; "),
+                    ],
+                )
+            })
+            .collect()
+    }
+
+    // Only the processes waiting for a CPU can change their priority, the ready queue is sorted
+    // again on the next scheduler run
+    fn set_priority(&mut self, p_id: usize, priority: u8) -> bool {
+        let Some(mut pcb) = self.memory.pcb(p_id) else {
+            return false;
+        };
+        if !matches!(pcb.process_state, ProcessState::New | ProcessState::Ready) {
+            return false;
+        }
+        let old = pcb.priority;
+        pcb.priority = priority;
+        self.memory.write_pcb(pcb);
+        self.scheduler_dirty = true;
+        log!(self, Level::Info, "priority_changed", p_id, old, priority);
+        true
+    }
+
+    // Terminate a running process on request, returns if it was on a CPU
+    fn kill(&mut self, p_id: usize) -> bool {
+        let Some(cpu_index) = self.cpus.iter().position(|x| x.assigned == Some(p_id)) else {
+            return false;
+        };
+        log!(self, Level::Warning, "process_killed", p_id, cpu_index);
+        self.terminate(cpu_index);
+        if let Some(post_mortem) = self.post_mortems.get_mut(&p_id) {
+            post_mortem.killed = true;
+        }
+        true
+    }
+
+    // Terminate a process that is not on a CPU: new, ready or blocked. Its registers are the
+    // ones saved on the PCB
+    fn kill_queued(&mut self, p_id: usize) -> bool {
+        let Some(mut pcb) = self.memory.pcb(p_id) else {
+            return false;
+        };
+        if pcb.process_state == ProcessState::Terminated
+            || self.cpus.iter().any(|x| x.assigned == Some(p_id))
+        {
+            return false;
+        }
+        log!(
+            self,
+            Level::Warning,
+            "process_killed_queued",
+            p_id,
+            format!("{:?}", pcb.process_state)
+        );
+        let mut cpu = CPU::new();
+        load_context(&mut cpu, &pcb);
+        let post_mortem = post_mortem(&self.memory, &pcb, &cpu, &History::default(), self.counter);
+        self.post_mortems.insert(
+            p_id,
+            PostMortem {
+                killed: true,
+                ..post_mortem
+            },
+        );
+        pcb.process_state = ProcessState::Terminated;
+        self.memory.write_pcb(pcb);
+        let _ = self.memory.free_memory(pcb.code_segment);
+        let _ = self.memory.free_memory(pcb.stack_segment);
+        if let Some((_, p_id)) = self.loaded_files.iter_mut().find(|x| x.1 == Some(p_id)) {
+            *p_id = None;
+        }
+        self.breakpoints.retain(|x| x.0 != p_id);
+        self.boosted.retain(|x| *x != p_id);
+        self.drop_input(p_id);
+        self.scheduler_dirty = true;
+        self.check_usage();
+        self.check_completed();
+        true
+    }
+
+    // Terminate the process that caused the fault with a single log entry, returns the message of
+    // the dialog for the first fault of each kind on the run
+    fn fault(&mut self, cpu_index: usize, fault: Fault) -> Option<String> {
+        let count = self.faults.entry(fault).or_default();
+        *count += 1;
+        let count = *count;
+        let lang = self.config.lang;
+        let (pc, pid) = self
+            .cpus
+            .get(cpu_index)
+            .map(|x| (x.cpu.pc, x.assigned.unwrap_or_default()))
+            .unwrap_or_default();
+        // The PC still points to the instruction that failed
+        let description = match (
+            self.memory.source_line(pid, pc),
+            self.diagram.get(pid).map(|x| &x.name),
+        ) {
+            (Some((line, _)), Some(name)) => trf(
+                lang,
+                "fault_at_line",
+                &[&tr(lang, fault.key()), &line, name],
+            ),
+            _ => tr(lang, fault.key()).to_string(),
+        };
+        let message = trf(lang, "process_fault", &[&pid, &description, &count]);
+        self.log.push(self.counter, Level::Warning, message.clone());
+        let tick = self.counter;
+        self.observe(|x| x.on_fault(tick, pid, cpu_index, fault.key()));
+        self.terminate(cpu_index);
+        if let Some(stats) = self
+            .stats_data
+            .iter_mut()
+            .rev()
+            .find(|x| x.process_id == pid)
+        {
+            stats.fault = Some(fault);
+        }
+        if let Some(post_mortem) = self.post_mortems.get_mut(&pid) {
+            post_mortem.fault = Some(fault);
+        }
+        (count == 1).then_some(message)
+    }
+
+    fn fault_dialog(&mut self, message: Option<String>) -> Task<Message> {
+        let Some(message) = message else {
+            return Task::none();
+        };
+        let title = tr(self.config.lang, "fault_title");
+        self.notify(Notification::new(Severity::Error, title, message))
+    }
+
+    // Info is only logged, warnings and errors open a dialog. Without a window everything is
+    // written to stderr
+    fn notify(&mut self, notification: Notification) -> Task<Message> {
+        self.ask(notification, rfd::MessageButtons::Ok, Message::DialogResult)
+    }
+
+    // Notification with a question, the answer of the dialog goes to `on_answer`. Headless runs
+    // never get an answer
+    fn ask(
+        &mut self,
+        notification: Notification,
+        buttons: rfd::MessageButtons,
+        on_answer: impl Fn(rfd::MessageDialogResult) -> Message + Send + 'static,
+    ) -> Task<Message> {
+        self.notifications.push(notification.clone());
+        if self.headless {
+            eprintln!("{}: {}", notification.title, notification.body);
+            return Task::none();
+        }
+        let level = match notification.severity {
+            Severity::Info => {
+                self.log.push(self.counter, Level::Info, notification.body);
+                return Task::none();
+            }
+            Severity::Warning => rfd::MessageLevel::Warning,
+            Severity::Error => rfd::MessageLevel::Error,
+        };
+        let dialog = rfd::AsyncMessageDialog::new()
+            .set_level(level)
+            .set_title(notification.title)
+            .set_description(notification.body)
+            .set_buttons(buttons)
+            .show();
+        Task::perform(dialog, on_answer)
+    }
+
+    // Pause the execution when a process reaches a breakpoint
+    fn breakpoint(&mut self, cpu_index: usize) {
+        if let Some(core) = self.cpus.get(cpu_index) {
+            self.flash_pcb = core.assigned;
+        }
+        self.mode = Some(Mode::Manual);
+    }
+
+    // Execute up to `ticks` ticks as a single action, when `until_event` is set it stops once a
+    // process blocks, terminates or its quantum expires. Returns if it stopped because of an event
+    fn run(&mut self, ticks: usize, until_event: bool) -> (Task<Message>, bool) {
+        let mut tasks = vec![];
+        for _ in 0..ticks {
+            // Nothing left to execute
+            if self.cpus.iter().all(|x| x.is_free()) && !self.arriving() {
+                break;
+            }
+            let (events, task) = self.step();
+            tasks.push(task);
+            let stop = events
+                .iter()
+                .any(|x| *x != TickEvent::QuantumExpired || self.uses_quantum());
+            // Breakpoints always stop the execution
+            if events.iter().any(|x| matches!(x, TickEvent::Breakpoint(_))) {
+                return (Task::batch(tasks), true);
+            }
+            if until_event && stop {
+                return (Task::batch(tasks), true);
+            }
+        }
+        (Task::batch(tasks), false)
+    }
+
+    // Execute up to `turbo_ticks` ticks on a single frame, it stops early when a process blocks
+    // or there is nothing left to execute, and when the frame takes too long
+    fn turbo(&mut self) -> Task<Message> {
+        let start = Instant::now();
+        let mut tasks = vec![];
+        self.turbo_executed = 0;
+        for _ in 0..self.config.turbo_ticks {
+            let busy = self.cpus.iter().filter(|x| !x.is_free()).count();
+            if (busy == 0 && !self.arriving()) || start.elapsed() >= TURBO_FRAME_TIME {
+                break;
+            }
+            let (events, task) = self.step();
+            tasks.push(task);
+            if self.limit_reached() {
+                tasks.push(self.tick_limit());
+                break;
+            }
+            if events.iter().any(|x| matches!(x, TickEvent::Breakpoint(_))) {
+                break;
+            }
+            self.turbo_executed += busy;
+            if events.iter().any(|x| matches!(x, TickEvent::Blocked(_))) {
+                break;
+            }
+        }
+        Task::batch(tasks)
+    }
+
+    // Keep the state before a tick of the manual mode, only the last `snapshot_ticks` are kept
+    fn snapshot(&mut self) {
+        if self.mode != Some(Mode::Manual) || self.config.snapshot_ticks == 0 {
+            return;
+        }
+        let storage = match self.snapshots.back() {
+            Some(last) if *last.storage == self.storage => last.storage.clone(),
+            _ => Rc::new(self.storage.clone()),
+        };
+        let snapshot = self.capture(storage);
+        self.snapshots.push_back(snapshot);
+        while self.snapshots.len() > self.config.snapshot_ticks {
+            self.snapshots.pop_front();
+        }
+    }
+
+    fn capture(&self, storage: Rc<Storage>) -> Snapshot {
+        Snapshot {
+            cpus: self.cpus.clone(),
+            history: self.history.clone(),
+            last_registers: self.last_registers.clone(),
+            usage: self.usage.clone(),
+            cache: self.cache.clone(),
+            dma: self.dma.clone(),
+            stats_data: self.stats_data.clone(),
+            post_mortems: self.post_mortems.clone(),
+            trace: self.trace.clone(),
+            memory: self.memory.clone(),
+            storage,
+            display_content: self.display_content.clone(),
+            cpu_output: self.cpu_output.clone(),
+            boosted: self.boosted.clone(),
+            waiting_queue: self.waiting_queue.clone(),
+            loaded_files: self.loaded_files.clone(),
+            pending_files: self.pending_files.clone(),
+            diagram: self.diagram.clone(),
+            completed: self.completed,
+            quantum: self.quantum,
+            quantum_start: self.quantum_start.clone(),
+            next_quantum: self.next_quantum,
+            counter: self.counter,
+            breakpoint_hit: self.breakpoint_hit,
+            arrival_policy: self.arrival_policy.clone(),
+            faults: self.faults.clone(),
+            log_len: self.log.len(),
+        }
+    }
+
+    // Write the recovery file every `autosnapshot_ticks`
+    fn autosnapshot(&mut self) {
+        let every = self.config.autosnapshot_ticks;
+        if every == 0 || self.counter < self.recovery_tick + every {
+            return;
+        }
+        self.recovery_tick = self.counter;
+        self.save_recovery();
+    }
+
+    fn save_recovery(&mut self) {
+        let Some(path) = &self.recovery_path else {
+            return;
+        };
+        if self.config.autosnapshot_ticks == 0 {
+            return;
+        }
+        let recovery = Recovery {
+            version: RECOVERY_VERSION,
+            counter: self.counter,
+            snapshot: self.capture(Rc::new(self.storage.clone())),
+        };
+        if let Err(error) = recovery.save(path) {
+            log!(self, Level::Warning, "recovery_not_saved", error);
+        }
+    }
+
+    // Offer the recovery left by the last session, a missing file means it closed cleanly
+    fn recover(&mut self) -> Task<Message> {
+        let Some(path) = self.recovery_path.clone() else {
+            return Task::none();
+        };
+        let recovery = match Recovery::load(&path) {
+            Ok(recovery) => recovery,
+            Err(Error::IO(std::io::ErrorKind::NotFound)) => return Task::none(),
+            Err(error) => {
+                log!(self, Level::Warning, "recovery_refused", error);
+                self.remove_recovery();
+                return Task::none();
+            }
+        };
+        let lang = self.config.lang;
+        let dialog = rfd::AsyncMessageDialog::new()
+            .set_level(rfd::MessageLevel::Warning)
+            .set_title(tr(lang, "recovery_title"))
+            .set_description(trf(
+                lang,
+                "recovery_description",
+                &[
+                    &recovery.counter,
+                    &recovery.snapshot.storage.used.len(),
+                    &recovery.snapshot.memory.pcbs().len(),
+                ],
+            ))
+            .set_buttons(rfd::MessageButtons::YesNo)
+            .show();
+        self.recovery = Some(recovery);
+        Task::perform(dialog, Message::Recover)
+    }
+
+    fn remove_recovery(&self) {
+        if let Some(path) = &self.recovery_path {
+            let _ = std::fs::remove_file(path);
+        }
+    }
+
+    // Go back to the state before the last tick
+    fn step_back(&mut self) {
+        if let Some(snapshot) = self.snapshots.pop_back() {
+            self.restore(snapshot);
+        }
+    }
+
+    fn restore(&mut self, snapshot: Snapshot) {
+        self.cpus = snapshot.cpus;
+        self.history = snapshot.history;
+        self.last_registers = snapshot.last_registers;
+        self.usage = snapshot.usage;
+        self.cache = snapshot.cache;
+        self.dma = snapshot.dma;
+        self.post_mortems = snapshot.post_mortems;
+        self.trace = snapshot.trace;
+        self.stats_data = snapshot.stats_data;
+        self.memory = snapshot.memory;
+        self.storage = Rc::unwrap_or_clone(snapshot.storage);
+        self.check_usage();
+        self.display_content = snapshot.display_content;
+        self.cpu_output = snapshot.cpu_output;
+        self.boosted = snapshot.boosted;
+        self.waiting_queue = snapshot.waiting_queue;
+        self.loaded_files = snapshot.loaded_files;
+        self.pending_files = snapshot.pending_files;
+        self.diagram = snapshot.diagram;
+        self.completed = snapshot.completed;
+        self.show_summary = false;
+        self.quantum = snapshot.quantum;
+        self.quantum_start = snapshot.quantum_start;
+        self.next_quantum = snapshot.next_quantum;
+        self.counter = snapshot.counter;
+        self.breakpoint_hit = snapshot.breakpoint_hit;
+        self.arrival_policy = snapshot.arrival_policy;
+        self.faults = snapshot.faults;
+        self.log.truncate(snapshot.log_len);
+        self.flash_pcb = None;
+        self.arrived.clear();
+        self.scheduler_dirty = true;
+    }
+
+    // Execute one instruction on every CPU with a process
+    fn tick(&mut self) -> Vec<TickEvent> {
+        let tick = self.counter;
+        self.observe(|x| x.on_tick(tick));
+        self.execute(None)
+    }
+
+    // Execute one instruction on a single CPU, the others are left as they are. It only counts
+    // as a tick on the usage of that CPU, the clock and the quantum don't move
+    fn tick_cpu(&mut self, cpu_index: usize) -> Vec<TickEvent> {
+        self.execute(Some(cpu_index))
+    }
+
+    // Send an event to the log console, the trace and the observers of other tools
+    fn observe(&mut self, event: impl Fn(&mut dyn Observer)) {
+        let lang = self.config.lang;
+        event(&mut LogWriter {
+            log: &mut self.log,
+            lang,
+        });
+        event(&mut self.trace);
+        for observer in &mut self.observers {
+            event(observer.as_mut());
+        }
+    }
+
+    // The process of the CPU is done, it keeps the CPU until the OS terminates it
+    fn halt(&mut self, events: &[TickEvent]) {
+        for event in events {
+            if let TickEvent::Terminated(cpu_index) | TickEvent::Fault(cpu_index, _) = event {
+                if let Some(core) = self.cpus.get_mut(*cpu_index) {
+                    core.state = CoreState::Halted;
+                }
+            }
+        }
+    }
+
+    fn execute(&mut self, only: Option<usize>) -> Vec<TickEvent> {
+        self.flash_pcb = None;
+        self.arrived.clear();
+        let quantum = self.quantum.filter(|_| self.uses_quantum());
+        // Every CPU executes its instruction, the OS handles their events once the tick is over
+        let mut events = vec![];
+        for (cpu_i, core) in self.cpus.iter_mut().enumerate() {
+            let (cpu, p) = (&mut core.cpu, core.assigned);
+            if only.is_some_and(|x| x != cpu_i) {
+                continue;
+            }
+            // Keep the registers before executing to know which ones changed
+            self.last_registers[cpu_i] = *cpu;
+            // Quantum left once the instruction is executed, stepping a single CPU doesn't use it
+            let quantum_left = quantum_left(quantum, self.quantum_start.get(&cpu_i), self.counter)
+                .map(|(left, _)| {
+                    if only.is_some() {
+                        left
+                    } else {
+                        left.saturating_sub(1)
+                    }
+                });
+            if p.is_some() {
+                // Stop before executing an instruction with a breakpoint, unless the execution
+                // is resuming from it
+                if let Some(pcb) = p.and_then(|id| self.memory.pcb(id)) {
+                    let at_breakpoint = self.breakpoints.iter().any(|(pcb_id, index)| {
+                        *pcb_id == pcb.id && pcb.code_segment + index * INSTRUCTION_SIZE == cpu.pc
+                    });
+                    if at_breakpoint && self.breakpoint_hit != Some((cpu_i, cpu.pc)) {
+                        self.breakpoint_hit = Some((cpu_i, cpu.pc));
+                        self.usage[cpu_i].tick(false);
+                        events.push(TickEvent::Breakpoint(cpu_i));
+                        continue;
+                    }
+                }
+                // The CPU is busy on the ticks it executes its process, a breakpoint stops it
+                // before that
+                self.usage[cpu_i].tick(true);
+
+                // Fetch instruction from memory, the decoded program is used when it's cached
+                let Some(instruction) = self.memory.instruction(p.unwrap_or_default(), cpu.pc)
+                else {
+                    events.push(TickEvent::Fault(cpu_i, Fault::InvalidInstruction));
+                    continue;
+                };
+
+                // Instructions that take more than one tick keep the CPU busy before their
+                // effect is applied
+                if cpu.stall == 0 {
+                    // A fetch that misses the cache takes longer
+                    let penalty = self.cache.fetch(p.unwrap_or_default(), cpu.pc);
+                    cpu.stall = self.config.costs.cost(instruction.operation).max(1) + penalty;
+                }
+                cpu.stall -= 1;
+                if cpu.stall > 0 {
+                    cpu.ir = Some(instruction.operation);
+                    if let Some(timing) = p.and_then(|x| self.diagram.get_mut(x)) {
+                        timing.run_tick();
+                    }
+                    continue;
+                }
+
+                // Make room on the stack before pushing to it, a process without room is terminated
+                let pushes = match (instruction.operation, instruction.operands) {
+                    (Operation::PUSH, _) => 1,
+                    (Operation::PARAM, Operands::V4(p1, p2, p3)) => {
+                        [p1, p2, p3].iter().filter(|x| **x != 0).count()
+                    }
+                    _ => 0,
+                };
+                if let Some(pcb) = p.and_then(|id| self.memory.pcb(id)).filter(|_| pushes > 0) {
+                    let used = cpu.sp.saturating_sub(pcb.stack_segment);
+                    if used + pushes > pcb.stack_segment_size {
+                        match self.memory.grow_stack(pcb.id, used + pushes) {
+                            Ok(stack) => {
+                                cpu.sp = stack.stack_segment + used;
+                                log!(
+                                    self,
+                                    Level::Info,
+                                    "stack_grown",
+                                    pcb.id,
+                                    stack.stack_segment_size
+                                );
+                            }
+                            Err(_) => {
+                                events.push(TickEvent::Fault(cpu_i, Fault::StackOverflow));
+                                continue;
+                            }
+                        }
+                    }
+                }
+
+                // Decode and Execute
+                cpu.ir = Some(instruction.operation);
+                // Address of the instruction for the history
+                let pc = cpu.pc;
+                // Value written to the display by INT 10H or the display register
+                let mut output = None;
+                // (address, owner) of the first byte of a code segment written by the instruction
+                let mut code_write = None;
+                // Taken jumps set the PC themselves instead of moving to the next instruction
+                let mut jump = None;
+                match instruction.operation {
+                    Operation::LOAD => {
+                        if let Operands::V2(r) = instruction.operands {
+                            match r {
+                                Register::AX => cpu.ac = cpu.ax,
+                                Register::BX => cpu.ac = cpu.bx,
+                                Register::CX => cpu.ac = cpu.cx,
+                                Register::DX => cpu.ac = cpu.dx,
+                            }
+                        }
+                    }
+                    Operation::STORE => {
+                        if let Operands::V2(r) = instruction.operands {
+                            match r {
+                                Register::AX => cpu.ax = cpu.ac,
+                                Register::BX => cpu.bx = cpu.ac,
+                                Register::CX => cpu.cx = cpu.ac,
+                                Register::DX => cpu.dx = cpu.ac,
+                            }
+                        }
+                    }
+                    Operation::MOV => match instruction.operands {
+                        Operands::V5(r, num) => match r {
+                            Register::AX => cpu.ax = num,
+                            Register::BX => cpu.bx = num,
+                            Register::CX => cpu.cx = num,
+                            Register::DX => cpu.dx = num,
+                        },
+                        Operands::V6(r1, r2) => match r1 {
+                            Register::AX => match r2 {
+                                Register::BX => cpu.ax = cpu.bx,
+                                Register::CX => cpu.ax = cpu.cx,
+                                Register::DX => cpu.ax = cpu.dx,
+                                _ => {}
+                            },
+                            Register::BX => match r2 {
+                                Register::AX => cpu.bx = cpu.ax,
+                                Register::CX => cpu.bx = cpu.cx,
+                                Register::DX => cpu.bx = cpu.dx,
+                                _ => {}
+                            },
+                            Register::CX => match r2 {
+                                Register::AX => cpu.cx = cpu.ax,
+                                Register::BX => cpu.cx = cpu.bx,
+                                Register::DX => cpu.cx = cpu.dx,
+                                _ => {}
+                            },
+                            Register::DX => match r2 {
+                                Register::AX => cpu.dx = cpu.ax,
+                                Register::BX => cpu.dx = cpu.bx,
+                                Register::CX => cpu.dx = cpu.cx,
+                                _ => {}
+                            },
+                        },
+                        _ => {}
+                    },
+                    Operation::ADD => {
+                        if let Operands::V2(r) = instruction.operands {
+                            match r {
+                                Register::AX => cpu.ac = cpu.ac.wrapping_add(cpu.ax),
+                                Register::BX => cpu.ac = cpu.ac.wrapping_add(cpu.bx),
+                                Register::CX => cpu.ac = cpu.ac.wrapping_add(cpu.cx),
+                                Register::DX => cpu.ac = cpu.ac.wrapping_add(cpu.dx),
+                            }
+                        }
+                    }
+                    Operation::SUB => {
+                        if let Operands::V2(r) = instruction.operands {
+                            match r {
+                                Register::AX => cpu.ac = cpu.ac.wrapping_sub(cpu.ax),
+                                Register::BX => cpu.ac = cpu.ac.wrapping_sub(cpu.bx),
+                                Register::CX => cpu.ac = cpu.ac.wrapping_sub(cpu.cx),
+                                Register::DX => cpu.ac = cpu.ac.wrapping_sub(cpu.dx),
+                            }
+                        }
+                    }
+                    Operation::INC => match instruction.operands {
+                        Operands::V0 => cpu.ac = cpu.ac.wrapping_add(1),
+                        Operands::V2(r) if !self.config.legacy_flags => {
+                            let register = cpu.register_mut(r);
+                            *register = register.wrapping_add(1);
+                        }
+                        // The first versions added the register to AC
+                        Operands::V2(r) => match r {
+                            Register::AX => cpu.ac = cpu.ac.wrapping_add(cpu.ax),
+                            Register::BX => cpu.ac = cpu.ac.wrapping_add(cpu.bx),
+                            Register::CX => cpu.ac = cpu.ac.wrapping_add(cpu.cx),
+                            Register::DX => cpu.ac = cpu.ac.wrapping_add(cpu.dx),
+                        },
+                        _ => {}
+                    },
+                    Operation::DEC => match instruction.operands {
+                        Operands::V0 => cpu.ac = cpu.ac.wrapping_sub(1),
+                        Operands::V2(r) if !self.config.legacy_flags => {
+                            let register = cpu.register_mut(r);
+                            *register = register.wrapping_sub(1);
+                        }
+                        // The first versions subtracted the register from AC
+                        Operands::V2(r) => match r {
+                            Register::AX => cpu.ac = cpu.ac.wrapping_sub(cpu.ax),
+                            Register::BX => cpu.ac = cpu.ac.wrapping_sub(cpu.bx),
+                            Register::CX => cpu.ac = cpu.ac.wrapping_sub(cpu.cx),
+                            Register::DX => cpu.ac = cpu.ac.wrapping_sub(cpu.dx),
+                        },
+                        _ => {}
+                    },
+                    Operation::SWAP => {
+                        if let Operands::V6(r1, r2) = instruction.operands {
+                            match r1 {
+                                Register::AX => match r2 {
+                                    Register::BX => mem::swap(&mut cpu.ax, &mut cpu.bx),
+                                    Register::CX => mem::swap(&mut cpu.ax, &mut cpu.cx),
+                                    Register::DX => mem::swap(&mut cpu.ax, &mut cpu.dx),
+                                    _ => {}
+                                },
+                                Register::BX => match r2 {
+                                    Register::AX => mem::swap(&mut cpu.bx, &mut cpu.ax),
+                                    Register::CX => mem::swap(&mut cpu.bx, &mut cpu.cx),
+                                    Register::DX => mem::swap(&mut cpu.bx, &mut cpu.dx),
+                                    _ => {}
+                                },
+                                Register::CX => match r2 {
+                                    Register::AX => mem::swap(&mut cpu.cx, &mut cpu.ax),
+                                    Register::BX => mem::swap(&mut cpu.cx, &mut cpu.bx),
+                                    Register::DX => mem::swap(&mut cpu.cx, &mut cpu.dx),
+                                    _ => {}
+                                },
+                                Register::DX => match r2 {
+                                    Register::AX => mem::swap(&mut cpu.dx, &mut cpu.ax),
+                                    Register::BX => mem::swap(&mut cpu.dx, &mut cpu.bx),
+                                    Register::CX => mem::swap(&mut cpu.dx, &mut cpu.cx),
+                                    _ => {}
+                                },
+                            }
+                        }
+                    }
+                    Operation::INT => {
+                        if let Operands::V3(i) = instruction.operands {
+                            match i {
+                                Interupt::H20 => {
+                                    //self.mode = None;
+                                    events.push(TickEvent::Terminated(cpu_i));
+                                    continue;
+                                }
+                                Interupt::H10 => output = Some(cpu.dx),
+                                // Only the low byte fits on DX, it wraps every 256 ticks
+                                Interupt::H11 => cpu.dx = self.counter as u8,
+                                Interupt::H12 => {
+                                    let free = self.memory.free_size();
+                                    cpu.dx = free.min(u8::MAX as usize) as u8;
+                                    log!(
+                                        self,
+                                        Level::Info,
+                                        "free_memory_query",
+                                        p.unwrap_or_default(),
+                                        free
+                                    );
+                                }
+                                Interupt::H09 => {
+                                    // Scripted values are read without blocking, once they run
+                                    // out the user is asked like on any other program
+                                    let p_id = p.unwrap_or_default();
+                                    let script = self
+                                        .diagram
+                                        .get_mut(p_id)
+                                        .and_then(|x| x.input.as_mut())
+                                        .map(|x| x.pop_front());
+                                    match script {
+                                        Some(Some(value)) => {
+                                            cpu.dx = value;
+                                            self.memory.write_keyboard(value);
+                                            log!(self, Level::Info, "input_scripted", p_id, value);
+                                        }
+                                        exhausted => {
+                                            if exhausted.is_some() {
+                                                if let Some(timing) = self.diagram.get_mut(p_id) {
+                                                    timing.input = None;
+                                                }
+                                                log!(self, Level::Info, "input_script_empty", p_id);
+                                            }
+                                            //self.mode = None;
+                                            self.history[cpu_i].push(
+                                                self.counter,
+                                                p_id,
+                                                pc,
+                                                instruction,
+                                                cpu.ac,
+                                                quantum_left,
+                                            );
+                                            events.push(TickEvent::Blocked(cpu_i));
+                                            continue;
+                                        }
+                                    }
+                                }
+                            }
+                        }
+                    }
+                    Operation::JMP => {
+                        if let Operands::V1(s, num) = instruction.operands {
+                            jump = Some(jump_target(cpu.pc, s, num));
+                        }
+                    }
+                    Operation::JE => {
+                        if cpu.z {
+                            if let Operands::V1(s, num) = instruction.operands {
+                                jump = Some(jump_target(cpu.pc, s, num));
+                            }
+                        }
+                    }
+                    Operation::JNE => {
+                        if !cpu.z {
+                            if let Operands::V1(s, num) = instruction.operands {
+                                jump = Some(jump_target(cpu.pc, s, num));
+                            }
+                        }
+                    }
+                    Operation::PUSH => {
+                        if let Operands::V2(r) = instruction.operands {
+                            let value = *cpu.register_mut(r);
+                            if let Some(owner) = self.memory.write(cpu.sp, value) {
+                                code_write = code_write.or(Some((cpu.sp, owner)));
+                            }
+                            cpu.sp += 1;
+                        }
+                    }
+                    Operation::POP => {
+                        if let Operands::V2(r) = instruction.operands {
+                            match r {
+                                Register::AX => {
+                                    cpu.ax = self.memory.data[cpu.sp];
+                                    cpu.sp -= 1;
+                                }
+                                Register::BX => {
+                                    cpu.bx = self.memory.data[cpu.sp];
+                                    cpu.sp -= 1;
+                                }
+                                Register::CX => {
+                                    cpu.cx = self.memory.data[cpu.sp];
+                                    cpu.sp -= 1;
+                                }
+                                Register::DX => {
+                                    cpu.dx = self.memory.data[cpu.sp];
+                                    cpu.sp -= 1;
+                                }
+                            }
+                        }
+                    }
+                    Operation::PARAM => {
+                        if let Operands::V4(p1, p2, p3) = instruction.operands {
+                            for value in [p1, p2, p3].into_iter().filter(|x| *x != 0) {
+                                if let Some(owner) = self.memory.write(cpu.sp, value) {
+                                    code_write = code_write.or(Some((cpu.sp, owner)));
+                                }
+                                cpu.sp += 1;
+                            }
+                        }
+                    }
+                    Operation::CMP => {
+                        if let Operands::V6(r1, r2) = instruction.operands {
+                            match r1 {
+                                Register::AX => match r2 {
+                                    Register::BX => cpu.z = cpu.ax == cpu.bx,
+                                    Register::CX => cpu.z = cpu.ax == cpu.cx,
+                                    Register::DX => cpu.z = cpu.ax == cpu.dx,
+                                    _ => {}
+                                },
+                                Register::BX => match r2 {
+                                    Register::AX => cpu.z = cpu.bx == cpu.ax,
+                                    Register::CX => cpu.z = cpu.bx == cpu.cx,
+                                    Register::DX => cpu.z = cpu.bx == cpu.dx,
+                                    _ => {}
+                                },
+                                Register::CX => match r2 {
+                                    Register::AX => cpu.z = cpu.cx == cpu.ax,
+                                    Register::BX => cpu.z = cpu.cx == cpu.bx,
+                                    Register::DX => cpu.z = cpu.cx == cpu.dx,
+                                    _ => {}
+                                },
+                                Register::DX => match r2 {
+                                    Register::AX => cpu.z = cpu.dx == cpu.ax,
+                                    Register::BX => cpu.z = cpu.dx == cpu.bx,
+                                    Register::CX => cpu.z = cpu.dx == cpu.cx,
+                                    _ => {}
+                                },
+                            }
+                        }
+                    }
+                    // The device region is the only memory programs can address
+                    Operation::LOADM | Operation::STOREM => {
+                        if let Operands::V5(r, address) = instruction.operands {
+                            let address = address as usize;
+                            if !self.memory.is_device(address) {
+                                events.push(TickEvent::Fault(cpu_i, Fault::MemoryViolation));
+                                continue;
+                            }
+                            let register = match r {
+                                Register::AX => &mut cpu.ax,
+                                Register::BX => &mut cpu.bx,
+                                Register::CX => &mut cpu.cx,
+                                Register::DX => &mut cpu.dx,
+                            };
+                            if instruction.operation == Operation::LOADM {
+                                *register = self.memory.data[address];
+                            } else {
+                                self.memory.data[address] = *register;
+                                if self.memory.device().map(|x| x.0) == Some(address) {
+                                    output = Some(*register);
+                                }
+                            }
+                        }
+                    }
+                    // The mismatch is logged with the line, the fault terminates the process
+                    Operation::ASSERT => {
+                        if let Operands::V5(r, expected) = instruction.operands {
+                            let found = *cpu.register_mut(r);
+                            if found != expected {
+                                let p_id = p.unwrap_or_default();
+                                let line = self
+                                    .memory
+                                    .source_line(p_id, cpu.pc)
+                                    .map(|x| x.0)
+                                    .unwrap_or_default();
+                                log!(
+                                    self,
+                                    Level::Warning,
+                                    "assertion_failed",
+                                    p_id,
+                                    line,
+                                    r,
+                                    expected,
+                                    found
+                                );
+                                events.push(TickEvent::Fault(cpu_i, Fault::AssertionFailed));
+                                continue;
+                            }
+                        }
+                    }
+                    // The offset is from the base of the stack, not from SP
+                    Operation::MOVN => {
+                        if let Operands::V7(offset, value) = instruction.operands {
+                            let (base, size) = p
+                                .and_then(|id| self.memory.pcb(id))
+                                .map(|x| (x.stack_segment, x.stack_segment_size))
+                                .unwrap_or_default();
+                            if offset as usize >= size {
+                                events.push(TickEvent::Fault(cpu_i, Fault::MemoryViolation));
+                                continue;
+                            }
+                            let address = base + offset as usize;
+                            if let Some(owner) = self.memory.write(address, value) {
+                                code_write = code_write.or(Some((address, owner)));
+                            }
+                        }
+                    }
+                }
+                // Writing on code usually means a broken stack, the instructions would fail much later
+                if let Some((address, owner)) = code_write {
+                    let writer = p.unwrap_or_default();
+                    let address = format!("0x{:02X}", address);
+                    log!(self, Level::Warning, "code_written", writer, address, owner);
+                    if !self.config.self_modifying_code {
+                        events.push(TickEvent::Fault(cpu_i, Fault::CodeWrite));
+                        continue;
+                    }
+                }
+                // The display has the newest value of every CPU and the log keeps all of them in
+                // order
+                if let Some(value) = output {
+                    let p_id = p.unwrap_or_default();
+                    self.display_content = value.to_string();
+                    self.cpu_output.insert(cpu_i, (p_id, value));
+                    log!(self, Level::Info, "process_output", p_id, value, cpu_i);
+                }
+                // Arithmetic sets the zero flag with its result, so DEC can drive a JNE loop
+                if !self.config.legacy_flags {
+                    match (instruction.operation, instruction.operands) {
+                        (Operation::INC | Operation::DEC, Operands::V2(r)) => {
+                            cpu.z = *cpu.register_mut(r) == 0
+                        }
+                        (Operation::ADD | Operation::SUB | Operation::INC | Operation::DEC, _) => {
+                            cpu.z = cpu.ac == 0
+                        }
+                        _ => {}
+                    }
+                }
+
+                self.history[cpu_i].push(
+                    self.counter,
+                    p.unwrap_or_default(),
+                    pc,
+                    instruction,
+                    cpu.ac,
+                    quantum_left,
+                );
+
+                if let Some(timing) = p.and_then(|x| self.diagram.get_mut(x)) {
+                    // Programs with jumps can execute more instructions than they have
+                    timing.run_tick();
+                    timing.executed += 1;
+                    timing.execution = Some(timing.start.unwrap().elapsed());
+                }
+
+                cpu.pc = jump.unwrap_or(cpu.pc + INSTRUCTION_SIZE);
+
+                // The breakpoint has been passed
+                if self.breakpoint_hit.map(|x| x.0) == Some(cpu_i) {
+                    self.breakpoint_hit = None;
+                }
+            } else {
+                self.usage[cpu_i].tick(false);
+            }
+        }
+        self.halt(&events);
+        if only.is_some() {
+            return events;
+        }
+        self.counter += 1;
+
+        if self.expired_cpu().is_some() {
+            // A quantum typed during the run is used from this boundary on
+            if let Some(next) = self.next_quantum.take() {
+                self.quantum = Some(next);
+            }
+            events.push(TickEvent::QuantumExpired);
+        }
+        events
+    }
+
+    fn view(&self) -> iced::Element<'_, Message> {
+        let lang = self.config.lang;
+        let palette = self.config.palette;
+        let mut play_button = if self.mode == Some(Mode::Manual) {
+            button(tr(lang, "play"))
+        } else if self.mode == Some(Mode::Automatic) {
+            button(tr(lang, "pause"))
+        } else {
+            button(tr(lang, "play_pause"))
+        };
+
+        let mut next_button = button(tr(lang, "next"));
+        let mut back_button = button(tr(lang, "back"));
+        let mut run_event_button = button(tr(lang, "run_to_event"));
+        let mut run_ticks_button = button(tr(lang, "run_n"));
+        let stats_button = button(tr(lang, "stats")).on_press(Message::StatsPressed);
+        let settings_button = button("⚙").on_press(Message::ToggleSettings);
+        let reset_button = button(tr(lang, "reset")).on_press(Message::ResetPressed);
+        if self.mode == Some(Mode::Manual) {
+            next_button = next_button.on_press(Message::Tick);
+            if !self.snapshots.is_empty() {
+                back_button = back_button.on_press(Message::StepBack);
+            }
+            run_event_button = run_event_button.on_press(Message::RunToEvent);
+            run_ticks_button = run_ticks_button.on_press(Message::RunTicks);
+        }
+        // Stored files that haven't been loaded can be started
+        if self.mode.is_some() || !self.storage.used.is_empty() {
+            play_button = play_button.on_press(Message::ChangeMode);
+        }
+        if let Some(settings) = &self.settings {
+            return settings_display(settings, self.config, self.mode.is_some(), lang);
+        }
+
+        if let Some(scheduler) = self.switch_scheduler {
+            return container(
+                column![
+                    text(tr(lang, "switch_title")).size(30),
+                    text(trf(lang, "switch_description", &[&scheduler])),
+                    row![
+                        button(tr(lang, "cancel")).on_press(Message::CancelSwitch),
+                        button(tr(lang, "switch_now")).on_press(Message::ConfirmSwitch),
+                    ]
+                    .spacing(5),
+                ]
+                .spacing(10),
+            )
+            .center_x(iced::Length::Fill)
+            .center_y(iced::Length::Fill)
+            .into();
+        }
+
+        if self.show_summary {
+            let mut summary = column![text(tr(lang, "summary_title")).size(30)].spacing(10);
+            for line in self.summary() {
+                summary = summary.push(text(line));
+            }
+            summary = summary.push(
+                row![
+                    button(tr(lang, "close")).on_press(Message::CloseSummary),
+                    button(tr(lang, "stats")).on_press(Message::SummaryStats),
+                    button(tr(lang, "run_again")).on_press(Message::SoftReset),
+                ]
+                .spacing(5),
+            );
+            return container(summary)
+                .center_x(iced::Length::Fill)
+                .center_y(iced::Length::Fill)
+                .into();
+        }
+
+        if self.confirm_reset {
+            return container(
+                column![
+                    text(tr(lang, "reset_title")).size(30),
+                    text(tr(lang, "reset_description")),
+                    row![
+                        button(tr(lang, "cancel")).on_press(Message::CancelReset),
+                        button(tr(lang, "soft_reset")).on_press(Message::SoftReset),
+                        button(tr(lang, "full_reset"))
+                            .on_press(Message::FullReset)
+                            .style(button::danger),
+                    ]
+                    .spacing(5),
+                ]
+                .spacing(10),
+            )
+            .center_x(iced::Length::Fill)
+            .center_y(iced::Length::Fill)
+            .into();
+        }
+
+        // Program editor
+        if let Some(editor) = &self.editor {
+            let mut errors = column![];
+            for error in &editor.errors {
+                errors = errors.push(text(error.clone()).color(color!(0xff5555)));
+            }
+            return container(
+                column![
+                    text(tr(lang, "program")).size(30),
+                    text_input(tr(lang, "name"), &editor.name).on_input(Message::EditorName),
+                    text_editor(&editor.content)
+                        .on_action(Message::EditorAction)
+                        .height(iced::Length::Fill),
+                    errors,
+                    row![
+                        button(tr(lang, "assemble")).on_press(Message::AssembleProgram),
+                        button(tr(lang, "cancel")).on_press(Message::CloseEditor),
+                    ]
+                    .spacing(5),
+                ]
+                .spacing(10),
+            )
+            .padding(10)
+            .width(iced::Length::Fill)
+            .height(iced::Length::Fill)
+            .into();
+        }
+
+        //Stats display
+        if self.show_stats {
+            let scheduler_text = match self.config.scheduler {
+                Some(Scheduler::RR) => rich_text([
+                    span(tr(lang, "selected_method")),
+                    span("Round Robin").size(22).color(color!(0x9E69E3)),
+                    span(format!(" (Quantum: {})", self.quantum.unwrap_or_default()))
+                        .size(18)
+                        .color(color!(0xFFD700)), // Muestra el quantum
+                ]),
+                Some(scheduler) => rich_text([
+                    span(tr(lang, "selected_method")),
+                    span(scheduler.to_string()).size(22).color(color!(0x9E69E3)),
+                ]),
+                None => rich_text([span(tr(lang, "no_method"))]),
+            };
+
+            // Inicia la construcción del bloque de estadísticas
+            let mut stats_view = column![
+                container(text(tr(lang, "stats_title")).size(30),)
+                    .padding(10)
+                    .style(container::rounded_box)
+                    .width(iced::Length::Fill)
+                    .center_x(iced::Length::Fill),
+                widget::Space::with_height(iced::Length::Fixed(20.0)),
+                scheduler_text,
+                widget::Space::with_height(iced::Length::Fixed(20.0)),
+            ];
+
+            // Añade cada estadística individualmente en el `stats_view`, agrupadas por origen
+            for group in group_stats(&self.stats_data) {
+                let collapsed = self.collapsed_groups.contains(&group.origin);
+                stats_view = stats_view.push(
+                    button(text(trf(
+                        lang,
+                        "stats_group",
+                        &[
+                            &if collapsed { "▸" } else { "▾" },
+                            &short_name(&group.origin),
+                            &group.processes.len(),
+                            &format!("{:.2}", group.turnaround_time),
+                            &format!("{:.2}", group.waiting_time),
+                        ],
+                    )))
+                    .style(button::text)
+                    .on_press(Message::ToggleGroup(group.origin.clone())),
+                );
+                if collapsed {
+                    continue;
+                }
+                for stat in group.processes.iter().map(|x| &self.stats_data[*x]) {
+                    stats_view = stats_view.push(column![
+                        text(trf(
+                            lang,
+                            "stats_process",
+                            &[&stat.process_id, &short_name(&stat.name), &stat.cpu_id]
+                        )),
+                        text(trf(
+                            lang,
+                            "stats_arrival",
+                            &[&format!("{:.2}", stat.arrival_time)]
+                        )),
+                        text(trf(
+                            lang,
+                            "stats_turnaround",
+                            &[&stat.process_id, &format!("{:.2}", stat.turnaround_time)]
+                        )),
+                        text(trf(
+                            lang,
+                            "stats_execution",
+                            &[
+                                &format!("{:.2}", stat.execution_time),
+                                &format!("{:.2}", stat.response_ratio)
+                            ]
+                        )),
+                        text(match stat.estimated.filter(|x| *x != usize::MAX) {
+                            Some(estimated) => trf(
+                                lang,
+                                "stats_estimation",
+                                &[
+                                    &estimated,
+                                    &stat.ticks,
+                                    &(estimated as i64 - stat.ticks as i64)
+                                ]
+                            ),
+                            None => trf(lang, "stats_no_estimate", &[&stat.ticks]),
+                        }),
+                        text(match stat.first_response {
+                            Some(ticks) => trf(lang, "stats_first_response", &[&ticks]),
+                            None => String::new(),
+                        }),
+                        widget::Space::with_height(iced::Length::Fixed(10.0)), // Espacio entre procesos
+                    ]);
+                }
+            }
+
+            // Suma el tiempo total de turnaround y añade al final del `stats_view`
+            let tiempo_total: f64 = self
+                .stats_data
+                .iter()
+                .map(|stat| stat.turnaround_time)
+                .sum();
+            stats_view = stats_view.push(text(trf(
+                lang,
+                "total_time",
+                &[&format!("{:.2}", tiempo_total)],
+            )));
+            // How long the processes waited for their first CPU with this run's schedulers
+            let responses = self
+                .stats_data
+                .iter()
+                .filter_map(|x| x.first_response)
+                .collect::<Vec<_>>();
+            if !responses.is_empty() {
+                let average = responses.iter().sum::<u64>() as f64 / responses.len() as f64;
+                stats_view = stats_view.push(text(trf(
+                    lang,
+                    "average_first_response",
+                    &[&format!("{:.2}", average)],
+                )));
+            }
+
+            // Fetches of the whole run
+            let (hits, misses) = self.cache.totals();
+            if let Some(rate) = hit_rate((hits, misses)) {
+                stats_view = stats_view.push(text(trf(
+                    lang,
+                    "cache_totals",
+                    &[&hits, &misses, &format!("{:.2}", rate)],
+                )));
+            }
+
+            // Faults of the run
+            for fault in [
+                Fault::StackOverflow,
+                Fault::InvalidInstruction,
+                Fault::MemoryViolation,
+                Fault::AssertionFailed,
+            ] {
+                if let Some(count) = self.faults.get(&fault) {
+                    stats_view = stats_view.push(text(trf(
+                        lang,
+                        "stats_faults",
+                        &[&tr(lang, fault.key()), count],
+                    )));
+                }
+            }
+
+            // Uso de cada CPU
+            for (cpu_i, usage) in self.usage.iter().enumerate() {
+                stats_view = stats_view.push(text(trf(
+                    lang,
+                    "cpu_usage",
+                    &[
+                        &cpu_i,
+                        &format!("{:.2}", usage.utilization()),
+                        &usage.busy_ticks,
+                        &usage.idle_ticks,
+                        &usage.context_switches,
+                    ],
+                )));
+            }
+            let busy_ticks: u64 = self.usage.iter().map(|x| x.busy_ticks).sum();
+            let idle_ticks: u64 = self.usage.iter().map(|x| x.idle_ticks).sum();
+            let context_switches: u64 = self.usage.iter().map(|x| x.context_switches).sum();
+            let total_usage = CpuUsage {
+                busy_ticks,
+                idle_ticks,
+                context_switches,
+            };
+            stats_view = stats_view.push(text(trf(
+                lang,
+                "total_usage",
+                &[
+                    &format!("{:.2}", total_usage.utilization()),
+                    &total_usage.context_switches,
+                ],
+            )));
+
+            // Añade el botón para regresar
+            stats_view = stats_view.push(row![
+                widget::Space::with_width(iced::Length::Fill),
+                button(tr(lang, "back"))
+                    .on_press(Message::StatsPressed)
+                    .width(iced::Length::Shrink),
+            ]);
+
+            // Retorna el `stats_view` dentro de un contenedor `scrollable`
+            return container(scrollable(stats_view))
+                .width(iced::Length::Fill)
+                .height(iced::Length::Fill)
+                .into();
+        }
+
+        // The quantum is only used by Round Robin
+        // Instructions executed on the last frame while the turbo is on
+        let turbo_display = if self.turbo {
+            text(trf(lang, "turbo_executed", &[&self.turbo_executed]))
+        } else {
+            text("")
+        };
+        let quantum_input: Element<Message> = if self.uses_quantum() {
+            let input = text_input(tr(lang, "quantum"), &self.quantum_input)
+                .on_input(Message::QuantumInput)
+                .width(50);
+            let mut quantum = row![text(tr(lang, "quantum")), input].spacing(5);
+            if parse_quantum(&self.quantum_input).is_none() {
+                quantum = quantum.push(text(tr(lang, "quantum_invalid")).color(color!(0xff5555)));
+            }
+            quantum.into()
+        } else {
+            widget::Space::with_width(0).into()
+        };
+
+        // Menu bar
+        let menu_bar = row![
+            button(tr(lang, "file")).on_press(Message::OpenFile),
+            button(tr(lang, "open_folder")).on_press(Message::OpenFolder),
+            button(tr(lang, "new_program")).on_press(Message::NewProgram),
+            play_button,
+            back_button,
+            next_button,
+            run_event_button,
+            run_ticks_button,
+            text_input("N", &self.run_ticks)
+                .on_input(Message::RunTicksInput)
+                .width(60),
+            reset_button,
+            stats_button,
+            settings_button,
+            button(tr(lang, "log")).on_press(Message::ToggleLog),
+            button(tr(lang, "allocator")).on_press(Message::ToggleAllocator),
+            pick_list(
+                Scheduler::ALL,
+                self.config.scheduler,
+                Message::SchedulerSelected
+            ),
+            quantum_input,
+            pick_list(Speed::ALL, Some(self.speed), Message::SpeedSelected),
+            widget::checkbox(tr(lang, "turbo"), self.turbo).on_toggle(Message::ToggleTurbo),
+            turbo_display,
+            pick_list(Theme::ALL, Some(&self.theme), Message::ThemeSelected),
+            pick_list(Lang::ALL, Some(lang), Message::LangSelected),
+            text(self.status()),
+            widget::Space::new(iced::Length::Shrink, iced::Length::Fill)
+        ]
+        .height(40)
+        .spacing(5)
+        .padding([5, 10]);
+
+        // Segments of a process and loaded files in the color of the process
+        let memory_lines = self
+            .memory
+            .layout()
+            .iter()
+            .zip(self.memory_map())
+            .map(|(segment, line)| (line, segment.owner.map(|x| process_color(palette, x))))
+            .collect();
+        let storage_lines = self
+            .storage
+            .layout()
+            .iter()
+            .zip(self.storage_map())
+            .map(|(segment, line)| {
+                let owner = self.loaded_files.iter().find(|x| x.0 == segment.address);
+                (
+                    line,
+                    owner.and_then(|x| x.1).map(|x| process_color(palette, x)),
+                )
+            })
+            .collect();
+
+        // Show the list of files
+        let mut files = column![].padding([5, 10]);
+        for (index, (file_name, address, _)) in self.storage.used.iter().enumerate() {
+            let loaded = self.loaded_files.iter().find(|x| x.0 == *address);
+            let pending = self.pending_files.iter().find(|x| x.0 == *address);
+            let mut name = span(file_name);
+            // Highlight the files with a running process
+            if let Some((_, p_id)) = loaded {
+                if self.cpus.iter().any(|x| x.assigned == *p_id) && p_id.is_some() {
+                    name = name.color(color!(0xff79c6));
+                }
+            }
+            let mut spans = vec![
+                span(index).font(Font {
+                    weight: font::Weight::Bold,
+                    ..Font::default()
+                }),
+                span(" "),
+                name,
+            ];
+            if let Some((_, Some(p_id))) = loaded {
+                spans.push(span(format!(" P{}", p_id)).color(process_color(palette, *p_id)));
+            }
+            if self.stale_source(*address).is_some() {
+                spans.push(
+                    span(format!(" ({})", tr(lang, "source_changed"))).color(color!(0xffb86c)),
+                );
+            }
+            match pending.map(|x| x.1) {
+                Some(Admission::Waiting) => spans.push(
+                    span(format!(" ({})", tr(lang, "waiting_memory"))).color(color!(0xf1fa8c)),
+                ),
+                Some(Admission::Failed) => {
+                    spans.push(span(format!(" ({})", tr(lang, "too_big"))).color(color!(0xff5555)))
+                }
+                None => (),
+            }
+            if self.selected_file == Some(*address) {
+                spans[2] = spans[2].clone().underline(true);
+            }
+            let entry = mouse_area(rich_text(spans)).on_press(Message::SelectFile(*address));
+            // Files without a live process can be edited
+            if loaded.is_some_and(|x| x.1.is_some()) {
+                files = files.push(entry);
+            } else {
+                files = files.push(
+                    row![
+                        entry,
+                        widget::Space::with_width(iced::Length::Fill),
+                        button(text(tr(lang, "edit")).size(12))
+                            .on_press(Message::EditFile(*address))
+                            .style(button::text)
+                            .padding(0),
+                    ]
+                    .spacing(5),
+                );
+            }
+        }
+        let files_display = container(scrollable(files))
+            .height(iced::Length::Fill)
+            .width(self.files_width)
+            .style(container::rounded_box);
+
+        // Display memory content
+        let memory_display = binary_display(
+            &self.memory.data[..],
+            MEMORY_SCROLL,
+            self.memory_width,
+            self.memory_view,
+            Message::MemoryScrolled,
+            &self.memory_highlights(),
+            HexFormat {
+                rows: hex_rows(
+                    self.memory.data.len(),
+                    self.memory_rows,
+                    &self.memory.layout(),
+                ),
+                ascii: self.show_ascii,
+                decimal: self.decimal,
+            },
+        );
+
+        // Display storage content
+        let storage_display = binary_display(
+            &self.storage.data[..],
+            STORAGE_SCROLL,
+            self.memory_width,
+            self.storage_view,
+            Message::StorageScrolled,
+            &self.storage_highlights(),
+            HexFormat {
+                rows: hex_rows(
+                    self.storage.data.len(),
+                    self.storage_rows,
+                    &self.storage.layout(),
+                ),
+                ascii: self.show_ascii,
+                decimal: self.decimal,
+            },
+        );
+
+        // Display CPU content
+        let mut cpus_display = row![].spacing(5);
+
+        for (cpu_i, core) in self.cpus.iter().enumerate() {
+            let (cpu, p) = (&core.cpu, core.assigned);
+            let ir = self.ir_label(cpu_i);
+            // Process running on the CPU
+            let label = match p.and_then(|x| self.diagram.get(x)) {
+                Some(timing) => format!("CPU {}: {}", cpu_i, timing.label()),
+                None => format!("CPU {}", cpu_i),
+            };
+            // Steps only this CPU
+            let mut step_button = button(tr(lang, "step_cpu")).width(115);
+            if self.mode == Some(Mode::Manual) && p.is_some() {
+                step_button = step_button.on_press(Message::TickCpu(cpu_i));
+            }
+            // Shrinks with every tick of the quantum
+            let quantum: Element<Message> = match self.quantum_left(cpu_i) {
+                Some((left, quantum)) => row![
+                    text(trf(lang, "quantum_left", &[&left, &quantum])).size(12),
+                    widget::progress_bar(0.0..=quantum as f32, left as f32).height(6),
+                ]
+                .spacing(5)
+                .width(115)
+                .align_y(iced::Alignment::Center)
+                .into(),
+                None => widget::Space::with_height(0).into(),
+            };
+            // Hit rate of the process on the CPU
+            let cache_rate = match p.filter(|_| self.cache.config.enabled) {
+                Some(p_id) => text(match hit_rate(self.cache.stats(p_id)) {
+                    Some(rate) => trf(lang, "cache_hit_rate", &[&format!("{:.0}", rate)]),
+                    None => tr(lang, "cache_no_fetches").to_string(),
+                })
+                .size(12),
+                None => text(""),
+            };
+            let mut cpu_column = column![
+                text(label).size(12),
+                quantum,
+                cpu_display(cpu, &self.last_registers[cpu_i], ir),
+                usage_display(&self.usage[cpu_i]),
+                cache_rate,
+                text(match self.cpu_output.get(&cpu_i) {
+                    Some((p_id, value)) => trf(lang, "cpu_output", &[p_id, value]),
+                    None => tr(lang, "no_output").to_string(),
+                })
+                .size(12),
+                step_button,
+                button(tr(lang, "history"))
+                    .on_press(Message::ToggleHistory(cpu_i))
+                    .width(115),
+            ]
+            .spacing(5);
+            if self.show_history.get(cpu_i) == Some(&true) {
+                cpu_column = cpu_column.push(history_display(&self.history[cpu_i]));
+            }
+            cpus_display = cpus_display.push(cpu_column);
+        }
+
+        let display = text_input(":$ ", &self.display_content).width(115);
+        // One input row for each process waiting for a number
+        let mut inputs = column![].spacing(5);
+        for (id, _, _) in &self.waiting_queue {
+            let id = *id;
+            let label = match self.diagram.get(id) {
+                Some(timing) => timing.label(),
+                None => format!("P{}", id),
+            };
+            let range = self.diagram.get(id).and_then(|x| x.input_range);
+            let value = self.input_values.get(&id).map(String::as_str).unwrap_or("");
+            let mut input = text_input(":$ ", value)
+                .width(115)
+                .on_input(move |x| Message::Input(id, x));
+            // Only numbers that fit on DX, and on the range of the program, can be submitted
+            let (expects, error) = match range {
+                Some((low, high)) => (
+                    trf(lang, "expects_number_range", &[&label, &low, &high]),
+                    trf(lang, "input_out_of_range", &[&low, &high]),
+                ),
+                None => (
+                    trf(lang, "expects_number", &[&label]),
+                    tr(lang, "input_range").to_string(),
+                ),
+            };
+            let mut row = column![text(expects).size(12)];
+            let num = value
+                .parse::<u8>()
+                .ok()
+                .filter(|x| range.is_none_or(|(low, high)| (low..=high).contains(x)));
+            if let Some(num) = num {
+                row = row.push(input.on_submit(Message::Unblock(id, num)));
+            } else {
+                input = input.style(|theme, status| {
+                    let mut style = text_input::default(theme, status);
+                    style.border.color = color!(0xff5555);
+                    style
+                });
+                row = row.push(input);
+                row = row.push(text(error).size(12).color(color!(0xff5555)));
+            }
+            inputs = inputs.push(row);
+        }
+
+        let mut pcbs_display = row![].spacing(5);
+        for pcb in self.memory.pcbs() {
+            let timing = self.diagram.get(pcb.id);
+            let card = pcb_display(
+                pcb,
+                timing,
+                self.flash_pcb == Some(pcb.id),
+                self.arrived.contains(&pcb.id),
+                self.boosted.contains(&pcb.id),
+                self.counter,
+                &self.config,
+            );
+            let loading = match self.dma.progress(pcb.id) {
+                Some((copied, total)) => {
+                    text(trf(lang, "dma_loading", &[&copied, &total])).size(10)
+                }
+                None => text(""),
+            };
+            let waiting = match self.waiting_for(pcb.id) {
+                Some(after) if pcb.process_state == ProcessState::New => {
+                    text(trf(lang, "waiting_for", &[&after])).size(10)
+                }
+                _ => text(""),
+            };
+            let stale = match self.stale_pcb(pcb.id) {
+                Some(_) => text(tr(lang, "source_changed"))
+                    .size(10)
+                    .color(color!(0xffb86c)),
+                None => text(""),
+            };
+            pcbs_display = pcbs_display.push(
+                mouse_area(column![card, loading, waiting, stale])
+                    .on_press(Message::SelectPcb(pcb.id)),
+            );
+        }
+
+        // Disassembly of the selected process
+        let mut disassembly = column![];
+        if let Some(pcb) = self.selected_pcb.and_then(|id| self.memory.pcb(id)) {
+            // The code segment is freed once the process is done, its post-mortem is shown
+            if let Some(post_mortem) = self
+                .post_mortems
+                .get(&pcb.id)
+                .filter(|_| pcb.process_state == ProcessState::Terminated)
+            {
+                disassembly = disassembly.push(post_mortem_display(pcb.id, post_mortem, lang));
+            } else if pcb.process_state != ProcessState::Terminated {
+                let pc = match self.cpus.iter().find(|x| x.assigned == Some(pcb.id)) {
+                    Some(core) => core.cpu.pc,
+                    None => pcb.pc,
+                };
+                // Only the bytes a DMA transfer already copied, they still may not decode after
+                // the process wrote its code
+                let copied = self
+                    .dma
+                    .progress(pcb.id)
+                    .map_or(pcb.code_segment_size, |x| x.0);
+                let instructions =
+                    disassemble(&self.memory.data[pcb.code_segment..pcb.code_segment + copied]);
+                disassembly = disassembly.push(text(trf(lang, "disassembly", &[&pcb.id])));
+                disassembly = disassembly.push(disassembly_display(
+                    &pcb,
+                    &instructions,
+                    self.memory.source(pcb.id),
+                    &self.breakpoints,
+                    pc,
+                    self.config.legacy_flags,
+                    lang,
+                ));
+            }
+        }
+
+        let mut log_panel = column![];
+        if self.show_log {
+            log_panel = log_panel.push(log_display(&self.log, self.log_level, lang));
+        }
+        if self.show_allocator {
+            log_panel = log_panel.push(allocator_display(self.allocator_tables(), lang));
+        }
+
+        // Why the scheduler picked the last process
+        let decision_bar = match &self.decision {
+            Some(decision) => row![text(decision.text(lang)).size(12)].padding([0, 10]),
+            None => row![],
+        };
+
+        widget::container(column![
+            menu_bar,
+            decision_bar,
+            log_panel,
+            row![
+                column![
+                    row![
+                        text(tr(lang, "files")),
+                        widget::Space::with_width(iced::Length::Fill),
+                        width_buttons(self.files_width, FILES_WIDTHS, Message::FilesWidth),
+                    ]
+                    .width(self.files_width),
+                    files_display
+                ],
+                column![
+                    row![
+                        text(tr(lang, "memory")),
+                        widget::Space::with_width(iced::Length::Fill),
+                        widget::checkbox(tr(lang, "ascii"), self.show_ascii)
+                            .on_toggle(Message::ToggleAscii)
+                            .text_size(12),
+                        widget::checkbox(tr(lang, "decimal"), self.decimal)
+                            .on_toggle(Message::ToggleDecimal)
+                            .text_size(12),
+                        pick_list(
+                            RowMode::ALL,
+                            Some(self.memory_rows),
+                            Message::MemoryRowsSelected
+                        )
+                        .text_size(12),
+                    ]
+                    .width(self.memory_width),
+                    memory_display,
+                    map_display(memory_lines, self.memory_width),
+                    legend_display(palette, &self.live_processes()),
+                    row![
+                        text(tr(lang, "storage")),
+                        widget::Space::with_width(iced::Length::Fill),
+                        width_buttons(self.memory_width, MEMORY_WIDTHS, Message::MemoryWidth),
+                        pick_list(
+                            RowMode::ALL,
+                            Some(self.storage_rows),
+                            Message::StorageRowsSelected
+                        )
+                        .text_size(12),
+                    ]
+                    .width(self.memory_width),
+                    storage_display,
+                    map_display(storage_lines, self.memory_width),
+                ]
+                .spacing(5),
+                column![
+                    text(tr(lang, "cpu")),
+                    cpus_display,
+                    text(tr(lang, "display")),
+                    display,
+                    inputs,
+                    text(tr(lang, "queues")),
+                    queues_display(
+                        &self.ready_queue(),
+                        &self.running_queue(),
+                        &self.blocked_queue(),
+                        palette,
+                        lang,
+                    ),
+                    legend_display(palette, &self.live_processes()),
+                    text(tr(lang, "pcb_list")),
+                    pcbs_display,
+                    disassembly,
+                ],
+                widget::Space::new(iced::Length::Fill, iced::Length::Fill)
+            ]
+            .spacing(40)
+            .padding([10, 10])
+        ])
+        .center_x(iced::Length::Fill)
+        .center_y(iced::Length::Fill)
+        .width(iced::Length::Fill)
+        .height(iced::Length::Fill)
+        .into()
+    }
+
+    fn subscription(&self) -> Subscription<Message> {
+        // Save the UI preferences before closing
+        let close = window::close_requests().map(Message::CloseRequested);
+        if self.mode == Some(Mode::Automatic) {
+            return Subscription::batch([
+                close,
+                time::every(self.speed.interval(self.config.tick_interval_ms))
+                    .map(|_| Message::Tick),
+            ]);
+        }
+        close
+    }
+
+    fn theme(&self) -> Theme {
+        self.theme.clone()
+    }
+}
+
+// How an instruction uses the zero flag
+fn flags_key(instruction: Instruction, legacy_flags: bool) -> &'static str {
+    match instruction.operation {
+        Operation::CMP => "flags_cmp",
+        Operation::INC | Operation::DEC
+            if !legacy_flags && matches!(instruction.operands, Operands::V2(_)) =>
+        {
+            "flags_register"
+        }
+        Operation::ADD | Operation::SUB | Operation::INC | Operation::DEC if !legacy_flags => {
+            "flags_arithmetic"
+        }
+        Operation::JE | Operation::JNE => "flags_jump",
+        _ => "flags_none",
+    }
+}
+
+// Instructions of the process, clicking one toggles its breakpoint
+fn disassembly_display(
+    pcb: &PCB,
+    instructions: &[Result<Instruction, Vec<u8>>],
+    source: Option<&SourceMap>,
+    breakpoints: &[(usize, usize)],
+    pc: usize,
+    legacy_flags: bool,
+    lang: Lang,
+) -> Container<'static, Message> {
+    let mut lines = column![];
+    for (index, instruction) in instructions.iter().enumerate() {
+        let marker = if breakpoints.contains(&(pcb.id, index)) {
+            "●"
+        } else {
+            " "
+        };
+        // The line of the file and its text, with the comments
+        let source = source.and_then(|x| x.line(index));
+        let label = match (instruction, source) {
+            (Ok(instruction), Some((line, _))) => trf(lang, "source_line", &[&line, instruction]),
+            (Ok(instruction), None) => instruction.to_string(),
+            // Shown as data, like "DB 00 00 00 00 00 00"
+            (Err(bytes), _) => {
+                let bytes = bytes.iter().map(|x| format!("{:02X}", x));
+                format!("DB {}", bytes.collect::<Vec<_>>().join(" "))
+            }
+        };
+        let flags = match instruction {
+            Ok(instruction) => tr(lang, flags_key(*instruction, legacy_flags)),
+            Err(_) => "",
+        };
+        let mut line = span(format!("{} {:03} {}", marker, index, label)).font(Font {
+            weight: font::Weight::Bold,
+            ..Font::default()
+        });
+        // Next instruction to execute
+        if pcb.code_segment + index * INSTRUCTION_SIZE == pc {
+            line = line.color(color!(0x9afcb3));
+        }
+        lines = lines.push(tooltip(
+            button(rich_text([line]))
+                .on_press(Message::ToggleBreakpoint(pcb.id, index))
+                .style(button::text)
+                .padding([0, 5]),
+            container(
+                column![
+                    text(source.map(|x| x.1).unwrap_or_default().to_string()).size(12),
+                    text(flags).size(12)
+                ]
+                .spacing(3),
+            )
+            .padding(5)
+            .style(container::rounded_box),
+            tooltip::Position::Right,
+        ));
+    }
+    container(scrollable(lines))
+        .height(200)
+        .width(240)
+        .padding([5, 10])
+        .style(container::rounded_box)
+}
+
+fn pcb_display(
+    pcb: &PCB,
+    timing: Option<&Timing>,
+    flash: bool,
+    arrived: bool,
+    boosted: bool,
+    counter: u64,
+    config: &Config,
+) -> Tooltip<'static, Message> {
+    let lang = config.lang;
+    // Processes that haven't arrived show when they will
+    let arrival = match (pcb.process_state, timing) {
+        (ProcessState::New, Some(timing)) => text(trf(
+            lang,
+            "arrives_at",
+            &[
+                &timing.arrival,
+                &(timing.arrival as u64).saturating_sub(counter),
+            ],
+        ))
+        .size(12),
+        _ => text(""),
+    };
+    // Terminated processes stay greyed until the reset, their post-mortem is shown on click
+    let waiting = matches!(
+        pcb.process_state,
+        ProcessState::New | ProcessState::Terminated
+    );
+    let color = process_color(config.palette, pcb.id);
+    // The priority is edited before the process gets a CPU
+    let mut priority_buttons = row![].spacing(2);
+    if matches!(pcb.process_state, ProcessState::New | ProcessState::Ready) {
+        let change = |label, priority: Option<u8>| {
+            button(text(label).size(10))
+                .padding([0, 4])
+                .on_press_maybe(priority.map(|x| Message::SetPriority(pcb.id, x)))
+        };
+        priority_buttons = priority_buttons
+            .push(change("−", pcb.priority.checked_sub(1)))
+            .push(change("+", pcb.priority.checked_add(1)));
+    }
+    tooltip(
+        // PCB container
+        container(column![
+            row![
+                rich_text([span(pcb.id)
+                    .font(Font {
+                        weight: font::Weight::Bold,
+                        ..Font::default()
+                    })
+                    .color(color)]),
+                vertical_rule(3),
+                rich_text([span(format!("{:?}", pcb.process_state))
+                    .font(Font {
+                        weight: font::Weight::Bold,
+                        ..Font::default()
+                    })
+                    .color(color!(0xbd93f9))]),
+                vertical_rule(3),
+                rich_text([span(pcb.priority).font(Font {
+                    weight: font::Weight::Bold,
+                    ..Font::default()
+                })]),
+                priority_buttons,
+            ]
+            .spacing(5),
+            text(timing.map(|x| short_name(&x.name)).unwrap_or_default()).size(12),
+            arrival,
+            burst_display(pcb.process_state, timing),
+        ])
+        .padding([10, 10])
+        .style(move |theme| {
+            // Highlight the process that hit a breakpoint
+            if flash {
+                container::rounded_box(theme).background(color!(0xffb86c))
+            } else if arrived {
+                // Just moved to the ready queue
+                container::rounded_box(theme).background(color!(0x50fa7b))
+            } else if waiting {
+                container::rounded_box(theme).color(color!(0x6272a4))
+            } else {
+                container::rounded_box(theme)
+            }
+        }),
+        // Tooltip content container
+        container(column![
+            row![
+                // ID
+                rich_text([
+                    span(tr(lang, "id")),
+                    span(pcb.id)
+                        .font(Font {
+                            weight: font::Weight::Bold,
+                            ..Font::default()
+                        })
+                        .color(color)
+                ]),
+                widget::Space::new(100, iced::Length::Shrink),
+                // Priority
+                rich_text([
+                    span(tr(lang, "priority")),
+                    span(pcb.priority).font(Font {
+                        weight: font::Weight::Bold,
+                        ..Font::default()
+                    })
+                ])
+            ],
+            // Process State
+            rich_text([
+                span(tr(lang, "state")),
+                span(format!("{:?}", pcb.process_state))
+                    .font(Font {
+                        weight: font::Weight::Bold,
+                        ..Font::default()
+                    })
+                    .color(color!(0xbd93f9))
+            ]),
+            text(trf(
+                lang,
+                "source_file",
+                &[&timing.map(|x| x.name.trim_matches('"')).unwrap_or_default()]
+            )),
+            text(trf(
+                lang,
+                "code_segment",
+                &[&pcb.code_segment, &pcb.code_segment_size]
+            )),
+            text(trf(
+                lang,
+                "stack_segment",
+                &[&pcb.stack_segment, &pcb.stack_segment_size]
+            )),
+            text(trf(
+                lang,
+                "stack_usage",
+                &[
+                    &pcb.sp.saturating_sub(pcb.stack_segment),
+                    &pcb.stack_segment_size
+                ]
+            )),
+            text(trf(lang, "arrival", &[&timing.unwrap().arrival])),
+            text(trf(lang, "burst", &[&timing.unwrap().burst])),
+            text(trf(
+                lang,
+                "remaining_burst",
+                &[&timing.unwrap().remaining_burst]
+            )),
+            // What SJF and SRT believe against what it ran
+            text(trf(
+                lang,
+                "estimated_burst",
+                &[
+                    &match timing.unwrap().expected_burst(config.burst_estimation) {
+                        usize::MAX => "-".to_string(),
+                        estimate => estimate.to_string(),
+                    },
+                    &timing.unwrap().ticks
+                ]
+            )),
+            if let Some(execution) = timing.unwrap().execution {
+                text(trf(lang, "execution_time", &[&execution.as_secs()]))
+            } else {
+                text("")
+            },
+            if boosted {
+                text(tr(lang, "boosted")).color(color!(0x9afcb3))
+            } else {
+                text("")
+            }
+        ])
+        .padding([10, 10])
+        .style(|_| container::background(color!(0x5a5e77))),
+        tooltip::Position::Top,
+    )
+}
+
+fn cpu_display(cpu: &CPU, last: &CPU, ir: String) -> Container<'static, Message> {
+    container(column![
+        register_dispay("AX", format!("{:03}", cpu.ax), cpu.ax != last.ax),
+        register_dispay("BX", format!("{:03}", cpu.bx), cpu.bx != last.bx),
+        register_dispay("CX", format!("{:03}", cpu.cx), cpu.cx != last.cx),
+        register_dispay("DX", format!("{:03}", cpu.dx), cpu.dx != last.dx),
+        register_dispay("AC", format!("{:03}", cpu.ac), cpu.ac != last.ac),
+        register_dispay("PC", format!("{:03}", cpu.pc), cpu.pc != last.pc),
+        register_dispay("SP", format!("{:03}", cpu.sp), cpu.sp != last.sp),
+        register_dispay("IR", ir, cpu.ir != last.ir),
+        register_dispay(" Z", format!("{}", cpu.z), cpu.z != last.z),
+    ])
+    .height(200)
+    .width(115)
+    .padding([5, 10])
+    .style(container::rounded_box)
+}
+
+fn usage_display(usage: &CpuUsage) -> Element<'static, Message> {
+    column![
+        text(format!(
+            "{:.0}% | {} cs",
+            usage.utilization(),
+            usage.context_switches
+        ))
+        .size(12),
+        progress_bar(0.0..=100.0, usage.utilization() as f32)
+            .height(6)
+            .width(115),
+    ]
+    .into()
+}
+
+fn queues_display(
+    ready: &[usize],
+    running: &[(usize, usize, Option<u64>)],
+    blocked: &[(usize, &'static str)],
+    palette: Palette,
+    lang: Lang,
+) -> Container<'static, Message> {
+    let entry = |label: String, pcb_id: usize| {
+        button(text(label).size(12).color(process_color(palette, pcb_id)))
+            .on_press(Message::ScrollToProcess(pcb_id))
+            .style(button::text)
+            .padding(0)
+    };
+
+    let mut ready_column = column![text(tr(lang, "ready"))].spacing(2).width(115);
+    for pcb_id in ready {
+        ready_column = ready_column.push(entry(format!("P{}", pcb_id), *pcb_id));
+    }
+    let mut running_column = column![text(tr(lang, "running"))].spacing(2).width(115);
+    for (cpu_index, pcb_id, ticks) in running {
+        let label = match ticks {
+            Some(ticks) => format!(
+                "P{} CPU {} {}",
+                pcb_id,
+                cpu_index,
+                trf(lang, "quantum_ticks", &[ticks])
+            ),
+            None => format!("P{} CPU {}", pcb_id, cpu_index),
+        };
+        running_column = running_column.push(entry(label, *pcb_id));
+    }
+    let mut blocked_column = column![text(tr(lang, "blocked"))].spacing(2).width(115);
+    for (pcb_id, reason) in blocked {
+        blocked_column =
+            blocked_column.push(entry(format!("P{} {}", pcb_id, tr(lang, reason)), *pcb_id));
+    }
+
+    container(row![ready_column, running_column, blocked_column].spacing(5))
+        .padding([5, 10])
+        .style(container::rounded_box)
+}
+
+// Progress of the process colored by its state
+fn burst_display(state: ProcessState, timing: Option<&Timing>) -> Element<'static, Message> {
+    let progress = timing.map(|x| x.progress()).unwrap_or_default();
+    let color = match state {
+        ProcessState::New => color!(0x8be9fd),
+        ProcessState::Ready => color!(0xf1fa8c),
+        ProcessState::Running => color!(0x50fa7b),
+        ProcessState::Blocked => color!(0xffb86c),
+        ProcessState::Terminated => color!(0x6272a4),
+    };
+    progress_bar(0.0..=100.0, progress)
+        .height(4)
+        .style(move |theme| progress_bar::Style {
+            bar: color.into(),
+            ..progress_bar::primary(theme)
+        })
+        .into()
+}
+
+// Settings are read only while the emulator is running
+fn settings_display(
+    settings: &Settings,
+    config: Config,
+    running: bool,
+    lang: Lang,
+) -> Element<'static, Message> {
+    let mut fields = column![].spacing(5);
+    for field in SettingsField::ALL {
+        let mut input = text_input("", settings.field(field)).width(200);
+        if !running {
+            input = input.on_input(move |x| Message::SettingsInput(field, x));
+        }
+        fields = fields.push(
+            row![text(tr(lang, field.key())).width(200), input]
+                .spacing(5)
+                .align_y(iced::Alignment::Center),
+        );
+    }
+    let scheduler: Element<Message> = if running {
+        text(
+            settings
+                .scheduler
+                .map(|x| x.to_string())
+                .unwrap_or_default(),
+        )
+        .width(200)
+        .into()
+    } else {
+        pick_list(
+            Scheduler::ALL,
+            settings.scheduler,
+            Message::SettingsScheduler,
+        )
+        .width(200)
+        .into()
+    };
+    fields = fields.push(row![text(tr(lang, "scheduler")).width(200), scheduler].spacing(5));
+
+    // Live validation
+    let mut errors = column![];
+    let mut apply = button(tr(lang, "apply"));
+    let mut save = button(tr(lang, "save_config"));
+    match settings.config(config) {
+        Ok(_) => {
+            if !running {
+                apply = apply.on_press(Message::ApplySettings);
+            }
+            save = save.on_press(Message::SaveSettings);
+        }
+        Err(messages) => {
+            for message in messages {
+                errors = errors.push(text(message).color(color!(0xff5555)));
+            }
+        }
+    }
+
+    let mut view = column![text(tr(lang, "settings")).size(30), fields, errors].spacing(10);
+    if running {
+        view = view.push(text(tr(lang, "settings_running")).color(color!(0xffb86c)));
+    }
+    view = view.push(
+        row![
+            apply,
+            save,
+            button(tr(lang, "back")).on_press(Message::ToggleSettings),
+        ]
+        .spacing(5),
+    );
+
+    container(scrollable(view))
+        .padding(10)
+        .width(iced::Length::Fill)
+        .height(iced::Length::Fill)
+        .into()
+}
+
+fn log_display(log: &Log, level: Level, lang: Lang) -> Container<'static, Message> {
+    let mut entries = column![].spacing(2);
+    for entry in log.filter(level) {
+        let mut line = text(entry.to_string()).size(12);
+        if entry.level == Level::Warning {
+            line = line.color(color!(0xffb86c));
+        }
+        entries = entries.push(line);
+    }
+    container(column![
+        row![
+            text(tr(lang, "log")),
+            pick_list(Level::ALL, Some(level), Message::LogLevelSelected),
+            button(tr(lang, "copy")).on_press(Message::CopyLog),
+        ]
+        .spacing(5),
+        scrollable(entries)
+            .anchor_bottom()
+            .width(iced::Length::Fill),
+    ])
+    .height(150)
+    .width(iced::Length::Fill)
+    .padding([5, 10])
+    .style(container::rounded_box)
+}
+
+// One column for each allocator table
+fn allocator_display(
+    tables: Vec<(&'static str, Vec<AllocationRow>)>,
+    lang: Lang,
+) -> Container<'static, Message> {
+    let mut columns = row![].spacing(20);
+    for (table, rows) in tables {
+        let mut entries = column![text(tr(lang, table))].spacing(2);
+        for allocation in rows {
+            let mut line = text(allocation.line).size(12);
+            if allocation.changed {
+                line = line.color(color!(0x50fa7b));
+            }
+            entries = entries.push(line);
+        }
+        columns = columns.push(entries);
+    }
+    container(column![
+        row![
+            text(tr(lang, "allocator")),
+            button(tr(lang, "dump_log")).on_press(Message::DumpAllocator),
+        ]
+        .spacing(5),
+        scrollable(columns).width(iced::Length::Fill),
+    ])
+    .height(150)
+    .width(iced::Length::Fill)
+    .padding([5, 10])
+    .style(container::rounded_box)
+}
+
+// Captured while the segments of the process are still on memory
+fn post_mortem(memory: &Memory, pcb: &PCB, cpu: &CPU, history: &History, tick: u64) -> PostMortem {
+    let code = pcb.code_segment..pcb.code_segment + pcb.code_segment_size;
+    let index = cpu.pc.saturating_sub(pcb.code_segment) / INSTRUCTION_SIZE;
+    let disassembly = (index.saturating_sub(2)..=index + 2)
+        .map(|x| pcb.code_segment + x * INSTRUCTION_SIZE)
+        .filter(|x| code.contains(&(x + INSTRUCTION_SIZE - 1)))
+        .filter_map(|x| memory.instruction(pcb.id, x).map(|i| (x, i)))
+        .collect();
+    PostMortem {
+        tick,
+        registers: *cpu,
+        fault: None,
+        killed: false,
+        stack: memory.data[pcb.stack_segment..pcb.stack_segment + pcb.stack_segment_size].to_vec(),
+        history: history
+            .newest_first()
+            .filter(|x| x.pid == pcb.id)
+            .take(POST_MORTEM_HISTORY)
+            .cloned()
+            .collect(),
+        disassembly,
+    }
+}
+
+fn post_mortem_display(
+    p_id: usize,
+    post_mortem: &PostMortem,
+    lang: Lang,
+) -> Container<'static, Message> {
+    let cpu = &post_mortem.registers;
+    let exit = match post_mortem.fault {
+        Some(fault) => tr(lang, fault.key()).to_string(),
+        None if post_mortem.killed => tr(lang, "exit_killed").to_string(),
+        None => tr(lang, "exit_normal").to_string(),
+    };
+    let mut lines = column![
+        text(trf(lang, "post_mortem", &[&p_id, &post_mortem.tick, &exit])),
+        text(format!(
+            "AX {:03} BX {:03} CX {:03} DX {:03} AC {:03} Z {}",
+            cpu.ax, cpu.bx, cpu.cx, cpu.dx, cpu.ac, cpu.z as u8
+        ))
+        .size(12),
+        text(trf(
+            lang,
+            "post_mortem_stack",
+            &[
+                &format!("0x{:02X}", cpu.sp),
+                &format!("{:02X?}", post_mortem.stack)
+            ],
+        ))
+        .size(12),
+    ]
+    .spacing(3);
+    for (address, instruction) in &post_mortem.disassembly {
+        let marker = if *address == cpu.pc { ">" } else { " " };
+        lines = lines.push(text(format!("{} 0x{:02X} {}", marker, address, instruction)).size(12));
+    }
+    lines = lines.push(text(tr(lang, "post_mortem_history")).size(12));
+    for entry in &post_mortem.history {
+        lines = lines.push(text(format!("{:03} {}", entry.tick, entry.instruction)).size(12));
+    }
+    container(lines)
+        .padding([5, 10])
+        .style(container::rounded_box)
+}
+
+fn history_display(history: &History) -> Container<'static, Message> {
+    let mut entries = column![].spacing(2);
+    // Show the newest instruction first
+    for entry in history.newest_first() {
+        let mut line = format!(
+            "{:03} P{} {}\nAC {:03}",
+            entry.tick, entry.pid, entry.instruction, entry.ac
+        );
+        if let Some(quantum) = entry.quantum {
+            line.push_str(&format!(" q: {}", quantum));
+        }
+        entries = entries.push(text(line).size(12));
+    }
+    container(scrollable(entries))
+        .height(200)
+        .width(115)
+        .padding([5, 10])
+        .style(container::rounded_box)
+}
+
+// Registers that changed on the last tick are highlighted
+fn register_dispay(r_name: &str, r: String, changed: bool) -> Element<'_, Message> {
+    let mut value = span(format!("\t{}", r)).font(Font {
+        weight: font::Weight::Bold,
+        ..Font::default()
+    });
+    if changed {
+        value = value.color(color!(0xf1fa8c));
+    }
+    rich_text(vec![
+        span(r_name).color(color!(0xff79c6)).font(Font {
+            weight: font::Weight::Bold,
+            ..Font::default()
+        }),
+        value,
+    ])
+    .into()
+}
+
+// Only the visible rows are built, the rest of the content is empty space
+fn binary_display(
+    bytes: &[u8],
+    id: &'static str,
+    width: f32,
+    view: ScrollView,
+    on_scroll: fn(scrollable::Viewport) -> Message,
+    highlights: &[Highlight],
+    format: HexFormat,
+) -> Container<'static, Message> {
+    let hex_rows = &format.rows;
+    let total = hex_rows.len();
+    // Short rows are padded so the ASCII column stays aligned
+    let row_width = hex_rows.iter().map(|x| x.end - x.start).max().unwrap_or(0);
+    let rows = view.rows(total);
+    let mut column =
+        column![widget::Space::with_height(rows.start as f32 * ROW_HEIGHT)].padding([5, 10]);
+    for hex_row in &hex_rows[rows.clone()] {
+        let mut spans = vec![span(hex_row.label.clone())
+            .color(color!(0x9afcb3))
+            .font(Font {
+                weight: font::Weight::Bold,
+                ..Font::default()
+            })];
+        let start = hex_row.start;
+        let mut labels: Vec<&str> = vec![];
+        let data = &bytes[hex_row.start..hex_row.end];
+        let mut ascii = vec![];
+        for (offset, (x, c)) in data.iter().zip(ascii_text(data).chars()).enumerate() {
+            let mut byte = span(format!("\t{}", byte_text(*x, format.decimal))).font(Font {
+                weight: font::Weight::Bold,
+                ..Font::default()
+            });
+            let mut character = span(c.to_string()).font(Font::MONOSPACE);
+            let address = start + offset;
+            if let Some(highlight) = highlights
+                .iter()
+                .find(|x| x.start <= address && address < x.end)
+            {
+                byte = byte
+                    .color(highlight.color)
+                    .link_maybe(highlight.on_press.clone());
+                character = character.color(highlight.color);
+                if !labels.contains(&highlight.label.as_str()) {
+                    labels.push(&highlight.label);
+                }
+            }
+            spans.push(byte);
+            ascii.push(character);
+        }
+        if format.ascii {
+            spans.extend((data.len()..row_width).map(|_| span("\t")));
+            spans.push(span("\t"));
+            spans.extend(ascii);
+        }
+        let line = container(rich_text(spans)).height(ROW_HEIGHT);
+        // Name of the highlighted ranges of the row
+        if labels.is_empty() {
+            column = column.push(line);
+        } else {
+            column = column.push(tooltip(
+                line,
+                container(text(labels.join(", ")))
+                    .padding(5)
+                    .style(|_| container::background(color!(0x5a5e77))),
+                tooltip::Position::Top,
+            ));
+        }
+    }
+    column = column.push(widget::Space::with_height(
+        (total - rows.end) as f32 * ROW_HEIGHT,
+    ));
+
+    container(
+        scrollable(column)
+            .id(scrollable::Id::new(id))
+            .on_scroll(on_scroll)
+            .width(iced::Length::Fill),
+    )
+    .height(iced::Length::Fill)
+    .width(width)
+    .style(container::rounded_box)
+}
+
+// Color of the process on every view
+fn process_color(palette: Palette, p_id: usize) -> iced::Color {
+    to_color(colors::process_color(palette, p_id))
+}
+
+fn to_color(color: u32) -> iced::Color {
+    let (r, g, b) = colors::rgb(color);
+    iced::Color::from_rgb8(r, g, b)
+}
+
+// "■ P1 ■ P2", the colors of the processes shown on a panel
+// − and + buttons that narrow and widen a pane, each one is off at the end of the range
+fn width_buttons(
+    width: f32,
+    (min, max): (f32, f32),
+    on_change: fn(f32) -> Message,
+) -> Row<'static, Message> {
+    let change = |label, enabled: bool, width: f32| {
+        button(text(label).size(10))
+            .padding([0, 4])
+            .on_press_maybe(enabled.then(|| on_change(width)))
+    };
+    row![
+        change("−", width > min, width - WIDTH_STEP),
+        change("+", width < max, width + WIDTH_STEP)
+    ]
+    .spacing(2)
+}
+
+fn legend_display(palette: Palette, p_ids: &[usize]) -> Row<'static, Message> {
+    p_ids.iter().fold(row![].spacing(8), |legend, p_id| {
+        legend.push(rich_text([
+            span("■ ").color(process_color(palette, *p_id)),
+            span(format!("P{}", p_id)),
+        ]))
+    })
+}
+
+// Row of an allocator table
+struct AllocationRow {
+    // "0x40–0x4B P1 code"
+    line: String,
+    // Moved by the last store or free
+    changed: bool,
+}
+
+impl AllocationRow {
+    fn new(address: usize, size: usize, label: &str, changed: &[usize]) -> Self {
+        Self {
+            line: format!(
+                "0x{:02X}–0x{:02X} {} B {}",
+                address,
+                (address + size).saturating_sub(1),
+                size,
+                label
+            )
+            .trim_end()
+            .to_string(),
+            changed: changed.contains(&address),
+        }
+    }
+}
+
+// (ticks left, quantum) of a quantum that started on `start`
+// Address reached by a jump of `num` instructions from the jump itself, so "JNE -1" goes back to
+// the instruction before it
+fn jump_target(pc: usize, sign: u8, num: u8) -> usize {
+    match sign {
+        1 => pc.saturating_sub(INSTRUCTION_SIZE * num as usize),
+        _ => pc + INSTRUCTION_SIZE * num as usize,
+    }
+}
+
+fn quantum_left(quantum: Option<u8>, start: Option<&u64>, counter: u64) -> Option<(u64, u64)> {
+    let quantum = quantum? as u64;
+    Some((quantum.saturating_sub(counter - start?), quantum))
+}
+
+// "0x00–0x25 P1 PCB"
+fn segment_line(segment: &SegmentInfo, label: &str) -> String {
+    format!(
+        "0x{:02X}–0x{:02X} {}",
+        segment.address,
+        segment.end().saturating_sub(1),
+        label
+    )
+}
+
+fn map_display(
+    lines: Vec<(String, Option<iced::Color>)>,
+    width: f32,
+) -> Container<'static, Message> {
+    let mut column = column![].padding([5, 10]);
+    for (line, color) in lines {
+        column = column.push(text(line).size(12).color_maybe(color));
+    }
+    container(scrollable(column).width(iced::Length::Fill))
+        .height(100)
+        .width(width)
+        .style(container::rounded_box)
+}
+
+// Name of a program of a file with several programs, like "file.asm:program"
+fn program_name(file_name: &str, program: &str) -> String {
+    match file_name.strip_suffix('"') {
+        Some(file_name) => format!("{}:{}\"", file_name, program),
+        None => format!("{}:{}", file_name, program),
+    }
+}
+
+// Reads the content of the selected files and groups the file name with the file content, the
+// files that can't be read are reported instead of stopping the rest
+async fn read_files(files: Vec<PathBuf>, max_file_size: usize) -> Result<Folder, Error> {
+    let mut files_content: Vec<(String, Vec<u8>)> = vec![];
+    let mut failures = vec![];
+    for path in files {
+        let file_name = path.file_name();
+        let file_name = format!("{:?}", file_name.ok_or(Error::NotFile)?);
+
+        match read_program(&path, &file_name, max_file_size).await {
+            Ok(contents) => files_content.push((file_name, contents)),
+            Err(error) => failures.push((file_name, error)),
+        }
+    }
+
+    Ok((files_content, failures))
+}
+
+async fn read_program(
+    path: &Path,
+    file_name: &str,
+    max_file_size: usize,
+) -> Result<Vec<u8>, Error> {
+    let size = tokio::fs::metadata(path)
+        .await
+        .map_err(|error| Error::IO(error.kind()))?
+        .len();
+    check_size(file_name, size, max_file_size)?;
+    let contents = tokio::fs::read(path)
+        .await
+        .map_err(|error| error.kind())
+        .map_err(Error::IO)?;
+    check_text(file_name, &contents)?;
+    // A .in file next to the program has the values of its INT 09H
+    Ok(
+        match tokio::fs::read_to_string(path.with_extension("in")).await {
+            Ok(script) => with_input_script(&script, contents),
+            Err(_) => contents,
+        },
+    )
+}
+
+// A program is never this big, the file is rejected before reading it
+fn check_size(file_name: &str, size: u64, max_file_size: usize) -> Result<(), Error> {
+    if size > max_file_size as u64 {
+        return Err(Error::FileTooLarge(file_name.to_string(), size as usize));
+    }
+    Ok(())
+}
+
+// Text files don't have NUL bytes, looking at the start is enough to spot a binary
+fn check_text(file_name: &str, contents: &[u8]) -> Result<(), Error> {
+    if contents.iter().take(1024).any(|x| *x == 0) {
+        return Err(Error::BinaryFile(file_name.to_string()));
+    }
+    Ok(())
+}
+
+// Add the values of an input script as an #INPUT directive, it goes first so every program of
+// the file gets it and an #INPUT written on the file takes precedence
+fn with_input_script(script: &str, contents: Vec<u8>) -> Vec<u8> {
+    let values = script
+        .split(|c: char| c == ',' || c.is_whitespace())
+        .filter(|x| !x.is_empty())
+        .collect::<Vec<_>>();
+    if values.is_empty() {
+        return contents;
+    }
+    let mut data = format!("#INPUT {}\n", values.join(",")).into_bytes();
+    data.extend(contents);
+    data
+}
+
+// Programs read from the files and the problems reading the rest
+type Folder = (Vec<(String, Vec<u8>)>, Vec<(String, Error)>);
+
+// Read the .asm files of the folder sorted by name, the files that can't be read are reported
+// instead of stopping the batch
+fn read_folder(dir: &Path, max_file_size: usize) -> Result<Folder, Error> {
+    let mut paths = std::fs::read_dir(dir)
+        .map_err(|error| Error::IO(error.kind()))?
+        .filter_map(|x| x.ok().map(|x| x.path()))
+        .filter(|x| x.is_file() && x.extension().is_some_and(|x| x == "asm"))
+        .collect::<Vec<_>>();
+    paths.sort();
+    let mut files = vec![];
+    let mut failures = vec![];
+    for path in paths {
+        let file_name = format!("{:?}", path.file_name().unwrap_or_default());
+        let contents = std::fs::metadata(&path)
+            .map_err(|error| Error::IO(error.kind()))
+            .and_then(|x| check_size(&file_name, x.len(), max_file_size))
+            .and_then(|_| std::fs::read(&path).map_err(|error| Error::IO(error.kind())))
+            .and_then(|x| check_text(&file_name, &x).map(|_| x));
+        match contents {
+            Ok(contents) => {
+                let contents = match std::fs::read_to_string(path.with_extension("in")) {
+                    Ok(script) => with_input_script(&script, contents),
+                    Err(_) => contents,
+                };
+                files.push((file_name, contents));
+            }
+            Err(error) => failures.push((file_name, error)),
+        }
+    }
+    Ok((files, failures))
+}
+
+// Run the folder given on the command line and print the summary, returns the exit code
+fn run_batch(args: Args) -> i32 {
+    let Some(dir) = args.batch.clone() else {
+        return 2;
+    };
+    let (mut emulator, _) = Emulator::new(args);
+    let (files, failures) = match read_folder(&dir, emulator.config.max_file_size) {
+        Ok(folder) => folder,
+        Err(error) => {
+            eprintln!("{}", error);
+            return 1;
+        }
+    };
+    emulator.store_batch(files, failures);
+    emulator.run_batch();
+    let lines = match emulator.completed {
+        Some(_) => emulator.summary(),
+        None => emulator.batch_results(),
+    };
+    for line in lines {
+        println!("{}", line);
+    }
+    // A failed program fails the batch, so it can check the programs on CI
+    if emulator.batch_failed() {
+        1
+    } else {
+        0
+    }
+}
+
+// Open the folder picker dialog to select a batch
+async fn pick_folder() -> Result<PathBuf, Error> {
+    let handle = rfd::AsyncFileDialog::new()
+        .set_title("Choose a folder...")
+        .pick_folder()
+        .await
+        .ok_or(Error::DialogClosed)?;
+
+    Ok(handle.path().to_owned())
+}
+
+// Open the file picker dialog to select the files
+async fn pick_file() -> Result<Vec<PathBuf>, Error> {
+    let handle = rfd::AsyncFileDialog::new()
+        .set_title("Choose a file...")
+        .add_filter("Assembly File", &["asm"])
+        .pick_files()
+        .await
+        .ok_or(Error::DialogClosed)?;
+
+    Ok(handle
+        .iter()
+        .map(|f| f.path().to_owned())
+        .collect::<Vec<PathBuf>>())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::alloc::{GlobalAlloc, Layout, System};
+    use std::cell::{Cell, RefCell};
+
+    // Counts the allocations made by each test thread
+    struct CountingAllocator;
+
+    thread_local! {
+        static ALLOCATIONS: Cell<usize> = const { Cell::new(0) };
+    }
+
+    unsafe impl GlobalAlloc for CountingAllocator {
+        unsafe fn alloc(&self, layout: Layout) -> *mut u8 {
+            let _ = ALLOCATIONS.try_with(|x| x.set(x.get() + 1));
+            System.alloc(layout)
+        }
+
+        unsafe fn dealloc(&self, ptr: *mut u8, layout: Layout) {
+            System.dealloc(ptr, layout)
+        }
+    }
+
+    #[global_allocator]
+    static ALLOCATOR: CountingAllocator = CountingAllocator;
+
+    fn emulator(cpu_quantity: usize) -> Emulator {
+        let mut config = Config::default();
+        config.cpu_quantity = cpu_quantity;
+        Emulator {
+            storage: Storage::new(config.storage),
+            memory: Memory::new(config.memory, config.os_segment),
+            cpus: vec![Core::default(); config.cpu_quantity],
+            history: vec![History::new(); config.cpu_quantity],
+            last_registers: vec![CPU::new(); config.cpu_quantity],
+            usage: vec![CpuUsage::new(); config.cpu_quantity],
+            show_history: vec![false; config.cpu_quantity],
+            config,
+            ..Default::default()
+        }
+    }
+
+    // Loaded and pending files with their names instead of the storage address
+    fn loaded(emulator: &Emulator) -> Vec<(String, Option<usize>)> {
+        emulator
+            .loaded_files
+            .iter()
+            .map(|x| (emulator.file_name(x.0).unwrap(), x.1))
+            .collect()
+    }
+
+    fn pending(emulator: &Emulator) -> Vec<(String, Admission)> {
+        emulator
+            .pending_files
+            .iter()
+            .map(|x| (emulator.file_name(x.0).unwrap(), x.1))
+            .collect()
+    }
+
+    // Store the program and create its PCB, returns the pcb table entry
+    fn load(emulator: &mut Emulator, file_name: &str, program: &str) -> (usize, usize, usize) {
+        let data = program.as_bytes().to_vec();
+        emulator.storage.store_files(file_name, data).unwrap();
+        let _ = emulator.admit();
+        *emulator.memory.pcb_table.last().unwrap()
+    }
+
+    #[test]
+    fn dma_loading() {
+        let mut emulator = emulator(1);
+        emulator.arrival_policy = ArrivalPolicy::FixedZero;
+        emulator.config.dma_bytes_per_tick = 10;
+        // 60 bytes of code
+        let program = format!("{}INT 20H", "MOV AX, 1\n".repeat(9));
+        let (p_id, _, _) = load(&mut emulator, "a.asm", &program);
+        let admitted = emulator.counter;
+        let pcb = emulator.memory.pcb(p_id).unwrap();
+        let code = pcb.code_segment..pcb.code_segment + pcb.code_segment_size;
+        assert_eq!(code.len(), 60);
+        assert!(emulator.memory.data[code.clone()].iter().all(|x| *x == 0));
+
+        // The segment fills 10 bytes on each tick and the process stays New meanwhile
+        for tick in 1..6 {
+            let _ = emulator.step();
+            assert_eq!(emulator.dma.progress(p_id), Some((tick * 10, 60)));
+            let pcb = emulator.memory.pcb(p_id).unwrap();
+            assert_eq!(pcb.process_state, ProcessState::New);
+            assert_eq!(emulator.cpus[0].assigned, None);
+            // The half loaded code is shown as data after the copied instructions
+            let copied = code.start..code.start + tick * 10;
+            let slots = disassemble(&emulator.memory.data[copied]);
+            assert_eq!(slots.iter().filter(|x| x.is_ok()).count(), tick * 10 / 6);
+            let _ = emulator.update(Message::SelectPcb(p_id));
+            let _ = emulator.view();
+        }
+        let _ = emulator.step();
+        assert_eq!(emulator.dma.progress(p_id), None);
+        let timing = emulator.diagram.get(p_id).unwrap();
+        assert_eq!(timing.ready_at, Some(admitted + 6));
+        assert_eq!(emulator.cpus[0].assigned, Some(p_id));
+        let (_, instructions) = read_file(program.as_bytes()).unwrap();
+        assert_eq!(emulator.memory.data[code], to_bytes(instructions));
+    }
+
+    #[test]
+    fn load_program_directly() {
+        let mut emulator = emulator(1);
+        emulator.arrival_policy = ArrivalPolicy::FromDirective;
+        let instruction = |operation, operands| Instruction {
+            operation,
+            operands,
+        };
+        let program = vec![
+            instruction(Operation::MOV, Operands::V5(Register::AX, 5)),
+            instruction(Operation::INC, Operands::V0),
+            instruction(Operation::INT, Operands::V3(Interupt::H20)),
+        ];
+        let meta = ProgramMeta {
+            priority: Some(3),
+            ..Default::default()
+        };
+        let mut load_program = |name, program, meta| {
+            load_program(
+                &mut emulator.memory,
+                &mut emulator.diagram,
+                &mut emulator.arrival_policy,
+                &emulator.config,
+                name,
+                program,
+                meta,
+            )
+        };
+        let pid = load_program("built", program, meta).unwrap();
+        let big = vec![instruction(Operation::INC, Operands::V0); 30];
+        // Bigger than the user memory
+        let error = load_program("big", big, ProgramMeta::default());
+        assert!(matches!(error, Err(Error::ProgramTooBig(136))));
+        emulator.arrive();
+        assert_eq!(pid, 1);
+        assert!(emulator.storage.used.is_empty());
+        let timing = emulator.diagram.get(pid).unwrap();
+        assert_eq!((timing.name.as_str(), timing.length), ("built", 3));
+        assert_eq!(emulator.memory.pcb(pid).unwrap().priority, 3);
+
+        let _ = emulator.schedule();
+        let _ = emulator.run(100, false);
+        assert_eq!(emulator.stats_data.len(), 1);
+        assert_eq!(
+            emulator.memory.pcb(pid).unwrap().process_state,
+            ProcessState::Terminated
+        );
+    }
+
+    #[test]
+    fn context_round_trip() {
+        let mut emulator = emulator(1);
+        let a = load(&mut emulator, "a.asm", "#ARRIVAL 0\nMOV AX, 1\nINT 20H");
+        let b = load(&mut emulator, "b.asm", "#ARRIVAL 0\nMOV AX, 1\nINT 20H");
+        let _ = emulator.update(Message::Distpacher((0, a)));
+        let cpu = &mut emulator.cpus[0].cpu;
+        (cpu.ax, cpu.bx, cpu.cx, cpu.dx, cpu.ac) = (1, 2, 3, 4, 5);
+        (cpu.pc, cpu.sp, cpu.ir, cpu.z) = (cpu.pc + 6, cpu.sp + 1, Some(Operation::SWAP), true);
+        let registers = *cpu;
+
+        // b runs on the CPU in between, its registers don't leak into the PCB of a
+        let _ = emulator.update(Message::Distpacher((0, b)));
+        emulator.cpus[0].cpu.bx = 99;
+        let _ = emulator.update(Message::Distpacher((0, a)));
+        let cpu = emulator.cpus[0].cpu;
+        assert_eq!(
+            (cpu.ax, cpu.bx, cpu.cx, cpu.dx, cpu.ac),
+            (
+                registers.ax,
+                registers.bx,
+                registers.cx,
+                registers.dx,
+                registers.ac
+            )
+        );
+        assert_eq!(
+            (cpu.pc, cpu.sp, cpu.ir, cpu.z),
+            (registers.pc, registers.sp, registers.ir, registers.z)
+        );
+        assert_eq!(emulator.memory.pcb(b.0).unwrap().bx, 99);
+    }
+
+    #[test]
+    fn ir_after_redispatch() {
+        let mut emulator = emulator(1);
+        emulator.config.scheduler = Some(Scheduler::RR);
+        emulator.quantum = Some(1);
+        load(
+            &mut emulator,
+            "a.asm",
+            "#ARRIVAL 0\nMOV AX, 1\nPUSH AX\nPOP BX\nINT 20H",
+        );
+        load(
+            &mut emulator,
+            "b.asm",
+            "#ARRIVAL 0\nMOV CX, 1\nMOV CX, 2\nMOV CX, 3\nINT 20H",
+        );
+        let _ = emulator.schedule();
+        emulator.mode = Some(Mode::Manual);
+        // a and b alternate, the last instruction of a before INT 20H is POP
+        for _ in 0..6 {
+            let _ = emulator.step();
+        }
+        assert_eq!(emulator.cpus[0].assigned, Some(1));
+        assert_eq!(emulator.cpus[0].cpu.ir, Some(Operation::POP));
+        assert_eq!(emulator.memory.pcb(1).unwrap().ir, Some(Operation::POP));
+        // The card shows the operation restored from the PCB, not the instruction of b
+        assert_eq!(emulator.ir_label(0), "POP");
+    }
+
+    #[test]
+    fn play_without_files() {
+        let mut emulator = emulator(1);
+        let _ = emulator.update(Message::ChangeMode);
+        let _ = emulator.update(Message::ChangeMode);
+        assert!(emulator.mode.is_none());
+        assert!(emulator.total_start_time.is_none());
+        assert!(emulator.start_time.is_none());
+        assert!(emulator
+            .log
+            .to_text(Level::Info)
+            .contains("Load programs first"));
+
+        // A run that completed starts its clock again with the next files
+        load(&mut emulator, "a.asm", "MOV AX, 1\nINT 20H");
+        let _ = emulator.update(Message::ChangeMode);
+        let _ = emulator.schedule();
+        emulator.mode = Some(Mode::Manual);
+        let _ = emulator.update(Message::RunToEvent);
+        assert!(emulator.completed.is_some());
+        let started = emulator.total_start_time.unwrap();
+        emulator
+            .store_file("b.asm".to_string(), b"MOV AX, 2\nINT 20H".to_vec())
+            .unwrap();
+        assert!(emulator.completed.is_none());
+        assert!(emulator.total_start_time.unwrap() > started);
+    }
+
+    #[test]
+    fn scheduler_decisions() {
+        let mut sjf = emulator(1);
+        sjf.arrival_policy = ArrivalPolicy::FromDirective;
+        sjf.config.scheduler = Some(Scheduler::SJF);
+        let program = |arrival: u8, length: usize| {
+            format!(
+                "#ARRIVAL {}\n{}INT 20H",
+                arrival,
+                "MOV AX, 1\n".repeat(length)
+            )
+        };
+        load(&mut sjf, "a.asm", &program(0, 5));
+        load(&mut sjf, "b.asm", &program(0, 2));
+        load(&mut sjf, "c.asm", &program(50, 1));
+        let _ = sjf.schedule();
+        let decision = sjf.decision.clone().unwrap();
+        assert_eq!(decision.rule, Rule::Burst);
+        assert_eq!(decision.chosen, (2, "3".to_string()));
+        assert_eq!(decision.candidates, vec![(1, "6".to_string())]);
+        assert_eq!(decision.not_arrived, vec![3]);
+        assert_eq!(
+            decision.text(Lang::En),
+            "SJF: picked P2 (burst 3) over P1 (burst 6), P3 not arrived"
+        );
+
+        // b waited as long as c, its ratio is lower because its burst is longer
+        let mut hrrn = emulator(1);
+        hrrn.config.scheduler = Some(Scheduler::HRRN);
+        load(&mut hrrn, "a.asm", &program(0, 3));
+        load(&mut hrrn, "b.asm", &program(0, 8));
+        load(&mut hrrn, "c.asm", &program(0, 1));
+        hrrn.mode = Some(Mode::Manual);
+        let _ = hrrn.schedule();
+        assert_eq!(hrrn.cpus[0].assigned, Some(1));
+        for _ in 0..4 {
+            let _ = hrrn.step();
+        }
+        assert_eq!(hrrn.cpus[0].assigned, Some(3));
+        assert_eq!(
+            hrrn.decision.unwrap().text(Lang::En),
+            "HRRN: picked P3 (ratio 3.00) over P2 (ratio 1.44)"
+        );
+    }
+
+    #[test]
+    fn deterministic_cpu_assignment() {
+        let run = |scheduler| {
+            let mut emulator = emulator(3);
+            emulator.config.scheduler = Some(scheduler);
+            emulator.quantum = Some(2);
+            emulator.arrival_policy = ArrivalPolicy::FixedZero;
+            // Every process fits on memory from the start and the last ones don't fit on the
+            // blocks of the first ones
+            emulator.memory = Memory::new(1024, emulator.config.os_segment);
+            for (name, length) in ["a.asm", "b.asm", "c.asm", "d.asm", "e.asm"]
+                .iter()
+                .zip([2, 2, 2, 3, 3])
+            {
+                let program = format!("{}INT 20H", "MOV AX, 1\n".repeat(length));
+                load(&mut emulator, name, &program);
+            }
+            emulator.mode = Some(Mode::Manual);
+            let _ = emulator.schedule();
+            // Process on each CPU before every tick
+            let mut trace = vec![];
+            for _ in 0..8 {
+                trace.push(emulator.cpus.iter().map(|x| x.assigned).collect::<Vec<_>>());
+                let _ = emulator.step();
+            }
+            trace
+        };
+        // The first processes take the CPUs in order, and so do the next ones on the CPUs freed
+        // on the same tick
+        let fcfs = run(Scheduler::FCFS);
+        assert_eq!(fcfs[0], [Some(1), Some(2), Some(3)]);
+        assert_eq!(fcfs[3], [Some(4), Some(5), None]);
+        for scheduler in Scheduler::ALL {
+            assert_eq!(run(scheduler), run(scheduler), "{}", scheduler);
+        }
+    }
+
+    #[test]
+    fn simultaneous_completions() {
+        let run = || {
+            let mut emulator = emulator(2);
+            emulator.config.scheduler = Some(Scheduler::FCFS);
+            emulator.arrival_policy = ArrivalPolicy::FixedZero;
+            for name in ["a.asm", "b.asm", "c.asm", "d.asm"] {
+                load(&mut emulator, name, "MOV AX, 1\nINT 20H");
+            }
+            emulator.mode = Some(Mode::Manual);
+            let _ = emulator.schedule();
+            let _ = emulator.step();
+            assert_eq!(
+                emulator.step().0,
+                vec![TickEvent::Terminated(0), TickEvent::Terminated(1)]
+            );
+            emulator.trace.events.clone()
+        };
+        // Both CPUs execute their INT 20H on the same tick, the lowest one is released first and
+        // gets the first process of the queue
+        let events = run();
+        let terminate = |p_id, cpu| TraceEvent::Terminate { tick: 2, p_id, cpu };
+        let dispatch = |p_id, cpu| TraceEvent::Dispatch { tick: 2, p_id, cpu };
+        assert_eq!(
+            events[events.len() - 4..],
+            [
+                terminate(1, 0),
+                terminate(2, 1),
+                dispatch(3, 0),
+                dispatch(4, 1)
+            ]
+        );
+        assert_eq!(run(), events);
+    }
+
+    #[test]
+    fn round_robin_within_priority() {
+        let mut emulator = emulator(1);
+        emulator.config.scheduler = Some(Scheduler::Priority);
+        emulator.config.priority_rr = true;
+        emulator.config.priority_preemptive = true;
+        emulator.quantum = Some(2);
+        emulator.arrival_policy = ArrivalPolicy::FromDirective;
+        emulator.memory = Memory::new(1024, emulator.config.os_segment);
+        let body = "MOV AX, 1\n".repeat(5);
+        load(
+            &mut emulator,
+            "a.asm",
+            &format!("#PRIORITY 1\n{}INT 20H", body),
+        );
+        load(
+            &mut emulator,
+            "b.asm",
+            &format!("#PRIORITY 1\n{}INT 20H", body),
+        );
+        load(
+            &mut emulator,
+            "c.asm",
+            "#PRIORITY 0\n#ARRIVAL 5\nMOV AX, 1\nINT 20H",
+        );
+        emulator.mode = Some(Mode::Manual);
+        let _ = emulator.schedule();
+        let mut trace = vec![];
+        for _ in 0..10 {
+            trace.push(emulator.cpus[0].assigned.unwrap_or_default());
+            let _ = emulator.step();
+        }
+        // a and b take turns every 2 ticks, c takes the CPU as soon as it arrives and b
+        // continues after it
+        assert_eq!(trace, vec![1, 1, 2, 2, 1, 3, 3, 2, 2, 1]);
+    }
+
+    #[test]
+    fn grouped_stats() {
+        let stat = |process_id, origin: &str, turnaround_time, execution_time| ProcessStats {
+            process_id,
+            name: origin.to_string(),
+            cpu_id: 0,
+            turnaround_time,
+            execution_time,
+            response_ratio: turnaround_time / execution_time,
+            arrival_time: 0.0,
+            scheduler: None,
+            estimated: None,
+            ticks: 0,
+            first_response: None,
+            origin: origin.to_string(),
+            fault: None,
+        };
+        let stats = [
+            stat(1, "b.asm", 4.0, 1.0),
+            stat(2, "a.asm", 2.0, 2.0),
+            stat(3, "b.asm", 6.0, 2.0),
+        ];
+        assert_eq!(
+            group_stats(&stats),
+            vec![
+                StatsGroup {
+                    origin: "b.asm".to_string(),
+                    processes: vec![0, 2],
+                    turnaround_time: 5.0,
+                    waiting_time: 3.5,
+                },
+                StatsGroup {
+                    origin: "a.asm".to_string(),
+                    processes: vec![1],
+                    turnaround_time: 2.0,
+                    waiting_time: 0.0,
+                },
+            ]
+        );
+        assert!(group_stats(&[]).is_empty());
+    }
+
+    #[test]
+    fn first_response() {
+        let mut emulator = emulator(1);
+        emulator.arrival_policy = ArrivalPolicy::FixedZero;
+        load(
+            &mut emulator,
+            "a.asm",
+            &format!("{}INT 20H", "MOV AX, 1\n".repeat(4)),
+        );
+        load(&mut emulator, "b.asm", "INT 20H");
+        emulator.mode = Some(Mode::Manual);
+        let _ = emulator.schedule();
+        for _ in 0..6 {
+            let _ = emulator.step();
+        }
+
+        // b is ready from tick 0 and gets the CPU on tick 5, once a executed its INT 20H
+        let response = |p_id| emulator.diagram.get(p_id).unwrap().first_response();
+        assert_eq!(response(1), Some(0));
+        assert_eq!(response(2), Some(5));
+        let stats = emulator
+            .stats_data
+            .iter()
+            .map(|x| (x.process_id, x.first_response))
+            .collect::<Vec<_>>();
+        assert_eq!(stats, vec![(1, Some(0)), (2, Some(5))]);
+    }
+
+    #[test]
+    fn edit_priority() {
+        let mut emulator = emulator(1);
+        emulator.config.scheduler = Some(Scheduler::Priority);
+        emulator.arrival_policy = ArrivalPolicy::FixedZero;
+        for name in ["a.asm", "b.asm", "c.asm"] {
+            load(&mut emulator, name, "#PRIORITY 5\nMOV AX, 1\nINT 20H");
+        }
+        emulator.mode = Some(Mode::Manual);
+        let _ = emulator.schedule();
+        assert_eq!(emulator.cpus[0].assigned, Some(1));
+        assert_eq!(emulator.ready_queue(), vec![2, 3]);
+
+        // The running process keeps its priority
+        let _ = emulator.update(Message::SetPriority(1, 0));
+        assert_eq!(emulator.memory.pcb(1).unwrap().priority, 5);
+        let _ = emulator.update(Message::SetPriority(3, 1));
+        assert_eq!(emulator.memory.pcb(3).unwrap().priority, 1);
+        assert_eq!(emulator.ready_queue(), vec![3, 2]);
+        assert!(emulator
+            .log
+            .to_text(Level::Info)
+            .contains("Priority of P3 changed from 5 to 1"));
+
+        for _ in 0..2 {
+            let _ = emulator.step();
+        }
+        assert_eq!(emulator.cpus[0].assigned, Some(3));
+        assert_eq!(
+            emulator.decision.unwrap().text(Lang::En),
+            "Priority: picked P3 (priority 1) over P2 (priority 5)"
+        );
+    }
+
+    #[test]
+    fn unsaved_run() {
+        let mut emulator = emulator(1);
+        emulator.config.scheduler = Some(Scheduler::FCFS);
+        load(&mut emulator, "a.asm", "MOV AX, 1\nINT 20H");
+        // Nothing ran yet
+        assert!(!emulator.unsaved());
+
+        let _ = emulator.schedule();
+        emulator.mode = Some(Mode::Manual);
+        let _ = emulator.step();
+        assert!(emulator.unsaved());
+
+        while emulator.completed.is_none() && emulator.counter < 10 {
+            let _ = emulator.step();
+        }
+        // Completed, its statistics are only kept once the log is copied
+        assert!(emulator.unsaved());
+        let _ = emulator.update(Message::CopyLog);
+        assert!(!emulator.unsaved());
+    }
+
+    #[test]
+    fn scheduler_throttle() {
+        let mut emulator = emulator(2);
+        emulator.config.scheduler = Some(Scheduler::RR);
+        emulator.quantum = Some(1);
+        for name in ["a.asm", "b.asm", "c.asm", "d.asm"] {
+            load(
+                &mut emulator,
+                name,
+                "#ARRIVAL 0\nMOV AX, 1\nMOV BX, 2\nMOV CX, 3\nINT 20H",
+            );
+        }
+        let _ = emulator.schedule();
+        emulator.mode = Some(Mode::Manual);
+        // Runs of the current tick, a tick that ends with an event is executed again
+        let runs = |emulator: &Emulator| match emulator.scheduler_calls {
+            (tick, runs, _) if tick == emulator.counter => runs,
+            _ => 0,
+        };
+        while emulator.completed.is_none() && emulator.counter < 100 {
+            let before = runs(&emulator);
+            let _ = emulator.step();
+            let stepped = runs(&emulator);
+            // Bounded by the CPUs: a run for the tick, one for each dispatch and a last one
+            // that finds nothing
+            assert!(stepped.saturating_sub(before) <= 2 * 2 + 1);
+            // Calls that find nothing new are skipped, only the first call of a tick runs
+            for _ in 0..5 {
+                let _ = emulator.update(Message::Scheduler);
+            }
+            assert!(runs(&emulator) <= stepped + 1);
+        }
+        assert!(emulator.completed.is_some());
+        assert!(emulator.log.to_text(Level::Debug).contains("calls skipped"));
+    }
+
+    #[test]
+    fn round_robin_rotation() {
+        let mut emulator = emulator(1);
+        emulator.config.scheduler = Some(Scheduler::RR);
+        emulator.quantum = Some(1);
+        emulator.arrival_policy = ArrivalPolicy::FixedZero;
+        for name in ["a.asm", "b.asm", "c.asm"] {
+            load(
+                &mut emulator,
+                name,
+                "MOV AX, 1\nMOV AX, 2\nMOV AX, 3\nINT 20H",
+            );
+        }
+        emulator.mode = Some(Mode::Manual);
+        let _ = emulator.schedule();
+        let mut order = vec![];
+        for _ in 0..6 {
+            order.push(emulator.cpus[0].assigned.unwrap());
+            let _ = emulator.step();
+        }
+        // The preempted process goes after the ones that were already waiting
+        assert_eq!(order, [1, 2, 3, 1, 2, 3]);
+        assert_eq!(emulator.ready_queue(), vec![2, 3]);
+        let decision = emulator.decision.clone().unwrap();
+        assert_eq!(decision.chosen, (1, "1".to_string()));
+        assert_eq!(decision.candidates, vec![(2, "2".to_string())]);
+    }
+
+    #[test]
+    fn round_robin_trace() {
+        let mut emulator = emulator(1);
+        emulator.config.scheduler = Some(Scheduler::RR);
+        emulator.quantum = Some(2);
+        load(
+            &mut emulator,
+            "a.asm",
+            "#ARRIVAL 0\nMOV AX, 1\nMOV AX, 2\nMOV AX, 3\nINT 20H",
+        );
+        load(
+            &mut emulator,
+            "b.asm",
+            "#ARRIVAL 0\nMOV BX, 1\nMOV BX, 2\nMOV BX, 3\nMOV BX, 4\nINT 20H",
+        );
+        let _ = emulator.schedule();
+        emulator.mode = Some(Mode::Manual);
+        // (tick, process on the CPU, event of the tick)
+        let mut trace = vec![];
+        while let Some(p_id) = emulator.cpus[0].assigned {
+            let tick = emulator.counter;
+            trace.push((tick, p_id, emulator.step().0));
+        }
+
+        // The quantum ends between ticks, the next process starts on the following tick. b gets
+        // its CPU after the INT 20H of a with a whole quantum, and keeps it when the quantum ends
+        // because nobody else is ready
+        assert_eq!(
+            trace,
+            vec![
+                (0, 1, vec![]),
+                (1, 1, vec![TickEvent::QuantumExpired]),
+                (2, 2, vec![]),
+                (3, 2, vec![TickEvent::QuantumExpired]),
+                (4, 1, vec![]),
+                (5, 1, vec![TickEvent::Terminated(0)]),
+                (6, 2, vec![]),
+                (7, 2, vec![TickEvent::QuantumExpired]),
+                (8, 2, vec![TickEvent::Terminated(0)]),
+            ]
+        );
+    }
+
+    #[test]
+    fn quantum_countdown() {
+        let mut emulator = emulator(1);
+        emulator.config.scheduler = Some(Scheduler::RR);
+        emulator.quantum = Some(3);
+        let program = format!("#ARRIVAL 0\n{}INT 20H", "MOV AX, 1\n".repeat(6));
+        load(&mut emulator, "a.asm", &program);
+        load(&mut emulator, "b.asm", &program);
+        let _ = emulator.schedule();
+        emulator.mode = Some(Mode::Manual);
+        // (process on the CPU, ticks left) before each tick
+        let mut countdown = vec![];
+        for _ in 0..9 {
+            countdown.push((
+                emulator.cpus[0].assigned.unwrap(),
+                emulator.quantum_left(0).unwrap(),
+            ));
+            let _ = emulator.step();
+        }
+        assert_eq!(
+            countdown,
+            vec![
+                (1, (3, 3)),
+                (1, (2, 3)),
+                (1, (1, 3)),
+                (2, (3, 3)),
+                (2, (2, 3)),
+                (2, (1, 3)),
+                (1, (3, 3)),
+                (1, (2, 3)),
+                (1, (1, 3)),
+            ]
+        );
+        // The trace has the quantum left after each instruction of a, on both dispatches
+        let trace = emulator.history[0]
+            .newest_first()
+            .filter(|x| x.pid == 1)
+            .map(|x| x.quantum)
+            .collect::<Vec<_>>();
+        assert_eq!(trace, [0, 1, 2, 0, 1, 2].map(Some));
+
+        // Other schedulers don't show it
+        emulator.config.scheduler = Some(Scheduler::FCFS);
+        assert_eq!(emulator.quantum_left(0), None);
+    }
+
+    #[test]
+    fn program_directives() {
+        let mut emulator = emulator(1);
+        let pcb = load(
+            &mut emulator,
+            "a.asm",
+            "#ARRIVAL 3\n#BURST 12\n#PRIORITY 2\nMOV AX, 5\nINT 20H",
+        );
+        let timing = emulator.diagram.iter().last().unwrap();
+        assert_eq!(
+            (timing.arrival, timing.burst, timing.remaining_burst),
+            (3, 12, 12)
+        );
+        let pcb = emulator.memory.pcb(pcb.0).unwrap();
+        assert_eq!(pcb.priority, 2);
+        // Directives are not stored as instructions
+        let code =
+            &emulator.memory.data[pcb.code_segment..pcb.code_segment + pcb.code_segment_size];
+        assert_eq!(from_bytes(code).unwrap().len(), 2);
+
+        // Without directives the instruction count is the burst
+        load(&mut emulator, "b.asm", "MOV AX, 5\nINC\nINT 20H");
+        let timing = emulator.diagram.iter().last().unwrap();
+        assert_eq!(timing.burst, 3);
+        assert!((1..=5).contains(&timing.arrival));
+    }
+
+    #[test]
+    fn multiple_programs() {
+        let mut emulator = emulator(1);
+        let file = "#PROGRAM a\nMOV AX, 5\nINT 20H\n#PROGRAM b\nFOO\nINT 20H\n#PROGRAM c\n#PRIORITY 3\nINC\nINT 20H";
+        let _ = emulator.update(Message::StoreFiles(Ok((
+            vec![("\"p.asm\"".to_string(), file.as_bytes().to_vec())],
+            vec![],
+        ))));
+        assert_eq!(emulator.storage.used.len(), 3);
+
+        // The program with the error is removed, the others get a PCB
+        let _ = emulator.schedule();
+        let _ = emulator.schedule();
+        let names = loaded(&emulator)
+            .into_iter()
+            .map(|x| x.0)
+            .collect::<Vec<_>>();
+        assert_eq!(names, vec!["\"p.asm:a\"", "\"p.asm:c\""]);
+        assert_eq!(emulator.storage.used.len(), 2);
+        assert_eq!(emulator.memory.pcb(2).unwrap().priority, 3);
+    }
+
+    #[test]
+    fn process_names() {
+        let mut emulator = emulator(1);
+        emulator.config.scheduler = Some(Scheduler::RR);
+        emulator.quantum = Some(1);
+        let program = "#ARRIVAL 0\nMOV AX, 1\nMOV BX, 2\nINT 20H";
+        load(&mut emulator, "\"first.asm\"", program);
+        load(&mut emulator, "\"a_very_long_name.asm\"", program);
+        assert_eq!(emulator.diagram.get(1).unwrap().label(), "P1 first.asm");
+        assert_eq!(
+            emulator.diagram.get(2).unwrap().label(),
+            "P2 a_very_long_n…"
+        );
+
+        // The names are kept after the context switches and the termination
+        let _ = emulator.schedule();
+        emulator.run_ticks = "100".to_string();
+        let _ = emulator.update(Message::RunTicks);
+        assert!(emulator.usage[0].context_switches > 2);
+        let mut names = emulator
+            .stats_data
+            .iter()
+            .map(|x| (x.process_id, x.name.as_str()))
+            .collect::<Vec<_>>();
+        names.sort();
+        assert_eq!(
+            names,
+            vec![(1, "\"first.asm\""), (2, "\"a_very_long_name.asm\"")]
+        );
+        assert_eq!(emulator.diagram.get(1).unwrap().name, "\"first.asm\"");
+    }
+
+    #[test]
+    fn aggregated_admission_errors() {
+        let mut emulator = emulator(1);
+        for (name, program) in [
+            ("a.asm", "FOO"),
+            ("b.asm", "MOV AX, 1\nINT 20H"),
+            ("c.asm", "MOV AX"),
+            ("d.asm", "#BURST x"),
+        ] {
+            let data = program.as_bytes().to_vec();
+            emulator.storage.store_files(name, data).unwrap();
+        }
+
+        // A single pass reports the three files and removes only them from storage
+        assert!(emulator.admit().is_some());
+        assert_eq!(emulator.log.filter(Level::Warning).count(), 3);
+        assert_eq!(emulator.notifications.len(), 1);
+        assert_eq!(emulator.notifications[0].severity, Severity::Warning);
+        assert_eq!(emulator.notifications[0].body.lines().count(), 3);
+        assert_eq!(emulator.storage.used.len(), 1);
+        assert_eq!(emulator.storage.used[0].0, "b.asm");
+        assert_eq!(loaded(&emulator), vec![("b.asm".to_string(), Some(1))]);
+        assert!(emulator.admit().is_none());
+    }
+
+    #[test]
+    fn notifications() {
+        let mut emulator = emulator(1);
+        let lang = emulator.config.lang;
+        let last = |emulator: &Emulator| emulator.notifications.last().unwrap().clone();
+
+        let _ = emulator.update(Message::FilePicked(Err(Error::DialogClosed)));
+        let error = Notification::new(
+            Severity::Error,
+            tr(lang, "error"),
+            Error::DialogClosed.to_string(),
+        );
+        assert_eq!(last(&emulator), error);
+        let _ = emulator.update(Message::StoreFiles(Err(Error::DialogClosed)));
+        assert_eq!(last(&emulator), error);
+
+        // The files that don't fit are reported together
+        emulator.storage = Storage::new(16);
+        let files = vec![
+            ("a.asm".to_string(), vec![b'a'; 20]),
+            ("b.asm".to_string(), vec![b'b'; 30]),
+        ];
+        let _ = emulator.update(Message::StoreFiles(Ok((files, vec![]))));
+        let notification = last(&emulator);
+        assert_eq!(notification.severity, Severity::Warning);
+        assert_eq!(notification.title, tr(lang, "memory_warning"));
+        assert_eq!(notification.body.lines().count(), 2);
+
+        // Without a config file the settings can't be saved
+        emulator.settings = Some(Settings::new(&emulator.config));
+        let _ = emulator.update(Message::SaveSettings);
+        assert_eq!(last(&emulator).body, Error::NotFile.to_string());
+
+        emulator.storage = Storage::new(emulator.config.storage);
+        let pcb = load(&mut emulator, "c.asm", "ASSERT AX, 1\nINT 20H");
+        let _ = emulator.update(Message::Distpacher((0, pcb)));
+        let (event, _) = emulator.step();
+        assert_eq!(event, vec![TickEvent::Fault(0, Fault::AssertionFailed)]);
+        assert_eq!(last(&emulator).title, tr(lang, "fault_title"));
+        assert_eq!(emulator.notifications.len(), 5);
+
+        // Info only goes to the log
+        let info = Notification::new(Severity::Info, "Info", "Saved");
+        let _ = emulator.notify(info.clone());
+        assert_eq!(
+            emulator.log.filter(Level::Info).last().unwrap().message,
+            "Saved"
+        );
+        emulator.headless = true;
+        let _ = emulator.notify(info);
+        assert_eq!(emulator.notifications.len(), 7);
+    }
+
+    #[test]
+    fn memory_map() {
+        let mut emulator = emulator(1);
+        emulator.memory = Memory::new(180, 120);
+        load(&mut emulator, "a.asm", "#STACK 4\nINC\nINT 20H");
+        assert_eq!(
+            emulator.memory_map(),
+            vec![
+                "0x00–0x25 P1 PCB",
+                "0x26–0x77 OS",
+                "0x78–0x83 P1 code",
+                "0x84–0x87 P1 stack",
+                "0x88–0xB3 free",
+            ]
+        );
+        assert_eq!(emulator.storage_map()[0], "0x00–0x13 a.asm");
+    }
+
+    #[test]
+    fn storage_highlights() {
+        let mut emulator = emulator(1);
+        for name in ["a.asm", "b.asm", "c.asm"] {
+            emulator.storage.store_files(name, vec![1; 4]).unwrap();
+        }
+        emulator.storage.free_file(4);
+        let highlights = emulator.storage_highlights();
+        let ranges = highlights
+            .iter()
+            .map(|x| (x.start, x.end, x.label.as_str()))
+            .collect::<Vec<_>>();
+        assert_eq!(
+            ranges,
+            vec![(0, 4, "a.asm"), (8, 12, "c.asm"), (4, 8, "Freed")]
+        );
+        // The colors are the same on every frame
+        assert_eq!(highlights[0].color, emulator.storage_highlights()[0].color);
+
+        // Clicking a file selects it
+        let _ = emulator.update(Message::SelectFile(8));
+        assert_eq!(emulator.selected_file, Some(8));
+    }
+
+    #[test]
+    fn same_color_on_every_view() {
+        let mut emulator = emulator(1);
+        load(&mut emulator, "a.asm", "INC\nINT 20H");
+        let (p_id, _, _) = load(&mut emulator, "b.asm", "DEC\nINT 20H");
+        let code = emulator.memory.pcb(p_id).unwrap().code_segment;
+        let color = process_color(emulator.config.palette, p_id);
+        let memory = emulator.memory_highlights();
+        let segment = memory.iter().find(|x| x.start == code).unwrap();
+        assert_eq!(segment.color, color);
+        assert_eq!(emulator.storage_highlights()[1].color, color);
+
+        // Switching the palette changes it everywhere
+        emulator.config.palette = Palette::Dracula;
+        let color = process_color(Palette::Dracula, p_id);
+        assert_eq!(emulator.storage_highlights()[1].color, color);
+        let memory = emulator.memory_highlights();
+        assert_eq!(
+            memory.iter().find(|x| x.start == code).unwrap().color,
+            color
+        );
+    }
+
+    #[test]
+    fn arrival_countdown() {
+        let mut emulator = emulator(1);
+        load(&mut emulator, "a.asm", "#ARRIVAL 5\nINT 20H");
+        let _ = emulator.schedule();
+        assert!(emulator.ready_queue().is_empty());
+
+        // The clock runs while nothing has arrived
+        emulator.mode = Some(Mode::Manual);
+        emulator.run_ticks = "1".to_string();
+        for _ in 0..4 {
+            let _ = emulator.update(Message::RunTicks);
+            assert_eq!(
+                emulator.memory.pcb(1).unwrap().process_state,
+                ProcessState::New
+            );
+            assert!(emulator.cpus[0].assigned.is_none());
+        }
+        assert_eq!(emulator.counter, 4);
+
+        let _ = emulator.update(Message::RunTicks);
+        assert_eq!(emulator.counter, 5);
+        assert_eq!(emulator.arrived, vec![1]);
+        assert_eq!(emulator.cpus[0].assigned, Some(1));
+        assert!(emulator
+            .log
+            .to_text(Level::Info)
+            .contains("Process 1 arrived on tick 5"));
+    }
+
+    #[test]
+    fn status_line() {
+        let mut emulator = emulator(1);
+        emulator.config.scheduler = Some(Scheduler::FCFS);
+        load(&mut emulator, "a.asm", "#ARRIVAL 0\nMOV AX, 1\nINT 20H");
+        load(&mut emulator, "b.asm", "#ARRIVAL 9\nINT 20H");
+        let _ = emulator.schedule();
+        assert!(emulator
+            .status()
+            .ends_with("New 1 · Ready 0 · Running 1 · Blocked 0 · Done 0 · FCFS"));
+
+        // The clock stops once every process terminated
+        emulator.mode = Some(Mode::Manual);
+        emulator.run_ticks = "100".to_string();
+        let _ = emulator.update(Message::RunTicks);
+        assert_eq!(emulator.completed.map(|x| x.0), Some(10));
+        let status = emulator.status();
+        assert!(status.starts_with("Completed in 10 ticks"));
+        assert!(status.contains("Done 2"));
+        let _ = emulator.update(Message::Tick);
+        assert_eq!(emulator.status(), status);
+        assert!(emulator
+            .log
+            .to_text(Level::Info)
+            .contains("Completed in 10 ticks"));
+    }
+
+    #[test]
+    fn rng_audit() {
+        let audit = |seed| {
+            let mut emulator = emulator(1);
+            emulator.arrival_policy = ArrivalPolicy::seeded(Some(seed));
+            emulator.config.audit_rng = true;
+            load(&mut emulator, "a.asm", "MOV AX, 1\nINT 20H");
+            emulator.counter = 4;
+            load(&mut emulator, "b.asm", "#ARRIVAL 2\nINT 20H");
+            load(&mut emulator, "c.asm", "MOV BX, 1\nINT 20H");
+            emulator
+                .log
+                .filter(Level::Debug)
+                .filter(|x| x.message.starts_with("Random draw"))
+                .map(|x| x.message.clone())
+                .collect::<Vec<_>>()
+        };
+        let draws = audit(3);
+        assert_eq!(draws.len(), 2);
+        assert!(draws[1].starts_with("Random draw for arrival: 1..=5 gave "));
+        assert!(draws[1].ends_with(" on tick 4"));
+        assert_eq!(audit(3), draws);
+        assert_ne!(audit(4), draws);
+    }
+
+    #[test]
+    fn run_summary() {
+        let mut emulator = emulator(1);
+        emulator.config.scheduler = Some(Scheduler::FCFS);
+        // Only one PCB fits, b waits until a terminates
+        emulator.memory = Memory::new(256, PCB::RECORD_SIZE + 1);
+        load(&mut emulator, "a.asm", "#ARRIVAL 0\nMOV AX, 1\nINT 20H");
+        load(&mut emulator, "b.asm", "#ARRIVAL 0\nMOV BX, 2\nINT 20H");
+        let _ = emulator.schedule();
+        emulator.mode = Some(Mode::Manual);
+        emulator.run_ticks = "100".to_string();
+        let _ = emulator.update(Message::RunTicks);
+
+        // Completed once, after b ran too
+        assert_eq!(emulator.stats_data.len(), 2);
+        let completed = emulator
+            .log
+            .filter(Level::Info)
+            .filter(|x| x.message.starts_with("Completed in"))
+            .count();
+        assert_eq!(completed, 1);
+        assert_eq!(emulator.completed.map(|x| x.0), Some(emulator.counter));
+        assert!(emulator.show_summary);
+        let summary = emulator.summary();
+        assert_eq!(summary[0], format!("Total ticks: {}", emulator.counter));
+        assert_eq!(summary.len(), 5);
+        // Both files have the directive, the arrivals didn't take a random value
+        assert_eq!(summary[2], "Random draws: 0");
+        assert_eq!(
+            summary[3],
+            "Dispatches: 2, preemptions: 0, blocks: 0, faults: 0"
+        );
+        assert!(summary[4].starts_with("FCFS: 2 processes"));
+
+        let _ = emulator.update(Message::SummaryStats);
+        assert!(!emulator.show_summary);
+        assert!(emulator.show_stats);
+    }
+
+    #[test]
+    fn fault_policy() {
+        let mut emulator = emulator(1);
+        emulator.config.scheduler = Some(Scheduler::FCFS);
+        emulator.memory = Memory::new(256, PCB::RECORD_SIZE + 1);
+        // Without INT 20H the next instruction is on the empty stack
+        for name in ["a.asm", "b.asm", "c.asm", "d.asm", "e.asm"] {
+            load(&mut emulator, name, "#ARRIVAL 0\nMOV AX, 1");
+        }
+        let _ = emulator.schedule();
+        let mut dialogs = 0;
+        for _ in 0..5 {
+            while !emulator
+                .tick()
+                .iter()
+                .any(|x| matches!(x, TickEvent::Fault(0, _)))
+            {}
+            dialogs += emulator.fault(0, Fault::InvalidInstruction).iter().count();
+            let _ = emulator.schedule();
+        }
+
+        assert_eq!(dialogs, 1);
+        let faults = emulator
+            .log
+            .filter(Level::Warning)
+            .filter(|x| {
+                x.message
+                    .contains("terminated by a fault: invalid instruction")
+            })
+            .count();
+        assert_eq!(faults, 5);
+        assert_eq!(emulator.faults.get(&Fault::InvalidInstruction), Some(&5));
+        assert_eq!(emulator.stats_data.len(), 5);
+    }
+
+    #[test]
+    fn jump_out_of_memory() {
+        let mut emulator = emulator(1);
+        let pcb = load(&mut emulator, "a.asm", "JMP +50\nINT 20H");
+        let _ = emulator.update(Message::Distpacher((0, pcb)));
+        // The jump lands past the end of memory, the next fetch faults instead of panicking
+        let event = (0..5).find_map(|_| emulator.step().0.pop());
+        assert_eq!(event, Some(TickEvent::Fault(0, Fault::InvalidInstruction)));
+        assert!(emulator.cpus[0].is_free());
+    }
+
+    #[test]
+    fn step_back() {
+        let mut emulator = emulator(1);
+        emulator.mode = Some(Mode::Manual);
+        let program = "MOV AX, 1\nINC\nINC\nINC\nINC\nINC\nINC\nINT 20H";
+        let pcb = load(&mut emulator, "a.asm", program);
+        let _ = emulator.update(Message::Distpacher((0, pcb)));
+        let state = |emulator: &Emulator| {
+            format!(
+                "{:?}",
+                (
+                    emulator.counter,
+                    emulator.cpus.clone(),
+                    emulator.memory.data.clone(),
+                    emulator.diagram.get(pcb.0).map(|x| x.remaining_burst),
+                    emulator.log.len(),
+                )
+            )
+        };
+        let mut states = vec![state(&emulator)];
+        for _ in 0..5 {
+            let _ = emulator.update(Message::Tick);
+            states.push(state(&emulator));
+        }
+        assert_eq!(emulator.snapshots.len(), 5);
+
+        let _ = emulator.update(Message::StepBack);
+        let _ = emulator.update(Message::StepBack);
+        assert_eq!(state(&emulator), states[3]);
+        assert_eq!(emulator.cpus[0].cpu.ac, 2);
+
+        // Going forward again repeats the same states
+        let _ = emulator.update(Message::Tick);
+        assert_eq!(state(&emulator), states[4]);
+        let _ = emulator.update(Message::Tick);
+        assert_eq!(state(&emulator), states[5]);
+
+        // The unchanged storage is shared between the snapshots
+        assert!(Rc::ptr_eq(
+            &emulator.snapshots[0].storage,
+            &emulator.snapshots[4].storage
+        ));
+    }
+
+    #[test]
+    fn reject_files() {
+        let dir = env::temp_dir().join(format!("read_files_{}", std::process::id()));
+        std::fs::create_dir_all(&dir).unwrap();
+        std::fs::write(dir.join("good.asm"), "MOV AX, 1\nINT 20H").unwrap();
+        std::fs::write(dir.join("big.asm"), "MOV AX, 1\n".repeat(10)).unwrap();
+        std::fs::write(dir.join("binary.asm"), [0x7f, b'E', b'L', b'F', 0, 0, 1]).unwrap();
+        let paths = ["big.asm", "good.asm", "binary.asm"]
+            .map(|x| dir.join(x))
+            .to_vec();
+        let runtime = tokio::runtime::Builder::new_current_thread()
+            .build()
+            .unwrap();
+        let (files, failures) = runtime.block_on(read_files(paths, 64)).unwrap();
+        std::fs::remove_dir_all(&dir).unwrap();
+
+        // The rejected files don't stop the good one
+        assert_eq!(files.len(), 1);
+        assert_eq!(files[0].0, "\"good.asm\"");
+        assert!(
+            matches!(&failures[0], (_, Error::FileTooLarge(name, 100)) if name == "\"big.asm\"")
+        );
+        assert!(matches!(&failures[1], (_, Error::BinaryFile(name)) if name == "\"binary.asm\""));
+
+        let mut emulator = emulator(1);
+        let _ = emulator.update(Message::StoreFiles(Ok((files, failures))));
+        assert_eq!(emulator.storage.used.len(), 1);
+    }
+
+    #[test]
+    fn batch_folder() {
+        let dir = env::temp_dir().join(format!("batch_{}", std::process::id()));
+        std::fs::create_dir_all(&dir).unwrap();
+        std::fs::write(dir.join("good.asm"), "#ARRIVAL 0\nMOV AX, 1\nINT 20H").unwrap();
+        std::fs::write(dir.join("bad.asm"), "#ARRIVAL 0\nFOO AX\nINT 20H").unwrap();
+        std::fs::write(dir.join("notes.txt"), "FOO").unwrap();
+        let (files, failures) = read_folder(&dir, 64).unwrap();
+        std::fs::remove_dir_all(&dir).unwrap();
+        assert_eq!(files.len(), 2);
+        assert!(failures.is_empty());
+
+        let mut emulator = emulator(1);
+        emulator.store_batch(files, failures);
+        emulator.run_batch();
+
+        // The bad file doesn't stop the good one
+        assert!(emulator.completed.is_some());
+        let results = emulator.batch_results();
+        assert_eq!(results.len(), 3);
+        assert!(results[1].starts_with("good.asm: terminated on CPU 0"));
+        assert!(results[2].contains("bad.asm"));
+        assert!(emulator.summary().ends_with(&results));
+    }
+
+    #[test]
+    fn assert_programs() {
+        // The example programs check themselves, the batch fails when one of them doesn't pass
+        let dir = Path::new(env!("CARGO_MANIFEST_DIR")).join("programs/asserts");
+        let (files, failures) = read_folder(&dir, 1024).unwrap();
+        assert!(files.len() >= 3 && failures.is_empty());
+        let (mut batch, mut failing) = (emulator(1), emulator(1));
+        batch.arrival_policy = ArrivalPolicy::FixedZero;
+        batch.store_batch(files, failures);
+        batch.run_batch();
+        assert!(batch.completed.is_some());
+        assert!(!batch.batch_failed(), "{:?}", batch.batch_results());
+
+        let pcb = load(
+            &mut failing,
+            "a.asm",
+            "MOV AX, 2\nASSERT AX, 2\nASSERT AX, 3\nINT 20H",
+        );
+        let _ = failing.update(Message::Distpacher((0, pcb)));
+        assert!(failing.tick().is_empty());
+        assert!(failing.tick().is_empty());
+        assert_eq!(
+            failing.tick(),
+            vec![TickEvent::Fault(0, Fault::AssertionFailed)]
+        );
+        let failure = failing.log.filter(Level::Warning).next().unwrap();
+        assert_eq!(
+            failure.message,
+            format!(
+                "P{} failed the ASSERT of line 3: AX should be 3 but it's 2",
+                pcb.0
+            )
+        );
+    }
+
+    #[test]
+    fn post_mortem_dump() {
+        let mut emulator = emulator(1);
+        let pcb = load(
+            &mut emulator,
+            "a.asm",
+            "MOV AX, 7\nPUSH AX\nASSERT AX, 3\nINT 20H",
+        );
+        let _ = emulator.update(Message::Distpacher((0, pcb)));
+        while !emulator
+            .step()
+            .0
+            .iter()
+            .any(|x| matches!(x, TickEvent::Fault(..)))
+        {}
+
+        // The state is kept after the segments are freed
+        let post_mortem = &emulator.post_mortems[&pcb.0];
+        assert_eq!(post_mortem.fault, Some(Fault::AssertionFailed));
+        let cpu = post_mortem.registers;
+        assert_eq!(cpu.ax, 7);
+        let failed = post_mortem.disassembly.iter().find(|x| x.0 == cpu.pc);
+        assert_eq!(failed.unwrap().1.operation, Operation::ASSERT);
+        assert_eq!(post_mortem.disassembly.len(), 4);
+        assert!(post_mortem.stack.contains(&7));
+        // The failed instruction never completed, it's only on the disassembly
+        assert_eq!(post_mortem.history.len(), 2);
+        assert_eq!(
+            post_mortem.history[0].instruction.operation,
+            Operation::PUSH
+        );
+
+        emulator.reset();
+        assert!(emulator.post_mortems.is_empty());
+    }
+
+    #[test]
+    fn system_time() {
+        // Three INC between the two reads, the difference ends on CX
+        let mut emulator = emulator(1);
+        let program = "INT 11H\nMOV BX, DX\nINC AX\nINC AX\nINC AX\nINT 11H\nLOAD DX\nSUB BX\nSTORE CX\nINT 20H";
+        let pcb = load(&mut emulator, "a.asm", program);
+        let _ = emulator.update(Message::Distpacher((0, pcb)));
+        while !emulator
+            .step()
+            .0
+            .iter()
+            .any(|x| matches!(x, TickEvent::Terminated(..)))
+        {}
+        let cpu = emulator.post_mortems[&pcb.0].registers;
+        assert_eq!(cpu.ax, 3);
+        // MOV, the INC and the second INT take a tick each
+        assert_eq!(cpu.cx, 5);
+
+        // Past 255 ticks only the low byte is read, 300 % 256
+        emulator.counter = 300;
+        let pcb = load(&mut emulator, "b.asm", "INT 11H\nINT 20H");
+        let _ = emulator.update(Message::Distpacher((0, pcb)));
+        while !emulator
+            .step()
+            .0
+            .iter()
+            .any(|x| matches!(x, TickEvent::Terminated(..)))
+        {}
+        assert_eq!(emulator.post_mortems[&pcb.0].registers.dx, 44);
+    }
+
+    #[test]
+    fn free_memory_query() {
+        let mut emulator = emulator(1);
+        emulator.memory = Memory::new(256, 64);
+        let path = Path::new(env!("CARGO_MANIFEST_DIR")).join("programs/free_memory.asm");
+        let program = std::fs::read_to_string(path).unwrap();
+        let pcb = load(&mut emulator, "free_memory.asm", &program);
+        // The program sees the memory after its own segments were allocated
+        let free = emulator.memory.free_size();
+        let p = emulator.memory.pcb(pcb.0).unwrap();
+        assert_eq!(free, 192 - p.code_segment_size - p.stack_segment_size);
+        let _ = emulator.update(Message::Distpacher((0, pcb)));
+        while !emulator
+            .step()
+            .0
+            .iter()
+            .any(|x| matches!(x, TickEvent::Terminated(..)))
+        {}
+        assert_eq!(emulator.post_mortems[&pcb.0].registers.bx as usize, free);
+
+        // It doesn't fit on DX past 255 bytes
+        emulator.memory = Memory::new(1024, 64);
+        let pcb = load(&mut emulator, "b.asm", &program);
+        let _ = emulator.update(Message::Distpacher((0, pcb)));
+        while !emulator
+            .step()
+            .0
+            .iter()
+            .any(|x| matches!(x, TickEvent::Terminated(..)))
+        {}
+        assert_eq!(emulator.post_mortems[&pcb.0].registers.bx, 255);
+    }
+
+    #[test]
+    fn usage_warnings() {
+        let (mut emulator, mut stored) = (emulator(1), emulator(1));
+        emulator.memory = Memory::new(448, 256);
+        let warnings = |emulator: &Emulator, region: &str| {
+            emulator
+                .log
+                .filter(Level::Warning)
+                .filter(|x| x.message.contains(region))
+                .count()
+        };
+        // 148 of the 192 bytes, 77%
+        let program = format!("{}INT 20H", "MOV AX, 1\n".repeat(21));
+        load(&mut emulator, "a.asm", &program);
+        assert_eq!(warnings(&emulator, "user memory"), 0);
+        // 88%, then 100%
+        let b = load(&mut emulator, "b.asm", "INT 20H");
+        assert_eq!(warnings(&emulator, "user memory"), 1);
+        load(&mut emulator, "c.asm", "INT 20H");
+        assert_eq!(warnings(&emulator, "user memory"), 2);
+        let last = emulator.log.filter(Level::Warning).last().unwrap();
+        assert_eq!(
+            last.message,
+            "The user memory is 100% used, over the 95% threshold."
+        );
+
+        // Back to 88%, only the 95% threshold is warned again
+        let _ = emulator.update(Message::Distpacher((0, b)));
+        emulator.terminate(0);
+        assert_eq!(warnings(&emulator, "user memory"), 2);
+        load(&mut emulator, "d.asm", "INT 20H");
+        assert_eq!(warnings(&emulator, "user memory"), 3);
+
+        stored.storage = Storage::new(100);
+        stored.store_file("a.asm".into(), vec![b'a'; 85]).unwrap();
+        stored.store_file("b.asm".into(), vec![b'b'; 5]).unwrap();
+        assert_eq!(warnings(&stored, "storage"), 1);
+        stored.store_file("c.asm".into(), vec![b'c'; 5]).unwrap();
+        assert_eq!(warnings(&stored, "storage"), 2);
+    }
+
+    #[test]
+    fn output_per_cpu() {
+        let mut emulator = emulator(2);
+        let a = load(&mut emulator, "a.asm", "MOV DX, 7\nINT 10H\nINT 20H");
+        let b = load(&mut emulator, "b.asm", "MOV DX, 9\nINT 10H\nINT 20H");
+        let _ = emulator.update(Message::Distpacher((0, a)));
+        let _ = emulator.update(Message::Distpacher((1, b)));
+        emulator.tick();
+        emulator.tick();
+
+        assert_eq!(emulator.cpu_output.get(&0), Some(&(a.0, 7)));
+        assert_eq!(emulator.cpu_output.get(&1), Some(&(b.0, 9)));
+        let outputs = emulator
+            .log
+            .filter(Level::Info)
+            .filter(|x| x.message.contains(" printed "))
+            .count();
+        assert_eq!(outputs, 2);
+
+        // A new occupant starts without output
+        let _ = emulator.update(Message::Terminated(0));
+        assert_eq!(emulator.cpu_output.get(&0), None);
+        let _ = emulator.update(Message::Distpacher((1, a)));
+        assert_eq!(emulator.cpu_output.get(&1), None);
+    }
+
+    #[test]
+    fn memory_mapped_devices() {
+        let mut emulator = emulator(1);
+        emulator.memory = Memory::from_config(&emulator.config);
+        let program = "#INPUT 42\nINT 09H\nLOADM AX, 119\nMOV DX, 5\nSTOREM DX, 118\nSTOREM AX, 3";
+        let pcb = load(&mut emulator, "a.asm", program);
+        let _ = emulator.update(Message::Distpacher((0, pcb)));
+        for _ in 0..4 {
+            assert!(emulator.tick().is_empty());
+        }
+
+        // The keyboard register has the last input and the display register goes to the console
+        assert_eq!(emulator.cpus[0].cpu.ax, 42);
+        assert_eq!(emulator.memory.data[118..120], [5, 42]);
+        assert_eq!(emulator.display_content, "5");
+        assert_eq!(emulator.cpu_output.get(&0), Some(&(pcb.0, 5)));
+        assert!(emulator
+            .log
+            .to_text(Level::Info)
+            .contains("Process 1 printed 5 on CPU 0"));
+        assert_eq!(emulator.memory_highlights()[0].label, "Display register: 5");
+
+        // The rest of the memory is protected
+        assert_eq!(
+            emulator.tick(),
+            vec![TickEvent::Fault(0, Fault::MemoryViolation)]
+        );
+    }
+
+    #[test]
+    fn stack_locals() {
+        let (mut emulator, mut unchecked) = (emulator(1), emulator(1));
+        // The first PUSH moves SP past the locals, so POP reads them back from the top
+        let program = "#STACK 4\nPUSH AX\nMOVN 0, 7\nMOVN 1, 9\nPOP AX\nPOP BX\nINT 20H";
+        let pcb = load(&mut emulator, "a.asm", program);
+        let _ = emulator.update(Message::Distpacher((0, pcb)));
+        for _ in 0..5 {
+            assert!(emulator.tick().is_empty());
+        }
+        let stack = emulator.memory.pcb(pcb.0).unwrap().stack_segment;
+        assert_eq!(emulator.memory.data[stack..stack + 2], [7, 9]);
+        assert_eq!((emulator.cpus[0].cpu.ax, emulator.cpus[0].cpu.bx), (9, 7));
+
+        // Offsets outside the stack are rejected even without the directive
+        let pcb = load(&mut unchecked, "b.asm", "MOVN 200, 1\nINT 20H");
+        let _ = unchecked.update(Message::Distpacher((0, pcb)));
+        assert_eq!(
+            unchecked.tick(),
+            vec![TickEvent::Fault(0, Fault::MemoryViolation)]
+        );
+    }
+
+    #[test]
+    fn source_lines() {
+        let mut emulator = emulator(1);
+        let program =
+            "; Writes outside the device region\n\nMOV AX, 1 ; value\n\nSTOREM AX, 3\nINT 20H";
+        let pcb = load(&mut emulator, "fib.asm", program);
+        let _ = emulator.update(Message::Distpacher((0, pcb)));
+        assert!(emulator.tick().is_empty());
+        assert_eq!(emulator.ir_label(0), "line 3: MOV AX, 1");
+        assert_eq!(
+            emulator.memory.source_line(pcb.0, emulator.cpus[0].cpu.pc),
+            Some((5, "STOREM AX, 3"))
+        );
+
+        assert_eq!(
+            emulator.tick(),
+            vec![TickEvent::Fault(0, Fault::MemoryViolation)]
+        );
+        let message = emulator.fault(0, Fault::MemoryViolation).unwrap();
+        assert!(
+            message.contains("outside of the device region at line 5 of fib.asm"),
+            "{}",
+            message
+        );
+    }
+
+    #[test]
+    fn mid_run_switch() {
+        let mut emulator = emulator(2);
+        emulator.config.scheduler = Some(Scheduler::FCFS);
+        emulator.quantum = Some(2);
+        for name in ["a.asm", "b.asm", "c.asm"] {
+            load(
+                &mut emulator,
+                name,
+                "#ARRIVAL 0\nMOV AX, 1\nMOV BX, 2\nMOV CX, 3\nINT 20H",
+            );
+        }
+        let _ = emulator.schedule();
+        emulator.run_ticks = "1".to_string();
+        let _ = emulator.update(Message::RunTicks);
+
+        // Needs a confirmation
+        let _ = emulator.update(Message::SchedulerSelected(Scheduler::RR));
+        assert_eq!(emulator.config.scheduler, Some(Scheduler::FCFS));
+        let _ = emulator.update(Message::ConfirmSwitch);
+        assert_eq!(emulator.config.scheduler, Some(Scheduler::RR));
+
+        // Every process is on a single queue
+        let mut ids = emulator.ready_queue();
+        ids.extend(emulator.running_queue().iter().map(|x| x.1));
+        ids.sort();
+        assert_eq!(ids, vec![1, 2, 3]);
+        assert_eq!(emulator.running_queue().len(), 2);
+
+        emulator.run_ticks = "100".to_string();
+        let _ = emulator.update(Message::RunTicks);
+        let mut terminated = emulator
+            .stats_data
+            .iter()
+            .map(|x| x.process_id)
+            .collect::<Vec<_>>();
+        terminated.sort();
+        assert_eq!(terminated, vec![1, 2, 3]);
+    }
+
+    #[test]
+    fn instruction_costs() {
+        // Ticks until the program terminates
+        let run = |program: &str| {
+            let mut emulator = emulator(1);
+            emulator.config.costs.add = 3;
+            let pcb = load(&mut emulator, "a.asm", program);
+            let _ = emulator.update(Message::Distpacher((0, pcb)));
+            let _ = emulator.update(Message::RunToEvent);
+            assert!(emulator.cpus[0].assigned.is_none());
+            (emulator.diagram.get(1).unwrap().burst, emulator.counter)
+        };
+        let (mov_burst, mov_ticks) = run("MOV AX, 1\nMOV BX, 2\nMOV CX, 3\nINT 20H");
+        let (add_burst, add_ticks) = run("ADD AX\nADD BX\nADD CX\nINT 20H");
+        assert_eq!((mov_burst, mov_ticks), (4, 4));
+        assert_eq!((add_burst, add_ticks), (10, 10));
+    }
+
+    #[test]
+    fn countdown_loop() {
+        // DEC executions of a loop counting down from CX
+        let run = |cx: u8, legacy_flags: bool| {
+            let mut emulator = emulator(1);
+            emulator.config.legacy_flags = legacy_flags;
+            let program = format!("MOV CX, {}\nLOAD CX\nDEC\nJNE -1\nSTORE CX\nINT 20H", cx);
+            let pcb = load(&mut emulator, "a.asm", &program);
+            let _ = emulator.update(Message::Distpacher((0, pcb)));
+            let (mut decs, mut exited) = (0, false);
+            for _ in 0..60 {
+                let _ = emulator.update(Message::Tick);
+                if emulator.cpus[0].cpu.ir == Some(Operation::DEC) {
+                    decs += 1;
+                }
+                exited |= emulator.cpus[0].cpu.ir == Some(Operation::STORE);
+            }
+            (decs, exited)
+        };
+        assert_eq!(run(3, false), (3, true));
+        assert_eq!(run(5, false), (5, true));
+        // Without the flag on arithmetic the loop never exits
+        assert!(!run(3, true).1);
+    }
+
+    #[test]
+    fn code_write() {
+        let run = |self_modifying_code: bool| {
+            let mut emulator = emulator(2);
+            emulator.config.self_modifying_code = self_modifying_code;
+            let victim = load(&mut emulator, "a.asm", "MOV AX, 1\nMOV AX, 2\nINT 20H");
+            let writer = load(&mut emulator, "b.asm", "MOV AX, 7\nPUSH AX\nINT 20H");
+            let _ = emulator.update(Message::Distpacher((0, victim)));
+            let _ = emulator.update(Message::Distpacher((1, writer)));
+            let _ = emulator.tick();
+            // A broken stack pointing to the code of the other process
+            emulator.cpus[1].cpu.sp = emulator.memory.pcb(victim.0).unwrap().code_segment + 1;
+            let event = emulator.tick();
+            (emulator, event)
+        };
+        let (emulator, event) = run(false);
+        assert_eq!(event, vec![TickEvent::Fault(1, Fault::CodeWrite)]);
+        let address = emulator.memory.pcb(1).unwrap().code_segment + 1;
+        let warning = format!("P2 wrote on 0x{:02X}, on the code of P1", address);
+        assert!(emulator.log.to_text(Level::Warning).contains(&warning));
+        let highlights = emulator.memory_highlights();
+        let highlight = highlights
+            .iter()
+            .find(|x| x.start <= address && address < x.end)
+            .unwrap();
+        assert_eq!(highlight.label, "Code modified by a process");
+
+        // Allowed, the writer keeps running and the victim reads the new byte
+        let (mut emulator, event) = run(true);
+        assert!(event.is_empty());
+        assert!(emulator.log.to_text(Level::Warning).contains(&warning));
+        assert_eq!(emulator.memory.written_code, vec![address]);
+
+        // A length byte overwritten with 0 is disassembled as data
+        emulator.memory.data[address - 1 + INSTRUCTION_SIZE] = 0;
+        let _ = emulator.update(Message::SelectPcb(1));
+        let _ = emulator.view();
+    }
+
+    #[test]
+    fn inc_dec_register() {
+        let run = |legacy_flags: bool| {
+            let mut emulator = emulator(1);
+            emulator.config.legacy_flags = legacy_flags;
+            let pcb = load(
+                &mut emulator,
+                "a.asm",
+                "MOV BX, 1\nINC BX\nDEC BX\nDEC BX\nINT 20H",
+            );
+            let _ = emulator.update(Message::Distpacher((0, pcb)));
+            for _ in 0..4 {
+                let _ = emulator.update(Message::Tick);
+            }
+            let cpu = emulator.cpus[0].cpu;
+            (cpu.bx, cpu.ac, cpu.z)
+        };
+        // The register changes and sets Z, AC keeps its value
+        assert_eq!(run(false), (0, 0, true));
+        // AC + BX - BX - BX, Z untouched
+        assert_eq!(run(true), (1, 255, false));
+    }
+
+    #[test]
+    fn repeated_file_names() {
+        let mut emulator = emulator(1);
+        let program = "MOV AX, 1\nINT 20H";
+        load(&mut emulator, "test.asm", program);
+        let pcb = load(&mut emulator, "test.asm", program);
+        assert_eq!(
+            loaded(&emulator),
+            vec![
+                ("test.asm".to_string(), Some(1)),
+                ("test.asm".to_string(), Some(2))
+            ]
+        );
+
+        // Only the entry of the terminated process is cleared
+        let _ = emulator.update(Message::Distpacher((0, pcb)));
+        let _ = emulator.update(Message::RunToEvent);
+        assert_eq!(
+            emulator.memory.pcb(2).unwrap().process_state,
+            ProcessState::Terminated
+        );
+        assert_eq!(
+            loaded(&emulator),
+            vec![
+                ("test.asm".to_string(), Some(1)),
+                ("test.asm".to_string(), None)
+            ]
+        );
+        assert_eq!(emulator.loaded_files[1].0, emulator.storage.used[1].1);
+    }
+
+    #[test]
+    fn tick_without_allocations() {
+        let mut emulator = emulator(1);
+        // Endless loop, the jump goes back to the first instruction
+        let pcb = load(
+            &mut emulator,
+            "a.asm",
+            &format!("{}JMP -6\nINT 20H", "MOV AX, 5\n".repeat(6)),
+        );
+        let _ = emulator.update(Message::Distpacher((0, pcb)));
+        // Fill the history buffer first
+        for _ in 0..64 {
+            assert!(emulator.tick().is_empty());
+        }
+
+        let before = ALLOCATIONS.with(|x| x.get());
+        for _ in 0..1000 {
+            assert!(emulator.tick().is_empty());
+        }
+        assert_eq!(ALLOCATIONS.with(|x| x.get()), before);
+        assert_eq!(emulator.cpus[0].cpu.ax, 5);
+    }
+
+    #[test]
+    fn instruction_cache() {
+        let run = |program: &str, ticks: usize| {
+            let mut emulator = emulator(1);
+            emulator.cache = Cache::new(CacheConfig {
+                enabled: true,
+                ..Default::default()
+            });
+            let pcb = load(&mut emulator, "a.asm", program);
+            let _ = emulator.update(Message::Distpacher((0, pcb)));
+            // The first fetch misses, the instruction takes an extra tick
+            assert!(emulator.tick().is_empty());
+            assert_eq!(emulator.cpus[0].cpu.ax, 0);
+            for _ in 1..ticks {
+                let _ = emulator.tick();
+            }
+            emulator
+        };
+        // The loop fits on the cache, every fetch after the first pass hits
+        let mut looping = run(&format!("{}JMP -6\nINT 20H", "MOV AX, 5\n".repeat(6)), 100);
+        let (hits, misses) = looping.cache.stats(1);
+        assert!(hits > 30 && misses <= 4, "{} {}", hits, misses);
+        // Each line has 2 instructions, only the second one hits. The 12 MOV take 18 ticks
+        let straight = run(&format!("{}INT 20H", "MOV AX, 5\n".repeat(12)), 18);
+        let rate = hit_rate(straight.cache.stats(1)).unwrap();
+        assert!((45.0..=55.0).contains(&rate), "{}", rate);
+        assert!(hit_rate(looping.cache.totals()).unwrap() > 90.0);
+
+        looping.reset();
+        assert_eq!(looping.cache.totals(), (0, 0));
+    }
+
+    #[test]
+    fn exponential_estimate() {
+        // The example of the textbook, τ0 = 10 and α = 1/2
+        let mut timing = Timing {
+            estimate: 10.0,
+            ..Default::default()
+        };
+        let mut estimates = vec![timing.estimate];
+        for burst in [6, 4, 6, 4, 13, 13, 13] {
+            for _ in 0..burst {
+                timing.run_tick();
+            }
+            timing.end_burst(0.5);
+            estimates.push(timing.estimate);
+        }
+        assert_eq!(estimates, [10.0, 8.0, 6.0, 6.0, 5.0, 9.0, 11.0, 12.0]);
+        assert_eq!(timing.ticks, 59);
+        // Leaving the CPU without running doesn't count as a burst
+        timing.end_burst(0.5);
+        assert_eq!(timing.estimate, 12.0);
+
+        // The remaining burst is the estimate minus what the current burst already ran
+        timing.run_tick();
+        timing.run_tick();
+        assert_eq!(timing.expected_burst(BurstEstimation::Exponential), 12);
+        assert_eq!(timing.expected_remaining(BurstEstimation::Exponential), 10);
+        assert_eq!(exponential_average(12.0, 2, 0.25), 9.5);
+        // Without #BURST the declared estimation knows nothing
+        assert_eq!(timing.expected_burst(BurstEstimation::Declared), usize::MAX);
+    }
+
+    #[test]
+    fn diagram_lookup() {
+        let mut diagram = Diagram::default();
+        for (p_id, burst) in [(1, 5), (2, 3), (3, 5), (4, 1)] {
+            diagram.push(Timing {
+                p_id,
+                burst,
+                ..Default::default()
+            });
+        }
+        diagram.get_mut(3).unwrap().remaining_burst = 2;
+
+        assert_eq!(diagram.get(3).unwrap().remaining_burst, 2);
+        assert!(diagram.get(5).is_none());
+        // Sorting gives the order of the pids without moving the timings
+        assert_eq!(diagram.order_by_key(|x| x.burst), vec![4, 2, 1, 3]);
+        let stored = diagram.iter().map(|x| x.p_id).collect::<Vec<_>>();
+        assert_eq!(stored, vec![1, 2, 3, 4]);
+    }
+
+    // Run with `cargo test -- --ignored lookup_benchmark --nocapture`
+    #[test]
+    #[ignore]
+    fn lookup_benchmark() {
+        let mut emulator = emulator(1);
+        emulator.memory = Memory::new(100_000, 20_000);
+        emulator.storage = Storage::new(100_000);
+        for i in 0..500 {
+            load(&mut emulator, &format!("{}.asm", i), "MOV AX, 5\nINT 20H");
+        }
+
+        let start = Instant::now();
+        for _ in 0..1000 {
+            for p_id in 1..=500 {
+                assert!(emulator.memory.pcb(p_id).is_some());
+                assert!(emulator.diagram.get(p_id).is_some());
+            }
+        }
+        println!("500k PCB and timing lookups in {:?}", start.elapsed());
+    }
+
+    #[test]
+    fn visible_rows() {
+        // Before the first scroll event a default height is used
+        assert_eq!(ScrollView::default().rows(4), 0..4);
+
+        let view = ScrollView {
+            offset: 100.0 * ROW_HEIGHT,
+            height: 10.0 * ROW_HEIGHT,
+        };
+        assert_eq!(view.rows(2048), 96..114);
+        // The last rows are cut at the end of the memory
+        assert_eq!(view.rows(105), 96..105);
+        assert_eq!(view.rows(50), 50..50);
+    }
+
+    #[test]
+    fn turbo_frame() {
+        let mut emulator = emulator(1);
+        emulator.config.turbo_ticks = 3;
+        let pcb = load(
+            &mut emulator,
+            "a.asm",
+            "MOV AX, 1\nMOV BX, 2\nMOV CX, 3\nINT 09H\nMOV DX, 4\nINT 20H",
+        );
+        let _ = emulator.update(Message::Distpacher((0, pcb)));
+        let _ = emulator.update(Message::ToggleTurbo(true));
+
+        // Runs up to turbo_ticks on a single tick
+        let _ = emulator.update(Message::Tick);
+        assert_eq!(emulator.counter, 3);
+        assert_eq!(emulator.turbo_executed, 3);
+
+        // Stops once the process waits for input
+        let _ = emulator.update(Message::Tick);
+        assert_eq!(emulator.turbo_executed, 1);
+        assert_eq!(emulator.waiting_queue.len(), 1);
+    }
+
+    #[test]
+    fn input_per_process() {
+        let mut emulator = emulator(2);
+        let a = load(&mut emulator, "a.asm", "INT 09H\nINT 20H");
+        let b = load(&mut emulator, "b.asm", "INT 09H\nINT 20H");
+        let _ = emulator.update(Message::Distpacher((0, a)));
+        let _ = emulator.update(Message::Distpacher((1, b)));
+        // Both CPUs reach the INT 09H
+        let _ = emulator.update(Message::Blocked(0));
+        let _ = emulator.update(Message::Blocked(1));
+
+        // Answered in the reverse order they asked
+        let _ = emulator.update(Message::Input(b.0, "7x".to_string()));
+        assert_eq!(emulator.input_values.get(&b.0), Some(&"7".to_string()));
+        let _ = emulator.update(Message::Unblock(b.0, 7));
+        assert_eq!(emulator.waiting_queue.len(), 1);
+        assert_eq!(emulator.waiting_queue[0].0, a.0);
+        let _ = emulator.update(Message::Unblock(a.0, 3));
+        assert!(emulator.waiting_queue.is_empty());
+        assert!(emulator.input_values.is_empty());
+        assert_eq!(emulator.memory.pcb(a.0).unwrap().dx, 3);
+        assert_eq!(emulator.memory.pcb(b.0).unwrap().dx, 7);
+    }
+
+    #[test]
+    fn blocked_releases_cpu() {
+        let mut emulator = emulator(1);
+        let a = load(&mut emulator, "a.asm", "#ARRIVAL 0\nINT 09H\nINT 20H");
+        let b = load(&mut emulator, "b.asm", "#ARRIVAL 0\nMOV AX, 1\nINT 20H");
+        let _ = emulator.update(Message::Distpacher((0, a)));
+        let pc = emulator.cpus[0].cpu.pc;
+        let (event, _) = emulator.step();
+        assert_eq!(event, vec![TickEvent::Blocked(0)]);
+
+        // The blocked process stays at the INT 09H and the other one gets the CPU
+        let pcb = emulator.memory.pcb(a.0).unwrap();
+        assert_eq!((pcb.process_state, pcb.pc), (ProcessState::Blocked, pc));
+        assert_eq!(emulator.diagram.get(a.0).unwrap().c_id, None);
+        assert_eq!(emulator.cpus[0].assigned, Some(b.0));
+        let _ = emulator.update(Message::Tick);
+        assert_eq!(emulator.memory.pcb(a.0).unwrap().pc, pc);
+        assert_eq!(emulator.cpus[0].cpu.ax, 1);
+    }
+
+    #[test]
+    fn every_cpu_executes() {
+        let mut emulator = emulator(3);
+        let a = load(&mut emulator, "a.asm", "INT 20H");
+        let b = load(&mut emulator, "b.asm", "MOV BX, 3\nASSERT BX, 2\nINT 20H");
+        let c = load(&mut emulator, "c.asm", "MOV AX, 1\nMOV AX, 2\nINT 20H");
+        for (cpu_index, pcb) in [a, b, c].into_iter().enumerate() {
+            let _ = emulator.update(Message::Distpacher((cpu_index, pcb)));
+        }
+        // The event of a CPU doesn't stop the ones after it, the clock moves once per tick
+        assert_eq!(emulator.step().0, vec![TickEvent::Terminated(0)]);
+        assert_eq!((emulator.cpus[1].cpu.bx, emulator.cpus[2].cpu.ax), (3, 1));
+        assert_eq!(emulator.counter, 1);
+        let events = emulator.step().0;
+        assert_eq!(events, vec![TickEvent::Fault(1, Fault::AssertionFailed)]);
+        assert_eq!(emulator.cpus[2].cpu.ax, 2);
+        assert_eq!(emulator.counter, 2);
+    }
+
+    #[test]
+    fn step_single_cpu() {
+        let mut emulator = emulator(2);
+        let program = "MOV AX, 1\nMOV BX, 2\nMOV CX, 3\nMOV DX, 4\nINT 20H";
+        let a = load(&mut emulator, "a.asm", program);
+        let b = load(&mut emulator, "b.asm", program);
+        let _ = emulator.update(Message::Distpacher((0, a)));
+        let _ = emulator.update(Message::Distpacher((1, b)));
+        let pc = emulator.cpus[0].cpu.pc;
+
+        for _ in 0..3 {
+            let _ = emulator.update(Message::TickCpu(1));
+        }
+        // Only CPU 1 moved, the clock stays and the steps count on its usage
+        assert_eq!(emulator.cpus[0].cpu.pc, pc);
+        assert_eq!(emulator.cpus[0].cpu.ax, 0);
+        assert_eq!(emulator.cpus[1].cpu.cx, 3);
+        assert_eq!(emulator.cpus[1].cpu.dx, 0);
+        assert_eq!(emulator.counter, 0);
+        assert_eq!(emulator.usage[1].busy_ticks, 3);
+        assert_eq!(emulator.usage[0].busy_ticks, 0);
+
+        // The global step still moves every CPU
+        let _ = emulator.update(Message::Tick);
+        assert_eq!(emulator.cpus[0].cpu.ax, 1);
+        assert_eq!(emulator.cpus[1].cpu.dx, 4);
+        assert_eq!(emulator.counter, 1);
+    }
+
+    #[test]
+    fn io_boost() {
+        // Process that gets the CPU when the first quantum after the input ends
+        let run = |io_boost: bool| {
+            let mut emulator = emulator(1);
+            emulator.config.scheduler = Some(Scheduler::RR);
+            emulator.config.io_boost = io_boost;
+            emulator.quantum = Some(2);
+            let long = format!("#ARRIVAL 0\n{}INT 20H", "MOV AX, 1\n".repeat(4));
+            load(&mut emulator, "a.asm", &long);
+            load(&mut emulator, "b.asm", &long);
+            let c = load(&mut emulator, "c.asm", "#ARRIVAL 0\nINT 09H\nINT 20H");
+            assert_eq!(c.0, 3);
+            let _ = emulator.update(Message::Distpacher((0, c)));
+            let _ = emulator.step();
+            assert_eq!(emulator.cpus[0].assigned, Some(1));
+
+            let _ = emulator.update(Message::Unblock(c.0, 4));
+            assert_eq!(emulator.boosted.contains(&c.0), io_boost);
+            while !emulator
+                .step()
+                .0
+                .iter()
+                .any(|x| matches!(x, TickEvent::QuantumExpired))
+            {}
+            // The boost is gone once the process is dispatched
+            assert!(emulator.boosted.is_empty());
+            emulator.cpus[0].assigned
+        };
+        assert_eq!(run(true), Some(3));
+        assert_eq!(run(false), Some(2));
+    }
+
+    #[test]
+    fn input_range_directive() {
+        let mut emulator = emulator(1);
+        let a = load(
+            &mut emulator,
+            "a.asm",
+            "#INPUT_RANGE 1 10\nINT 09H\nINT 20H",
+        );
+        let _ = emulator.update(Message::Distpacher((0, a)));
+        let _ = emulator.update(Message::Blocked(0));
+        assert_eq!(
+            emulator.diagram.get(a.0).unwrap().input_range,
+            Some((1, 10))
+        );
+
+        // Just outside of the range the process keeps waiting
+        for num in [0, 11] {
+            let _ = emulator.update(Message::Unblock(a.0, num));
+            assert_eq!(emulator.waiting_queue.len(), 1);
+        }
+        assert!(emulator
+            .log
+            .to_text(Level::Warning)
+            .contains("P1 only accepts numbers from 1 to 10, 11 was rejected."));
+        let _ = emulator.update(Message::Unblock(a.0, 10));
+        assert!(emulator.waiting_queue.is_empty());
+        assert_eq!(emulator.memory.pcb(a.0).unwrap().dx, 10);
+
+        let b = load(
+            &mut emulator,
+            "b.asm",
+            "#INPUT_RANGE 1 10\nINT 09H\nINT 20H",
+        );
+        let _ = emulator.update(Message::Distpacher((0, b)));
+        let _ = emulator.update(Message::Blocked(0));
+        let _ = emulator.update(Message::Unblock(b.0, 1));
+        assert_eq!(emulator.memory.pcb(b.0).unwrap().dx, 1);
+    }
+
+    #[test]
+    fn input_validation() {
+        let mut emulator = emulator(1);
+        let a = load(&mut emulator, "a.asm", "INT 09H\nINT 20H");
+        let _ = emulator.update(Message::Distpacher((0, a)));
+        let _ = emulator.update(Message::Blocked(0));
+
+        // Out of range values are kept so the row can show why they can't be submitted
+        let _ = emulator.update(Message::Input(a.0, "999".to_string()));
+        assert!(emulator.input_values[&a.0].parse::<u8>().is_err());
+        let _ = emulator.update(Message::Input(a.0, "1000".to_string()));
+        assert_eq!(emulator.input_values[&a.0], "999");
+
+        // The process is killed before the value is submitted
+        let mut pcb = emulator.memory.pcb(a.0).unwrap();
+        pcb.process_state = ProcessState::Terminated;
+        emulator.memory.write_pcb(pcb);
+        let _ = emulator.update(Message::Unblock(a.0, 5));
+        assert!(emulator.waiting_queue.is_empty());
+        assert!(emulator.input_values.is_empty());
+        assert_eq!(emulator.log.filter(Level::Warning).count(), 1);
+        assert_eq!(emulator.memory.pcb(a.0).unwrap().dx, 0);
+    }
+
+    #[test]
+    fn scripted_input() {
+        let mut emulator = emulator(1);
+        let program = "LOAD DX\nINT 09H\nADD DX\nSTORE AX\nINT 20H";
+        let data = with_input_script("5\n7, 9\n", format!("INT 09H\n{}", program).into_bytes());
+        assert!(data.starts_with(b"#INPUT 5,7,9\n"));
+        let data = String::from_utf8(data).unwrap();
+        let pcb = load(&mut emulator, "a.asm", &data);
+        let _ = emulator.update(Message::Distpacher((0, pcb)));
+        let _ = emulator.update(Message::RunToEvent);
+
+        // Ran to the end without waiting for the user
+        assert!(emulator.waiting_queue.is_empty());
+        assert!(emulator.cpus[0].assigned.is_none());
+        let pcb = emulator.memory.pcb(pcb.0).unwrap();
+        assert_eq!((pcb.ax, pcb.dx), (12, 7));
+
+        // Once the values run out the process blocks
+        let pcb = load(
+            &mut emulator,
+            "b.asm",
+            "#INPUT 1\nINT 09H\nINT 09H\nINT 20H",
+        );
+        let _ = emulator.update(Message::Distpacher((0, pcb)));
+        let _ = emulator.update(Message::RunToEvent);
+        assert_eq!(emulator.waiting_queue.len(), 1);
+        assert_eq!(emulator.memory.pcb(pcb.0).unwrap().dx, 1);
+    }
+
+    #[test]
+    fn admission_without_memory() {
+        let mut emulator = emulator(1);
+        // Only one PCB fits on the OS segment
+        emulator.memory = Memory::new(256, PCB::RECORD_SIZE + 1);
+        let pcb = load(&mut emulator, "a.asm", "MOV AX, 5\nINT 20H");
+        load(&mut emulator, "b.asm", "#ARRIVAL 0\nMOV BX, 5\nINT 20H");
+
+        // The code and stack of b are freed again
+        assert_eq!(emulator.memory.pcb_table.len(), 1);
+        assert_eq!(emulator.memory.used.len(), 2);
+        assert_eq!(
+            pending(&emulator),
+            vec![("b.asm".to_string(), Admission::Waiting)]
+        );
+        assert_eq!(emulator.loaded_files.len(), 1);
+
+        // Reported only once
+        assert!(emulator.admit().is_none());
+
+        // b is loaded on the record of a once a terminates
+        let _ = emulator.update(Message::Distpacher((0, pcb)));
+        let _ = emulator.update(Message::RunToEvent);
+        let _ = emulator.schedule();
+        assert!(emulator.pending_files.is_empty());
+        assert_eq!(
+            loaded(&emulator),
+            vec![("a.asm".to_string(), None), ("b.asm".to_string(), Some(2))]
+        );
+        assert_eq!(emulator.memory.pcb_table[0].0, 2);
+        assert_eq!(emulator.cpus[0].assigned, Some(2));
+    }
+
+    #[test]
+    fn pending_admission() {
+        let mut emulator = emulator(1);
+        // The code and stack of only one program fit at a time
+        emulator.memory = Memory::new(148, 120);
+        emulator.config.stack_size = 5;
+        let pcb = load(&mut emulator, "a.asm", "MOV AX, 5\nINT 20H");
+        load(&mut emulator, "b.asm", "#ARRIVAL 0\nMOV BX, 5\nINT 20H");
+        assert_eq!(
+            pending(&emulator),
+            vec![("b.asm".to_string(), Admission::Waiting)]
+        );
+        // Waiting files are not reported with a dialog
+        assert!(emulator.admit().is_none());
+
+        // Programs bigger than the user memory are never loaded
+        load(&mut emulator, "c.asm", &"INC\n".repeat(5));
+        assert_eq!(
+            pending(&emulator)[1],
+            ("c.asm".to_string(), Admission::Failed)
+        );
+        assert!(emulator.admit().is_none());
+
+        // b is loaded once a terminates
+        let _ = emulator.update(Message::Distpacher((0, pcb)));
+        let _ = emulator.update(Message::RunToEvent);
+        let _ = emulator.schedule();
+        assert_eq!(
+            pending(&emulator),
+            vec![("c.asm".to_string(), Admission::Failed)]
+        );
+        assert_eq!(emulator.cpus[0].assigned, Some(2));
+        assert!(emulator
+            .log
+            .to_text(Level::Info)
+            .contains("b.asm was loaded after waiting for memory"));
+    }
+
+    #[test]
+    fn stack_growth() {
+        let program = "#STACK 2\nMOV AX, 7\nPUSH AX\nPUSH AX\nPUSH AX\nINT 20H";
+        let mut grown = emulator(1);
+        let pcb = load(&mut grown, "a.asm", program);
+        assert_eq!(grown.memory.pcb(pcb.0).unwrap().stack_segment_size, 2);
+        let _ = grown.update(Message::Distpacher((0, pcb)));
+        for _ in 0..4 {
+            let _ = grown.update(Message::Tick);
+        }
+
+        // The stack was moved to a bigger block with its content
+        let pcb = grown.memory.pcb(pcb.0).unwrap();
+        assert_eq!(pcb.stack_segment_size, 4);
+        assert_eq!(
+            grown.memory.data[pcb.stack_segment..pcb.stack_segment + 4],
+            [7, 7, 7, 0]
+        );
+        assert_eq!(grown.cpus[0].cpu.sp, pcb.stack_segment + 3);
+
+        // Without free memory the process is terminated
+        let mut full = emulator(1);
+        full.memory = Memory::new(155, 120);
+        let pcb = load(&mut full, "a.asm", program);
+        let _ = full.update(Message::Distpacher((0, pcb)));
+        let _ = full.update(Message::RunToEvent);
+        assert!(full.cpus[0].assigned.is_none());
+        assert_eq!(full.memory.pcb(pcb.0).unwrap().stack_segment_size, 2);
+        assert!(full
+            .log
+            .to_text(Level::Warning)
+            .contains("Process 1 terminated by a fault: stack overflow"));
+    }
+
+    #[test]
+    fn history_after_run() {
+        let mut emulator = emulator(1);
+        let pcb = load(&mut emulator, "a.asm", "MOV AX, 5\nLOAD AX\nINC\nINT 20H");
+        let _ = emulator.update(Message::Distpacher((0, pcb)));
+
+        for _ in 0..3 {
+            let _ = emulator.update(Message::Tick);
+        }
+
+        let history = emulator.history[0]
+            .newest_first()
+            .map(|x| (x.tick, x.pid, x.instruction.to_string(), x.ac))
+            .collect::<Vec<_>>();
+        assert_eq!(
+            history,
+            vec![
+                (2, 1, "INC".to_string(), 6),
+                (1, 1, "LOAD AX".to_string(), 5),
+                (0, 1, "MOV AX, 5".to_string(), 0),
+            ]
+        );
+
+        // The CPU becomes idle once the process is done
+        let _ = emulator.update(Message::Tick);
+        let _ = emulator.update(Message::Terminated(0));
+        assert!(emulator.history[0].is_empty());
+    }
+
+    #[test]
+    fn last_registers_after_tick() {
+        let mut emulator = emulator(1);
+        let pcb = load(&mut emulator, "a.asm", "MOV BX, 7\nINT 20H");
+        let _ = emulator.update(Message::Distpacher((0, pcb)));
+        assert_eq!(emulator.last_registers[0].pc, emulator.cpus[0].cpu.pc);
+
+        let _ = emulator.update(Message::Tick);
+
+        let cpu = emulator.cpus[0].cpu;
+        let last = emulator.last_registers[0];
+        assert_eq!((last.bx, cpu.bx), (0, 7));
+        assert_eq!(last.ax, cpu.ax);
+        assert_ne!(last.pc, cpu.pc);
+    }
+
+    #[test]
+    fn usage_with_single_process() {
+        let mut emulator = emulator(2);
+        let pcb = load(
+            &mut emulator,
+            "a.asm",
+            "MOV AX, 1\nMOV BX, 2\nMOV CX, 3\nINT 20H",
+        );
+        let _ = emulator.update(Message::Distpacher((0, pcb)));
+
+        for _ in 0..3 {
+            let _ = emulator.update(Message::Tick);
+        }
+
+        assert_eq!(emulator.usage[0].utilization(), 100.0);
+        assert_eq!(emulator.usage[1].utilization(), 0.0);
+        assert_eq!(emulator.usage[0].context_switches, 1);
+        assert_eq!(emulator.usage[1].idle_ticks, 3);
+    }
+
+    #[test]
+    fn run_to_event() {
+        let mut emulator = emulator(1);
+        let pcb = load(
+            &mut emulator,
+            "a.asm",
+            "MOV AX, 1\nMOV BX, 2\nINT 09H\nMOV CX, 3\nINT 20H",
+        );
+        let _ = emulator.update(Message::Distpacher((0, pcb)));
+        let _ = emulator.update(Message::RunToEvent);
+
+        // Stops on the input interrupt, which uses its tick
+        assert_eq!(emulator.counter, 3);
+        assert_eq!(emulator.waiting_queue.len(), 1);
+        // The registers were saved on the PCB and the CPU is free
+        let pcb = emulator.memory.pcb(pcb.0).unwrap();
+        assert_eq!((pcb.bx, pcb.cx), (2, 0));
+        assert!(emulator.cpus[0].assigned.is_none());
+    }
+
+    #[test]
+    fn run_ticks() {
+        let mut emulator = emulator(1);
+        let pcb = load(
+            &mut emulator,
+            "a.asm",
+            "MOV AX, 1\nMOV BX, 2\nMOV CX, 3\nMOV DX, 4\nINT 20H",
+        );
+        let _ = emulator.update(Message::Distpacher((0, pcb)));
+        emulator.run_ticks = "3".to_string();
+        let _ = emulator.update(Message::RunTicks);
+
+        assert_eq!(emulator.history[0].len(), 3);
+        assert_eq!(emulator.cpus[0].cpu.cx, 3);
+        assert_eq!(emulator.cpus[0].cpu.dx, 0);
+
+        // Runs until the process is done even if more ticks are requested
+        emulator.run_ticks = "100".to_string();
+        let _ = emulator.update(Message::RunTicks);
+        assert!(emulator.cpus[0].assigned.is_none());
+        assert_eq!(emulator.counter, 5);
+    }
+
+    #[test]
+    fn breakpoint_pauses_execution() {
+        let mut emulator = emulator(1);
+        let pcb = load(
+            &mut emulator,
+            "a.asm",
+            "MOV AX, 1\nMOV BX, 2\nMOV CX, 3\nMOV DX, 4\nINT 20H",
+        );
+        let _ = emulator.update(Message::Distpacher((0, pcb)));
+        let _ = emulator.update(Message::ToggleBreakpoint(pcb.0, 2));
+        emulator.mode = Some(Mode::Automatic);
+
+        // Pauses before executing MOV CX, 3
+        emulator.run_ticks = "100".to_string();
+        let _ = emulator.update(Message::RunTicks);
+        assert!(emulator.mode == Some(Mode::Manual));
+        assert_eq!(emulator.flash_pcb, Some(pcb.0));
+        assert_eq!(emulator.history[0].len(), 2);
+        assert_eq!((emulator.cpus[0].cpu.bx, emulator.cpus[0].cpu.cx), (2, 0));
+        // The CPU didn't execute on the tick it stopped
+        let usage = &emulator.usage[0];
+        assert_eq!((usage.busy_ticks, usage.idle_ticks), (2, 1));
+
+        // Resumes from the breakpoint
+        let _ = emulator.update(Message::Tick);
+        assert_eq!(emulator.cpus[0].cpu.cx, 3);
+        assert_eq!(emulator.flash_pcb, None);
+
+        // Breakpoints are removed with the process
+        let _ = emulator.update(Message::RunToEvent);
+        assert!(emulator.cpus[0].assigned.is_none());
+        assert!(emulator.breakpoints.is_empty());
+    }
+
+    #[test]
+    fn speed_interval() {
+        let intervals = Speed::ALL
+            .iter()
+            .map(|x| x.interval(1000).as_millis())
+            .collect::<Vec<_>>();
+        assert_eq!(intervals, vec![4000, 1000, 250, 100, 1]);
+        // Never ticks without waiting
+        assert_eq!(Speed::Faster.interval(5).as_millis(), 1);
+    }
+
+    fn editor(emulator: &mut Emulator, name: &str, program: &str) {
+        let _ = emulator.update(Message::NewProgram);
+        let _ = emulator.update(Message::EditorName(name.to_string()));
+        emulator.editor.as_mut().unwrap().content = text_editor::Content::with_text(program);
+    }
+
+    #[test]
+    fn assemble_program() {
+        let mut emulator = emulator(1);
+
+        // Invalid programs are not stored
+        editor(&mut emulator, "a.asm", "MOV AX, 5\nFOO\nINT 20H");
+        let _ = emulator.update(Message::AssembleProgram);
+        assert_eq!(emulator.editor.as_ref().unwrap().errors.len(), 1);
+        assert!(emulator.storage.used.is_empty());
+
+        editor(&mut emulator, "a.asm", "#ARRIVAL 0\nMOV AX, 5\nINT 20H");
+        let _ = emulator.update(Message::AssembleProgram);
+        assert!(emulator.editor.is_none());
+        let _ = emulator.schedule();
+        assert_eq!(loaded(&emulator), vec![("\"a.asm\"".to_string(), Some(1))]);
+        assert_eq!(emulator.cpus[0].assigned, Some(1));
+
+        // Run it and edit it again after it's done
+        let _ = emulator.update(Message::RunToEvent);
+        assert!(emulator.cpus[0].assigned.is_none());
+        let address = emulator.storage.used[0].1;
+        let _ = emulator.update(Message::EditFile(address));
+        let content = emulator.editor.as_ref().unwrap().content.text();
+        assert_eq!(content.trim(), "#ARRIVAL 0\nMOV AX, 5\nINT 20H");
+        emulator.editor.as_mut().unwrap().content =
+            text_editor::Content::with_text("#ARRIVAL 0\nMOV AX, 7\nMOV BX, 1\nINT 20H");
+        let _ = emulator.update(Message::AssembleProgram);
+        let _ = emulator.schedule();
+
+        assert_eq!(emulator.storage.used.len(), 1);
+        assert_eq!(loaded(&emulator), vec![("\"a.asm\"".to_string(), Some(2))]);
+        let _ = emulator.update(Message::Tick);
+        assert_eq!(emulator.cpus[0].cpu.ax, 7);
+    }
+
+    #[test]
+    fn stale_source() {
+        let mut emulator = emulator(1);
+        editor(&mut emulator, "a.asm", "#ARRIVAL 0\nMOV AX, 5\nINT 20H");
+        let _ = emulator.update(Message::AssembleProgram);
+        let _ = emulator.schedule();
+        let _ = emulator.update(Message::RunToEvent);
+        let address = emulator.storage.used[0].1;
+        assert_eq!(emulator.stale_source(address), None);
+
+        // Another file with the same name is not the copy P1 ran
+        let other = b"#ARRIVAL 9\nMOV AX, 1\nINT 20H".to_vec();
+        emulator.storage.store_files("\"a.asm\"", other).unwrap();
+        let other = emulator.storage.changed[0];
+        emulator.storage.used.reverse();
+        assert_eq!(emulator.stale_source(address), None);
+        assert_eq!(emulator.stale_pcb(1), None);
+
+        // The stored copy no longer is what P1 ran, even after it moved to fit a longer program
+        let _ = emulator.update(Message::EditFile(address));
+        emulator.editor.as_mut().unwrap().content =
+            text_editor::Content::with_text("#ARRIVAL 0\nMOV AX, 7\nMOV BX, 7\nINT 20H");
+        let _ = emulator.update(Message::AssembleProgram);
+        assert_ne!(emulator.storage.changed, vec![address]);
+        let address = emulator.storage.changed[0];
+        let (run, stored) = emulator.stale_source(address).unwrap();
+        assert_eq!(run, emulator.diagram.get(1).unwrap().source_hash);
+        assert_eq!(
+            stored,
+            content_hash(b"#ARRIVAL 0\nMOV AX, 7\nMOV BX, 7\nINT 20H")
+        );
+        assert_ne!(run, stored);
+        assert!(emulator.stale_pcb(1).is_some());
+
+        // Running it again clears the badge
+        emulator.storage.free_file(other);
+        let _ = emulator.schedule();
+        assert_eq!(emulator.cpus[0].assigned, Some(2));
+        assert_eq!(emulator.stale_source(address), None);
+        assert_eq!(emulator.stale_pcb(1), None);
+        assert_eq!(emulator.stale_pcb(2), None);
+    }
+
+    #[test]
+    fn log_entries() {
+        let mut emulator = emulator(1);
+        emulator.config.lang = Lang::Es;
+        let pcb = load(&mut emulator, "a.asm", "INT 20H");
+        let _ = emulator.update(Message::Distpacher((0, pcb)));
+        emulator.mode = Some(Mode::Manual);
+        let _ = emulator.update(Message::RunToEvent);
+        let _ = emulator.update(Message::SchedulerSelected(Scheduler::RR));
+        let _ = emulator.update(Message::ConfirmSwitch);
+
+        let messages = emulator
+            .log
+            .filter(Level::Info)
+            .map(|x| x.message.clone())
+            .collect::<Vec<_>>();
+        assert_eq!(messages[0], "Asignando proceso con ID: 1 en CPU 0");
+        assert!(messages.contains(&"Proceso con ID: 1 ha finalizado en CPU 0".to_string()));
+        assert!(messages.contains(&"Planificador cambiado a RR en el tick 1".to_string()));
+
+        let _ = emulator.update(Message::ResetPressed);
+        let _ = emulator.update(Message::FullReset);
+        assert!(emulator.log.is_empty());
+    }
+
+    #[test]
+    fn process_queues() {
+        let mut emulator = emulator(1);
+        emulator.config.scheduler = Some(Scheduler::SJF);
+        load(
+            &mut emulator,
+            "a.asm",
+            "#ARRIVAL 0\nMOV AX, 1\nMOV BX, 2\nINT 20H",
+        );
+        let pcb = load(&mut emulator, "b.asm", "#ARRIVAL 0\nINT 09H\nINT 20H");
+
+        // The shortest job goes first
+        assert_eq!(emulator.ready_queue(), vec![2, 1]);
+
+        let _ = emulator.update(Message::Distpacher((0, pcb)));
+        assert_eq!(emulator.ready_queue(), vec![1]);
+        assert_eq!(emulator.running_queue(), vec![(0, 2, None)]);
+
+        emulator.mode = Some(Mode::Manual);
+        let _ = emulator.update(Message::RunToEvent);
+        assert_eq!(emulator.blocked_queue(), vec![(2, "awaiting_input")]);
+    }
+
+    #[test]
+    fn sjf_order() {
+        let mut emulator = emulator(1);
+        emulator.config.scheduler = Some(Scheduler::SJF);
+        load(
+            &mut emulator,
+            "a.asm",
+            "#ARRIVAL 0\nMOV AX, 1\nMOV BX, 2\nMOV CX, 3\nINT 20H",
+        );
+        // b and c arrive while a runs and tie on burst, c arrived first
+        load(&mut emulator, "b.asm", "#ARRIVAL 2\nMOV AX, 1\nINT 20H");
+        load(&mut emulator, "c.asm", "#ARRIVAL 1\nMOV AX, 1\nINT 20H");
+        let order = emulator.diagram.iter().map(|x| x.p_id).collect::<Vec<_>>();
+        let _ = emulator.schedule();
+        assert_eq!(emulator.cpus[0].assigned, Some(1));
+
+        emulator.mode = Some(Mode::Manual);
+        emulator.run_ticks = "100".to_string();
+        let _ = emulator.update(Message::RunTicks);
+        let completed = emulator
+            .stats_data
+            .iter()
+            .map(|x| x.process_id)
+            .collect::<Vec<_>>();
+        assert_eq!(completed, vec![1, 3, 2]);
+        // Scheduling didn't reorder the diagram
+        let after = emulator.diagram.iter().map(|x| x.p_id).collect::<Vec<_>>();
+        assert_eq!(after, order);
+    }
+
+    #[test]
+    fn fcfs_arrival_order() {
+        let mut emulator = emulator(1);
+        emulator.config.scheduler = Some(Scheduler::FCFS);
+        load(&mut emulator, "a.asm", "#ARRIVAL 3\nMOV AX, 1\nINT 20H");
+        // b is still running when a and c arrive
+        load(
+            &mut emulator,
+            "b.asm",
+            "#ARRIVAL 0\nMOV AX, 1\nMOV AX, 2\nMOV AX, 3\nMOV AX, 4\nINT 20H",
+        );
+        load(&mut emulator, "c.asm", "#ARRIVAL 1\nMOV AX, 1\nINT 20H");
+        let _ = emulator.schedule();
+
+        emulator.mode = Some(Mode::Manual);
+        emulator.run_ticks = "100".to_string();
+        let _ = emulator.update(Message::RunTicks);
+        let completed = emulator
+            .stats_data
+            .iter()
+            .map(|x| x.process_id)
+            .collect::<Vec<_>>();
+        assert_eq!(completed, vec![2, 3, 1]);
+    }
+
+    #[test]
+    fn tick_limit() {
+        // Jumps back to the first instruction forever
+        let program =
+            "MOV AX, 1\nMOV AX, 2\nMOV AX, 3\nMOV AX, 4\nMOV AX, 5\nMOV AX, 6\nJMP -6\nINT 20H";
+        let (mut batch, mut emulator) = (emulator(2), emulator(1));
+        batch.config.max_ticks = 50;
+        batch.config.runaway_factor = 0;
+        batch.arrival_policy = ArrivalPolicy::FixedZero;
+        let files = vec![
+            ("loop.asm".to_string(), program.as_bytes().to_vec()),
+            ("ok.asm".to_string(), b"INT 20H".to_vec()),
+        ];
+        batch.store_batch(files, vec![]);
+        batch.run_batch();
+        assert_eq!(batch.counter, 50);
+        assert!(batch.batch_failed());
+        let results = batch.batch_results();
+        assert!(results[1].starts_with("ok.asm"));
+        assert_eq!(results.last().unwrap(), "loop.asm (P1): timed out");
+
+        // The automatic mode pauses on the limit and goes on for as many ticks when it continues
+        emulator.config.max_ticks = 20;
+        emulator.config.runaway_factor = 0;
+        let pcb = load(&mut emulator, "a.asm", program);
+        let _ = emulator.update(Message::Distpacher((0, pcb)));
+        emulator.mode = Some(Mode::Automatic);
+        while emulator.mode == Some(Mode::Automatic) {
+            let _ = emulator.update(Message::Tick);
+        }
+        assert_eq!(emulator.counter, 20);
+        let notification = emulator.notifications.last().unwrap();
+        assert_eq!(notification.title, "Tick limit reached");
+        let process = notification.body.lines().nth(1).unwrap();
+        assert!(process.starts_with("P1 a.asm: Running, PC "), "{}", process);
+
+        let answer = |x: &str| Message::TickLimit(rfd::MessageDialogResult::Custom(x.to_string()));
+        let _ = emulator.update(answer("Continue"));
+        while emulator.mode == Some(Mode::Automatic) {
+            let _ = emulator.update(Message::Tick);
+        }
+        assert_eq!(emulator.counter, 40);
+        let _ = emulator.update(answer("Kill the unfinished processes"));
+        assert!(emulator.cpus[0].assigned.is_none());
+        assert!(emulator.unfinished().is_empty());
+
+        // The processes off the CPU are killed too
+        let running = load(&mut emulator, "a.asm", program);
+        let ready = load(&mut emulator, "b.asm", program);
+        let _ = emulator.update(Message::Distpacher((0, running)));
+        emulator.mode = Some(Mode::Automatic);
+        while emulator.mode == Some(Mode::Automatic) {
+            let _ = emulator.update(Message::Tick);
+        }
+        assert_eq!(emulator.unfinished().len(), 2);
+        let _ = emulator.update(answer("Kill the unfinished processes"));
+        assert!(emulator.unfinished().is_empty());
+        assert!(emulator.post_mortems.contains_key(&ready.0));
+        let layout = emulator.memory.layout();
+        assert!(layout
+            .iter()
+            .all(|x| x.kind == SegmentKind::Pcb || x.owner != Some(ready.0)));
+    }
+
+    #[test]
+    fn tick_handles_events() {
+        let mut emulator = emulator(1);
+        emulator.config.scheduler = Some(Scheduler::RR);
+        emulator.arrival_policy = ArrivalPolicy::FixedZero;
+        let first = load(&mut emulator, "a.asm", "INT 20H");
+        let second = load(&mut emulator, "b.asm", "MOV AX, 1\nINT 20H");
+        let _ = emulator.update(Message::Distpacher((0, first)));
+        emulator.mode = Some(Mode::Automatic);
+        // The process is terminated and the CPU given to the next one on the same tick
+        let _ = emulator.update(Message::Tick);
+        let pcb = emulator.memory.pcb(first.0).unwrap();
+        assert_eq!(pcb.process_state, ProcessState::Terminated);
+        assert_eq!(emulator.cpus[0].assigned, Some(second.0));
+    }
+
+    #[test]
+    fn after_directive() {
+        let (mut killed, mut picked) = (emulator(1), emulator(1));
+        let (mut emulator, mut faulted) = (emulator(2), emulator(1));
+        emulator.arrival_policy = ArrivalPolicy::FixedZero;
+        let files = vec![
+            (
+                "stage_2.asm".to_string(),
+                b"#AFTER stage_1.asm\nINT 20H".to_vec(),
             ),
-            pick_list(
-                [1, 2, 3, 4, 5, 6, 7,],
-                self.quantum,
-                Message::QuantumSelected
+            (
+                "stage_1.asm".to_string(),
+                b"MOV AX, 1\nMOV BX, 2\nINT 20H".to_vec(),
             ),
-            widget::Space::new(iced::Length::Shrink, iced::Length::Fill)
-        ]
-        .height(40)
-        .spacing(5)
-        .padding([5, 10]);
+        ];
+        emulator.store_batch(files, vec![]);
+        while emulator.admit().is_some() {}
+        assert_eq!(emulator.waiting_for(1), Some("stage_1.asm"));
+        assert_eq!(emulator.waiting_for(2), None);
+        let state = |emulator: &Emulator, p_id| emulator.memory.pcb(p_id).unwrap().process_state;
+        assert_eq!(state(&emulator, 1), ProcessState::New);
 
-        // Show the list of files
-        let mut files = column![].padding([5, 10]);
-        for (index, (file_name, _, _)) in self.storage.used.iter().enumerate() {
-            if let Some((file, p_id)) = self.loaded_files.iter().find(|x| x.0 == *file_name) {
-                if self.cpus.iter().any(|x| x.1 == *p_id) && p_id.is_some() {
-                    if file_name == file {
-                        files = files.push(rich_text([
-                            span(index).font(Font {
-                                weight: font::Weight::Bold,
-                                ..Font::default()
-                            }),
-                            span(" "),
-                            span(file).color(color!(0xff79c6)),
-                        ]));
-                    }
-                } else {
-                    files = files.push(rich_text([
-                        span(index).font(Font {
-                            weight: font::Weight::Bold,
-                            ..Font::default()
-                        }),
-                        span(" "),
-                        span(file_name),
-                    ]));
-                }
-            } else {
-                files = files.push(rich_text([
-                    span(index).font(Font {
-                        weight: font::Weight::Bold,
-                        ..Font::default()
-                    }),
-                    span(" "),
-                    span(file_name),
-                ]));
-            }
+        // The second stage arrives once the first one terminated, even with a free CPU
+        emulator.run_batch();
+        assert!(!emulator.batch_failed());
+        let terminated = emulator.post_mortems[&2].tick;
+        let ready = emulator.diagram.get(1).unwrap().ready_at.unwrap();
+        assert!(ready >= terminated, "{} {}", ready, terminated);
+        assert_eq!(emulator.waiting_for(1), None);
+
+        // A dependency that faults never lets it arrive
+        faulted.arrival_policy = ArrivalPolicy::FixedZero;
+        let pcb = load(&mut faulted, "a.asm", "ASSERT AX, 1\nINT 20H");
+        load(&mut faulted, "b.asm", "#AFTER a.asm\nINT 20H");
+        let _ = faulted.update(Message::Distpacher((0, pcb)));
+        for _ in 0..10 {
+            let _ = faulted.step();
         }
-        let files_display = container(scrollable(files))
-            .height(iced::Length::Fill)
-            .width(220)
-            .style(container::rounded_box);
+        assert!(faulted.post_mortems[&1].fault.is_some());
+        assert_eq!(faulted.waiting_for(2), Some("a.asm"));
+        assert_eq!(state(&faulted, 2), ProcessState::New);
 
-        // Display memory content
-        let memory_display = binary_display(&self.memory.data[..]);
+        // Neither does one that is killed
+        killed.arrival_policy = ArrivalPolicy::FixedZero;
+        let pcb = load(&mut killed, "a.asm", "MOV AX, 1\nINT 20H");
+        load(&mut killed, "b.asm", "#AFTER a.asm\nINT 20H");
+        let _ = killed.update(Message::Distpacher((0, pcb)));
+        let _ = killed.update(Message::Runaway(1, rfd::MessageDialogResult::Yes));
+        assert!(killed.post_mortems[&1].killed);
+        assert_eq!(killed.waiting_for(2), Some("a.asm"));
 
-        // Display storage content
-        let storage_display = binary_display(&self.storage.data[..]);
+        // The file picker stores the names between quotes
+        picked.arrival_policy = ArrivalPolicy::FixedZero;
+        let files = vec![
+            (
+                "\"stage_2.asm\"".to_string(),
+                b"#AFTER stage_1.asm\nINT 20H".to_vec(),
+            ),
+            ("\"stage_1.asm\"".to_string(), b"INT 20H".to_vec()),
+        ];
+        let _ = picked.update(Message::StoreFiles(Ok((files, vec![]))));
+        while picked.admit().is_some() {}
+        assert_eq!(picked.waiting_for(1), Some("stage_1.asm"));
+        assert!(picked.notifications.is_empty());
+        picked.run_batch();
+        assert!(!picked.batch_failed());
+        assert_eq!(picked.waiting_for(1), None);
+        assert_eq!(state(&picked, 1), ProcessState::Terminated);
+    }
 
-        // Display CPU content
-        let mut cpus_display = row![].spacing(5);
+    #[test]
+    fn after_cycle() {
+        let mut emulator = emulator(1);
+        for (name, program) in [
+            ("a.asm", "#AFTER b.asm\nINT 20H"),
+            ("b.asm", "#AFTER a.asm\nINT 20H"),
+            ("c.asm", "#AFTER a.asm\nINT 20H"),
+        ] {
+            let data = program.as_bytes().to_vec();
+            emulator.storage.store_files(name, data).unwrap();
+        }
+        // Both files of the cycle are rejected, the one that only depends on it waits
+        assert!(emulator.admit().is_some());
+        let body = &emulator.notifications[0].body;
+        assert_eq!(body.lines().count(), 2);
+        assert!(body.contains("a.asm -> b.asm -> a.asm"), "{}", body);
+        assert!(body.contains("b.asm -> a.asm -> b.asm"), "{}", body);
+        assert_eq!(loaded(&emulator), vec![("c.asm".to_string(), Some(1))]);
+        assert_eq!(emulator.waiting_for(1), Some("a.asm"));
+        assert!(emulator.admit().is_none());
+
+        // A dependency that is not stored is reported the same way
+        let data = b"#AFTER nope.asm\nINT 20H".to_vec();
+        emulator.storage.store_files("\"d.asm\"", data).unwrap();
+        assert!(emulator.admit().is_some());
+        let body = &emulator.notifications[1].body;
+        assert!(
+            body.contains("d.asm is not loaded, its #AFTER names nope.asm"),
+            "{}",
+            body
+        );
+        assert_eq!(loaded(&emulator).len(), 1);
+    }
+
+    #[test]
+    fn runaway_detection() {
+        let (mut emulator, mut headless) = (emulator(1), emulator(1));
+        emulator.config.runaway_factor = 2;
+        // Jumps back to the first instruction forever
+        let program =
+            "MOV AX, 1\nMOV AX, 2\nMOV AX, 3\nMOV AX, 4\nMOV AX, 5\nMOV AX, 6\nJMP -6\nINT 20H";
+        let pcb = load(&mut emulator, "a.asm", program);
+        let _ = emulator.update(Message::Distpacher((0, pcb)));
+        emulator.mode = Some(Mode::Automatic);
+        let warned = |emulator: &Emulator| {
+            emulator
+                .log
+                .filter(Level::Warning)
+                .filter(|x| x.message.starts_with("Process 1 executed"))
+                .count()
+        };
 
-        for (cpu, _) in &self.cpus {
-            cpus_display = cpus_display.push(cpu_display(cpu));
+        // Up to twice the 8 instructions of the program is fine
+        for _ in 0..16 {
+            let _ = emulator.step();
         }
+        assert_eq!(warned(&emulator), 0);
+        assert!(emulator.mode == Some(Mode::Automatic));
 
-        let mut display = text_input(":$ ", &self.display_content).width(115);
-        if !self.waiting_queue.is_empty() {
-            display = display.on_input(Message::Input).on_submit(Message::Unblock);
+        let _ = emulator.step();
+        assert_eq!(warned(&emulator), 1);
+        assert!(emulator
+            .log
+            .to_text(Level::Warning)
+            .contains("Process 1 executed 17 instructions, 2 times the length of its program"));
+        assert!(emulator.mode == Some(Mode::Manual));
+        // Warned only once
+        for _ in 0..20 {
+            let _ = emulator.step();
         }
+        assert_eq!(warned(&emulator), 1);
+        assert_eq!(emulator.notifications.len(), 1);
+        assert_eq!(
+            emulator.notifications[0].title,
+            "Process may never terminate"
+        );
 
-        let mut pcbs_display = row![].spacing(5);
-        for (_, address, size) in &self.memory.pcb_table {
-            let pcb = PCB::from(&self.memory.data[*address..*address + *size]);
-            let timing = self.diagram.iter().find(|x| x.p_id == pcb.id);
-            pcbs_display = pcbs_display.push(pcb_display(&pcb, timing));
+        let _ = emulator.update(Message::Runaway(1, rfd::MessageDialogResult::Yes));
+        assert_eq!(emulator.cpus[0].assigned, None);
+        assert!(emulator
+            .log
+            .to_text(Level::Warning)
+            .contains("Process 1 killed on CPU 0"));
+
+        // A headless run is only notified, it doesn't pause
+        headless.headless = true;
+        headless.config.runaway_factor = 2;
+        let pcb = load(&mut headless, "a.asm", program);
+        let _ = headless.update(Message::Distpacher((0, pcb)));
+        headless.mode = Some(Mode::Automatic);
+        for _ in 0..20 {
+            let _ = headless.step();
         }
+        assert_eq!(headless.notifications.len(), 1);
+        assert!(headless.mode == Some(Mode::Automatic));
+    }
 
-        widget::container(column![
-            menu_bar,
-            row![
-                column![text("Files"), files_display],
-                column![
-                    text("Memory"),
-                    memory_display,
-                    text("Storage"),
-                    storage_display
-                ],
-                column![
-                    text("CPU"),
-                    cpus_display,
-                    text("Display"),
-                    display,
-                    text("PCB List"),
-                    pcbs_display,
-                ],
-                widget::Space::new(iced::Length::Fill, iced::Length::Fill)
+    #[test]
+    fn burst_progress_with_loop() {
+        let mut emulator = emulator(1);
+        // Counts to 3, -6 goes back to the INC
+        let pcb = load(
+            &mut emulator,
+            "a.asm",
+            "MOV BX, 3\nINC\nSTORE AX\nMOV CX, 0\nMOV CX, 0\nMOV CX, 0\nCMP AX, BX\nJNE -6\nINT 20H",
+        );
+        let _ = emulator.update(Message::Distpacher((0, pcb)));
+        emulator.mode = Some(Mode::Manual);
+        emulator.run_ticks = "22".to_string();
+        let _ = emulator.update(Message::RunTicks);
+
+        // Executed more instructions than the program has
+        let timing = emulator.diagram.get(pcb.0).unwrap();
+        assert_eq!(timing.burst, 9);
+        assert_eq!(timing.remaining_burst, 0);
+        assert_eq!(timing.progress(), 100.0);
+        assert_eq!(emulator.cpus[0].cpu.ax, 3);
+        assert_eq!(emulator.cpus[0].assigned, Some(pcb.0));
+    }
+
+    #[test]
+    fn hex_row_modes() {
+        let ranges = |rows: Vec<HexRow>| {
+            rows.iter()
+                .map(|x| (x.start, x.end, x.label.clone()))
+                .collect::<Vec<_>>()
+        };
+        assert_eq!(
+            ranges(hex_rows(20, RowMode::Eight, &[])),
+            vec![
+                (0, 8, "00".to_string()),
+                (8, 16, "01".to_string()),
+                (16, 20, "02".to_string()),
             ]
-            .spacing(40)
-            .padding([10, 10])
-        ])
-        .center_x(iced::Length::Fill)
-        .center_y(iced::Length::Fill)
-        .width(iced::Length::Fill)
-        .height(iced::Length::Fill)
-        .into()
+        );
+        assert_eq!(
+            ranges(hex_rows(20, RowMode::Sixteen, &[])),
+            vec![(0, 16, "00".to_string()), (16, 20, "01".to_string())]
+        );
+
+        // Code rows break on every instruction
+        let layout = [
+            SegmentInfo {
+                owner: None,
+                kind: SegmentKind::Os,
+                address: 0,
+                size: 10,
+            },
+            SegmentInfo {
+                owner: Some(1),
+                kind: SegmentKind::Code,
+                address: 10,
+                size: 12,
+            },
+            SegmentInfo {
+                owner: Some(1),
+                kind: SegmentKind::Stack,
+                address: 22,
+                size: 4,
+            },
+        ];
+        assert_eq!(
+            ranges(hex_rows(26, RowMode::Instructions, &layout)),
+            vec![
+                (0, 8, "00".to_string()),
+                (8, 10, "08".to_string()),
+                (10, 16, "#0".to_string()),
+                (16, 22, "#1".to_string()),
+                (22, 26, "16".to_string()),
+            ]
+        );
+        // Without code segments it's the same as 8 bytes rows
+        assert_eq!(
+            hex_rows(20, RowMode::Instructions, &[]).len(),
+            hex_rows(20, RowMode::Eight, &[]).len()
+        );
     }
 
-    fn subscription(&self) -> Subscription<Message> {
-        if self.mode == Some(Mode::Automatic) {
-            return time::every(Duration::from_millis(1000)).map(|_| Message::Tick);
+    #[test]
+    fn ascii_column() {
+        assert_eq!(ascii_text(b"MOV AX, 5\n"), "MOV AX, 5.");
+        assert_eq!(ascii_text(&[0, 0x41, 0x7F, b'~', 0xFF, b' ']), ".A.~. ");
+        assert_eq!(byte_text(10, false), "0A");
+        assert_eq!(byte_text(10, true), " 10");
+    }
+
+    #[test]
+    fn recovery_file() {
+        let path = env::temp_dir().join(format!("recovery_{}.json", std::process::id()));
+        let mut crashed = emulator(1);
+        crashed.recovery_path = Some(path.clone());
+        crashed.config.autosnapshot_ticks = 4;
+        crashed.arrival_policy = ArrivalPolicy::FixedZero;
+        // Storing a file writes it right away
+        crashed
+            .store_file(
+                "a.asm".to_string(),
+                b"MOV AX, 1\nMOV BX, 2\nMOV CX, 3\nMOV DX, 4\nINT 20H".to_vec(),
+            )
+            .unwrap();
+        assert_eq!(Recovery::load(&path).unwrap().counter, 0);
+        let _ = crashed.admit();
+        let _ = crashed.schedule();
+
+        // Then only once every 4 ticks
+        for _ in 0..4 {
+            let _ = crashed.step();
+        }
+        assert_eq!(Recovery::load(&path).unwrap().counter, 0);
+        let _ = crashed.step();
+        let recovery = Recovery::load(&path).unwrap();
+        assert_eq!(recovery.counter, 4);
+
+        // The run goes on from the saved tick with the process on the CPU
+        let mut restored = emulator(1);
+        restored.recovery_path = Some(path.clone());
+        restored.recovery = Some(recovery);
+        let _ = restored.update(Message::Recover(rfd::MessageDialogResult::Yes));
+        assert!(restored.recovery.is_none());
+        assert_eq!(restored.storage, crashed.storage);
+        assert_eq!(restored.counter, 4);
+        assert_eq!(restored.cpus[0].assigned, Some(1));
+        assert_eq!(restored.cpus[0].cpu.ax, 1);
+        assert_eq!(restored.cpus[0].cpu.dx, 4);
+        while restored.cpus[0].assigned.is_some() {
+            let _ = restored.step();
         }
-        Subscription::none()
+        let pcb = restored.memory.pcb(1).unwrap();
+        assert_eq!(pcb.process_state, ProcessState::Terminated);
+        assert_eq!(restored.counter, crashed.counter);
+
+        // A clean exit leaves nothing to recover
+        restored.remove_recovery();
+        assert!(matches!(
+            Recovery::load(&path),
+            Err(Error::IO(std::io::ErrorKind::NotFound))
+        ));
     }
 
-    fn theme(&self) -> Theme {
-        self.theme.clone()
+    #[test]
+    fn stale_recovery() {
+        let path = env::temp_dir().join(format!("stale_recovery_{}.json", std::process::id()));
+        std::fs::write(&path, r#"{"version": 0, "counter": 3, "files": []}"#).unwrap();
+        assert!(matches!(
+            Recovery::load(&path),
+            Err(Error::UnknownRecoveryVersion(0))
+        ));
+        std::fs::write(&path, r#"{"counter": 3}"#).unwrap();
+        assert!(matches!(
+            Recovery::load(&path),
+            Err(Error::UnknownRecoveryVersion(0))
+        ));
+
+        // It's refused without asking and removed so it isn't offered again
+        let mut restored = emulator(1);
+        restored.recovery_path = Some(path.clone());
+        let _ = restored.recover();
+        assert!(restored.recovery.is_none());
+        assert_eq!(restored.storage, Storage::new(restored.config.storage));
+        assert!(!path.exists());
     }
-}
 
-fn color_square() -> Container<'static, Message> {
-    container("")
-        .width(20)
-        .height(20)
-        .style(|_| container::background(color!(0x9afcb3)))
-}
+    #[test]
+    fn ui_state_file() {
+        let path = env::temp_dir().join(format!("ui_state_{}.json", std::process::id()));
+        let ui_state = UiState {
+            theme: Theme::Nord.to_string(),
+            show_stats: true,
+            speed: Speed::Fast,
+            ..Default::default()
+        };
+        ui_state.save(&path).unwrap();
+        let loaded = UiState::load(&path);
+        assert_eq!(loaded, ui_state);
+        assert_eq!(loaded.theme(), Theme::Nord);
 
-fn square() -> Container<'static, Message> {
-    container("").width(20).height(20)
-}
+        // Corrupt and missing files use the defaults
+        std::fs::write(&path, "{ theme: ").unwrap();
+        assert_eq!(UiState::load(&path), UiState::default());
+        std::fs::remove_file(&path).unwrap();
+        assert_eq!(UiState::load(&path), UiState::default());
 
-fn pcb_display(pcb: &PCB, timing: Option<&Timing>) -> Tooltip<'static, Message> {
-    tooltip(
-        // PCB container
-        container(
-            row![
-                rich_text([span(pcb.id)
-                    .font(Font {
-                        weight: font::Weight::Bold,
-                        ..Font::default()
-                    })
-                    .color(color!(0x1ef956))]),
-                vertical_rule(3),
-                rich_text([span(format!("{:?}", pcb.process_state))
-                    .font(Font {
-                        weight: font::Weight::Bold,
-                        ..Font::default()
-                    })
-                    .color(color!(0xbd93f9))]),
-                vertical_rule(3),
-                rich_text([span(pcb.priority).font(Font {
-                    weight: font::Weight::Bold,
-                    ..Font::default()
-                })]),
-            ]
-            .spacing(5),
-        )
-        .height(40)
-        .padding([10, 10])
-        .style(container::rounded_box),
-        // Tooltip content container
-        container(column![
-            row![
-                // ID
-                rich_text([
-                    span("ID: "),
-                    span(pcb.id)
-                        .font(Font {
-                            weight: font::Weight::Bold,
-                            ..Font::default()
-                        })
-                        .color(color!(0x1ef956))
-                ]),
-                widget::Space::new(100, iced::Length::Shrink),
-                // Priority
-                rich_text([
-                    span("Priority: "),
-                    span(pcb.priority).font(Font {
-                        weight: font::Weight::Bold,
-                        ..Font::default()
-                    })
-                ])
-            ],
-            // Process State
-            rich_text([
-                span("State: "),
-                span(format!("{:?}", pcb.process_state))
-                    .font(Font {
-                        weight: font::Weight::Bold,
-                        ..Font::default()
-                    })
-                    .color(color!(0xbd93f9))
-            ]),
-            text(format!(
-                "Code Segment: [{}; {}]",
-                &pcb.code_segment, &pcb.code_segment_size
-            )),
-            text(format!(
-                "Stack Segment: [{}; {}]",
-                &pcb.stack_segment, &pcb.stack_segment_size
-            )),
-            text(format!("Arrival: {}", timing.unwrap().arrival)),
-            text(format!("Burst: {}", timing.unwrap().burst)),
-            text(format!(
-                "Remaining Burst: {}",
-                timing.unwrap().remaining_burst
-            )),
-            if let Some(execution) = timing.unwrap().execution {
-                text(format!("Execution Time: {}", execution.as_secs()))
-            } else {
-                text("")
-            }
-        ])
-        .padding([10, 10])
-        .style(|_| container::background(color!(0x5a5e77))),
-        tooltip::Position::Top,
-    )
-}
+        // Unknown themes and missing fields
+        let partial: UiState = serde_json::from_str(r#"{"theme": "Nope"}"#).unwrap();
+        assert_eq!(partial.theme(), Theme::Dracula);
+        assert_eq!(partial.speed, Speed::Normal);
 
-fn cpu_display(cpu: &CPU) -> Container<'static, Message> {
-    container(column![
-        register_dispay("AX", format!("{:03}", cpu.ax)),
-        register_dispay("BX", format!("{:03}", cpu.bx)),
-        register_dispay("CX", format!("{:03}", cpu.cx)),
-        register_dispay("DX", format!("{:03}", cpu.dx)),
-        register_dispay("AC", format!("{:03}", cpu.ac)),
-        register_dispay("PC", format!("{:03}", cpu.pc)),
-        register_dispay("SP", format!("{:03}", cpu.sp)),
-        register_dispay(
-            "IR",
-            match cpu.ir {
-                Some(operation) => format!("{}", operation),
-                None => "None".to_string(),
-            }
-        ),
-        register_dispay(" Z", format!("{}", cpu.z)),
-    ])
-    .height(200)
-    .width(115)
-    .padding([5, 10])
-    .style(container::rounded_box)
-}
+        // The panes are resized within their range
+        let mut emulator = emulator(1);
+        emulator.files_width = UiState::default().files_width;
+        let _ = emulator.update(Message::FilesWidth(emulator.files_width + WIDTH_STEP));
+        assert_eq!(emulator.files_width, 240.0);
+        let _ = emulator.update(Message::MemoryWidth(10_000.0));
+        assert_eq!(emulator.memory_width, MEMORY_WIDTHS.1);
+        assert_eq!(emulator.ui_state().files_width, 240.0);
+    }
 
-fn register_dispay(r_name: &str, r: String) -> Element<'_, Message> {
-    rich_text(vec![
-        span(r_name).color(color!(0xff79c6)).font(Font {
-            weight: font::Weight::Bold,
-            ..Font::default()
-        }),
-        span(format!("\t{}", r)).font(Font {
-            weight: font::Weight::Bold,
-            ..Font::default()
-        }),
-    ])
-    .into()
-}
+    #[test]
+    fn soft_reset_runs_files_again() {
+        let mut emulator = emulator(1);
+        load(&mut emulator, "a.asm", "MOV AX, 1\nINT 20H");
+        let _ = emulator.schedule();
+        let _ = emulator.update(Message::RunToEvent);
+        assert_eq!(emulator.stats_data.len(), 1);
 
-fn binary_display(bytes: &[u8]) -> Container<'static, Message> {
-    let mut column = column![].padding([5, 10]);
-    for (index, data) in bytes.chunks(8).enumerate() {
-        let mut spans = vec![span(format!("{:02X}", index))
-            .color(color!(0x9afcb3))
-            .font(Font {
-                weight: font::Weight::Bold,
-                ..Font::default()
-            })];
-        spans.append(
-            &mut data
-                .iter()
-                .map(|x| {
-                    span(format!("\t{:02X}", x)).font(Font {
-                        weight: font::Weight::Bold,
-                        ..Font::default()
-                    })
-                })
-                .collect::<Vec<_>>(),
-        );
-        column = column.push(rich_text(spans));
+        let _ = emulator.update(Message::ResetPressed);
+        assert!(emulator.confirm_reset);
+        let _ = emulator.update(Message::SoftReset);
+        assert!(!emulator.confirm_reset);
+        assert_eq!(emulator.storage.used.len(), 1);
+        assert!(emulator.loaded_files.is_empty());
+        assert!(emulator.memory.pcb_table.is_empty());
+        assert!(emulator.stats_data.is_empty());
+
+        // Play loads the stored files again
+        let _ = emulator.update(Message::ChangeMode);
+        let _ = emulator.schedule();
+        assert_eq!(loaded(&emulator), vec![("a.asm".to_string(), Some(1))]);
+        let _ = emulator.update(Message::RunToEvent);
+        assert_eq!(emulator.stats_data.len(), 1);
+        assert_eq!(emulator.cpus[0].cpu.ax, 0);
+
+        let _ = emulator.update(Message::ResetPressed);
+        let _ = emulator.update(Message::FullReset);
+        assert!(emulator.storage.used.is_empty());
     }
 
-    container(scrollable(column).width(iced::Length::Fill))
-        .height(iced::Length::Fill)
-        .width(320)
-        .style(container::rounded_box)
-}
+    #[test]
+    fn quantum_only_for_round_robin() {
+        let mut emulator = emulator(1);
+        emulator.quantum = Some(1);
 
-fn create_pcbs(
-    storage: &mut Storage,
-    memory: &mut Memory,
-    loaded_files: &mut Vec<(String, Option<usize>)>,
-    diagram: &mut Vec<Timing>,
-) -> Option<Task<Message>> {
-    // Before selecting the process to execute we have to make sure that PCBs have been created
-    // Check the list of stored files
-    for (file_name, address, data_size) in &storage.used {
-        // We only load 5 files at a time
-        /*if memory.pcb_table.len() == 5 {
-            break;
-        }*/
-        if loaded_files.iter().any(|x| x.0 == *file_name) {
-            // File already loaded , so we can ignore it
-        }
-        // Load only files that have not already being loaded
-        else {
-            // Parse the file into to list of instructions
-            let instructions = match read_file(&storage.data[*address..(*address + *data_size)]) {
-                Ok(instructions) => instructions,
-                // Parsing Error
-                Err(error) => {
-                    // Remove file from memory
-                    storage.data[*address..*address + *data_size]
-                        .copy_from_slice(&vec![0; *data_size]);
-                    storage.freed.push(storage.used.remove(0));
-
-                    // Display the error to the user
-                    let dialog = rfd::AsyncMessageDialog::new()
-                        .set_level(rfd::MessageLevel::Warning)
-                        .set_title("Memory Warning")
-                        .set_description(format!("{}", error))
-                        .set_buttons(rfd::MessageButtons::Ok)
-                        .show();
-
-                    return Some(
-                        Task::perform(dialog, Message::DialogResult)
-                            .chain(Task::done(Message::Scheduler)),
-                    );
-                }
-            };
-            // Create the PCB only if there is enough space in memory
-            if instructions.len() + 5 <= memory.free_size() {
-                let num_instructions = instructions.len();
-                // Create new PCB
-                let next_id = memory.last_pcb_id() + 1;
-                let mut new_pcb = PCB::new(next_id);
-                // Store the instructions on memory
-                let serialized = to_bytes(instructions);
-                let size = &serialized.len();
-                let (address, size) = match memory.store(serialized, *size) {
-                    Ok(address) => address,
-                    // No more memory to store the instructions
-                    Err(error) => {
-                        // Display the error to the user
-                        let dialog = rfd::AsyncMessageDialog::new()
-                            .set_level(rfd::MessageLevel::Warning)
-                            .set_title("Memory Warning")
-                            .set_description(format!(" Cant store instructions. {}", error))
-                            .set_buttons(rfd::MessageButtons::Ok)
-                            .show();
-
-                        return Some(Task::perform(dialog, Message::DialogResult));
-                    }
-                };
-                new_pcb.code_segment(address, size);
-
-                // Allocate the stack memory
-                let (address, size) = match memory.store(vec![0; 5], 5) {
-                    Ok(address) => address,
-                    // No more memory to allocate the stack
-                    Err(_) => {
-                        todo!();
-                    }
-                };
-                new_pcb.stack_segment(address, size);
+        // Ignored by the other schedulers
+        let _ = emulator.update(Message::QuantumInput("4".to_string()));
+        assert_eq!(emulator.quantum, Some(1));
 
-                match memory.store_pcb(new_pcb) {
-                    Ok(_) => (),
-                    // No more memory to store PCBs
-                    Err(_) => todo!(),
-                }
+        emulator.config.scheduler = Some(Scheduler::RR);
+        let _ = emulator.update(Message::QuantumInput("12".to_string()));
+        assert_eq!(emulator.quantum, Some(12));
+        let _ = emulator.update(Message::QuantumInput("0".to_string()));
+        assert_eq!(emulator.quantum, Some(12));
+        assert_eq!(emulator.quantum_input, "0");
 
-                loaded_files.push((file_name.to_string(), Some(new_pcb.id)));
+        // Switching back to RR restores the last quantum
+        let _ = emulator.update(Message::SchedulerSelected(Scheduler::SJF));
+        let _ = emulator.update(Message::SchedulerSelected(Scheduler::RR));
+        assert_eq!(emulator.quantum, Some(12));
 
-                diagram.push(Timing {
-                    p_id: new_pcb.id,
-                    burst: num_instructions,
-                    remaining_burst: num_instructions,
-                    arrival: rand::thread_rng().gen_range(1..=5),
-                    start: None,
-                    ..Default::default()
-                });
+        // In the middle of a run it's used from the next quantum boundary
+        let long = format!("#ARRIVAL 0\n{}INT 20H", "MOV AX, 1\n".repeat(8));
+        let a = load(&mut emulator, "a.asm", &long);
+        let _ = emulator.update(Message::Distpacher((0, a)));
+        emulator.mode = Some(Mode::Manual);
+        let _ = emulator.update(Message::QuantumInput("3".to_string()));
+        assert_eq!(emulator.quantum, Some(12));
+        emulator.counter = 11;
+        assert_eq!(emulator.tick(), vec![TickEvent::QuantumExpired]);
+        assert_eq!(emulator.quantum, Some(3));
+        // Nobody else is ready, the process starts a new quantum
+        emulator.renew_quanta();
+        emulator.counter = 14;
+        assert_eq!(emulator.tick(), vec![TickEvent::QuantumExpired]);
+    }
+
+    #[test]
+    fn apply_settings() {
+        let mut emulator = emulator(1);
+        let _ = emulator.update(Message::ToggleSettings);
+        for (field, value) in [
+            (SettingsField::Memory, "300"),
+            (SettingsField::CpuQuantity, "3"),
+            (SettingsField::Quantum, "4"),
+        ] {
+            let _ = emulator.update(Message::SettingsInput(field, value.to_string()));
+        }
+
+        // Invalid values can't be applied
+        let _ = emulator.update(Message::SettingsInput(
+            SettingsField::OsSegment,
+            "400".to_string(),
+        ));
+        let errors = emulator
+            .settings
+            .as_ref()
+            .unwrap()
+            .config(emulator.config)
+            .unwrap_err();
+        assert_eq!(errors.len(), 1);
+        let _ = emulator.update(Message::ApplySettings);
+        assert_eq!(emulator.cpus.len(), 1);
+
+        let _ = emulator.update(Message::SettingsInput(
+            SettingsField::OsSegment,
+            "100".to_string(),
+        ));
+        let _ = emulator.update(Message::ApplySettings);
+        assert!(emulator.settings.is_none());
+        assert_eq!(emulator.memory.data.len(), 300);
+        assert_eq!(emulator.cpus.len(), 3);
+        assert_eq!(emulator.usage.len(), 3);
+        assert_eq!(emulator.show_history.len(), 3);
+        assert_eq!(emulator.quantum, Some(4));
+
+        // Not allowed once a file is loaded
+        load(&mut emulator, "a.asm", "INT 20H");
+        let _ = emulator.update(Message::ToggleSettings);
+        let _ = emulator.update(Message::SettingsInput(
+            SettingsField::CpuQuantity,
+            "1".to_string(),
+        ));
+        let _ = emulator.update(Message::ApplySettings);
+        assert!(emulator.settings.is_some());
+        assert_eq!(emulator.cpus.len(), 3);
+        assert_eq!(emulator.log.filter(Level::Warning).count(), 1);
+    }
+
+    #[test]
+    fn cores() {
+        let (mut emulator, mut single) = (emulator(2), emulator(1));
+        emulator.config.scheduler = Some(Scheduler::RR);
+        emulator.quantum = Some(2);
+        emulator.arrival_policy = ArrivalPolicy::FixedZero;
+        for (name, program) in [
+            ("a.asm", "MOV AX, 0\nINT 20H"),
+            ("b.asm", "MOV AX, 3\nINC AX\nINC AX\nPUSH AX\nINT 20H"),
+            ("c.asm", "MOV BX, 1\nASSERT BX, 2\nINT 20H"),
+            ("d.asm", "MOV CX, 4\nDEC CX\nDEC CX\nINT 20H"),
+        ] {
+            load(&mut emulator, name, program);
+        }
+        emulator.mode = Some(Mode::Manual);
+        let _ = emulator.schedule();
+        let mut trace = vec![];
+        for _ in 0..9 {
+            trace.push(emulator.cpus.iter().map(|x| x.assigned).collect::<Vec<_>>());
+            for core in &emulator.cpus {
+                // Only the assignment tells if the core is idle, P1 has its registers at 0
+                match core.assigned {
+                    Some(p_id) => {
+                        assert_eq!(core.state, CoreState::Running);
+                        let pcb = emulator.memory.pcb(p_id).unwrap();
+                        assert_eq!(pcb.process_state, ProcessState::Running);
+                    }
+                    None => {
+                        assert_eq!(core.state, CoreState::Idle);
+                        assert_eq!((core.cpu.ax, core.cpu.pc, core.cpu.sp), (0, 0, 0));
+                    }
+                }
             }
+            let _ = emulator.step();
         }
+        // The same CPUs as with the (CPU, Option<usize>) tuples
+        assert_eq!(
+            trace,
+            [
+                [Some(1), Some(2)],
+                [Some(1), Some(2)],
+                [Some(3), Some(4)],
+                [Some(3), Some(4)],
+                [Some(2), Some(4)],
+                [Some(2), Some(4)],
+                [Some(2), None],
+                [None, None],
+                [None, None],
+            ]
+        );
+        assert_eq!(emulator.post_mortems[&2].registers.ax, 5);
+        assert_eq!(emulator.post_mortems[&4].registers.cx, 2);
+
+        // The core is halted between the end of its process and the OS releasing it
+        let pcb = load(&mut single, "a.asm", "INT 20H");
+        let _ = single.update(Message::Distpacher((0, pcb)));
+        assert_eq!(single.tick(), vec![TickEvent::Terminated(0)]);
+        assert_eq!(single.cpus[0].state, CoreState::Halted);
+        assert!(!single.cpus[0].is_free());
+        single.terminate(0);
+        assert_eq!(single.cpus[0].state, CoreState::Idle);
+        assert!(single.cpus[0].is_free());
     }
-    None
-}
 
-// Reads the content of the selected files and groups the file name with the file content
-async fn read_files(files: Vec<PathBuf>) -> Result<Vec<(String, Vec<u8>)>, Error> {
-    let mut files_content: Vec<(String, Vec<u8>)> = vec![];
-    for path in files {
-        let file_name = path.file_name();
-        let file_name = file_name.ok_or(Error::NotFile)?;
+    // Events seen by an observer: tick, dispatch, preempt, block, unblock, terminate, fault
+    struct Counting(Rc<RefCell<[usize; 7]>>);
+
+    impl Observer for Counting {
+        fn on_tick(&mut self, _: u64) {
+            self.0.borrow_mut()[0] += 1;
+        }
+
+        fn on_dispatch(&mut self, _: u64, _: usize, _: usize) {
+            self.0.borrow_mut()[1] += 1;
+        }
+
+        fn on_preempt(&mut self, _: u64, _: usize, _: usize) {
+            self.0.borrow_mut()[2] += 1;
+        }
+
+        fn on_block(&mut self, _: u64, _: usize, _: usize) {
+            self.0.borrow_mut()[3] += 1;
+        }
+
+        fn on_unblock(&mut self, _: u64, _: usize) {
+            self.0.borrow_mut()[4] += 1;
+        }
 
-        let contents = tokio::fs::read(&path)
-            .await
-            .map_err(|error| error.kind())
-            .map_err(Error::IO)?;
+        fn on_terminate(&mut self, _: u64, _: usize, _: usize) {
+            self.0.borrow_mut()[5] += 1;
+        }
 
-        files_content.push((format!("{:?}", file_name).to_string(), contents));
+        fn on_fault(&mut self, _: u64, _: usize, _: usize, fault: &str) {
+            assert_eq!(fault, "fault_assertion");
+            self.0.borrow_mut()[6] += 1;
+        }
     }
 
-    Ok(files_content)
-}
+    #[test]
+    fn observers() {
+        let mut emulator = emulator(1);
+        emulator.config.scheduler = Some(Scheduler::RR);
+        emulator.quantum = Some(2);
+        emulator.arrival_policy = ArrivalPolicy::FixedZero;
+        let counts = Rc::new(RefCell::new([0; 7]));
+        emulator.observers.push(Box::new(Counting(counts.clone())));
+        for (name, program) in [
+            ("a.asm", "MOV AX, 1\nINT 09H\nINT 20H"),
+            ("b.asm", "MOV AX, 1\nINC AX\nINC AX\nINC AX\nINT 20H"),
+            ("c.asm", "MOV BX, 1\nASSERT BX, 2\nINT 20H"),
+        ] {
+            load(&mut emulator, name, program);
+        }
+        emulator.mode = Some(Mode::Manual);
+        let _ = emulator.schedule();
+        let mut ticks = 0;
+        while emulator.completed.is_none() && ticks < 50 {
+            // a gets its input as soon as it asks for it
+            if !emulator.waiting_queue.is_empty() {
+                let _ = emulator.update(Message::Unblock(1, 7));
+            }
+            let _ = emulator.step();
+            ticks += 1;
+        }
 
-// Open the file picker dialog to select the files
-async fn pick_file() -> Result<Vec<PathBuf>, Error> {
-    let handle = rfd::AsyncFileDialog::new()
-        .set_title("Choose a file...")
-        .add_filter("Assembly File", &["asm"])
-        .pick_files()
-        .await
-        .ok_or(Error::DialogClosed)?;
+        // a blocks, b is preempted by c, which was waiting longer than a, c fails and b ends last
+        assert_eq!(*counts.borrow(), [ticks, 5, 1, 1, 1, 3, 1]);
+        let trace = &emulator.trace;
+        assert_eq!(trace.ticks, ticks as u64);
+        let order = trace
+            .events
+            .iter()
+            .filter_map(|x| match x {
+                TraceEvent::Dispatch { p_id, .. } => Some(*p_id),
+                _ => None,
+            })
+            .collect::<Vec<_>>();
+        assert_eq!(order, [1, 2, 3, 1, 2]);
+        let dispatch = |tick, p_id| TraceEvent::Dispatch { tick, p_id, cpu: 0 };
+        let terminate = |tick, p_id| TraceEvent::Terminate { tick, p_id, cpu: 0 };
+        assert_eq!(
+            trace.events[trace.events.len() - 6..],
+            [
+                TraceEvent::Fault {
+                    tick: 6,
+                    p_id: 3,
+                    cpu: 0,
+                    fault: "fault_assertion".to_string()
+                },
+                terminate(6, 3),
+                dispatch(6, 1),
+                terminate(7, 1),
+                dispatch(7, 2),
+                terminate(10, 2),
+            ]
+        );
+        // The log console got the same dispatches
+        let assigned = emulator
+            .log
+            .filter(Level::Info)
+            .filter(|x| x.message.starts_with("Assigning process"))
+            .count();
+        assert_eq!(assigned, 5);
 
-    Ok(handle
-        .iter()
-        .map(|f| f.path().to_owned())
-        .collect::<Vec<PathBuf>>())
+        emulator.reset();
+        assert!(emulator.trace.events.is_empty());
+        assert_eq!(emulator.observers.len(), 1);
+    }
 }
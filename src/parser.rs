@@ -1,49 +1,238 @@
-use crate::emulator::{Instruction, Interupt, Operands, Operation, Register};
+use crate::emulator::{Instruction, Interupt, OperandShape, Operands, Operation, Register};
 use crate::error::Error;
+use serde::{Deserialize, Serialize};
 use std::str::FromStr;
 
 const REGISTERS: [&str; 4] = ["AX", "BX", "CX", "DX"];
-const INTERUPTS: [&str; 3] = ["09H", "10H", "20H"];
+const INTERUPTS: [&str; 5] = ["09H", "10H", "11H", "12H", "20H"];
+
+// Line of the file of every instruction, so a running instruction can be traced back to its
+// source
+#[derive(Debug, Default, Clone, PartialEq, Deserialize, Serialize)]
+pub struct SourceMap {
+    // (line number starting at 1, text of the line) of each instruction
+    lines: Vec<(usize, String)>,
+}
+
+impl SourceMap {
+    pub fn line(&self, index: usize) -> Option<(usize, &str)> {
+        self.lines
+            .get(index)
+            .map(|(line, text)| (*line, text.as_str()))
+    }
+}
+
+// Values given by the header directives of the file (#ARRIVAL, #BURST, #PRIORITY, #STACK,
+// #INPUT, #INPUT_RANGE, #AFTER) and the source lines of the instructions
+#[derive(Debug, Default, Clone, PartialEq)]
+pub struct ProgramMeta {
+    pub arrival: Option<u8>,
+    pub burst: Option<usize>,
+    pub priority: Option<u8>,
+    pub stack: Option<usize>,
+    // Values read by INT 09H instead of asking the user, like `#INPUT 5,7,9`
+    pub input: Vec<u8>,
+    // Lowest and highest values INT 09H accepts from the user, like `#INPUT_RANGE 1 10`
+    pub input_range: Option<(u8, u8)>,
+    // File that has to terminate without a fault before this one arrives, like `#AFTER a.asm`
+    pub after: Option<String>,
+    pub source: SourceMap,
+}
 
 // Parse the asm file
-pub fn read_file(stream: &[u8]) -> Result<Vec<Instruction>, Error> {
-    // Read bytes to string and remove trailing spaces
-    let string = match std::str::from_utf8(stream) {
-        Ok(content) => content.trim(),
-        Err(_) => return Err(Error::Utf8Error),
-    };
+pub fn read_file(stream: &[u8]) -> Result<(ProgramMeta, Vec<Instruction>), Error> {
+    let string = read_text(stream)?;
 
+    let mut meta = ProgramMeta::default();
     let mut instructions: Vec<Instruction> = vec![];
+    // (line, offset) of every MOVN, checked once #STACK has been read
+    let mut stack_writes = vec![];
 
     // Read each line of the file
-    for (i, line) in string.lines().enumerate() {
-        let line = &line.replace(",", "");
-        let mut instruction = line.split(" ").collect::<Vec<&str>>();
-        instruction.reverse();
+    for (i, original) in string.lines().enumerate() {
+        let line = clean_line(original);
+        // Directives are not instructions, so they don't move the jumps
+        if is_directive(&line) {
+            read_directive(i, &line, &mut meta)?;
+        } else if let Some(instruction) = read_line(i, &line)? {
+            if let Operands::V7(offset, _) = instruction.operands {
+                stack_writes.push((i, offset));
+            }
+            instructions.push(instruction);
+            meta.source.lines.push((i + 1, original.trim().to_string()));
+        }
+    }
 
-        let operation = instruction.pop().unwrap();
+    // Without #STACK the size is only known when the program is loaded, the CPU checks it
+    if let Some(stack) = meta.stack {
+        if let Some((i, offset)) = stack_writes.iter().find(|x| x.1 as usize >= stack) {
+            return Err(Error::InvalidOperand(
+                *i,
+                Operation::MOVN,
+                offset.to_string(),
+            ));
+        }
+    }
 
-        // Ingore empty lines
-        if !operation.is_empty() {
-            instruction.reverse();
+    Ok((meta, instructions))
+}
 
-            // Validate the operation part of the expresion
-            let operation = match Operation::from_str(operation) {
-                Ok(operation) => operation,
-                Err(_) => return Err(Error::InvalidOperation(i, operation.to_string())),
-            };
+// Split a file into its programs, each #PROGRAM directive starts a new one. The lines before
+// the first #PROGRAM are shared by every program. Files without the directive are one program
+pub fn split_programs(stream: &[u8]) -> Result<Vec<(Option<String>, String)>, Error> {
+    let string = read_text(stream)?;
 
-            // Validate the number of operators
-            let operands = validate_operators(i, &operation, &instruction)?;
+    let mut header: Vec<&str> = vec![];
+    let mut programs: Vec<(Option<String>, Vec<&str>)> = vec![];
+    for line in string.lines() {
+        let mut parts = line.split_whitespace();
+        if parts.next().map(|x| x.to_uppercase()) == Some("#PROGRAM".to_string()) {
+            let name = parts.collect::<Vec<&str>>().join(" ");
+            programs.push((Some(name), header.clone()));
+        } else if let Some((_, lines)) = programs.last_mut() {
+            lines.push(line);
+        } else {
+            header.push(line);
+        }
+    }
+    if programs.is_empty() {
+        return Ok(vec![(None, string.to_string())]);
+    }
+
+    Ok(programs
+        .into_iter()
+        .map(|(name, lines)| (name, lines.join("\n")))
+        .collect())
+}
+
+// Parse the asm file and collect the errors of every line instead of stopping on the first one
+pub fn check_file(stream: &[u8]) -> Vec<Error> {
+    let string = match read_text(stream) {
+        Ok(string) => string,
+        Err(error) => return vec![error],
+    };
+
+    string
+        .lines()
+        .map(clean_line)
+        .enumerate()
+        .filter_map(|(i, line)| {
+            if is_directive(&line) {
+                read_directive(i, &line, &mut ProgramMeta::default()).err()
+            } else {
+                read_line(i, &line).err()
+            }
+        })
+        .collect()
+}
+
+fn is_directive(line: &str) -> bool {
+    line.trim_start().starts_with('#')
+}
+
+// Parse a directive line like `#ARRIVAL 3`
+fn read_directive(i: usize, line: &str, meta: &mut ProgramMeta) -> Result<(), Error> {
+    let mut parts = line.split_whitespace();
+    let name = parts.next().unwrap_or_default();
+    let values = parts.collect::<Vec<&str>>();
+    let invalid = || Error::InvalidDirective(i, line.trim().to_string());
+    // The range has two values, the rest of the directives one
+    if name.to_uppercase() == "#INPUT_RANGE" {
+        let [low, high] = values[..] else {
+            return Err(invalid());
+        };
+        match (low.parse::<u8>(), high.parse::<u8>()) {
+            (Ok(low), Ok(high)) if low <= high => meta.input_range = Some((low, high)),
+            _ => return Err(invalid()),
+        }
+        return Ok(());
+    }
+    let [value] = values[..] else {
+        return Err(invalid());
+    };
 
-            instructions.push(Instruction {
-                operation,
-                operands,
-            });
+    match name.to_uppercase().as_str() {
+        "#ARRIVAL" => meta.arrival = Some(value.parse().map_err(|_| Error::ParseIntError)?),
+        "#BURST" => meta.burst = Some(value.parse().map_err(|_| Error::ParseIntError)?),
+        "#PRIORITY" => meta.priority = Some(value.parse().map_err(|_| Error::ParseIntError)?),
+        "#STACK" => match value.parse() {
+            Ok(0) | Err(_) => return Err(Error::InvalidDirective(i, line.trim().to_string())),
+            Ok(stack) => meta.stack = Some(stack),
+        },
+        "#INPUT" => {
+            meta.input = value
+                .split(',')
+                .map(|x| x.parse())
+                .collect::<Result<_, _>>()
+                .map_err(|_| Error::InvalidDirective(i, line.trim().to_string()))?
         }
+        "#AFTER" => meta.after = Some(value.to_string()),
+        // The programs are split before being parsed
+        "#PROGRAM" => (),
+        _ => return Err(Error::InvalidDirective(i, line.trim().to_string())),
+    }
+    Ok(())
+}
+
+fn read_text(stream: &[u8]) -> Result<&str, Error> {
+    // Read bytes to string and remove trailing spaces, and the BOM some editors add
+    match std::str::from_utf8(stream) {
+        Ok(content) => Ok(content.strip_prefix('\u{feff}').unwrap_or(content).trim()),
+        Err(_) => Err(Error::Utf8Error),
     }
+}
+
+// Line without the surrounding spaces, the comment after `;` or the \r of CRLF files. Tabs are
+// spaces and the other control characters are escaped, so the error messages can show them
+fn clean_line(line: &str) -> String {
+    let line = line.split(';').next().unwrap_or_default();
+    line.trim()
+        .chars()
+        .map(|c| match c {
+            '\t' => " ".to_string(),
+            c if c.is_control() => c.escape_default().to_string(),
+            c => c.to_string(),
+        })
+        .collect()
+}
+
+// Parse a line of the file, empty lines have no instruction
+fn read_line(i: usize, line: &str) -> Result<Option<Instruction>, Error> {
+    let line = &line.replace(",", "");
+    let mut instruction = line.split_whitespace().collect::<Vec<&str>>();
+    instruction.reverse();
 
-    Ok(instructions)
+    // Ingore empty lines
+    let Some(operation) = instruction.pop() else {
+        return Ok(None);
+    };
+    instruction.reverse();
+
+    // Validate the operation part of the expresion
+    let operation = match Operation::from_str(operation) {
+        Ok(operation) => operation,
+        Err(_) => return Err(Error::InvalidOperation(i, operation.to_string())),
+    };
+
+    // Validate the number of operators
+    let operands = validate_operators(i, &operation, &instruction)?;
+
+    Ok(Some(Instruction {
+        operation,
+        operands,
+    }))
+}
+
+// Value of MOV and PARAM, a negative one is stored as its two's complement so -5 is 251
+fn immediate(row: usize, operation: &Operation, operator: &str) -> Result<u8, Error> {
+    match operator.parse::<i16>() {
+        Ok(value) if (-128..=255).contains(&value) => Ok(value as u8),
+        _ => Err(Error::InvalidImmediate(
+            row,
+            *operation,
+            operator.to_string(),
+        )),
+    }
 }
 
 fn validate_operators(
@@ -51,8 +240,8 @@ fn validate_operators(
     operation: &Operation,
     operators: &[&str],
 ) -> Result<Operands, Error> {
-    match operation {
-        Operation::PARAM => {
+    match operation.spec().operand_shape {
+        OperandShape::Params => {
             if operators.len() > 3 || operators.is_empty() {
                 return Err(Error::InvalidNumberOperands(
                     row,
@@ -66,36 +255,19 @@ fn validate_operators(
                     }
                 }
             }
-            let line1 = &operators[0].replace("-", "");
-            let line1 = line1.replace("+", "");
-            match line1.parse::<u8>() {
-                Ok(num1) => {
-                    if operators.len() == 2 {
-                        let line2 = &operators[1].replace("-", "");
-                        let line2 = line2.replace("+", "");
-                        match line2.parse::<u8>() {
-                            Ok(num2) => {
-                                if operators.len() == 3 {
-                                    let line3 = &operators[2].replace("-", "");
-                                    let line3 = line3.replace("+", "");
-                                    match line3.parse::<u8>() {
-                                        Ok(num3) => Ok(Operands::V4(num1, num2, num3)),
-                                        Err(_) => Err(Error::ParseIntError),
-                                    }
-                                } else {
-                                    Ok(Operands::V4(num1, num2, 0))
-                                }
-                            }
-                            Err(_) => Err(Error::ParseIntError),
-                        }
-                    } else {
-                        Ok(Operands::V4(num1, 0, 0))
-                    }
-                }
-                Err(_) => Err(Error::ParseIntError),
+            // Every value is checked, the ones that aren't given are 0
+            let values = operators
+                .iter()
+                .map(|x| immediate(row, operation, x))
+                .collect::<Result<Vec<u8>, Error>>()?;
+            match values[..] {
+                [num1] => Ok(Operands::V4(num1, 0, 0)),
+                [num1, num2] => Ok(Operands::V4(num1, num2, 0)),
+                [num1, num2, num3] => Ok(Operands::V4(num1, num2, num3)),
+                _ => unreachable!(),
             }
         }
-        Operation::MOV => {
+        OperandShape::RegisterOrValue => {
             if operators.len() != 2 {
                 return Err(Error::InvalidNumberOperands(
                     row,
@@ -117,18 +289,41 @@ fn validate_operators(
                             Err(err) => Err(err),
                         }
                     } else {
-                        let line = &operators[1].replace("-", "");
-                        let line = line.replace("+", "");
-                        match line.parse::<u8>() {
+                        match immediate(row, operation, operators[1]) {
                             Ok(num) => Ok(Operands::V5(r1, num)),
-                            Err(_) => Err(Error::ParseIntError),
+                            Err(err) => Err(err),
                         }
                     }
                 }
                 Err(err) => Err(err),
             }
         }
-        Operation::SWAP | Operation::CMP => {
+        // LOADM AX, 118 or ASSERT AX, 5
+        OperandShape::RegisterAddress | OperandShape::RegisterValue => {
+            if operators.len() != 2 {
+                return Err(Error::InvalidNumberOperands(
+                    row,
+                    *operation,
+                    operators.iter().map(|s| s.to_string()).collect(),
+                ));
+            } else if !REGISTERS.contains(&operators[0]) {
+                return Err(Error::InvalidOperand(
+                    row,
+                    *operation,
+                    operators[0].to_string(),
+                ));
+            }
+            let register = Register::from_str(operators[0])?;
+            match operators[1].parse::<u8>() {
+                Ok(address) => Ok(Operands::V5(register, address)),
+                Err(_) => Err(Error::InvalidOperand(
+                    row,
+                    *operation,
+                    operators[1].to_string(),
+                )),
+            }
+        }
+        OperandShape::TwoRegisters => {
             if operators.len() != 2 {
                 return Err(Error::InvalidNumberOperands(
                     row,
@@ -150,7 +345,7 @@ fn validate_operators(
                 Err(err) => Err(err),
             }
         }
-        Operation::JMP | Operation::JE | Operation::JNE => {
+        OperandShape::Offset => {
             if operators.len() != 1 {
                 return Err(Error::InvalidNumberOperands(
                     row,
@@ -178,12 +373,7 @@ fn validate_operators(
                 }
             }
         }
-        Operation::ADD
-        | Operation::SUB
-        | Operation::LOAD
-        | Operation::STORE
-        | Operation::PUSH
-        | Operation::POP => {
+        OperandShape::Register => {
             if operators.len() != 1 {
                 return Err(Error::InvalidNumberOperands(
                     row,
@@ -202,7 +392,7 @@ fn validate_operators(
                 Err(err) => Err(err),
             }
         }
-        Operation::INT => {
+        OperandShape::Interupt => {
             if operators.len() != 1 {
                 return Err(Error::InvalidNumberOperands(
                     row,
@@ -221,29 +411,289 @@ fn validate_operators(
                 Err(err) => Err(err),
             }
         }
-        Operation::INC | Operation::DEC => {
-            if operators.len() > 1 {
+        // MOVN 2, 5
+        OperandShape::OffsetValue => {
+            if operators.len() != 2 {
                 return Err(Error::InvalidNumberOperands(
                     row,
                     *operation,
                     operators.iter().map(|s| s.to_string()).collect(),
                 ));
+            }
+            let number = |x: &str| {
+                x.parse::<u8>()
+                    .map_err(|_| Error::InvalidOperand(row, *operation, x.to_string()))
+            };
+            Ok(Operands::V7(number(operators[0])?, number(operators[1])?))
+        }
+        OperandShape::OptionalRegister => {
+            if operators.len() > 1 {
+                Err(Error::InvalidNumberOperands(
+                    row,
+                    *operation,
+                    operators.iter().map(|s| s.to_string()).collect(),
+                ))
             } else if operators.len() == 1 && !REGISTERS.contains(&operators[0]) {
-                return Err(Error::InvalidOperand(
+                Err(Error::InvalidOperand(
                     row,
                     *operation,
                     operators[0].to_string(),
-                ));
+                ))
             } else if operators.len() == 1 {
                 match Register::from_str(operators[0]) {
-                    Ok(test) => {
-                        return Ok(Operands::V2(test));
-                    }
-                    Err(err) => return Err(err),
-                };
+                    Ok(test) => Ok(Operands::V2(test)),
+                    Err(err) => Err(err),
+                }
             } else {
-                return Ok(Operands::V0);
+                Ok(Operands::V0)
             }
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::emulator::{from_bytes, to_bytes};
+
+    #[test]
+    fn check_file_errors() {
+        let program = "MOV AX, 5\nFOO AX\nINC\nMOV 5, AX\nINT 20H";
+
+        let errors = check_file(program.as_bytes());
+
+        assert_eq!(errors.len(), 2);
+        assert!(matches!(errors[0], Error::InvalidOperation(1, _)));
+        assert!(matches!(
+            errors[1],
+            Error::InvalidOperand(3, Operation::MOV, _)
+        ));
+        assert!(read_file(program.as_bytes()).is_err());
+
+        // The device registers are addressed with a byte
+        let errors = check_file(b"LOADM AX, 119\nSTOREM DX, 300\nSTOREM 5, DX");
+        assert_eq!(errors.len(), 2);
+        assert!(matches!(
+            errors[0],
+            Error::InvalidOperand(1, Operation::STOREM, _)
+        ));
+    }
+
+    #[test]
+    fn line_endings() {
+        let clean = "#ARRIVAL 2\nMOV AX, 5\nPARAM 1, 2\nINT 20H";
+        let expected = read_file(clean.as_bytes()).unwrap();
+        for file in [
+            clean.replace('\n', "\r\n"),
+            clean.replace('\n', "  \n\t"),
+            format!("\u{feff}{}", clean),
+            format!("\u{feff}{}\r\n", clean.replace('\n', " \r\n")),
+        ] {
+            let (meta, instructions) = read_file(file.as_bytes()).unwrap();
+            assert_eq!(
+                (meta.arrival, instructions),
+                (expected.0.arrival, expected.1.clone())
+            );
+            assert!(check_file(file.as_bytes()).is_empty());
+        }
+
+        // Control characters are escaped on the errors
+        let errors = check_file(b"MOV AX, 5\nMO\x07V BX, 1\nINT 20H");
+        assert_eq!(errors.len(), 1);
+        assert!(errors[0].to_string().contains("MO\\u{7}V"));
+        assert!(!errors[0].to_string().chars().any(|c| c.is_control()));
+    }
+
+    #[test]
+    fn encoding_unchanged() {
+        let program = "PARAM 1, 2, 3\nMOV AX, 5\nMOV BX, CX\nSWAP AX, DX\nCMP CX, DX\nADD BX\n\
+            SUB CX\nLOAD DX\nSTORE AX\nINC\nDEC BX\nINT 10H\nJMP +2\nJE -1\nJNE 3\nPUSH AX\n\
+            POP BX\nLOADM AX, 118\nSTOREM DX, 119";
+        let (_, instructions) = read_file(program.as_bytes()).unwrap();
+        // Bytes written before the instruction set table existed, except PARAM that kept only its
+        // first value with 3 operands
+        #[rustfmt::skip]
+        let expected = vec![
+            6, 1, 4, 1, 2, 3, 6, 2, 5, 1, 5, 0, 6, 2, 6, 2, 3, 0, 6, 3, 6, 1, 4, 0, 6, 4, 6, 3, 4,
+            0, 6, 5, 2, 2, 0, 0, 6, 6, 2, 3, 0, 0, 6, 7, 2, 4, 0, 0, 6, 8, 2, 1, 0, 0, 6, 9, 0, 0,
+            0, 0, 6, 10, 2, 2, 0, 0, 6, 11, 3, 2, 0, 0, 6, 12, 1, 0, 2, 0, 6, 13, 1, 1, 1, 0, 6,
+            14, 1, 0, 3, 0, 6, 15, 2, 1, 0, 0, 6, 16, 2, 2, 0, 0, 6, 17, 5, 1, 118, 0, 6, 18, 5,
+            4, 119, 0,
+        ];
+        let bytes = to_bytes(instructions.clone());
+        assert_eq!(bytes, expected);
+        assert_eq!(from_bytes(&bytes).unwrap(), instructions);
+    }
+
+    #[test]
+    fn directives() {
+        let program = "#ARRIVAL 3\n#BURST 12\n#PRIORITY 2\nMOV AX, 5\nJMP +1\nINC\nINT 20H";
+
+        let (meta, instructions) = read_file(program.as_bytes()).unwrap();
+
+        assert_eq!(
+            meta,
+            ProgramMeta {
+                arrival: Some(3),
+                burst: Some(12),
+                priority: Some(2),
+                stack: None,
+                input: vec![],
+                input_range: None,
+                after: None,
+                source: meta.source.clone(),
+            }
+        );
+
+        let (meta, _) = read_file(b"#INPUT 5,7,255\nINT 09H\nINT 20H").unwrap();
+        assert_eq!(meta.input, vec![5, 7, 255]);
+        // Only the instructions are returned
+        assert_eq!(instructions.len(), 4);
+        assert_eq!(instructions[0].operation, Operation::MOV);
+
+        let (meta, _) = read_file(b"MOV AX, 5\nINT 20H").unwrap();
+        assert_eq!(
+            meta,
+            ProgramMeta {
+                source: meta.source.clone(),
+                ..Default::default()
+            }
+        );
+
+        let (meta, _) = read_file(b"#INPUT_RANGE 1 10\nINT 09H\nINT 20H").unwrap();
+        assert_eq!(meta.input_range, Some((1, 10)));
+        let (meta, _) = read_file(b"#AFTER stage_1.asm\nINT 20H").unwrap();
+        assert_eq!(meta.after.as_deref(), Some("stage_1.asm"));
+
+        let errors =
+            check_file(b"#ARRIVAL\n#FOO 1\n#BURST x\n#INPUT 5,256\n#INPUT_RANGE 9 2\nINT 20H");
+        assert_eq!(errors.len(), 5);
+        assert!(matches!(errors[0], Error::InvalidDirective(0, _)));
+    }
+
+    #[test]
+    fn stack_writes() {
+        let (_, instructions) = read_file(b"#STACK 4\nMOVN 3, 200\nINT 20H").unwrap();
+        assert_eq!(instructions[0].operands, Operands::V7(3, 200));
+        assert_eq!(instructions[0].to_string(), "MOVN 3, 200");
+
+        // The offset has to be inside the declared stack, wherever the directive is
+        assert!(matches!(
+            read_file(b"MOVN 4, 1\n#STACK 4\nINT 20H"),
+            Err(Error::InvalidOperand(0, Operation::MOVN, _))
+        ));
+        assert!(read_file(b"MOVN 40, 1\nINT 20H").is_ok());
+
+        let errors = check_file(b"MOVN AX, 1\nMOVN 1\nMOVN 1, 256\nINT 20H");
+        assert_eq!(errors.len(), 3);
+        assert!(matches!(
+            errors[1],
+            Error::InvalidNumberOperands(1, Operation::MOVN, _)
+        ));
+    }
+
+    #[test]
+    fn assertions() {
+        let (_, instructions) = read_file(b"ASSERT CX, 12\nINT 20H").unwrap();
+        assert_eq!(instructions[0].operands, Operands::V5(Register::CX, 12));
+        assert_eq!(instructions[0].to_string(), "ASSERT CX, 12");
+        assert_eq!(
+            from_bytes(&to_bytes(instructions.clone())).unwrap(),
+            instructions
+        );
+
+        let errors = check_file(b"ASSERT 1, 2\nASSERT AX\nASSERT AX, BX\nINT 20H");
+        assert_eq!(errors.len(), 3);
+        assert!(matches!(
+            errors[2],
+            Error::InvalidOperand(2, Operation::ASSERT, _)
+        ));
+    }
+
+    #[test]
+    fn negative_immediates() {
+        let (_, instructions) =
+            read_file(b"MOV AX, -5\nMOV BX, +7\nPARAM -128, 255\nJMP -1").unwrap();
+        assert_eq!(instructions[0].operands, Operands::V5(Register::AX, 251));
+        assert_eq!(instructions[1].operands, Operands::V5(Register::BX, 7));
+        assert_eq!(instructions[2].operands, Operands::V4(128, 255, 0));
+        // JMP keeps the sign on its own byte
+        assert_eq!(instructions[3].operands, Operands::V1(1, 1));
+
+        let errors = check_file(b"MOV AX, -129\nMOV AX, 256\nPARAM 1, -300\nMOV AX, 5-\nINT 20H");
+        assert_eq!(errors.len(), 4);
+        assert!(matches!(
+            errors[0],
+            Error::InvalidImmediate(0, Operation::MOV, _)
+        ));
+        assert!(matches!(
+            errors[2],
+            Error::InvalidImmediate(2, Operation::PARAM, _)
+        ));
+        assert!(errors[1].to_string().contains("-5 is stored as 251"));
+
+        // The three values of PARAM are parsed and checked
+        let (_, instructions) = read_file(b"PARAM 1, 2, -3").unwrap();
+        assert_eq!(instructions[0].operands, Operands::V4(1, 2, 253));
+        let errors = check_file(b"PARAM 1, 2, -300\nPARAM 1, 2, 256\nINT 20H");
+        assert_eq!(errors.len(), 2);
+        assert!(matches!(
+            errors[0],
+            Error::InvalidImmediate(0, Operation::PARAM, _)
+        ));
+        assert!(matches!(
+            errors[1],
+            Error::InvalidImmediate(1, Operation::PARAM, _)
+        ));
+    }
+
+    #[test]
+    fn source_lines() {
+        let program =
+            "#ARRIVAL 1\n; Counts down from 3\n\nMOV AX, 3 ; counter\n\n  DEC AX\nJNE -1\nINT 20H";
+        let (meta, instructions) = read_file(program.as_bytes()).unwrap();
+        assert_eq!(instructions.len(), 4);
+        assert_eq!(instructions[0].to_string(), "MOV AX, 3");
+        // Lines start at 1 and keep the text of the file, comments included
+        assert_eq!(meta.source.line(0), Some((4, "MOV AX, 3 ; counter")));
+        assert_eq!(meta.source.line(1), Some((6, "DEC AX")));
+        assert_eq!(meta.source.line(3), Some((8, "INT 20H")));
+        assert_eq!(meta.source.line(4), None);
+        assert!(check_file(program.as_bytes()).is_empty());
+    }
+
+    #[test]
+    fn multiple_programs() {
+        let file = "#PRIORITY 1\n#PROGRAM a\n#ARRIVAL 2\nMOV AX, 5\nINT 20H\n#PROGRAM b\nFOO\nINT 20H\n#PROGRAM c\nJMP +1\nINC\nINT 20H";
+
+        let programs = split_programs(file.as_bytes()).unwrap();
+        let names = programs.iter().map(|x| x.0.clone()).collect::<Vec<_>>();
+        assert_eq!(
+            names,
+            vec![
+                Some("a".to_string()),
+                Some("b".to_string()),
+                Some("c".to_string())
+            ]
+        );
+
+        // Only the program with the error is rejected
+        let results = programs
+            .iter()
+            .map(|(_, source)| read_file(source.as_bytes()))
+            .collect::<Vec<_>>();
+        let (meta, instructions) = results[0].as_ref().unwrap();
+        assert_eq!((meta.arrival, meta.priority), (Some(2), Some(1)));
+        assert_eq!(instructions.len(), 2);
+        assert!(results[1].is_err());
+        let (meta, instructions) = results[2].as_ref().unwrap();
+        // Directives are scoped to their program
+        assert_eq!((meta.arrival, meta.priority), (None, Some(1)));
+        assert_eq!(instructions.len(), 3);
+
+        assert_eq!(
+            split_programs(b"MOV AX, 5\nINT 20H").unwrap(),
+            vec![(None, "MOV AX, 5\nINT 20H".to_string())]
+        );
+    }
+}